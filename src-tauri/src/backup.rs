@@ -0,0 +1,253 @@
+//! Content backups.
+//!
+//! Before a file is overwritten, renamed or deleted, a copy of its content can be
+//! kept in the vault's data directory so that an earlier version can be restored.
+//! Each file's backups live in a folder mirroring its vault-relative path, and are
+//! named by the time they were taken.
+
+use crate::{
+    config::{MAX_BACKUPS_PER_FILE, VAULT_DATA_DIR_NAME},
+    error::{ChroniclerError, Result},
+    utils::is_markdown_file,
+    writer::atomic_write,
+};
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{instrument, warn};
+use walkdir::WalkDir;
+
+/// The folder inside the vault's data directory that holds the backups.
+pub const BACKUPS_DIR_NAME: &str = "backups";
+
+/// The format of a backup's file stem. It sorts chronologically as a string.
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.3f";
+
+/// A stored copy of a file's earlier content.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Backup {
+    /// The absolute path of the backup file.
+    pub path: PathBuf,
+    /// When the backup was taken, formatted as `YYYY-MM-DDTHH:MM:SS`.
+    pub created: String,
+    /// The size of the backup in bytes.
+    pub size: u64,
+}
+
+fn backups_root(vault_root: &Path) -> PathBuf {
+    vault_root.join(VAULT_DATA_DIR_NAME).join(BACKUPS_DIR_NAME)
+}
+
+/// Returns the folder holding the backups of the file at `path`.
+fn backup_dir(vault_root: &Path, path: &Path) -> Result<PathBuf> {
+    let relative = path.strip_prefix(vault_root).map_err(|_| {
+        ChroniclerError::InvalidArgument(format!("{:?} is not inside the vault", path))
+    })?;
+    Ok(backups_root(vault_root).join(relative))
+}
+
+/// Reads the time a backup was taken from its file name.
+fn backup_time(backup_path: &Path) -> Option<NaiveDateTime> {
+    let stem = backup_path.file_stem()?.to_str()?;
+    NaiveDateTime::parse_from_str(stem, BACKUP_TIMESTAMP_FORMAT).ok()
+}
+
+/// Returns the backup files of the file at `path`, oldest first.
+fn backup_files(vault_root: &Path, path: &Path) -> Result<Vec<PathBuf>> {
+    let dir = backup_dir(vault_root, path)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && backup_time(p).is_some())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Copies the current content of the file at `path` into its backups, taken at
+/// `now`, and returns the backup's path. Only the newest `MAX_BACKUPS_PER_FILE`
+/// backups of a file are kept.
+///
+/// Does nothing and returns `None` if `path` is not an existing file.
+#[instrument]
+pub fn create_backup(
+    vault_root: &Path,
+    path: &Path,
+    now: NaiveDateTime,
+) -> Result<Option<PathBuf>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let dir = backup_dir(vault_root, path)?;
+    fs::create_dir_all(&dir)?;
+
+    // Two backups taken within the same millisecond would share a name, so the
+    // later one is nudged forward rather than replacing the earlier one.
+    let mut taken = now;
+    let backup_path = loop {
+        // `set_extension` would replace the milliseconds, which follow a dot.
+        let stamp = taken.format(BACKUP_TIMESTAMP_FORMAT);
+        let candidate = match path.extension() {
+            Some(ext) => dir.join(format!("{}.{}", stamp, ext.to_string_lossy())),
+            None => dir.join(stamp.to_string()),
+        };
+        if !candidate.exists() {
+            break candidate;
+        }
+        taken += Duration::milliseconds(1);
+    };
+    fs::copy(path, &backup_path)?;
+
+    let files = backup_files(vault_root, path)?;
+    let excess = files.len().saturating_sub(MAX_BACKUPS_PER_FILE);
+    for old in &files[..excess] {
+        if let Err(e) = fs::remove_file(old) {
+            warn!("Failed to remove old backup {:?}: {}", old, e);
+        }
+    }
+    Ok(Some(backup_path))
+}
+
+/// Backs up every Markdown file in the folder at `path`, or the file itself if
+/// `path` is a file.
+pub fn create_backups_for(vault_root: &Path, path: &Path, now: NaiveDateTime) -> Result<()> {
+    if path.is_dir() {
+        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_file() && is_markdown_file(entry.path()) {
+                create_backup(vault_root, entry.path(), now)?;
+            }
+        }
+        Ok(())
+    } else {
+        create_backup(vault_root, path, now).map(|_| ())
+    }
+}
+
+/// Returns `true` if the file at `path` has a backup taken less than `interval`
+/// before `now`.
+pub fn has_recent_backup(
+    vault_root: &Path,
+    path: &Path,
+    now: NaiveDateTime,
+    interval: Duration,
+) -> bool {
+    backup_files(vault_root, path)
+        .ok()
+        .and_then(|files| files.last().and_then(|latest| backup_time(latest)))
+        .is_some_and(|latest| now - latest < interval)
+}
+
+/// Lists the backups of the file at `path`, newest first.
+pub fn list_backups(vault_root: &Path, path: &Path) -> Result<Vec<Backup>> {
+    let mut backups = Vec::new();
+    for file in backup_files(vault_root, path)?.into_iter().rev() {
+        let Some(created) = backup_time(&file) else {
+            continue;
+        };
+        backups.push(Backup {
+            size: fs::metadata(&file)?.len(),
+            created: created.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            path: file,
+        });
+    }
+    Ok(backups)
+}
+
+/// Restores the file a backup was taken from to the backup's content and returns
+/// the restored file's path.
+///
+/// The file's current content is backed up first, taken at `now`, so a restore
+/// can itself be undone. A file that has since been deleted is recreated.
+#[instrument]
+pub fn restore_backup(
+    vault_root: &Path,
+    backup_path: &Path,
+    now: NaiveDateTime,
+) -> Result<PathBuf> {
+    let relative_dir = backup_path
+        .strip_prefix(backups_root(vault_root))
+        .ok()
+        .and_then(Path::parent)
+        .filter(|dir| !dir.as_os_str().is_empty() && backup_time(backup_path).is_some())
+        .ok_or_else(|| {
+            ChroniclerError::InvalidArgument(format!("{:?} is not a backup", backup_path))
+        })?;
+    let original = vault_root.join(relative_dir);
+
+    let content = fs::read_to_string(backup_path)?;
+    create_backup(vault_root, &original, now)?;
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write(&original, &content)?;
+    Ok(original)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::tempdir;
+
+    fn at(minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_opt(10, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backups_are_bounded_and_listed_newest_first() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("lore")).unwrap();
+        let page = root.join("lore").join("Vex.md");
+
+        for minute in 0..(MAX_BACKUPS_PER_FILE as u32 + 3) {
+            fs::write(&page, format!("Version {}", minute)).unwrap();
+            create_backup(root, &page, at(minute)).unwrap();
+        }
+
+        let backups = list_backups(root, &page).unwrap();
+        assert_eq!(backups.len(), MAX_BACKUPS_PER_FILE);
+        let newest = MAX_BACKUPS_PER_FILE as u32 + 2;
+        assert_eq!(
+            backups[0].created,
+            format!("2024-03-05T10:{:02}:00", newest)
+        );
+        assert_eq!(
+            fs::read_to_string(&backups[0].path).unwrap(),
+            format!("Version {}", newest)
+        );
+        assert_eq!(
+            backups[0].path.file_name().unwrap(),
+            format!("20240305T10{:02}00.000.md", newest).as_str()
+        );
+        assert!(backups[0].path.starts_with(
+            root.join(VAULT_DATA_DIR_NAME)
+                .join(BACKUPS_DIR_NAME)
+                .join("lore")
+        ));
+
+        assert!(has_recent_backup(
+            root,
+            &page,
+            at(newest + 1),
+            Duration::minutes(5)
+        ));
+        assert!(!has_recent_backup(
+            root,
+            &page,
+            at(newest + 10),
+            Duration::minutes(5)
+        ));
+
+        // Only backups can be restored.
+        assert!(restore_backup(root, &page, at(59)).is_err());
+    }
+}
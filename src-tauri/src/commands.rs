@@ -5,18 +5,27 @@
 
 use crate::licensing;
 use crate::licensing::License;
-use crate::models::{BrokenLink, FullPageData, PageHeader};
+use crate::models::{
+    BrokenLink, DeadLink, FolderStat, FullPageData, IndexReport, ManifestDiff, PageHeader,
+    References, SearchResult, ValidationAdvisory,
+};
 use crate::{
-    config,
+    backup::Backup,
+    config::{
+        self, FileSettings, IndexSettings, RenderSettings, ValidationSettings,
+        DEFAULT_EXCERPT_LENGTH,
+    },
     error::Result,
-    fonts, importer,
+    fonts, importer, indexer,
     models::{FileNode, RenderedPage},
     template,
+    word_count::WordCountSnapshot,
     world::World,
 };
 use chrono::{Local, NaiveDate};
-use std::path::PathBuf;
-use tauri::{command, AppHandle, State};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, Emitter, State};
 use tauri_plugin_opener::OpenerExt;
 use tracing::instrument;
 
@@ -29,6 +38,16 @@ pub fn get_vault_path(app_handle: AppHandle) -> Result<Option<String>> {
     config::get_vault_path(&app_handle)
 }
 
+/// Returns the root of the vault currently loaded in memory, which may differ from
+/// the stored vault path if initialization failed or has not happened yet.
+#[command]
+#[instrument(skip(world))]
+pub fn get_active_vault(world: State<World>) -> Option<String> {
+    world
+        .get_active_vault()
+        .map(|path| path.to_string_lossy().to_string())
+}
+
 /// Sets the vault path, saves it to config, and initializes the world state.
 /// This uses fine-grained locking internally instead of a single write lock on the world.
 #[command]
@@ -46,6 +65,222 @@ pub fn get_all_tags(world: State<World>) -> Result<Vec<(String, Vec<PageHeader>)
     world.get_all_tags()
 }
 
+/// Returns each tag with the number of pages using it, without the pages themselves.
+#[command]
+#[instrument(skip(world))]
+pub fn get_tag_counts(world: State<World>) -> Vec<(String, usize)> {
+    world.get_tag_counts()
+}
+
+/// Returns pairs of tags that appear together on pages, with their co-occurrence count.
+#[command]
+#[instrument(skip(world))]
+pub fn get_tag_cooccurrence(world: State<World>) -> Vec<(String, String, usize)> {
+    world.get_tag_cooccurrence()
+}
+
+/// Returns the tags as an OPML outline for mind-mapping tools. Tags are nested on
+/// `/`, with each tag's pages as leaf outlines.
+#[command]
+#[instrument(skip(world))]
+pub fn export_tags_opml(world: State<World>) -> Result<String> {
+    world.export_tags_opml()
+}
+
+/// Returns the number of distinct pages each page links to and is linked from,
+/// keyed by page path, as `(outbound, inbound)` pairs.
+#[command]
+#[instrument(skip(world))]
+pub fn get_link_degrees(world: State<World>) -> HashMap<PathBuf, (usize, usize)> {
+    world.get_link_degrees()
+}
+
+/// Returns the pages linked from at least `min_backlinks` other pages, paired with
+/// their backlink counts and sorted most linked first. Useful for finding hub pages.
+#[command]
+#[instrument(skip(world))]
+pub fn get_hub_pages(world: State<World>, min_backlinks: usize) -> Vec<(PageHeader, usize)> {
+    world.get_hub_pages(min_backlinks)
+}
+
+/// Returns the pages modified after `since`, an RFC 3339 timestamp, most recently
+/// modified first. Useful for reviewing what changed since a given time.
+#[command]
+#[instrument(skip(world))]
+pub fn get_pages_modified_since(world: State<World>, since: String) -> Result<Vec<PageHeader>> {
+    world.get_pages_modified_since(&since)
+}
+
+/// Returns all pages grouped by the uppercased first letter of their title, for an
+/// alphabetical index. Titles that don't start with a letter are grouped under `#`.
+#[command]
+#[instrument(skip(world))]
+pub fn get_alphabetical_index(world: State<World>) -> Vec<(char, Vec<PageHeader>)> {
+    world.get_alphabetical_index()
+}
+
+/// Returns up to `limit` pages paired with their word counts, shortest first when
+/// `ascending` is set and longest first otherwise. Useful for finding stubs and
+/// overly long pages.
+#[command]
+#[instrument(skip(world))]
+pub fn get_pages_by_length(
+    world: State<World>,
+    ascending: bool,
+    limit: usize,
+) -> Vec<(PageHeader, usize)> {
+    world.get_pages_by_length(ascending, limit)
+}
+
+/// Returns the images a page uses (embeds and `image`/`banner` fields) that don't
+/// exist on disk, as written in the page.
+#[command]
+#[instrument(skip(world))]
+pub fn get_page_missing_images(world: State<World>, path: String) -> Vec<String> {
+    world.get_page_missing_images(Path::new(&path))
+}
+
+/// Returns every page that uses images missing from disk, with those images.
+#[command]
+#[instrument(skip(world))]
+pub fn get_all_missing_images(world: State<World>) -> Vec<(PageHeader, Vec<String>)> {
+    world.get_all_missing_images()
+}
+
+/// Returns the pages without tags, each with whether it failed to parse.
+#[command]
+#[instrument(skip(world))]
+pub fn get_untagged_pages(world: State<World>) -> Vec<(PageHeader, bool)> {
+    world.get_untagged_pages()
+}
+
+/// Returns every link to a page, grouped into direct links, aliased links, section
+/// links and embeds, each with the linking page and the link as written.
+#[command]
+#[instrument(skip(world))]
+pub fn get_all_references(world: State<World>, path: String) -> References {
+    world.get_all_references(Path::new(&path))
+}
+
+/// Returns every web URL in the vault's pages, from Markdown links and bare URLs,
+/// as `(page path, url)` pairs.
+#[command]
+#[instrument(skip(world))]
+pub fn get_external_links(world: State<World>) -> Vec<(PathBuf, String)> {
+    world.get_external_links()
+}
+
+/// Requests every web URL in the vault, a few at a time, and returns the links
+/// that failed, timed out, or answered with an error status.
+#[command]
+#[instrument(skip(world))]
+pub async fn check_external_links(world: State<'_, World>) -> Result<Vec<DeadLink>> {
+    world.check_external_links().await
+}
+
+/// Returns the pages that link to themselves, which is usually a mistake.
+#[command]
+#[instrument(skip(world))]
+pub fn get_self_linking_pages(world: State<World>) -> Vec<PageHeader> {
+    world.get_self_linking_pages()
+}
+
+/// Returns a snapshot of the vault, mapping each page's vault-relative path to a
+/// hash of its content. The frontend can keep it to compare against later.
+#[command]
+#[instrument(skip(world))]
+pub fn get_manifest(world: State<World>) -> HashMap<PathBuf, String> {
+    world.get_manifest()
+}
+
+/// Compares the index against a fresh scan of the vault and reports where they
+/// disagree, e.g. after files were changed outside the app while the watcher lagged.
+/// With `repair` set, a disagreeing index is replaced by the fresh scan.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn verify_index(
+    world: State<World>,
+    app_handle: AppHandle,
+    repair: Option<bool>,
+) -> Result<IndexReport> {
+    let report = world.verify_index(repair.unwrap_or(false))?;
+    if report.repaired {
+        if let Err(e) = app_handle.emit("index-updated", ()) {
+            tracing::error!("Failed to emit index-updated event: {}", e);
+        }
+    }
+    Ok(report)
+}
+
+/// Reports the pages added, removed and modified between two manifests.
+#[command]
+#[instrument(skip(old, new))]
+pub fn diff_manifests(
+    old: HashMap<PathBuf, String>,
+    new: HashMap<PathBuf, String>,
+) -> ManifestDiff {
+    indexer::diff_manifests(&old, &new)
+}
+
+/// Returns the pages that are the only link between parts of the vault.
+#[command]
+#[instrument(skip(world))]
+pub fn get_critical_pages(world: State<World>) -> Vec<PageHeader> {
+    world.get_critical_pages()
+}
+
+/// Records today's total vault word count and returns the daily history.
+/// Called when a vault is opened; a second call on the same day replaces the first.
+#[command]
+#[instrument(skip(world))]
+pub fn record_word_count_snapshot(world: State<World>) -> Result<Vec<WordCountSnapshot>> {
+    world.record_word_count_snapshot()
+}
+
+/// Returns the vault's daily word-count history, oldest first, with the change
+/// from each previous snapshot.
+#[command]
+#[instrument(skip(world))]
+pub fn get_word_count_history(world: State<World>) -> Result<Vec<WordCountSnapshot>> {
+    world.get_word_count_history()
+}
+
+/// Groups pages into board columns by the value of a frontmatter field (e.g., `status`).
+/// Pages without the field are grouped under "Unset".
+#[command]
+#[instrument(skip(world))]
+pub fn get_board(world: State<World>, field: String) -> HashMap<String, Vec<PageHeader>> {
+    world.get_board(&field)
+}
+
+/// Finds the pages containing `query`, ignoring case, sorted by title. The string
+/// values in their frontmatter are searched too if `include_frontmatter` is set,
+/// which is slower on large vaults. In player mode, GM blocks aren't searched.
+#[command]
+#[instrument(skip(world))]
+pub fn search(
+    world: State<World>,
+    query: String,
+    include_frontmatter: Option<bool>,
+) -> Vec<SearchResult> {
+    world.search(&query, include_frontmatter.unwrap_or(false))
+}
+
+/// Finds the pages whose title or one of whose `aliases` fuzzily matches `query`,
+/// best match first, for a quick switcher. Unlike `search`, page bodies aren't read.
+#[command]
+#[instrument(skip(world))]
+pub fn fuzzy_find_pages(world: State<World>, query: String) -> Vec<PageHeader> {
+    world.fuzzy_find_pages(&query)
+}
+
+/// Finds all pages whose vault-relative path matches a glob pattern (e.g., `lore/**/*.md`).
+#[command]
+#[instrument(skip(world))]
+pub fn find_pages_by_glob(world: State<World>, pattern: String) -> Result<Vec<PageHeader>> {
+    world.find_pages_by_glob(&pattern)
+}
+
 /// Returns the hierarchical file tree structure of the vault.
 #[command]
 #[instrument(skip(world))]
@@ -60,6 +295,22 @@ pub fn get_all_directory_paths(world: State<World>) -> Result<Vec<PathBuf>> {
     world.get_all_directory_paths()
 }
 
+/// Returns storage statistics (page, image and attachment counts, and total size)
+/// for every folder in the vault, including the contents of subfolders.
+#[command]
+#[instrument(skip(world))]
+pub fn get_folder_stats(world: State<World>) -> Result<Vec<FolderStat>> {
+    world.get_folder_stats()
+}
+
+/// Returns the pages in a folder sorted by title: only its direct children, or with
+/// `recursive` set, every page in its subfolders as well.
+#[command]
+#[instrument(skip(world))]
+pub fn get_folder_pages(world: State<World>, path: String, recursive: bool) -> Vec<PageHeader> {
+    world.get_folder_pages(Path::new(&path), recursive)
+}
+
 /// Returns a list of all broken links in the vault.
 #[command]
 #[instrument(skip(world))]
@@ -67,22 +318,98 @@ pub fn get_all_broken_links(world: State<World>) -> Result<Vec<BrokenLink>> {
     world.get_all_broken_links()
 }
 
+/// Returns the unresolved link targets of a single page.
+#[command]
+#[instrument(skip(world))]
+pub fn get_page_broken_links(world: State<World>, path: String) -> Vec<String> {
+    world.get_page_broken_links(Path::new(&path))
+}
+
+/// Returns a short plain-text excerpt of a page for search results and cards.
+/// Excerpts derived from the body are capped at `max_length` characters.
+#[command]
+#[instrument(skip(world))]
+pub fn get_page_excerpt(
+    world: State<World>,
+    path: String,
+    max_length: Option<usize>,
+) -> Result<Option<String>> {
+    let max_length = max_length.unwrap_or(DEFAULT_EXCERPT_LENGTH);
+    world.get_page_excerpt(Path::new(&path), max_length)
+}
+
+/// Resolves a wikilink target to the path of an existing page, or `None` if unresolved.
+#[command]
+#[instrument(skip(world))]
+pub fn resolve_wikilink(world: State<World>, target: String) -> Option<PathBuf> {
+    world.resolve_wikilink(&target)
+}
+
+/// Resolves a page's stable id (its `uid` or `id` frontmatter field) to its path,
+/// or `None` if no page has that id.
+#[command]
+#[instrument(skip(world))]
+pub fn resolve_uid(world: State<World>, uid: String) -> Option<PathBuf> {
+    world.resolve_uid(&uid)
+}
+
+/// Returns the ids claimed by more than one page, with those pages.
+#[command]
+#[instrument(skip(world))]
+pub fn get_uid_collisions(world: State<World>) -> Vec<(String, Vec<PageHeader>)> {
+    world.get_uid_collisions()
+}
+
 // --- Page Rendering and Content ---
 
 /// Processes raw markdown content, renders it to HTML with wikilinks resolved,
-/// and returns a structured object for the frontend preview.
+/// and returns a structured object for the frontend preview. The optional `path`
+/// is the page the content belongs to, used to resolve ambiguous links. With
+/// `focus` set, only the body is rendered, without the infobox or TOC. The TOC
+/// lists headings down to level `toc_depth`, all of them by default.
 #[command]
 #[instrument(skip(content, world))]
-pub fn render_page_preview(content: String, world: State<World>) -> Result<RenderedPage> {
-    world.render_page_preview(&content)
+pub fn render_page_preview(
+    content: String,
+    path: Option<String>,
+    focus: Option<bool>,
+    toc_depth: Option<u32>,
+    world: State<World>,
+) -> Result<RenderedPage> {
+    world.render_page_preview(
+        &content,
+        path.as_deref().map(Path::new),
+        focus.unwrap_or(false),
+        toc_depth.unwrap_or(6),
+    )
+}
+
+/// Renders one section of a page to HTML, for previewing a `[[Page#Section]]` link.
+/// `section` may be the heading's text or its anchor slug. Fails if the page has
+/// no such section.
+#[command]
+#[instrument(skip(world))]
+pub fn render_section_preview(
+    path: String,
+    section: String,
+    world: State<World>,
+) -> Result<String> {
+    let path = PathBuf::from(path);
+    world.ensure_within_vault(&path)?;
+    world.render_section_preview(&path, &section)
 }
 
 /// Parses the file on disk, renders the markdown to HTML, and returns a composed
-/// object containing the raw content, and the rendered preview.
+/// object containing the raw content, and the rendered preview. The page's local
+/// link graph is only built if `include_local_graph` is set.
 #[command]
 #[instrument(skip(world))]
-pub fn build_page_view(path: String, world: State<World>) -> Result<FullPageData> {
-    world.build_page_view(&path)
+pub fn build_page_view(
+    path: String,
+    include_local_graph: Option<bool>,
+    world: State<World>,
+) -> Result<FullPageData> {
+    world.build_page_view(&path, include_local_graph.unwrap_or(false))
 }
 
 /// Renders a string of pure Markdown to a `RenderedPage` object containing only HTML.
@@ -93,6 +420,120 @@ pub fn render_markdown(content: String, world: State<World>) -> Result<RenderedP
     world.render_markdown(&content)
 }
 
+/// Returns the render settings currently in effect, or the saved ones while no
+/// vault is loaded.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn get_render_settings(world: State<World>, app_handle: AppHandle) -> Result<RenderSettings> {
+    world.get_render_settings(&app_handle)
+}
+
+/// Saves the render settings and applies them to subsequent renders.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn set_render_settings(
+    settings: RenderSettings,
+    world: State<World>,
+    app_handle: AppHandle,
+) -> Result<()> {
+    world.set_render_settings(settings, &app_handle)
+}
+
+/// Returns the index settings currently in effect.
+#[command]
+#[instrument(skip(world))]
+pub fn get_index_settings(world: State<World>) -> IndexSettings {
+    world.get_index_settings()
+}
+
+/// Saves the index settings and re-indexes the vault with them.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn set_index_settings(
+    settings: IndexSettings,
+    world: State<World>,
+    app_handle: AppHandle,
+) -> Result<()> {
+    world.set_index_settings(settings, &app_handle)
+}
+
+/// Renames a frontmatter key on every page that has it, without overwriting pages
+/// that already have the new key. Returns the number of pages changed.
+#[command]
+#[instrument(skip(world))]
+pub fn rename_frontmatter_key(
+    world: State<World>,
+    old_key: String,
+    new_key: String,
+) -> Result<usize> {
+    world.rename_frontmatter_key(&old_key, &new_key)
+}
+
+/// Rewrites the line endings of every page to LF, or CRLF if `to_lf` is false,
+/// returning the number of pages changed.
+#[command]
+#[instrument(skip(world))]
+pub fn normalize_line_endings(world: State<World>, to_lf: bool) -> Result<usize> {
+    world.normalize_line_endings(to_lf)
+}
+
+/// Adds each linked page's `title` as an alias to the bare wikilinks across the
+/// vault, returning the number of pages changed.
+#[command]
+#[instrument(skip(world))]
+pub fn prettify_links(world: State<World>) -> Result<usize> {
+    world.prettify_links()
+}
+
+/// Returns the saved file settings.
+#[command]
+#[instrument(skip(app_handle))]
+pub fn get_file_settings(app_handle: AppHandle) -> Result<FileSettings> {
+    Ok(config::load(&app_handle)?.file_settings)
+}
+
+/// Loads the file settings for a file operation. An unreadable config shouldn't
+/// block the operation; it just falls back to the defaults.
+fn file_settings(app_handle: &AppHandle) -> FileSettings {
+    config::load(app_handle)
+        .map(|config| config.file_settings)
+        .unwrap_or_default()
+}
+
+/// Saves the file settings.
+#[command]
+#[instrument(skip(app_handle))]
+pub fn set_file_settings(settings: FileSettings, app_handle: AppHandle) -> Result<()> {
+    config::set_file_settings(settings, &app_handle)
+}
+
+/// Returns the saved frontmatter validation settings.
+#[command]
+#[instrument(skip(app_handle))]
+pub fn get_validation_settings(app_handle: AppHandle) -> Result<ValidationSettings> {
+    Ok(config::load(&app_handle)?.validation_settings)
+}
+
+/// Saves the frontmatter validation settings.
+#[command]
+#[instrument(skip(app_handle))]
+pub fn set_validation_settings(settings: ValidationSettings, app_handle: AppHandle) -> Result<()> {
+    config::set_validation_settings(settings, &app_handle)
+}
+
+/// Checks all pages' frontmatter against the saved validation settings, and for
+/// duplicate keys, and returns any advisories. These are informational and never
+/// block saving.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn validate_frontmatter(
+    world: State<World>,
+    app_handle: AppHandle,
+) -> Result<Vec<ValidationAdvisory>> {
+    let settings = config::load(&app_handle)?.validation_settings;
+    Ok(world.validate_frontmatter(&settings))
+}
+
 /// Converts a relative or absolute image path to a Base64 Data URL string.
 #[command]
 #[instrument(skip(world))]
@@ -103,22 +544,35 @@ pub fn get_image_as_base64(path: String, world: State<World>) -> Result<String>
 // --- File and Folder Operations ---
 
 /// Writes content to a page on disk. The file watcher will pick up the change.
+/// The previous content is backed up first if `backup_before_changes` is enabled.
 #[command]
-#[instrument(skip(world, content))]
-pub fn write_page_content(world: State<World>, path: String, content: String) -> Result<()> {
-    world.write_page_content(&path, &content)
+#[instrument(skip(world, app_handle, content))]
+pub fn write_page_content(
+    world: State<World>,
+    app_handle: AppHandle,
+    path: String,
+    content: String,
+) -> Result<()> {
+    world.write_page_content(&path, &content, &file_settings(&app_handle))
 }
 
-/// Creates a new, empty markdown file and synchronously updates the index.
+/// Creates a new markdown file and synchronously updates the index. The page
+/// starts with the default tags configured for its folder.
 #[command]
-#[instrument(skip(world))]
+#[instrument(skip(world, app_handle))]
 pub fn create_new_file(
     world: State<World>,
+    app_handle: AppHandle,
     parent_dir: String,
     file_name: String,
     template_path: Option<String>,
 ) -> Result<PageHeader> {
-    world.create_new_file(parent_dir, file_name, template_path)
+    world.create_new_file(
+        parent_dir,
+        file_name,
+        template_path,
+        &file_settings(&app_handle),
+    )
 }
 
 /// Creates a new, empty folder.
@@ -132,18 +586,95 @@ pub fn create_new_folder(
     world.create_new_folder(parent_dir, folder_name)
 }
 
-/// Renames a file or folder on disk, updates backlinks, and returns the new path.
+/// Creates the vault's images folder if it is missing and returns its path.
 #[command]
 #[instrument(skip(world))]
-pub fn rename_path(world: State<World>, path: String, new_name: String) -> Result<PathBuf> {
-    world.rename_path(PathBuf::from(path), new_name)
+pub fn ensure_images_dir(world: State<World>) -> Result<PathBuf> {
+    world.ensure_images_dir()
+}
+
+/// Saves pasted image bytes into the vault's images folder and returns the path to
+/// embed, relative to that folder.
+#[command]
+#[instrument(skip(world, bytes))]
+pub fn save_image(world: State<World>, bytes: Vec<u8>, suggested_name: String) -> Result<PathBuf> {
+    world.save_image(&bytes, &suggested_name)
+}
+
+/// Renames a file or folder on disk, updates backlinks, and returns the new path.
+/// The page's `title` field is updated too if `sync_title_on_rename` is enabled,
+/// and the renamed pages are backed up first if `backup_before_changes` is.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn rename_path(
+    world: State<World>,
+    app_handle: AppHandle,
+    path: String,
+    new_name: String,
+) -> Result<PathBuf> {
+    world.rename_path(PathBuf::from(path), new_name, &file_settings(&app_handle))
+}
+
+/// Renames an image, keeping its extension, rewrites every embed of it and every
+/// `image` or `banner` frontmatter field using it to the new name, and returns the
+/// image's new path. The changed pages are backed up first if
+/// `backup_before_changes` is enabled.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn rename_image(
+    world: State<World>,
+    app_handle: AppHandle,
+    path: String,
+    new_name: String,
+) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    world.ensure_within_vault(&path)?;
+    world.rename_image(path, &new_name, &file_settings(&app_handle))
 }
 
 /// Deletes a file or folder from disk and updates the index.
+/// The deleted pages are backed up first if `backup_before_changes` is enabled.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn delete_path(world: State<World>, app_handle: AppHandle, path: String) -> Result<()> {
+    world.delete_path(PathBuf::from(path), &file_settings(&app_handle))
+}
+
+/// Merges the `source` page into the `target` page, redirects all links to the
+/// source to the target, and deletes the source. The target's frontmatter wins
+/// over the source's, except that tags are combined. Both pages are backed up
+/// first if `backup_before_changes` is enabled.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn merge_pages(
+    world: State<World>,
+    app_handle: AppHandle,
+    source: String,
+    target: String,
+) -> Result<()> {
+    let source = PathBuf::from(source);
+    let target = PathBuf::from(target);
+    world.ensure_within_vault(&source)?;
+    world.ensure_within_vault(&target)?;
+    world.merge_pages(source, target, &file_settings(&app_handle))
+}
+
+/// Returns the backups of a file, newest first.
 #[command]
 #[instrument(skip(world))]
-pub fn delete_path(world: State<World>, path: String) -> Result<()> {
-    world.delete_path(PathBuf::from(path))
+pub fn get_backups(world: State<World>, path: String) -> Result<Vec<Backup>> {
+    let path = PathBuf::from(path);
+    world.ensure_within_vault(&path)?;
+    world.get_backups(&path)
+}
+
+/// Restores a file to the content of one of its backups and returns the file's path.
+#[command]
+#[instrument(skip(world))]
+pub fn restore_backup(world: State<World>, backup_path: String) -> Result<PathBuf> {
+    let backup_path = PathBuf::from(backup_path);
+    world.ensure_within_vault(&backup_path)?;
+    world.restore_backup(&backup_path)
 }
 
 /// Moves a file or folder to a new directory, updates backlinks, and returns the new path.
@@ -153,6 +684,51 @@ pub fn move_path(world: State<World>, source_path: String, dest_dir: String) ->
     world.move_path(PathBuf::from(source_path), PathBuf::from(dest_dir))
 }
 
+/// Moves every page with the given tag into a folder, updates backlinks, and
+/// returns the new paths of the moved pages.
+#[command]
+#[instrument(skip(world))]
+pub fn move_tagged_to_folder(
+    world: State<World>,
+    tag: String,
+    dest_dir: String,
+) -> Result<Vec<PathBuf>> {
+    world.move_tagged_to_folder(&tag, PathBuf::from(dest_dir))
+}
+
+/// Renames and relocates a file or folder in one step, given its full new path,
+/// updates backlinks, and returns the new path.
+#[command]
+#[instrument(skip(world))]
+pub fn rename_and_move(world: State<World>, path: String, new_path: String) -> Result<PathBuf> {
+    world.rename_and_move(PathBuf::from(path), PathBuf::from(new_path))
+}
+
+/// Writes a "Backlinks" section listing every page that links to the given page
+/// into the page itself, replacing any section written earlier. The links are
+/// written in the configured `link_style`.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn materialize_backlinks(
+    path: String,
+    world: State<World>,
+    app_handle: AppHandle,
+) -> Result<()> {
+    world.materialize_backlinks(PathBuf::from(path), &file_settings(&app_handle))
+}
+
+/// Returns a wikilink to the given page, such as `[[Vex]]`, written in the
+/// configured `link_style`.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn format_page_link(
+    path: String,
+    world: State<World>,
+    app_handle: AppHandle,
+) -> Result<String> {
+    world.format_page_link(Path::new(&path), &file_settings(&app_handle))
+}
+
 /// Duplicates a page, creating a new file with a numerical suffix.
 #[command]
 #[instrument(skip(world))]
@@ -160,10 +736,40 @@ pub fn duplicate_page(path: String, world: State<World>) -> Result<PageHeader> {
     world.duplicate_page(path)
 }
 
+/// Splits a page into separate pages at each heading of the given level.
+#[command]
+#[instrument(skip(world))]
+pub fn split_page_by_heading(path: String, level: u8, world: State<World>) -> Result<Vec<PathBuf>> {
+    world.split_page_by_heading(PathBuf::from(path), level)
+}
+
+/// Exports the vault's pages as a static HTML site into the given folder,
+/// skipping anything matched by the vault's `.exportignore` file.
+#[command]
+#[instrument(skip(world))]
+pub fn export_static_site(output_dir: String, world: State<World>) -> Result<Vec<PathBuf>> {
+    world.export_static_site(PathBuf::from(output_dir))
+}
+
+/// Copies every page with the given tag, and the images they use, into a new
+/// folder as Markdown, flattening links to pages that aren't part of the export.
+#[command]
+#[instrument(skip(world, app_handle))]
+pub fn export_tagged(
+    tag: String,
+    output_dir: String,
+    world: State<World>,
+    app_handle: AppHandle,
+) -> Result<Vec<PathBuf>> {
+    world.export_tagged(&tag, PathBuf::from(output_dir), &app_handle)
+}
+
 /// Opens the specified path in the OS's default file explorer.
+/// Only paths inside the vault can be opened.
 #[command]
-#[instrument(skip(app_handle))]
-pub fn open_in_explorer(app_handle: AppHandle, path: String) -> Result<()> {
+#[instrument(skip(app_handle, world))]
+pub fn open_in_explorer(app_handle: AppHandle, path: String, world: State<World>) -> Result<()> {
+    world.ensure_within_vault(Path::new(&path))?;
     app_handle.opener().open_path(path, None::<&str>)?;
     Ok(())
 }
@@ -192,6 +798,28 @@ pub fn import_docx_from_folder(
     world.import_docx_from_folder(&app_handle, folder_path)
 }
 
+/// Imports plain-text .txt files into a vault folder as Markdown pages.
+#[command]
+#[instrument(skip(world))]
+pub fn import_text_files(
+    world: State<World>,
+    txt_paths: Vec<PathBuf>,
+    dest_dir: PathBuf,
+) -> Result<importer::TextImportResult> {
+    world.import_text_files(txt_paths, dest_dir)
+}
+
+/// Converts a pasted MediaWiki wikitext snippet to Markdown.
+#[command]
+#[instrument(skip(world, app_handle, text))]
+pub fn convert_wikitext(
+    world: State<World>,
+    app_handle: AppHandle,
+    text: String,
+) -> Result<String> {
+    world.convert_wikitext(&app_handle, &text)
+}
+
 /// Imports a MediaWiki XML dump file.
 #[command]
 #[instrument(skip(world, app_handle))]
@@ -217,6 +845,13 @@ pub async fn download_pandoc(app_handle: AppHandle) -> Result<()> {
     importer::download_pandoc(app_handle).await
 }
 
+/// Cancels an in-progress Pandoc download.
+#[command]
+#[instrument]
+pub fn cancel_pandoc_download() {
+    importer::cancel_pandoc_download();
+}
+
 // --- Licensing ---
 
 /// Retrieves the current license status from the stored license file.
@@ -299,16 +934,21 @@ pub fn read_template(path: String) -> Result<String> {
 
 /// Saves content to a template file.
 #[command]
-#[instrument(skip(app_handle, content))]
-pub fn write_template(app_handle: AppHandle, name: String, content: String) -> Result<PathBuf> {
-    template::write_template(&app_handle, &name, &content)
+#[instrument(skip(world, app_handle, content))]
+pub fn write_template(
+    world: State<World>,
+    app_handle: AppHandle,
+    name: String,
+    content: String,
+) -> Result<PathBuf> {
+    world.write_template(&app_handle, &name, &content)
 }
 
 /// Deletes a template file.
 #[command]
-#[instrument]
-pub fn delete_template(path: String) -> Result<()> {
-    template::delete_template(&PathBuf::from(path))
+#[instrument(skip(world))]
+pub fn delete_template(world: State<World>, path: String) -> Result<()> {
+    world.delete_template(&PathBuf::from(path))
 }
 
 // --- Custom Fonts ---
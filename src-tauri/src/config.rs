@@ -5,11 +5,20 @@
 //! a JSON file in the app's config directory.
 
 use crate::error::Result;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+/// The current version of the configuration file's layout. Bump this and add a
+/// step to `migrate` whenever a change needs older files to be rewritten.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// The number of previously opened vaults remembered in the config file.
+pub const MAX_RECENT_VAULTS: usize = 10;
 
 /// The debounce interval for file changes in milliseconds.
 /// This helps prevent multiple rapid updates from triggering too many re-indexes.
@@ -29,11 +38,300 @@ pub const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 100;
 /// The name of the directory within the vault where images and other media are stored.
 pub const IMAGES_DIR_NAME: &str = "images";
 
+/// The hidden directory within the vault where Chronicler keeps per-vault data,
+/// such as the word-count history. Hidden directories are never indexed.
+pub const VAULT_DATA_DIR_NAME: &str = ".chronicler";
+
+/// The number of days of word-count history kept per vault.
+pub const MAX_WORD_COUNT_HISTORY_DAYS: usize = 365;
+
+/// The number of backups kept per file. Older backups are removed as new ones are taken.
+pub const MAX_BACKUPS_PER_FILE: usize = 20;
+
+/// The minimum time in minutes between two backups of a page taken before a save.
+/// Autosaves within this window reuse the earlier backup rather than adding new ones.
+pub const MIN_BACKUP_INTERVAL_MINUTES: i64 = 10;
+
+/// The most external links checked at the same time.
+pub const MAX_CONCURRENT_LINK_CHECKS: usize = 8;
+
+/// How long to wait for an external link to respond before counting it as dead.
+pub const LINK_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most related pages suggested on a page.
+pub const MAX_RELATED_PAGES: usize = 8;
+
+/// The default `strftime`-style format for human-readable frontmatter dates (e.g., "March 5, 2024").
+pub const DEFAULT_DATE_FORMAT: &str = "%B %-d, %Y";
+
+/// The default maximum length, in characters, of an excerpt derived from a page body.
+pub const DEFAULT_EXCERPT_LENGTH: usize = 200;
+
+/// The default size limit (5MB) above which images are not embedded as Base64.
+pub const DEFAULT_MAX_EMBED_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Options that control how pages are rendered.
+///
+/// Missing fields fall back to their defaults, so older configuration files
+/// without this section keep rendering as before.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RenderSettings {
+    /// Omits a leading H1 from the body when it matches the frontmatter title.
+    pub suppress_duplicate_title: bool,
+    /// Images larger than this are served via the asset protocol instead of
+    /// being embedded as a Base64 Data URL. Note that the asset protocol is only
+    /// scoped to the vault, so oversized images outside it will not display.
+    pub max_embed_image_bytes: u64,
+    /// Shows ISO dates in date-like frontmatter fields in a human-readable format.
+    pub humanize_dates: bool,
+    /// The `strftime`-style format used when `humanize_dates` is enabled.
+    pub date_format: String,
+    /// Removes `:::gm` blocks from rendered pages entirely, so GM-only content
+    /// never reaches the page source. When off, they are shown marked as GM-only.
+    pub player_mode: bool,
+    /// Adds a `data-suggestion` attribute to broken links naming the page they most
+    /// likely meant, when one is confidently close, so the preview can offer a fix.
+    pub suggest_broken_links: bool,
+    /// Includes the alias a backlinking page uses for its link, e.g. "The Butler"
+    /// for `[[Bob|The Butler]]`, so the backlink can be shown as it reads there.
+    pub show_backlink_aliases: bool,
+    /// The name of a glossary page. Each of its headings below the top level is a
+    /// term, and the first use of each term on a rendered page links to its heading.
+    pub glossary_page: Option<String>,
+    /// The widest the page body is shown, in pixels. When set, tables and images
+    /// wider than it are wrapped so they scroll sideways instead of widening the page.
+    pub max_content_width: Option<u32>,
+    /// Prefixes each rendered heading with its number from the table of contents,
+    /// e.g. "1.2", as in technical or reference documents.
+    pub number_headings: bool,
+    /// Adds a `data-preview` attribute holding the target page's excerpt to each
+    /// resolved internal link, so it can be previewed on hover without another
+    /// request to the backend.
+    pub link_previews: bool,
+    /// Fields written by a newer version of the app, kept so that saving from this
+    /// version doesn't drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            suppress_duplicate_title: false,
+            max_embed_image_bytes: DEFAULT_MAX_EMBED_IMAGE_BYTES,
+            humanize_dates: false,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+            player_mode: false,
+            suggest_broken_links: false,
+            show_backlink_aliases: false,
+            glossary_page: None,
+            max_content_width: None,
+            number_headings: false,
+            link_previews: false,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// The frontmatter key that is always read for tags.
+pub const DEFAULT_TAG_KEY: &str = "tags";
+
+/// The frontmatter key read for a page's title by default.
+pub const DEFAULT_TITLE_KEY: &str = "title";
+
+/// Options that control how pages are indexed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct IndexSettings {
+    /// Frontmatter keys whose values are collected into a page's tags, e.g.
+    /// `keywords` or `categories` for imported content. `tags` is always read,
+    /// whether or not it is listed.
+    pub tag_keys: Vec<String>,
+    /// Extensions of other files to list in the file tree, such as `canvas` for
+    /// whiteboards. They aren't indexed, only shown so they can be opened.
+    pub extra_file_extensions: Vec<String>,
+    /// Frontmatter keys checked for a page's title, in priority order, e.g. `name`
+    /// or `label`. The first one holding text wins; pages with none of them are
+    /// titled after their file name.
+    pub title_keys: Vec<String>,
+    /// Leaves the `asset_folders` out of the file tree. Images in them still
+    /// resolve when pages embed them.
+    pub hide_asset_folders: bool,
+    /// Vault-relative folders that hold images and other assets rather than pages.
+    pub asset_folders: Vec<String>,
+    /// Fields written by a newer version of the app, kept so that saving from this
+    /// version doesn't drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for IndexSettings {
+    fn default() -> Self {
+        Self {
+            tag_keys: vec![DEFAULT_TAG_KEY.to_string()],
+            extra_file_extensions: Vec::new(),
+            title_keys: vec![DEFAULT_TITLE_KEY.to_string()],
+            hide_asset_folders: false,
+            asset_folders: vec![IMAGES_DIR_NAME.to_string()],
+            extra: Map::new(),
+        }
+    }
+}
+
+/// How generated wikilinks to a page are written, e.g. in a backlinks section or
+/// when a link is completed in the editor.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum LinkStyle {
+    /// The page's file name, e.g. `[[char_042]]`.
+    #[default]
+    Bare,
+    /// The file name with the page's title as the alias when they differ, e.g.
+    /// `[[char_042|Vex]]`.
+    Aliased,
+    /// The page's vault-relative path without the extension, e.g. `[[people/char_042]]`.
+    Path,
+}
+
+/// Options that control how file operations behave.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct FileSettings {
+    /// Updates a renamed page's `title` frontmatter field to its new name. When off,
+    /// a title that differs from the file name is left as it is.
+    pub sync_title_on_rename: bool,
+    /// Keeps a copy of a file's content under `.chronicler/backups/` before it is
+    /// overwritten, renamed or deleted.
+    pub backup_before_changes: bool,
+    /// How generated links to pages are written.
+    pub link_style: LinkStyle,
+    /// Tags given to new pages created inside particular folders.
+    pub folder_tags: Vec<FolderTags>,
+    /// Fields written by a newer version of the app, kept so that saving from this
+    /// version doesn't drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Tags that new pages created in a folder start with.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FolderTags {
+    /// The folder, relative to the vault root, e.g. `characters` or `places/cities`.
+    /// Pages created anywhere below it are covered as well.
+    pub folder: String,
+    /// The tags to add to the new page's frontmatter.
+    pub tags: Vec<String>,
+}
+
+impl FileSettings {
+    /// Returns the default tags for a page created in `folder`, which is relative
+    /// to the vault root. When several configured folders contain it, the deepest
+    /// one wins.
+    pub fn default_tags_for(&self, folder: &Path) -> &[String] {
+        self.folder_tags
+            .iter()
+            .filter_map(|entry| {
+                let configured = Path::new(entry.folder.trim_matches(['/', '\\']));
+                folder
+                    .starts_with(configured)
+                    .then(|| (configured.components().count(), &entry.tags))
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map_or(&[], |(_, tags)| tags.as_slice())
+    }
+}
+
+/// An advisory maximum length for a frontmatter field.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldLengthLimit {
+    /// The frontmatter key to check, e.g. `description`.
+    pub field: String,
+    /// The maximum number of characters before the field is flagged.
+    pub max_length: usize,
+    /// Only check pages whose `type` frontmatter field has this value (compared
+    /// case-insensitively). When unset, the limit applies to every page.
+    #[serde(default)]
+    pub page_type: Option<String>,
+}
+
+/// Which way a page's `type` field and its tags are expected to agree.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TypeTagCheck {
+    /// A page's `type` must also be one of its tags.
+    TypeNeedsTag,
+    /// A tag that is some page's `type` must also be the type of every page using it.
+    TagNeedsType,
+    /// Both of the above.
+    Both,
+}
+
+/// Options for the non-blocking frontmatter checks.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct ValidationSettings {
+    pub field_length_limits: Vec<FieldLengthLimit>,
+    /// Flags pages whose `type` field and tags disagree. Off when unset.
+    pub type_tag_check: Option<TypeTagCheck>,
+    /// Fields written by a newer version of the app, kept so that saving from this
+    /// version doesn't drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
 /// Defines the structure of the application's configuration file.
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// The layout version the file was written with. Files from before versioning
+    /// have no `version` field and are treated as version 0.
+    #[serde(default)]
+    pub version: u32,
     pub vault_path: Option<String>,
+    /// Previously opened vaults, most recent first.
+    #[serde(default)]
+    pub recent_vaults: Vec<String>,
     pub first_launch_date: Option<String>,
+    #[serde(default)]
+    pub render_settings: RenderSettings,
+    #[serde(default)]
+    pub index_settings: IndexSettings,
+    #[serde(default)]
+    pub validation_settings: ValidationSettings,
+    #[serde(default)]
+    pub file_settings: FileSettings,
+    /// Fields written by a newer version of the app, kept so that saving from this
+    /// version doesn't drop them.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+    /// Sections of a newer file that this version can't read. They are loaded as
+    /// defaults but written back unchanged when the config is saved.
+    #[serde(skip)]
+    unreadable_sections: Map<String, Value>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            vault_path: None,
+            recent_vaults: Vec::new(),
+            first_launch_date: None,
+            render_settings: RenderSettings::default(),
+            index_settings: IndexSettings::default(),
+            validation_settings: ValidationSettings::default(),
+            file_settings: FileSettings::default(),
+            extra: Map::new(),
+            unreadable_sections: Map::new(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Moves `path` to the front of the recently opened vaults.
+    fn remember_vault(&mut self, path: &str) {
+        self.recent_vaults.retain(|vault| vault != path);
+        self.recent_vaults.insert(0, path.to_string());
+        self.recent_vaults.truncate(MAX_RECENT_VAULTS);
+    }
 }
 
 /// Retrieves the path to the configuration file.
@@ -57,16 +355,121 @@ pub fn load(app_handle: &AppHandle) -> Result<AppConfig> {
         return Ok(AppConfig::default());
     }
     let content = fs::read_to_string(path)?;
-    serde_json::from_str(&content).map_err(Into::into)
+    parse(&content)
+}
+
+/// Parses the contents of a configuration file, upgrading older layouts.
+///
+/// A file written by a newer version of the app is loaded as far as it is
+/// understood, rather than failing to start: unknown fields are kept aside and
+/// sections that no longer parse fall back to their defaults. Both are written
+/// back as they were when the config is saved.
+fn parse(content: &str) -> Result<AppConfig> {
+    let mut value: Value = serde_json::from_str(content)?;
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+
+    if version > u64::from(CONFIG_VERSION) {
+        warn!(
+            "Config file version {} is newer than supported version {}; loading what is understood",
+            version, CONFIG_VERSION
+        );
+        return Ok(serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+            warn!(
+                "Falling back to defaults for unreadable config sections: {}",
+                e
+            );
+            let mut unreadable = Map::new();
+            let mut config = AppConfig {
+                version: version as u32,
+                vault_path: field_or_default(&value, "vault_path", &mut unreadable),
+                recent_vaults: field_or_default(&value, "recent_vaults", &mut unreadable),
+                first_launch_date: field_or_default(&value, "first_launch_date", &mut unreadable),
+                render_settings: field_or_default(&value, "render_settings", &mut unreadable),
+                index_settings: field_or_default(&value, "index_settings", &mut unreadable),
+                validation_settings: field_or_default(
+                    &value,
+                    "validation_settings",
+                    &mut unreadable,
+                ),
+                file_settings: field_or_default(&value, "file_settings", &mut unreadable),
+                extra: Map::new(),
+                unreadable_sections: unreadable,
+            };
+            // Every other top-level field is unknown to this version.
+            let known = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+            if let Some(fields) = value.as_object() {
+                config.extra = fields
+                    .iter()
+                    .filter(|(key, _)| known.get(key.as_str()).is_none())
+                    .map(|(key, field)| (key.clone(), field.clone()))
+                    .collect();
+            }
+            config
+        }));
+    }
+
+    migrate(&mut value, version);
+    serde_json::from_value(value).map_err(Into::into)
+}
+
+/// Deserializes a single top-level field, or returns its default if it is missing
+/// or can't be read. A field that can't be read is added to `unreadable` as it is.
+fn field_or_default<T: DeserializeOwned + Default>(
+    value: &Value,
+    key: &str,
+    unreadable: &mut Map<String, Value>,
+) -> T {
+    let Some(field) = value.get(key) else {
+        return T::default();
+    };
+    serde_json::from_value(field.clone()).unwrap_or_else(|_| {
+        unreadable.insert(key.to_string(), field.clone());
+        T::default()
+    })
+}
+
+/// Upgrades a configuration file's JSON from `from_version` to `CONFIG_VERSION`,
+/// one version at a time.
+fn migrate(value: &mut Value, from_version: u64) {
+    let Some(config) = value.as_object_mut() else {
+        return;
+    };
+
+    if from_version < 1 {
+        // Version 1 remembers every opened vault, not just the current one.
+        if !config.contains_key("recent_vaults") {
+            let recent: Vec<Value> = config
+                .get("vault_path")
+                .filter(|path| path.is_string())
+                .cloned()
+                .into_iter()
+                .collect();
+            config.insert("recent_vaults".to_string(), Value::Array(recent));
+        }
+    }
+
+    config.insert("version".to_string(), CONFIG_VERSION.into());
 }
 
 /// Saves the application configuration to disk.
 pub fn save(app_handle: &AppHandle, config: &AppConfig) -> Result<()> {
     let path = get_config_path(app_handle)?;
-    let content = serde_json::to_string_pretty(config)?;
+    let content = serde_json::to_string_pretty(&to_json(config)?)?;
     fs::write(path, content).map_err(Into::into)
 }
 
+/// Converts the configuration to the JSON written to disk, putting back any
+/// sections of a newer file that couldn't be read.
+fn to_json(config: &AppConfig) -> Result<Value> {
+    let mut value = serde_json::to_value(config)?;
+    if let Some(fields) = value.as_object_mut() {
+        for (key, section) in &config.unreadable_sections {
+            fields.insert(key.clone(), section.clone());
+        }
+    }
+    Ok(value)
+}
+
 /// Gets the vault path directly from the config file.
 pub fn get_vault_path(app_handle: &AppHandle) -> Result<Option<String>> {
     let config = load(app_handle)?;
@@ -76,6 +479,122 @@ pub fn get_vault_path(app_handle: &AppHandle) -> Result<Option<String>> {
 /// Sets and saves the vault path in the config file.
 pub fn set_vault_path(path: String, app_handle: &AppHandle) -> Result<()> {
     let mut config = load(app_handle)?;
+    config.remember_vault(&path);
     config.vault_path = Some(path);
     save(app_handle, &config)
 }
+
+/// Sets and saves the render settings in the config file.
+pub fn set_render_settings(settings: RenderSettings, app_handle: &AppHandle) -> Result<()> {
+    let mut config = load(app_handle)?;
+    config.render_settings = settings;
+    save(app_handle, &config)
+}
+
+/// Sets and saves the index settings in the config file.
+pub fn set_index_settings(settings: IndexSettings, app_handle: &AppHandle) -> Result<()> {
+    let mut config = load(app_handle)?;
+    config.index_settings = settings;
+    save(app_handle, &config)
+}
+
+/// Sets and saves the validation settings in the config file.
+pub fn set_validation_settings(settings: ValidationSettings, app_handle: &AppHandle) -> Result<()> {
+    let mut config = load(app_handle)?;
+    config.validation_settings = settings;
+    save(app_handle, &config)
+}
+
+/// Sets and saves the file settings in the config file.
+pub fn set_file_settings(settings: FileSettings, app_handle: &AppHandle) -> Result<()> {
+    let mut config = load(app_handle)?;
+    config.file_settings = settings;
+    save(app_handle, &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_migrates_unversioned_config() {
+        let old = r#"{
+            "vault_path": "/home/gm/Campaign",
+            "first_launch_date": "2024-01-01",
+            "render_settings": { "player_mode": true }
+        }"#;
+
+        let config = parse(old).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.vault_path.as_deref(), Some("/home/gm/Campaign"));
+        assert_eq!(config.recent_vaults, vec!["/home/gm/Campaign".to_string()]);
+        assert!(config.render_settings.player_mode);
+        assert_eq!(config.index_settings, IndexSettings::default());
+
+        // An old config without a vault has no recent vaults.
+        let config = parse(r#"{ "vault_path": null }"#).unwrap();
+        assert!(config.recent_vaults.is_empty());
+    }
+
+    #[test]
+    fn test_parse_newer_config_loads_defensively() {
+        let newer = r#"{
+            "version": 99,
+            "vault_path": "/home/gm/Campaign",
+            "some_future_setting": { "enabled": true },
+            "render_settings": "reshaped in a later version"
+        }"#;
+
+        let config = parse(newer).unwrap();
+        assert_eq!(config.version, 99);
+        assert_eq!(config.vault_path.as_deref(), Some("/home/gm/Campaign"));
+        assert_eq!(config.render_settings, RenderSettings::default());
+
+        // Saving writes back what this version didn't understand.
+        let saved = to_json(&config).unwrap();
+        assert_eq!(saved["version"], 99);
+        assert_eq!(saved["some_future_setting"]["enabled"], true);
+        assert_eq!(saved["render_settings"], "reshaped in a later version");
+    }
+
+    #[test]
+    fn test_newer_config_keeps_unknown_fields() {
+        let newer = r#"{
+            "version": 99,
+            "vault_path": "/home/gm/Campaign",
+            "cloud_sync": true,
+            "render_settings": { "player_mode": true, "dice_style": "fancy" }
+        }"#;
+
+        let mut config = parse(newer).unwrap();
+        assert!(config.render_settings.player_mode);
+        config.render_settings.humanize_dates = true;
+
+        let saved = to_json(&config).unwrap();
+        assert_eq!(saved["version"], 99);
+        assert_eq!(saved["cloud_sync"], true);
+        assert_eq!(saved["render_settings"]["dice_style"], "fancy");
+        assert_eq!(saved["render_settings"]["humanize_dates"], true);
+    }
+
+    #[test]
+    fn test_remember_vault_orders_and_bounds_recent_vaults() {
+        let mut config = AppConfig::default();
+        for i in 0..MAX_RECENT_VAULTS + 2 {
+            config.remember_vault(&format!("/vaults/{}", i));
+        }
+        config.remember_vault("/vaults/5");
+
+        assert_eq!(config.recent_vaults.len(), MAX_RECENT_VAULTS);
+        assert_eq!(config.recent_vaults[0], "/vaults/5");
+        assert_eq!(config.recent_vaults[1], "/vaults/11");
+        assert_eq!(
+            config
+                .recent_vaults
+                .iter()
+                .filter(|v| *v == "/vaults/5")
+                .count(),
+            1
+        );
+    }
+}
@@ -63,6 +63,9 @@ pub enum ChroniclerError {
     #[error("Invalid path provided: {0:?}")]
     InvalidPath(PathBuf),
 
+    #[error("Path is outside the vault: {0:?}")]
+    PathOutsideVault(PathBuf),
+
     #[error("Network request failed: {0}")]
     Network(#[from] reqwest::Error),
 
@@ -72,6 +75,9 @@ pub enum ChroniclerError {
     #[error("Could not find the pandoc executable in the expected directory.")]
     PandocNotFound,
 
+    #[error("Download was cancelled.")]
+    DownloadCancelled,
+
     #[error("XML parse error: {0}")]
     XmlParse(#[from] quick_xml::Error),
 
@@ -83,6 +89,9 @@ pub enum ChroniclerError {
 
     #[error("License is invalid: {0}")]
     LicenseInvalid(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
 }
 
 // We need to implement Serialize for the error type to be able to return
@@ -0,0 +1,592 @@
+//! Static site and subset exporter.
+//!
+//! Renders the pages of a vault to standalone HTML files so they can be published
+//! or shared outside the application. Pages can be kept out of an export with an
+//! `.exportignore` file at the vault root, which is independent of what the app
+//! itself indexes.
+//!
+//! A subset of pages can also be copied out as Markdown, together with the images
+//! they use, to share part of a vault as a smaller vault of its own.
+
+use crate::{
+    error::{ChroniclerError, Result},
+    indexer::Indexer,
+    parser,
+    renderer::{RenderContext, Renderer},
+    utils::resolve_image_path,
+    wikilink::WIKILINK_RE,
+};
+use glob::{MatchOptions, Pattern};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+use tracing::{instrument, warn};
+
+/// The name of the file at the vault root listing paths to leave out of exports.
+pub const EXPORT_IGNORE_FILE: &str = ".exportignore";
+
+/// Matches the internal links produced by the renderer, both resolved and broken.
+/// Any further attributes (such as a broken link's `data-suggestion`) are skipped.
+/// Captures: 1: href, 2: `path` or `target`, 3: the attribute's value, 4: link text
+static INTERNAL_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"<a href="([^"]*)" class="internal-link(?: broken)?" data-(path|target)="([^"]*)"[^>]*>(.*?)</a>"#,
+    )
+    .unwrap()
+});
+
+/// The characters percent-encoded in the path segments of exported asset links.
+const SEGMENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Matches the `src` of images and media that the renderer points at the app's
+/// asset protocol, which only works inside the app.
+/// Captures: 1: the percent-encoded absolute path
+static ASSET_SRC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"src="(?:asset://localhost|http://asset\.localhost)/([^"]+)""#).unwrap()
+});
+
+/// The name of the event emitted to the frontend while a subset export runs.
+pub const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// The payload of an `export-progress` event.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct ExportProgress {
+    /// The number of pages copied so far.
+    pub exported: usize,
+    /// The total number of pages being exported.
+    pub total: usize,
+}
+
+/// A single rule from an `.exportignore` file.
+#[derive(Debug)]
+struct IgnoreRule {
+    pattern: Pattern,
+    /// The rule only matches folders (it was written with a trailing `/`).
+    dir_only: bool,
+    /// The rule is matched against the vault-relative path rather than a single
+    /// file or folder name (it contains a `/`).
+    anchored: bool,
+}
+
+/// A set of `.gitignore`-style patterns describing which pages to leave out of an export.
+///
+/// Supported syntax is a practical subset of `.gitignore`:
+/// * Blank lines and lines starting with `#` are ignored.
+/// * A pattern without a `/` matches any file or folder with that name, e.g. `_drafts`.
+/// * A pattern containing a `/` matches from the vault root, e.g. `lore/secrets/*.md`.
+/// * A trailing `/` restricts the pattern to folders, e.g. `_drafts/`.
+///
+/// A page inside an ignored folder is ignored too.
+#[derive(Debug, Default)]
+pub struct ExportIgnore {
+    rules: Vec<IgnoreRule>,
+}
+
+impl ExportIgnore {
+    /// Loads the `.exportignore` file from the vault root. A missing file yields an
+    /// empty set that ignores nothing.
+    pub fn load(vault_root: &Path) -> Result<Self> {
+        let path = vault_root.join(EXPORT_IGNORE_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the contents of an `.exportignore` file.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let dir_only = line.ends_with('/');
+            let line = line.trim_end_matches('/');
+            let anchored = line.contains('/');
+            let line = line.trim_start_matches('/');
+            if line.is_empty() {
+                continue;
+            }
+            rules.push(IgnoreRule {
+                pattern: Pattern::new(line)?,
+                dir_only,
+                anchored,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Returns `true` if a vault-relative path is excluded from exports.
+    pub fn is_ignored(&self, relative: &Path) -> bool {
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        // Check the path itself and every folder containing it.
+        (1..=components.len()).any(|depth| {
+            let is_dir = depth < components.len();
+            let prefix = components[..depth].join("/");
+            let name = &components[depth - 1];
+            self.rules.iter().any(|rule| {
+                if rule.dir_only && !is_dir {
+                    return false;
+                }
+                let candidate = if rule.anchored { &prefix } else { name };
+                rule.pattern.matches_with(candidate, options)
+            })
+        })
+    }
+}
+
+/// Exports the given pages as HTML files into `output_dir`, mirroring the vault's
+/// folder structure.
+///
+/// Pages matched by the vault's `.exportignore` file are skipped. Links to skipped
+/// or missing pages are reduced to their display text so the exported site has no
+/// dangling links. The vault images and media the pages show are copied alongside
+/// them and linked with relative paths.
+///
+/// # Returns
+/// The paths of the HTML files that were written, followed by the copied assets.
+#[instrument(skip(renderer, pages))]
+pub fn export_static_site(
+    renderer: &Renderer,
+    vault_root: &Path,
+    pages: &[PathBuf],
+    output_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    if output_dir.starts_with(vault_root) {
+        return Err(ChroniclerError::InvalidArgument(
+            "the export folder must be outside the vault".to_string(),
+        ));
+    }
+
+    let ignore = ExportIgnore::load(vault_root)?;
+
+    // Work out every page that will be exported first, so links to pages left out
+    // of the export can be removed.
+    let mut exported: Vec<(&PathBuf, PathBuf)> = pages
+        .iter()
+        .filter_map(|page| {
+            let relative = page.strip_prefix(vault_root).ok()?;
+            (!ignore.is_ignored(relative)).then(|| (page, relative.with_extension("html")))
+        })
+        .collect();
+    exported.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let targets: HashSet<String> = exported
+        .iter()
+        .map(|(page, _)| path_to_web_str(page))
+        .collect();
+
+    let mut written = Vec::with_capacity(exported.len());
+    let mut assets = BTreeSet::new();
+    for (page, relative) in &exported {
+        let content = match fs::read_to_string(page) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Skipping {:?} during export: {}", page, e);
+                continue;
+            }
+        };
+        let rendered = renderer.render_page_with_context(
+            &content,
+            RenderContext {
+                source: Some(page.as_path()),
+                export: true,
+                ..Default::default()
+            },
+        )?;
+
+        let title = renderer.page_title(page);
+        let body = format!("{}{}", rendered.html_before_toc, rendered.html_after_toc);
+        let body = finalize_internal_links(&body, &targets);
+        let body = localize_asset_srcs(&body, vault_root, relative, &mut assets);
+
+        let output_path = output_dir.join(relative);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output_path, wrap_in_document(&title, &body))?;
+        written.push(output_path);
+    }
+
+    for asset in assets {
+        let Ok(relative) = asset.strip_prefix(vault_root) else {
+            continue;
+        };
+        if !asset.is_file() {
+            continue;
+        }
+        let output_path = output_dir.join(relative);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&asset, &output_path)?;
+        written.push(output_path);
+    }
+
+    Ok(written)
+}
+
+/// Copies a subset of pages into `output_dir` as Markdown, mirroring the vault's
+/// folder structure, along with every vault image they reference.
+///
+/// Links between exported pages are kept as they are, since page names don't
+/// change. Links that resolve to any other page, or to no page at all, are reduced
+/// to their display text. Images outside the vault are not copied.
+///
+/// `on_progress` is called after each page is written.
+///
+/// # Returns
+/// The paths of the pages and images that were written.
+#[instrument(skip(indexer, pages, on_progress))]
+pub fn export_pages(
+    indexer: &Indexer,
+    vault_root: &Path,
+    pages: &[PathBuf],
+    output_dir: &Path,
+    mut on_progress: impl FnMut(ExportProgress),
+) -> Result<Vec<PathBuf>> {
+    if output_dir.starts_with(vault_root) {
+        return Err(ChroniclerError::InvalidArgument(
+            "the export folder must be outside the vault".to_string(),
+        ));
+    }
+
+    let exported: HashSet<&Path> = pages.iter().map(PathBuf::as_path).collect();
+    let mut pages: Vec<&PathBuf> = pages.iter().collect();
+    pages.sort();
+
+    let mut written = Vec::with_capacity(pages.len());
+    let mut images = BTreeSet::new();
+    for (index, page) in pages.iter().enumerate() {
+        let relative = page
+            .strip_prefix(vault_root)
+            .map_err(|_| ChroniclerError::PathOutsideVault(page.to_path_buf()))?;
+        match fs::read_to_string(page) {
+            Ok(content) => {
+                images.extend(referenced_images(vault_root, page, &content));
+                let content = flatten_excluded_links(indexer, page, &content, &exported);
+
+                let output_path = output_dir.join(relative);
+                if let Some(parent) = output_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&output_path, content)?;
+                written.push(output_path);
+            }
+            Err(e) => warn!("Skipping {:?} during export: {}", page, e),
+        }
+        on_progress(ExportProgress {
+            exported: index + 1,
+            total: pages.len(),
+        });
+    }
+
+    for image in images {
+        let Ok(relative) = image.strip_prefix(vault_root) else {
+            warn!("Not exporting image outside the vault: {:?}", image);
+            continue;
+        };
+        if !image.is_file() {
+            continue;
+        }
+        let output_path = output_dir.join(relative);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&image, &output_path)?;
+        written.push(output_path);
+    }
+
+    Ok(written)
+}
+
+/// Replaces wikilinks that don't resolve to an exported page with their display text.
+/// Image embeds are left alone.
+fn flatten_excluded_links(
+    indexer: &Indexer,
+    source: &Path,
+    content: &str,
+    exported: &HashSet<&Path>,
+) -> String {
+    WIKILINK_RE
+        .replace_all(content, |caps: &Captures| {
+            let whole = caps.get(0).unwrap();
+            if content[..whole.start()].ends_with('!') {
+                return whole.as_str().to_string();
+            }
+            let target = caps[1].replace('\\', "");
+            let target = target.trim();
+            match indexer.resolve_name(target, Some(source)) {
+                Some(path) if exported.contains(path.as_path()) => whole.as_str().to_string(),
+                _ => caps
+                    .get(3)
+                    .map_or(target, |alias| alias.as_str().trim())
+                    .to_string(),
+            }
+        })
+        .to_string()
+}
+
+/// Collects the absolute paths of the local images a page uses, from its
+/// `image`/`banner` frontmatter fields and its image embeds.
+fn referenced_images(vault_root: &Path, page: &Path, content: &str) -> Vec<PathBuf> {
+    let (frontmatter_str, body) = parser::extract_frontmatter(content);
+    let frontmatter = parser::parse_frontmatter(frontmatter_str, page).unwrap_or_default();
+
+    parser::extract_image_refs(&frontmatter, body)
+        .iter()
+        .map(|src| resolve_image_path(vault_root, src))
+        .collect()
+}
+
+/// Turns the renderer's internal links into plain links for the exported site.
+/// Links to pages that are not part of the export keep only their text.
+fn finalize_internal_links(html: &str, targets: &HashSet<String>) -> String {
+    INTERNAL_LINK_RE
+        .replace_all(html, |caps: &Captures| {
+            let text = &caps[4];
+            if &caps[2] == "path" && targets.contains(&caps[3]) {
+                format!(r#"<a href="{}">{}</a>"#, &caps[1], text)
+            } else {
+                text.to_string()
+            }
+        })
+        .to_string()
+}
+
+/// Points asset protocol `src`s at copies of the files in the exported site,
+/// relative to the exported page at `page_relative`, and adds the files to
+/// `assets` so they can be copied. Files outside the vault are left as they are.
+fn localize_asset_srcs(
+    html: &str,
+    vault_root: &Path,
+    page_relative: &Path,
+    assets: &mut BTreeSet<PathBuf>,
+) -> String {
+    let depth = page_relative.components().count().saturating_sub(1);
+    ASSET_SRC_RE
+        .replace_all(html, |caps: &Captures| {
+            let path = PathBuf::from(percent_decode_str(&caps[1]).decode_utf8_lossy().as_ref());
+            let Ok(relative) = path.strip_prefix(vault_root) else {
+                return caps[0].to_string();
+            };
+            let segments: Vec<String> = relative
+                .components()
+                .map(|c| {
+                    utf8_percent_encode(&c.as_os_str().to_string_lossy(), SEGMENT_ENCODE_SET)
+                        .to_string()
+                })
+                .collect();
+            let src = format!("{}{}", "../".repeat(depth), segments.join("/"));
+            assets.insert(path.clone());
+            format!("src=\"{}\"", src)
+        })
+        .to_string()
+}
+
+/// Wraps a rendered page body in a minimal standalone HTML document.
+fn wrap_in_document(title: &str, body: &str) -> String {
+    let title = html_escape::encode_text(title);
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<article>\n<h1>{title}</h1>\n{body}</article>\n</body>\n</html>\n"
+    )
+}
+
+/// Converts a path to a string with forward slashes, matching the renderer's links.
+fn path_to_web_str(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IMAGES_DIR_NAME;
+    use crate::indexer::Indexer;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_export_pages_flattens_links_to_excluded_pages() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        fs::create_dir_all(root.join(IMAGES_DIR_NAME).join("maps")).unwrap();
+        fs::create_dir(root.join("Places")).unwrap();
+        fs::write(
+            root.join(IMAGES_DIR_NAME).join("maps").join("city.png"),
+            [0u8; 4],
+        )
+        .unwrap();
+        fs::write(root.join(IMAGES_DIR_NAME).join("unused.png"), [0u8; 4]).unwrap();
+
+        let city_path = root.join("Places").join("City.md");
+        let hero_path = root.join("Hero.md");
+        fs::write(
+            &city_path,
+            "---\ntags: [public]\nimage: maps/city.png\n---\nHome of [[Hero]] and [[Villain|a dark secret]].",
+        )
+        .unwrap();
+        fs::write(
+            &hero_path,
+            "---\ntags: [public]\n---\nLives in [[City]], hunts [[Villain]]. ![[maps/city.png]]",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Villain.md"),
+            "---\ntags: [private]\n---\nSecret.",
+        )
+        .unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let pages: Vec<PathBuf> = indexer.tags["public"].iter().cloned().collect();
+
+        let output = tempdir().unwrap();
+        let mut progress = Vec::new();
+        let written =
+            export_pages(&indexer, root, &pages, output.path(), |p| progress.push(p)).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                output.path().join("Hero.md"),
+                output.path().join("Places").join("City.md"),
+                output
+                    .path()
+                    .join(IMAGES_DIR_NAME)
+                    .join("maps")
+                    .join("city.png"),
+            ]
+        );
+        assert!(!output.path().join("Villain.md").exists());
+        assert!(!output
+            .path()
+            .join(IMAGES_DIR_NAME)
+            .join("unused.png")
+            .exists());
+        assert_eq!(
+            progress.last(),
+            Some(&ExportProgress {
+                exported: 2,
+                total: 2
+            })
+        );
+
+        let city = fs::read_to_string(output.path().join("Places").join("City.md")).unwrap();
+        assert!(city.ends_with("Home of [[Hero]] and a dark secret."));
+        let hero = fs::read_to_string(output.path().join("Hero.md")).unwrap();
+        assert!(hero.ends_with("Lives in [[City]], hunts Villain. ![[maps/city.png]]"));
+    }
+
+    #[test]
+    fn test_finalize_internal_links_drops_broken_link_suggestions() {
+        let html = r##"<a href="#" class="internal-link broken" data-target="Publik" data-suggestion="Public">Publik</a>"##;
+        assert_eq!(finalize_internal_links(html, &HashSet::new()), "Publik");
+    }
+
+    #[test]
+    fn test_export_ignore_patterns() {
+        let ignore =
+            ExportIgnore::parse("# comment\n_drafts/\nlore/secrets/*.md\n*.private.md\n").unwrap();
+
+        assert!(ignore.is_ignored(Path::new("_drafts/Idea.md")));
+        assert!(ignore.is_ignored(Path::new("lore/_drafts/Idea.md")));
+        assert!(ignore.is_ignored(Path::new("lore/secrets/Plot.md")));
+        assert!(ignore.is_ignored(Path::new("Notes.private.md")));
+        assert!(!ignore.is_ignored(Path::new("_drafts.md")));
+        assert!(!ignore.is_ignored(Path::new("secrets/Plot.md")));
+        assert!(!ignore.is_ignored(Path::new("lore/secrets/deep/Plot.md")));
+    }
+
+    #[test]
+    fn test_export_skips_ignored_pages_but_keeps_them_indexed() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        fs::create_dir(root.join("_drafts")).unwrap();
+        let public_path = root.join("Public.md");
+        let draft_path = root.join("_drafts").join("Draft.md");
+        fs::write(&public_path, "See [[Draft]] and [[Other]].").unwrap();
+        fs::write(&draft_path, "Unfinished.").unwrap();
+        fs::write(
+            root.join("Other.md"),
+            "---\ntitle: Fish & Chips\n---\nBack to [[Public]].",
+        )
+        .unwrap();
+        fs::write(root.join(EXPORT_IGNORE_FILE), "_drafts/\n").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let pages: Vec<PathBuf> = indexer.pages.keys().cloned().collect();
+        let indexer = Arc::new(RwLock::new(indexer));
+        let renderer = Renderer::new(indexer.clone(), root.to_path_buf());
+
+        let output = tempdir().unwrap();
+        let written = export_static_site(&renderer, root, &pages, output.path()).unwrap();
+
+        assert_eq!(
+            written,
+            vec![
+                output.path().join("Other.html"),
+                output.path().join("Public.html")
+            ]
+        );
+        assert!(!output.path().join("_drafts").exists());
+
+        let public_html = fs::read_to_string(output.path().join("Public.html")).unwrap();
+        assert!(public_html.contains("<title>Public</title>"));
+        assert!(public_html.contains(r#"<a href="Other.html">Other</a>"#));
+        assert!(public_html.contains("See Draft and"));
+        let other_html = fs::read_to_string(output.path().join("Other.html")).unwrap();
+        assert!(other_html.contains("<title>Fish &amp; Chips</title>"));
+
+        // The draft is excluded from the export only, not from the app's index.
+        assert!(indexer.read().pages.contains_key(&draft_path));
+    }
+
+    #[test]
+    fn test_export_copies_images_and_media() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        fs::create_dir_all(root.join(IMAGES_DIR_NAME)).unwrap();
+        fs::create_dir(root.join("lore")).unwrap();
+        fs::write(root.join(IMAGES_DIR_NAME).join("city map.png"), [0u8; 4]).unwrap();
+        fs::write(root.join(IMAGES_DIR_NAME).join("theme.mp3"), [0u8; 4]).unwrap();
+        let page = root.join("lore").join("City.md");
+        fs::write(&page, "![[city map.png]]\n\n![[theme.mp3]]").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let output = tempdir().unwrap();
+        let written = export_static_site(&renderer, root, &[page], output.path()).unwrap();
+
+        let images = output.path().join(IMAGES_DIR_NAME);
+        assert!(written.contains(&images.join("city map.png")));
+        assert!(images.join("theme.mp3").is_file());
+        let html = fs::read_to_string(output.path().join("lore").join("City.html")).unwrap();
+        assert!(html.contains("src=\"../images/city%20map.png\""));
+        assert!(html.contains("src=\"../images/theme.mp3\""));
+        assert!(!html.contains("asset"));
+    }
+}
@@ -1,16 +1,55 @@
-//! Handles importing documents by converting them with Pandoc.
+//! Handles importing documents, either by converting them with Pandoc or, for
+//! plain-text files, by copying them in directly.
 
 use crate::config::IMAGES_DIR_NAME;
 use crate::error::{ChroniclerError, Result};
+use crate::writer::atomic_write;
+use serde::Serialize;
 use std::env::consts::{ARCH, OS};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use tauri::{AppHandle, Manager};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
 use tracing::{error, info, instrument, warn};
 use walkdir::WalkDir;
 
 const PANDOC_VERSION: &str = "3.7.0.2";
 
+/// The name of the event emitted to the frontend while Pandoc is downloading.
+const PANDOC_PROGRESS_EVENT: &str = "pandoc-download-progress";
+
+/// A shared flag that is checked between chunks of the Pandoc download.
+/// Setting it to `true` aborts the download at the next opportunity.
+static PANDOC_DOWNLOAD_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// The payload of a `pandoc-download-progress` event.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DownloadProgress {
+    /// The number of bytes received so far.
+    pub downloaded: u64,
+    /// The total size of the download, if the server sent a Content-Length header.
+    pub total: Option<u64>,
+    /// The completion percentage (0-100), if the total size is known.
+    pub percentage: Option<f64>,
+}
+
+impl DownloadProgress {
+    /// Calculates the progress for a given number of downloaded bytes.
+    ///
+    /// The percentage is clamped to 100 in case the server under-reports the
+    /// content length, and is omitted entirely when the total is unknown or zero.
+    pub fn new(downloaded: u64, total: Option<u64>) -> Self {
+        let percentage = total
+            .filter(|&t| t > 0)
+            .map(|t| ((downloaded as f64 / t as f64) * 100.0).min(100.0));
+        Self {
+            downloaded,
+            total,
+            percentage,
+        }
+    }
+}
+
 /// Returns the platform-specific directory where Pandoc should be.
 fn get_pandoc_dir(app_handle: &AppHandle) -> Result<PathBuf> {
     let config_dir = app_handle.path().app_config_dir()?;
@@ -46,7 +85,16 @@ pub fn is_pandoc_installed(app_handle: &AppHandle) -> Result<bool> {
     }
 }
 
+/// Requests cancellation of an in-progress Pandoc download.
+pub fn cancel_pandoc_download() {
+    info!("Pandoc download cancellation requested.");
+    PANDOC_DOWNLOAD_CANCELLED.store(true, Ordering::SeqCst);
+}
+
 /// Downloads and extracts Pandoc for the correct architecture.
+///
+/// The archive is streamed chunk by chunk so that `pandoc-download-progress`
+/// events can be emitted and the download can be cancelled part-way through.
 #[instrument(skip(app_handle))]
 pub async fn download_pandoc(app_handle: AppHandle) -> Result<()> {
     let target_triple = match (OS, ARCH) {
@@ -67,43 +115,82 @@ pub async fn download_pandoc(app_handle: AppHandle) -> Result<()> {
         PANDOC_VERSION, target_triple, extension
     );
 
-    info!("Downloading Pandoc from: {}", url);
+    // Reset the flag so a previous cancellation doesn't abort this download.
+    PANDOC_DOWNLOAD_CANCELLED.store(false, Ordering::SeqCst);
 
-    let response = reqwest::get(&url).await?.bytes().await?;
     let target_dir = get_pandoc_dir(&app_handle)?;
-
     if target_dir.exists() {
         std::fs::remove_dir_all(&target_dir)?;
     }
     std::fs::create_dir_all(&target_dir)?;
 
-    info!("Extracting Pandoc to: {:?}", target_dir);
-
-    let extraction_result = if extension == "zip" {
-        zip_extract::extract(std::io::Cursor::new(response), &target_dir, true)
-            .map_err(|e| ChroniclerError::ArchiveExtractionFailed(e.to_string()))
-    } else {
-        let tar = flate2::read::GzDecoder::new(std::io::Cursor::new(response));
-        let mut archive = tar::Archive::new(tar);
-        archive
-            .unpack(&target_dir)
-            .map_err(|e| ChroniclerError::ArchiveExtractionFailed(e.to_string()))
+    let result = match stream_download(&app_handle, &url).await {
+        Ok(archive) => extract_pandoc_archive(archive, extension, &target_dir),
+        Err(e) => Err(e),
     };
 
-    match extraction_result {
+    match result {
         Ok(_) => {
             info!("Pandoc downloaded and extracted successfully.");
             Ok(())
         }
         Err(e) => {
-            error!("Failed to extract Pandoc archive: {}", e);
-            // Clean up partial extraction if it fails
+            error!("Failed to download or extract Pandoc: {}", e);
+            // Clean up the partial download or extraction.
             let _ = std::fs::remove_dir_all(&target_dir);
             Err(e)
         }
     }
 }
 
+/// Streams a file into memory, emitting progress events and honoring cancellation.
+async fn stream_download(app_handle: &AppHandle, url: &str) -> Result<Vec<u8>> {
+    info!("Downloading Pandoc from: {}", url);
+
+    let mut response = reqwest::get(url).await?.error_for_status()?;
+    let total = response.content_length();
+    let mut archive = Vec::with_capacity(total.unwrap_or(0) as usize);
+    // Only emit when the whole-number percentage changes to avoid flooding the frontend.
+    let mut last_reported: Option<u64> = None;
+
+    while let Some(chunk) = response.chunk().await? {
+        if PANDOC_DOWNLOAD_CANCELLED.load(Ordering::SeqCst) {
+            warn!("Pandoc download cancelled by user.");
+            return Err(ChroniclerError::DownloadCancelled);
+        }
+        archive.extend_from_slice(&chunk);
+
+        let progress = DownloadProgress::new(archive.len() as u64, total);
+        let bucket = progress
+            .percentage
+            .map_or(archive.len() as u64 / (1024 * 1024), |p| p as u64);
+        if last_reported != Some(bucket) {
+            last_reported = Some(bucket);
+            if let Err(e) = app_handle.emit(PANDOC_PROGRESS_EVENT, progress) {
+                warn!("Failed to emit Pandoc download progress: {}", e);
+            }
+        }
+    }
+
+    Ok(archive)
+}
+
+/// Extracts a downloaded Pandoc archive into the target directory.
+fn extract_pandoc_archive(archive: Vec<u8>, extension: &str, target_dir: &Path) -> Result<()> {
+    info!("Extracting Pandoc to: {:?}", target_dir);
+
+    if extension == "zip" {
+        zip_extract::extract(std::io::Cursor::new(archive), target_dir, true)
+            .map_err(|e| ChroniclerError::ArchiveExtractionFailed(e.to_string()))
+    } else {
+        let tar = flate2::read::GzDecoder::new(std::io::Cursor::new(archive));
+        let mut archive = tar::Archive::new(tar);
+        archive
+            .unpack(target_dir)
+            .map_err(|e| ChroniclerError::ArchiveExtractionFailed(e.to_string()))
+    }
+}
+
 /// Moves the temporarily extracted media folder to its final destination in the images directory.
 fn move_media_directory(output_dir: &Path, file_stem: &str) -> Result<()> {
     let temp_media_abs_path = output_dir.join(file_stem);
@@ -230,3 +317,131 @@ pub fn convert_docx_in_folder(
     info!("Found {} .docx files to import.", docx_paths.len());
     convert_docx_to_markdown(app_handle, docx_paths, output_dir)
 }
+
+/// A file that was left out of a plain-text import, and why.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SkippedImport {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// The outcome of importing plain-text files.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct TextImportResult {
+    /// The Markdown pages that were created.
+    pub imported: Vec<PathBuf>,
+    /// The files that could not be imported.
+    pub skipped: Vec<SkippedImport>,
+}
+
+/// Imports plain-text (`.txt`) files as Markdown pages in `output_dir`.
+///
+/// Each file's content is kept unchanged as the page body, below a frontmatter
+/// `title` taken from the file name. No external tools are needed. Files that are
+/// not valid UTF-8, or whose page name is already taken, are skipped and reported
+/// rather than imported with mangled or overwritten content.
+#[instrument(skip(txt_paths))]
+pub fn import_text_files(txt_paths: &[PathBuf], output_dir: &Path) -> Result<TextImportResult> {
+    let mut result = TextImportResult::default();
+
+    for txt_path in txt_paths {
+        let skip = |reason: &str| SkippedImport {
+            path: txt_path.clone(),
+            reason: reason.to_string(),
+        };
+
+        let title = txt_path
+            .file_stem()
+            .ok_or_else(|| ChroniclerError::InvalidPath(txt_path.clone()))?
+            .to_string_lossy()
+            .to_string();
+        let output_path = output_dir.join(format!("{}.md", title));
+        if output_path.exists() {
+            result
+                .skipped
+                .push(skip("a page with this name already exists"));
+            continue;
+        }
+
+        let bytes = std::fs::read(txt_path)?;
+        let Ok(text) = String::from_utf8(bytes) else {
+            warn!("Skipping {:?}: not valid UTF-8", txt_path);
+            result
+                .skipped
+                .push(skip("the file is not UTF-8 encoded text"));
+            continue;
+        };
+        let body = text.strip_prefix('\u{feff}').unwrap_or(&text);
+
+        // Serialize the title so file names with YAML-significant characters stay valid.
+        let frontmatter = serde_yaml::to_string(&serde_json::json!({ "title": title }))?;
+        let content = format!("---\n{}---\n\n{}", frontmatter, body);
+
+        atomic_write(&output_path, &content)?;
+        result.imported.push(output_path);
+    }
+
+    info!(
+        "Imported {} text files, skipped {}.",
+        result.imported.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_progress_with_known_length() {
+        let progress = DownloadProgress::new(0, Some(200));
+        assert_eq!(progress.percentage, Some(0.0));
+
+        let progress = DownloadProgress::new(50, Some(200));
+        assert_eq!(progress.downloaded, 50);
+        assert_eq!(progress.total, Some(200));
+        assert_eq!(progress.percentage, Some(25.0));
+
+        let progress = DownloadProgress::new(200, Some(200));
+        assert_eq!(progress.percentage, Some(100.0));
+    }
+
+    #[test]
+    fn test_download_progress_is_clamped_when_length_is_under_reported() {
+        let progress = DownloadProgress::new(300, Some(200));
+        assert_eq!(progress.percentage, Some(100.0));
+    }
+
+    #[test]
+    fn test_download_progress_without_length() {
+        assert_eq!(DownloadProgress::new(1024, None).percentage, None);
+        // A zero Content-Length must not cause a division by zero.
+        assert_eq!(DownloadProgress::new(1024, Some(0)).percentage, None);
+    }
+
+    #[test]
+    fn test_import_text_files() {
+        let source = tempfile::tempdir().unwrap();
+        let vault = tempfile::tempdir().unwrap();
+
+        let notes = source.path().join("[Draft] Old Notes.txt");
+        std::fs::write(&notes, "\u{feff}First line.\nSee [[Other]].\n").unwrap();
+        let binary = source.path().join("Garbled.txt");
+        std::fs::write(&binary, [0xff, 0xfe, 0x00, 0x41]).unwrap();
+
+        let result = import_text_files(&[notes, binary.clone()], vault.path()).unwrap();
+
+        let page_path = vault.path().join("[Draft] Old Notes.md");
+        assert_eq!(result.imported, vec![page_path.clone()]);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(result.skipped[0].path, binary);
+
+        let page = crate::parser::parse_file(&page_path, &crate::config::IndexSettings::default())
+            .unwrap();
+        assert_eq!(page.title, "[Draft] Old Notes");
+        assert_eq!(page.links.len(), 1);
+        let content = std::fs::read_to_string(&page_path).unwrap();
+        assert!(content.ends_with("\n\nFirst line.\nSee [[Other]].\n"));
+    }
+}
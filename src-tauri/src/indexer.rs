@@ -4,22 +4,51 @@
 //! The indexer processes individual file events but doesn't manage its own subscriptions.
 
 use crate::{
+    config::IndexSettings,
     error::{ChroniclerError, Result},
     events::FileEvent,
-    models::{BrokenLink, FileNode, FileType, Link, Page, PageHeader},
+    models::{
+        BrokenLink, FileNode, FileType, FolderStat, GraphData, GraphEdge, IndexReport, Link,
+        ManifestDiff, Page, PageHeader, Reference, References, SearchResult,
+    },
     parser,
-    utils::{file_stem_string, is_image_file, is_markdown_file},
+    utils::{
+        content_hash, edit_distance, file_stem_string, fuzzy_score, is_image_file,
+        is_markdown_file, resolve_image_path,
+    },
 };
+use glob::{MatchOptions, Pattern};
 use natord::compare as nat_compare;
 use std::{
-    collections::{HashMap, HashSet},
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
     fs, mem,
     path::{Path, PathBuf},
-    time::Instant,
+    time::{Instant, SystemTime},
 };
 use tracing::{info, instrument, warn};
 use walkdir::WalkDir;
 
+/// The board column for pages that have no value for the grouping field.
+pub const UNSET_BOARD_COLUMN: &str = "Unset";
+
+/// The alphabetical index group for titles that don't start with a letter.
+pub const NON_ALPHABETIC_INDEX_GROUP: char = '#';
+
+/// The frontmatter key listing other names a page goes by, as a string or a list.
+pub const ALIASES_KEY: &str = "aliases";
+
+/// Frontmatter keys holding a page's stable id, in order of preference.
+pub const UID_KEYS: &[&str] = &["uid", "id"];
+
+/// The prefix of a link that refers to a page by its stable id, e.g. `[[uid:a1b2]]`.
+pub const UID_LINK_PREFIX: &str = "uid:";
+
+/// The most edits a broken link's name may be from a page name for that page to be
+/// suggested as the intended target.
+pub const MAX_SUGGESTION_DISTANCE: usize = 3;
+
 /// The main Indexer struct holds the entire knowledge base of the vault.
 ///
 /// This indexer processes individual file events but doesn't manage async event loops
@@ -28,14 +57,35 @@ use walkdir::WalkDir;
 pub struct Indexer {
     pub root_path: Option<PathBuf>,
     pub pages: HashMap<PathBuf, Page>,
+    /// Maps a lowercased tag to the pages that use it, so tags are grouped case-insensitively.
     pub tags: HashMap<String, HashSet<PathBuf>>,
 
+    /// Maps a lowercased tag to its display label: the most common casing across
+    /// pages, with ties broken alphabetically.
+    pub tag_labels: HashMap<String, String>,
+
     /// Fast lookup for resolving a normalized link name (String) to a file path.
+    /// When several pages share a name, this holds the one closest to the vault root.
     pub link_resolver: HashMap<String, PathBuf>,
 
+    /// Every page sharing each normalized link name, used to resolve ambiguous
+    /// links to the page nearest the linking page.
+    pub link_candidates: HashMap<String, Vec<PathBuf>>,
+
+    /// Maps a page's stable id (its `uid` or `id` frontmatter field) to its path, so
+    /// pages can be referenced in a way that survives renames.
+    pub uid_resolver: HashMap<String, PathBuf>,
+
+    /// Ids claimed by more than one page, with every page claiming them. The first
+    /// path (in sorted order) is the one registered in `uid_resolver`.
+    pub uid_collisions: HashMap<String, Vec<PathBuf>>,
+
     /// Stores the complete link graph: Source Path -> Target Path -> Vec<Link>.
     /// The Vec<Link> captures every link instance, to calculate link strength.
     pub link_graph: HashMap<PathBuf, HashMap<PathBuf, Vec<Link>>>,
+
+    /// Options that control how pages are parsed, such as which frontmatter keys hold tags.
+    pub settings: IndexSettings,
 }
 
 impl Indexer {
@@ -50,6 +100,14 @@ impl Indexer {
         }
     }
 
+    /// Creates a new indexer for the specified root path with custom index settings.
+    pub fn with_settings(root_path: &Path, settings: IndexSettings) -> Self {
+        Self {
+            settings,
+            ..Self::new(root_path)
+        }
+    }
+
     /// Performs a complete scan of the vault directory to build the initial index.
     ///
     /// This is typically called once during application startup before starting
@@ -74,14 +132,16 @@ impl Indexer {
         self.root_path = Some(root_path.to_path_buf());
         self.pages.clear();
 
-        // First pass: Parse all markdown files and populate the pages map
+        // First pass: Parse all markdown files and populate the pages map. Hidden
+        // folders, like the vault's data folder with its backups, are skipped.
         for entry in WalkDir::new(root_path)
             .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
             .filter_map(|e| e.ok())
             .filter(|e| is_markdown_file(e.path()))
         {
             let path = entry.path();
-            match parser::parse_file(path) {
+            match parser::parse_file(path, &self.settings) {
                 Ok(page) => {
                     self.pages.insert(path.to_path_buf(), page);
                 }
@@ -94,15 +154,7 @@ impl Indexer {
                         "Failed to parse file {:?}, creating a default entry: {}",
                         path, e
                     );
-                    let default_page = Page {
-                        path: path.to_path_buf(),
-                        title: file_stem_string(path),
-                        tags: HashSet::new(),
-                        links: Vec::new(),
-                        backlinks: HashSet::new(),
-                        frontmatter: serde_json::Value::Null,
-                    };
-                    self.pages.insert(path.to_path_buf(), default_page);
+                    self.pages.insert(path.to_path_buf(), unparsed_page(path));
                 }
             }
         }
@@ -185,7 +237,12 @@ impl Indexer {
         // Remove any existing page data
         self.pages.remove(path);
 
-        match parser::parse_file(path) {
+        // Images appear in the file tree but aren't pages.
+        if !is_markdown_file(path) {
+            return;
+        }
+
+        match parser::parse_file(path, &self.settings) {
             Ok(new_page) => {
                 // Add the newly parsed page to the index.
                 self.pages.insert(path.to_path_buf(), new_page);
@@ -243,7 +300,7 @@ impl Indexer {
             };
 
             // Re-parse the file at its new location to get fresh, consistent data.
-            match parser::parse_file(&new_path) {
+            match parser::parse_file(&new_path, &self.settings) {
                 Ok(new_page) => {
                     self.pages.insert(new_path, new_page);
                 }
@@ -252,15 +309,8 @@ impl Indexer {
                         "Could not re-parse renamed file {:?}, creating a default entry: {}",
                         new_path, e
                     );
-                    let default_page = Page {
-                        path: new_path.clone(),
-                        title: file_stem_string(&new_path),
-                        tags: HashSet::new(),
-                        links: Vec::new(),
-                        backlinks: HashSet::new(),
-                        frontmatter: serde_json::Value::Null,
-                    };
-                    self.pages.insert(new_path, default_page);
+                    let page = unparsed_page(&new_path);
+                    self.pages.insert(new_path, page);
                 }
             }
         }
@@ -274,21 +324,25 @@ impl Indexer {
 
         // Create local state to build into
         let mut new_tags: HashMap<String, HashSet<PathBuf>> = HashMap::new();
+        let mut tag_casings: HashMap<String, HashMap<String, usize>> = HashMap::new();
         let mut new_link_graph: HashMap<PathBuf, HashMap<PathBuf, Vec<Link>>> = HashMap::new();
         let mut new_backlinks: HashMap<PathBuf, HashSet<PathBuf>> = HashMap::new();
 
         for (source_path, page) in &self.pages {
-            // Rebuild tag associations
+            // Rebuild tag associations, grouping tags case-insensitively.
             for tag in &page.tags {
-                new_tags
-                    .entry(tag.clone())
+                let key = tag.to_lowercase();
+                *tag_casings
+                    .entry(key.clone())
                     .or_default()
-                    .insert(source_path.clone());
+                    .entry(tag.clone())
+                    .or_default() += 1;
+                new_tags.entry(key).or_default().insert(source_path.clone());
             }
 
             // Rebuild the link graph and calculate backlinks
             for link in &page.links {
-                if let Some(target_path) = self.resolve_link(link) {
+                if let Some(target_path) = self.resolve_link(link, source_path) {
                     // Add the link to the graph.
                     new_link_graph
                         .entry(source_path.clone())
@@ -312,28 +366,217 @@ impl Indexer {
             page.backlinks = new_backlinks.remove(path).unwrap_or_default();
         }
 
+        // Pick the display label for each tag from the casings seen.
+        let new_tag_labels: HashMap<String, String> = tag_casings
+            .into_iter()
+            .filter_map(|(key, casings)| {
+                casings
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                    .map(|(label, _)| (key, label))
+            })
+            .collect();
+
         // Atomically swap the new state into place
         let _ = mem::replace(&mut self.tags, new_tags);
+        let _ = mem::replace(&mut self.tag_labels, new_tag_labels);
         let _ = mem::replace(&mut self.link_graph, new_link_graph);
     }
 
-    /// Rebuilds the map for resolving link names to file paths.
+    /// Rebuilds the maps for resolving link names to file paths.
     #[instrument(level = "debug", skip(self))]
     fn rebuild_link_resolver(&mut self) {
         self.link_resolver.clear();
+        self.link_candidates.clear();
         for path in self.pages.keys() {
             if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                self.link_resolver.insert(stem.to_lowercase(), path.clone());
+                self.link_candidates
+                    .entry(stem.to_lowercase())
+                    .or_default()
+                    .push(path.clone());
+            }
+        }
+
+        // Order candidates shallowest first, then by path, so the flat resolver's
+        // choice between same-named pages is stable rather than arbitrary.
+        for (name, candidates) in self.link_candidates.iter_mut() {
+            candidates.sort_by(|a, b| {
+                a.components()
+                    .count()
+                    .cmp(&b.components().count())
+                    .then_with(|| a.cmp(b))
+            });
+            self.link_resolver
+                .insert(name.clone(), candidates[0].clone());
+        }
+
+        self.rebuild_uid_resolver();
+    }
+
+    /// Rebuilds the map from stable page ids to file paths, recording any id that
+    /// more than one page claims.
+    fn rebuild_uid_resolver(&mut self) {
+        let mut claims: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for (path, page) in &self.pages {
+            if let Some(uid) = page_uid(&page.frontmatter) {
+                claims.entry(uid).or_default().push(path.clone());
+            }
+        }
+
+        self.uid_resolver.clear();
+        self.uid_collisions.clear();
+        for (uid, mut paths) in claims {
+            paths.sort();
+            self.uid_resolver.insert(uid.clone(), paths[0].clone());
+            if paths.len() > 1 {
+                warn!(
+                    "The id {:?} is used by {} pages: {:?}",
+                    uid,
+                    paths.len(),
+                    paths
+                );
+                self.uid_collisions.insert(uid, paths);
+            }
+        }
+    }
+
+    /// Resolves a page's stable id to its path.
+    pub fn resolve_uid(&self, uid: &str) -> Option<PathBuf> {
+        self.uid_resolver.get(uid.trim()).cloned()
+    }
+
+    /// Returns the ids claimed by more than one page, with those pages, sorted by id.
+    pub fn get_uid_collisions(&self) -> Vec<(String, Vec<PageHeader>)> {
+        let mut collisions: Vec<(String, Vec<PageHeader>)> = self
+            .uid_collisions
+            .iter()
+            .map(|(uid, paths)| {
+                let pages = paths
+                    .iter()
+                    .filter_map(|path| self.pages.get(path))
+                    .map(|page| PageHeader {
+                        path: page.path.clone(),
+                        title: page.title.clone(),
+                    })
+                    .collect();
+                (uid.clone(), pages)
+            })
+            .collect();
+        collisions.sort_by(|a, b| nat_compare(&a.0, &b.0));
+        collisions
+    }
+
+    /// Resolves a wikilink found on the page at `source` to an absolute file path.
+    ///
+    /// If several pages share the link's name, the one nearest to `source` wins.
+    /// See [`Indexer::resolve_name`].
+    pub fn resolve_link(&self, link: &Link, source: &Path) -> Option<PathBuf> {
+        self.resolve_name(&link.target, Some(source))
+    }
+
+    /// Resolves a page name to an absolute file path.
+    ///
+    /// A name starting with [`UID_LINK_PREFIX`] is resolved by the page's stable id
+    /// instead. See [`Indexer::resolve_uid`].
+    ///
+    /// A name with a leading path, like `people/Vex`, resolves to the page at that
+    /// vault-relative path. If there is none, the path may be out of date, so the
+    /// only page with the bare name is used instead, but only if the folder doesn't
+    /// exist: `[[AC/DC]]` doesn't resolve to `DC.md` when there is an `AC` folder.
+    ///
+    /// When more than one page has this name and the linking page is known, the
+    /// candidate nearest to it is chosen: one in the same folder, then one in the
+    /// closest ancestor folder, then the one sharing the deepest common folder.
+    /// Otherwise the flat resolver's choice is used.
+    pub fn resolve_name(&self, name: &str, source: Option<&Path>) -> Option<PathBuf> {
+        if let Some(uid) = name.strip_prefix(UID_LINK_PREFIX) {
+            return self.resolve_uid(uid);
+        }
+        let name = match name.rsplit_once('/') {
+            Some((dir, stem)) => {
+                let root = self.root_path.as_deref()?;
+                let dir = Path::new(dir.trim_matches('/'));
+                let candidates = self.link_candidates.get(&stem.to_lowercase())?;
+                let at_path = candidates.iter().find(|candidate| {
+                    candidate
+                        .parent()
+                        .is_some_and(|parent| parent.strip_prefix(root).is_ok_and(|rel| rel == dir))
+                });
+                return match (at_path, candidates.as_slice()) {
+                    (Some(path), _) => Some(path.clone()),
+                    (None, [only]) if !root.join(dir).is_dir() => Some(only.clone()),
+                    _ => None,
+                };
+            }
+            None => name,
+        };
+        let key = name.to_lowercase();
+        let candidates = self.link_candidates.get(&key)?;
+        let source_dir = source.and_then(Path::parent);
+
+        match (candidates.as_slice(), source_dir) {
+            ([only], _) => Some(only.clone()),
+            (_, Some(dir)) => candidates
+                .iter()
+                .min_by_key(|candidate| {
+                    let shared = candidate
+                        .parent()
+                        .map_or(0, |parent| shared_depth(parent, dir));
+                    (Reverse(shared), candidate.components().count())
+                })
+                .cloned(),
+            (_, None) => self.link_resolver.get(&key).cloned(),
+        }
+    }
+
+    /// Resolves a raw wikilink target (e.g., `Page#Section|Alias`) to a file path.
+    ///
+    /// The section and alias are ignored; only the page name is used for resolution.
+    pub fn resolve_target(&self, target: &str) -> Option<PathBuf> {
+        let page_name = target.split('|').next().unwrap_or_default();
+        let page_name = page_name.split('#').next().unwrap_or_default();
+        self.resolve_name(page_name.replace('\\', "").trim(), None)
+    }
+
+    /// Suggests the existing page a broken link most likely meant, by comparing the
+    /// link's name against every page name.
+    ///
+    /// A suggestion is only made when it is confidently close: within roughly one
+    /// edit per four characters (at most [`MAX_SUGGESTION_DISTANCE`]) and strictly
+    /// closer than any other page. Returns the page's file name, as used in links.
+    pub fn suggest_page_name(&self, name: &str) -> Option<String> {
+        let key = name.trim().to_lowercase();
+        let max_distance = (key.chars().count() / 4).clamp(1, MAX_SUGGESTION_DISTANCE);
+
+        let mut best: Option<(&String, usize)> = None;
+        let mut tied = false;
+        for candidate in self.link_resolver.keys() {
+            let distance = edit_distance(&key, candidate);
+            if distance == 0 || distance > max_distance {
+                continue;
+            }
+            match best {
+                Some((_, best_distance)) if distance > best_distance => {}
+                Some((_, best_distance)) if distance == best_distance => tied = true,
+                _ => {
+                    best = Some((candidate, distance));
+                    tied = false;
+                }
             }
         }
+
+        let (candidate, _) = best.filter(|_| !tied)?;
+        self.link_resolver
+            .get(candidate)
+            .map(|path| file_stem_string(path))
     }
 
-    /// Resolves a wikilink to an absolute file path using the resolver map.
-    pub fn resolve_link(&self, link: &Link) -> Option<PathBuf> {
-        self.link_resolver.get(&link.target.to_lowercase()).cloned()
+    /// Returns the display label for a lowercased tag key.
+    fn tag_label<'a>(&'a self, key: &'a str) -> &'a str {
+        self.tag_labels.get(key).map_or(key, String::as_str)
     }
 
-    /// Returns all tags and the pages that reference them.
+    /// Returns all tags (by display label) and the pages that reference them.
     #[instrument(level = "debug", skip(self))]
     pub fn get_all_tags(&self) -> Result<Vec<(String, Vec<PageHeader>)>> {
         // Collect all tags and their associated page references first
@@ -355,321 +598,2482 @@ impl Indexer {
                 // Sort pages by title (case-insensitive)
                 pages.sort_by_key(|page| page.title.to_lowercase());
 
-                (tag.clone(), pages)
+                (self.tag_label(tag).to_string(), pages)
             })
             .collect();
 
-        // Sort tags by name
-        tags.sort_by(|a, b| a.0.cmp(&b.0));
+        // Sort tags by name, ignoring case since the labels may be mixed-case.
+        tags.sort_by(|a, b| {
+            a.0.to_lowercase()
+                .cmp(&b.0.to_lowercase())
+                .then_with(|| a.0.cmp(&b.0))
+        });
 
         Ok(tags)
     }
 
-    /// Generates a hierarchical file tree representation of the vault.
+    /// Exports the tags as an OPML outline for mind-mapping tools.
     ///
-    /// # Returns
-    /// `Result<FileNode>` representing the root of the file tree
+    /// Tags are nested on `/`, so `location/city` is an outline inside `location`,
+    /// and each tag's pages are leaf outlines below its nested tags.
     #[instrument(level = "debug", skip(self))]
-    pub fn get_file_tree(&self) -> Result<FileNode> {
-        let root = self
-            .root_path
-            .as_ref()
-            .ok_or(ChroniclerError::VaultNotInitialized)?;
-        let root_name = root
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
+    pub fn export_tags_opml(&self) -> Result<String> {
+        let mut root = TagOutline::default();
+        for (tag, pages) in self.get_all_tags()? {
+            let node = tag
+                .split('/')
+                .map(str::trim)
+                .filter(|segment| !segment.is_empty())
+                .fold(&mut root, |node, segment| node.child(segment));
+            node.pages.extend(pages);
+        }
 
-        Self::build_tree_recursive(root, &root_name)
+        let title = self
+            .root_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or("Tags".into(), |name| name.to_string_lossy());
+        let mut opml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head>\n    <title>{}</title>\n  </head>\n  <body>\n",
+            html_escape::encode_text(&title)
+        );
+        for child in &root.children {
+            child.write_opml(&mut opml, 2);
+        }
+        opml.push_str("  </body>\n</opml>\n");
+        Ok(opml)
     }
 
-    /// Recursively builds the file tree structure.
-    #[instrument(level = "debug", skip(path, name))]
-    fn build_tree_recursive(path: &Path, name: &str) -> Result<FileNode> {
-        // Determine the file type first.
-        let file_type = if path.is_dir() {
-            FileType::Directory
-        } else if is_image_file(path) {
-            FileType::Image
-        } else {
-            FileType::Markdown
-        };
+    /// Returns each tag (by display label) with the number of pages using it, sorted
+    /// like `get_all_tags`. Cheaper than `get_all_tags` when only counts are needed.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self
+            .tags
+            .iter()
+            .map(|(tag, paths)| (self.tag_label(tag).to_string(), paths.len()))
+            .collect();
+        counts.sort_by(|a, b| {
+            a.0.to_lowercase()
+                .cmp(&b.0.to_lowercase())
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        counts
+    }
 
-        let mut children = if file_type == FileType::Directory {
-            Some(Vec::new())
-        } else {
-            None
-        };
+    /// Counts how often each pair of tags appears together on the same page.
+    ///
+    /// Tags are grouped case-insensitively and reported by their display labels.
+    /// Each pair is returned once, with the two tags in alphabetical order.
+    /// Results are sorted by count descending, then by tag names.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_tag_cooccurrence(&self) -> Vec<(String, String, usize)> {
+        let mut counts: HashMap<(String, String), usize> = HashMap::new();
 
-        if file_type == FileType::Directory {
-            if let Some(children_vec) = children.as_mut() {
-                for entry in fs::read_dir(path)? {
-                    let entry = entry?;
-                    let child_path = entry.path();
+        for page in self.pages.values() {
+            let mut tags: Vec<String> = page.tags.iter().map(|t| t.to_lowercase()).collect();
+            tags.sort_unstable();
+            tags.dedup();
 
-                    if let Some(file_name) = child_path.file_name().and_then(|n| n.to_str()) {
-                        // Skip hidden files
-                        if file_name.starts_with('.') {
-                            continue;
-                        }
-                        if child_path.is_dir()
-                            || is_markdown_file(&child_path)
-                            || is_image_file(&child_path)
-                        {
-                            children_vec.push(Self::build_tree_recursive(&child_path, file_name)?);
-                        }
-                    }
+            for (i, first) in tags.iter().enumerate() {
+                for second in &tags[i + 1..] {
+                    *counts.entry((first.clone(), second.clone())).or_default() += 1;
                 }
-
-                // Sort children: directories first (based on Ord impl), then alphanumerically by name.
-                children_vec.sort_by(|a, b| {
-                    a.file_type
-                        .cmp(&b.file_type)
-                        .then_with(|| nat_compare(&a.name, &b.name))
-                });
             }
         }
 
-        let name = if file_type == FileType::Markdown {
-            file_stem_string(path)
-        } else {
-            name.to_string()
-        };
+        let mut pairs: Vec<_> = counts
+            .into_iter()
+            .map(|((a, b), count)| {
+                (
+                    self.tag_label(&a).to_string(),
+                    self.tag_label(&b).to_string(),
+                    count,
+                )
+            })
+            .collect();
 
-        Ok(FileNode {
-            name,
-            path: path.to_path_buf(),
-            file_type,
-            children,
-        })
-    }
+        pairs.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| (&a.0, &a.1).cmp(&(&b.0, &b.1))));
 
-    /// Returns a list of all directory paths in the vault.
-    pub fn get_all_directory_paths(&self) -> Result<Vec<PathBuf>> {
-        let root_node = self.get_file_tree()?;
-        let mut dirs = Vec::new();
-        // Add the root directory itself
-        dirs.push(root_node.path.clone());
-        Self::collect_dirs_recursive(&root_node, &mut dirs);
-        Ok(dirs)
+        pairs
     }
 
-    /// Helper function to recursively collect directory paths.
-    fn collect_dirs_recursive(node: &FileNode, dirs: &mut Vec<PathBuf>) {
-        if let Some(children) = &node.children {
-            for child in children {
-                if child.file_type == FileType::Directory {
-                    dirs.push(child.path.clone());
-                    Self::collect_dirs_recursive(child, dirs);
+    /// Computes per-folder storage statistics by walking the vault on disk.
+    ///
+    /// Every file is counted towards its own folder and all of its ancestors up to
+    /// the vault root. Hidden files and folders are skipped, as in the file tree.
+    /// The result is sorted by path, so the vault root comes first.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_folder_stats(&self) -> Result<Vec<FolderStat>> {
+        let root = self
+            .root_path
+            .as_ref()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let mut stats: HashMap<PathBuf, FolderStat> = HashMap::new();
+
+        let walker = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+
+        for entry in walker.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if entry.file_type().is_dir() {
+                stats
+                    .entry(path.to_path_buf())
+                    .or_insert_with(|| FolderStat {
+                        path: path.to_path_buf(),
+                        ..Default::default()
+                    });
+                continue;
+            }
+
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            for folder in path.ancestors().skip(1) {
+                let stat = stats
+                    .entry(folder.to_path_buf())
+                    .or_insert_with(|| FolderStat {
+                        path: folder.to_path_buf(),
+                        ..Default::default()
+                    });
+                if is_markdown_file(path) {
+                    stat.pages += 1;
+                } else if is_image_file(path) {
+                    stat.images += 1;
+                } else {
+                    stat.attachments += 1;
+                }
+                stat.total_bytes += size;
+
+                if folder == root {
+                    break;
                 }
             }
         }
+
+        let mut result: Vec<FolderStat> = stats.into_values().collect();
+        result.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(result)
     }
 
-    /// Finds all broken links in the vault and aggregates them by target.
+    /// Returns the number of distinct pages each page links to (outbound) and is
+    /// linked from (inbound), for every page in the vault.
+    ///
+    /// Only resolved links are counted. The result is keyed by page path and holds
+    /// `(outbound, inbound)` pairs.
     #[instrument(level = "debug", skip(self))]
-    pub fn get_all_broken_links(&self) -> Result<Vec<BrokenLink>> {
-        let mut broken_links_map: HashMap<String, HashSet<PageHeader>> = HashMap::new();
+    pub fn get_link_degree_map(&self) -> HashMap<PathBuf, (usize, usize)> {
+        self.pages
+            .iter()
+            .map(|(path, page)| {
+                let outbound = self.link_graph.get(path).map_or(0, |targets| targets.len());
+                (path.clone(), (outbound, page.backlinks.len()))
+            })
+            .collect()
+    }
 
-        // Iterate through all pages and their outgoing links
-        for (source_path, page) in &self.pages {
-            for link in &page.links {
-                // A link is broken if it cannot be resolved by the indexer.
-                if self.resolve_link(link).is_none() {
-                    let source_header = PageHeader {
-                        path: source_path.clone(),
+    /// Returns the images a page uses that don't exist on disk, as written in the
+    /// page, in order of first appearance. Returns an empty list if the page isn't
+    /// indexed or the vault root is unknown.
+    pub fn get_missing_images_for_page(&self, path: &Path) -> Vec<String> {
+        let (Some(page), Some(root)) = (self.pages.get(path), self.root_path.as_deref()) else {
+            return Vec::new();
+        };
+        let mut seen = HashSet::new();
+        page.image_refs
+            .iter()
+            .filter(|src| seen.insert(src.as_str()))
+            .filter(|src| !resolve_image_path(root, src).is_file())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the pages that use the image at `image_path`, as an embed or in a
+    /// frontmatter image field, sorted by path. Returns an empty list if the vault
+    /// root is unknown.
+    pub fn get_pages_using_image(&self, image_path: &Path) -> Vec<PathBuf> {
+        let Some(root) = self.root_path.as_deref() else {
+            return Vec::new();
+        };
+        let mut pages: Vec<PathBuf> = self
+            .pages
+            .values()
+            .filter(|page| {
+                page.image_refs
+                    .iter()
+                    .any(|src| resolve_image_path(root, src) == image_path)
+            })
+            .map(|page| page.path.clone())
+            .collect();
+        pages.sort();
+        pages
+    }
+
+    /// Returns every page that uses images missing from disk, with those images,
+    /// sorted by page title.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_all_missing_images(&self) -> Vec<(PageHeader, Vec<String>)> {
+        let mut result: Vec<(PageHeader, Vec<String>)> = self
+            .pages
+            .values()
+            .filter_map(|page| {
+                let missing = self.get_missing_images_for_page(&page.path);
+                (!missing.is_empty()).then(|| {
+                    let header = PageHeader {
+                        path: page.path.clone(),
                         title: page.title.clone(),
                     };
-                    // Add the source page to the set for this broken target.
-                    broken_links_map
-                        .entry(link.target.clone())
-                        .or_default()
-                        .insert(source_header);
-                }
-            }
-        }
+                    (header, missing)
+                })
+            })
+            .collect();
+        result.sort_by(|a, b| nat_compare(&a.0.title, &b.0.title));
+        result
+    }
 
-        // Convert the map into the final Vec<BrokenLink> structure for the frontend.
-        let mut result: Vec<BrokenLink> = broken_links_map
+    /// Returns every web URL in the vault's page bodies with the page it's in,
+    /// sorted by page path and then in order of appearance.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_external_links(&self) -> Vec<(PathBuf, String)> {
+        let mut pages: Vec<&Page> = self.pages.values().collect();
+        pages.sort_by(|a, b| a.path.cmp(&b.path));
+        pages
             .into_iter()
-            .map(|(target, sources_set)| {
-                let mut sources: Vec<PageHeader> = sources_set.into_iter().collect();
-                // Sort the source pages by title using natural ordering.
-                sources.sort_by(|a, b| nat_compare(&a.title, &b.title));
-                BrokenLink { target, sources }
+            .flat_map(|page| {
+                page.external_links
+                    .iter()
+                    .map(|url| (page.path.clone(), url.clone()))
             })
-            .collect();
+            .collect()
+    }
 
-        // Sort the final list of broken links by their target name using natural ordering.
-        result.sort_by(|a, b| nat_compare(&a.target, &b.target));
+    /// Returns the pages without any tags, sorted by title, each paired with whether
+    /// it failed to parse. A page that failed to parse has no tags because its
+    /// frontmatter couldn't be read, so it is worth fixing rather than tagging.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_untagged_pages(&self) -> Vec<(PageHeader, bool)> {
+        let mut pages: Vec<(PageHeader, bool)> = self
+            .pages
+            .values()
+            .filter(|page| page.tags.is_empty())
+            .map(|page| {
+                let header = PageHeader {
+                    path: page.path.clone(),
+                    title: page.title.clone(),
+                };
+                (header, page.parse_failed)
+            })
+            .collect();
+        pages.sort_by(|a, b| nat_compare(&a.0.title, &b.0.title));
+        pages
+    }
 
-        Ok(result)
+    /// Returns the pages that link to themselves, which is usually a mistake, sorted
+    /// by title.
+    ///
+    /// Links to a section of the same page (`[[This Page#Section]]`) are a normal
+    /// way to jump within a page, so they aren't reported.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_self_linking_pages(&self) -> Vec<PageHeader> {
+        let mut pages: Vec<PageHeader> = self
+            .link_graph
+            .iter()
+            .filter(|(source, targets)| {
+                targets
+                    .get(*source)
+                    .is_some_and(|links| links.iter().any(|link| link.section.is_none()))
+            })
+            .filter_map(|(source, _)| self.pages.get(source))
+            .map(|page| PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            })
+            .collect();
+        pages.sort_by(|a, b| nat_compare(&a.title, &b.title));
+        pages
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::events::FileEvent;
-    use std::{collections::HashSet, fs, path::PathBuf};
-    use tempfile::tempdir;
+    /// Returns the pages whose removal would split the link graph into more
+    /// disconnected parts (its articulation points), sorted by title.
+    ///
+    /// Links are treated as undirected, so a page is connected to both the pages it
+    /// links to and the pages linking to it. These pages are the only route between
+    /// parts of the vault, which makes them worth linking around.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_critical_pages(&self) -> Vec<PageHeader> {
+        let mut nodes: Vec<&PathBuf> = self.pages.keys().collect();
+        nodes.sort();
+        let index: HashMap<&PathBuf, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (*path, i))
+            .collect();
+
+        let mut neighbours: Vec<HashSet<usize>> = vec![HashSet::new(); nodes.len()];
+        let edges = self
+            .link_graph
+            .iter()
+            .flat_map(|(source, targets)| targets.keys().map(move |target| (source, target)));
+        let backlink_edges = self
+            .pages
+            .iter()
+            .flat_map(|(target, page)| page.backlinks.iter().map(move |source| (source, target)));
+        for (source, target) in edges.chain(backlink_edges) {
+            if let (Some(&a), Some(&b)) = (index.get(source), index.get(target)) {
+                if a != b {
+                    neighbours[a].insert(b);
+                    neighbours[b].insert(a);
+                }
+            }
+        }
+        let neighbours: Vec<Vec<usize>> = neighbours
+            .into_iter()
+            .map(|set| {
+                let mut list: Vec<usize> = set.into_iter().collect();
+                list.sort_unstable();
+                list
+            })
+            .collect();
+
+        // Tarjan's algorithm, with an explicit stack so that long chains of pages
+        // can't overflow the call stack.
+        const UNVISITED: usize = usize::MAX;
+        let mut discovered = vec![UNVISITED; nodes.len()];
+        let mut low = vec![0; nodes.len()];
+        let mut parent: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut critical = vec![false; nodes.len()];
+        let mut time = 0;
+
+        for root in 0..nodes.len() {
+            if discovered[root] != UNVISITED {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            let mut stack = vec![(root, 0)];
+
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                if let Some(&neighbour) = neighbours[node].get(*next) {
+                    *next += 1;
+                    if discovered[neighbour] == UNVISITED {
+                        parent[neighbour] = Some(node);
+                        discovered[neighbour] = time;
+                        low[neighbour] = time;
+                        time += 1;
+                        stack.push((neighbour, 0));
+                    } else if parent[node] != Some(neighbour) {
+                        low[node] = low[node].min(discovered[neighbour]);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                if let Some(above) = parent[node] {
+                    low[above] = low[above].min(low[node]);
+                    if above == root {
+                        root_children += 1;
+                    } else if low[node] >= discovered[above] {
+                        critical[above] = true;
+                    }
+                }
+            }
+            critical[root] = root_children > 1;
+        }
+
+        let mut pages: Vec<PageHeader> = nodes
+            .iter()
+            .zip(critical)
+            .filter(|(_, critical)| *critical)
+            .filter_map(|(path, _)| self.pages.get(*path))
+            .map(|page| PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            })
+            .collect();
+        pages.sort_by(|a, b| nat_compare(&a.title, &b.title));
+        pages
+    }
+
+    /// Returns every link to the page at `path`, grouped into direct links, aliased
+    /// links, section links and embeds, each with the page containing it and the
+    /// link as written. Each group is sorted by the linking page's title.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_all_references(&self, path: &Path) -> References {
+        let mut references = References::default();
+        for (source, targets) in &self.link_graph {
+            let (Some(links), Some(page)) = (targets.get(path), self.pages.get(source)) else {
+                continue;
+            };
+            for link in links {
+                let group = if link.embed {
+                    &mut references.embeds
+                } else if link.section.is_some() {
+                    &mut references.sections
+                } else if link.alias.is_some() {
+                    &mut references.aliased
+                } else {
+                    &mut references.direct
+                };
+                group.push(Reference {
+                    source: PageHeader {
+                        path: page.path.clone(),
+                        title: page.title.clone(),
+                    },
+                    text: link_text(link),
+                });
+            }
+        }
+
+        for group in [
+            &mut references.direct,
+            &mut references.aliased,
+            &mut references.sections,
+            &mut references.embeds,
+        ] {
+            group.sort_by(|a, b| {
+                nat_compare(&a.source.title, &b.source.title)
+                    .then_with(|| a.source.path.cmp(&b.source.path))
+                    .then_with(|| a.text.cmp(&b.text))
+            });
+        }
+        references
+    }
+
+    /// Returns a lightweight snapshot of the vault: each page's vault-relative path
+    /// mapped to a hash of its content. Two manifests can be compared with
+    /// `diff_manifests` to see what changed between them.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_manifest(&self) -> HashMap<PathBuf, String> {
+        let root = self.root_path.as_deref().unwrap_or(Path::new(""));
+        self.pages
+            .values()
+            .map(|page| {
+                let relative = page.path.strip_prefix(root).unwrap_or(&page.path);
+                (relative.to_path_buf(), page.content_hash.clone())
+            })
+            .collect()
+    }
+
+    /// Compares this index against `fresh`, a new scan of the same vault, and reports
+    /// where they disagree: pages only one of them knows, pages indexed from older
+    /// content, and pages whose links resolve to other pages or whose backlinks
+    /// differ.
+    #[instrument(level = "debug", skip(self, fresh))]
+    pub fn compare_with(&self, fresh: &Indexer) -> IndexReport {
+        let root = self.root_path.as_deref().unwrap_or(Path::new(""));
+        let relative = |path: &PathBuf| path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        let link_targets = |index: &Indexer, path: &PathBuf| -> HashSet<PathBuf> {
+            index
+                .link_graph
+                .get(path)
+                .map(|targets| targets.keys().cloned().collect())
+                .unwrap_or_default()
+        };
+
+        let mut report = IndexReport::default();
+        for (path, page) in &self.pages {
+            let Some(fresh_page) = fresh.pages.get(path) else {
+                report.missing_on_disk.push(relative(path));
+                continue;
+            };
+            if page.content_hash != fresh_page.content_hash {
+                report.outdated.push(relative(path));
+            }
+            if page.backlinks != fresh_page.backlinks
+                || link_targets(self, path) != link_targets(fresh, path)
+            {
+                report.link_mismatches.push(relative(path));
+            }
+        }
+        report.missing_from_index = fresh
+            .pages
+            .keys()
+            .filter(|path| !self.pages.contains_key(*path))
+            .map(relative)
+            .collect();
+
+        report.missing_on_disk.sort();
+        report.missing_from_index.sort();
+        report.outdated.sort();
+        report.link_mismatches.sort();
+        report
+    }
+
+    /// Returns the pages linked from at least `min_backlinks` other pages, with their
+    /// backlink counts, to surface the hubs of a vault.
+    ///
+    /// Pages are sorted by backlink count descending, then by title.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_pages_with_min_backlinks(&self, min_backlinks: usize) -> Vec<(PageHeader, usize)> {
+        let mut pages: Vec<(PageHeader, usize)> = self
+            .pages
+            .values()
+            .filter(|page| page.backlinks.len() >= min_backlinks)
+            .map(|page| {
+                (
+                    PageHeader {
+                        path: page.path.clone(),
+                        title: page.title.clone(),
+                    },
+                    page.backlinks.len(),
+                )
+            })
+            .collect();
+
+        pages.sort_by(|(a, a_count), (b, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| nat_compare(&a.title, &b.title))
+        });
+        pages
+    }
+
+    /// Returns the pages whose files were modified after `since`, most recently
+    /// modified first. Pages whose modification time is unknown are left out.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_pages_modified_since(&self, since: SystemTime) -> Vec<PageHeader> {
+        let mut pages: Vec<(&Page, SystemTime)> = self
+            .pages
+            .values()
+            .filter_map(|page| page.modified.map(|modified| (page, modified)))
+            .filter(|(_, modified)| *modified > since)
+            .collect();
+
+        pages.sort_by(|(a, a_modified), (b, b_modified)| {
+            b_modified
+                .cmp(a_modified)
+                .then_with(|| nat_compare(&a.title, &b.title))
+        });
+        pages
+            .into_iter()
+            .map(|(page, _)| PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            })
+            .collect()
+    }
+
+    /// Finds the pages whose title or one of whose aliases fuzzily matches `query`,
+    /// for a quick switcher. The query's characters must appear in order but may be
+    /// spread out, so `gnd` finds "Gandalf".
+    ///
+    /// Pages are ranked by their best match, then shorter titles first, then by
+    /// title. Only titles and the [`ALIASES_KEY`] frontmatter field are searched,
+    /// never page bodies; use [`Indexer::search`] for full-text search.
+    #[instrument(level = "debug", skip(self))]
+    pub fn fuzzy_find_pages(&self, query: &str) -> Vec<PageHeader> {
+        let query = query.trim();
+        let mut matches: Vec<(&Page, i32)> = self
+            .pages
+            .values()
+            .filter_map(|page| {
+                let aliases = match page.frontmatter.get(ALIASES_KEY) {
+                    Some(serde_json::Value::String(alias)) => vec![alias.as_str()],
+                    Some(serde_json::Value::Array(items)) => {
+                        items.iter().filter_map(|item| item.as_str()).collect()
+                    }
+                    _ => Vec::new(),
+                };
+                std::iter::once(page.title.as_str())
+                    .chain(aliases)
+                    .filter_map(|name| fuzzy_score(query, name))
+                    .max()
+                    .map(|score| (page, score))
+            })
+            .collect();
+
+        matches.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| a.title.chars().count().cmp(&b.title.chars().count()))
+                .then_with(|| nat_compare(&a.title, &b.title))
+        });
+        matches
+            .into_iter()
+            .map(|(page, _)| PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the pages in `folder`, sorted by title. With `recursive`, pages in its
+    /// subfolders are included too; otherwise only the folder's direct children.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_pages_in_folder(&self, folder: &Path, recursive: bool) -> Vec<PageHeader> {
+        let mut pages: Vec<PageHeader> = self
+            .pages
+            .values()
+            .filter(|page| {
+                if recursive {
+                    page.path.starts_with(folder)
+                } else {
+                    page.path.parent() == Some(folder)
+                }
+            })
+            .map(|page| PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            })
+            .collect();
+        pages.sort_by(|a, b| nat_compare(&a.title, &b.title));
+        pages
+    }
+
+    /// Groups all pages by the first character of their title, for an
+    /// alphabetical index.
+    ///
+    /// Letters in any script are uppercased, so `élan` and `Écu` share the `É`
+    /// group. Titles starting with anything else, such as a digit or a quote, are
+    /// grouped under [`NON_ALPHABETIC_INDEX_GROUP`]. Groups are ordered by
+    /// character, which puts that group first, and pages within each group are
+    /// sorted by title, ignoring case.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_alphabetical_index(&self) -> Vec<(char, Vec<PageHeader>)> {
+        let mut groups: BTreeMap<char, Vec<PageHeader>> = BTreeMap::new();
+        for page in self.pages.values() {
+            let letter = page
+                .title
+                .trim_start()
+                .chars()
+                .next()
+                .filter(|c| c.is_alphabetic())
+                .and_then(|c| c.to_uppercase().next())
+                .unwrap_or(NON_ALPHABETIC_INDEX_GROUP);
+            groups.entry(letter).or_default().push(PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            });
+        }
+
+        groups
+            .into_iter()
+            .map(|(letter, mut pages)| {
+                pages.sort_by(|a, b| {
+                    natord::compare_ignore_case(&a.title, &b.title)
+                        .then_with(|| nat_compare(&a.title, &b.title))
+                });
+                (letter, pages)
+            })
+            .collect()
+    }
+
+    /// Returns the graph of the page at `path` and its immediate neighbors: the pages
+    /// it links to and the pages linking to it. Every link between two of these
+    /// pages is an edge, not only those touching the page itself.
+    ///
+    /// Nodes are sorted by title, with the page itself first; edges by source, then
+    /// target. Returns an empty graph if the page isn't indexed.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_neighborhood(&self, path: &Path) -> GraphData {
+        let Some(page) = self.pages.get(path) else {
+            return GraphData::default();
+        };
+        let mut members: HashSet<&PathBuf> = page.backlinks.iter().collect();
+        if let Some(targets) = self.link_graph.get(path) {
+            members.extend(targets.keys());
+        }
+        members.insert(&page.path);
+
+        let header = |p: &Page| PageHeader {
+            path: p.path.clone(),
+            title: p.title.clone(),
+        };
+        let mut nodes: Vec<PageHeader> = members
+            .iter()
+            .filter(|member| member.as_path() != path)
+            .filter_map(|member| self.pages.get(*member).map(header))
+            .collect();
+        nodes.sort_by(|a, b| nat_compare(&a.title, &b.title));
+        nodes.insert(0, header(page));
+
+        let mut edges = Vec::new();
+        for (source, targets) in &self.link_graph {
+            if !members.contains(source) {
+                continue;
+            }
+            for (target, links) in targets {
+                if target != source && members.contains(target) {
+                    edges.push(GraphEdge {
+                        source: source.clone(),
+                        target: target.clone(),
+                        weight: links.len(),
+                    });
+                }
+            }
+        }
+        edges.sort_by(|a, b| {
+            a.source
+                .cmp(&b.source)
+                .then_with(|| a.target.cmp(&b.target))
+        });
+
+        GraphData { nodes, edges }
+    }
+
+    /// Returns up to `limit` pages related to the page at `path`, most related first.
+    ///
+    /// A page scores a point for each tag it shares with this page (ignoring case)
+    /// and for each page they both link to. Pages with no overlap are left out, and
+    /// ties are ordered by title.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_related_pages(&self, path: &Path, limit: usize) -> Vec<PageHeader> {
+        let Some(page) = self.pages.get(path) else {
+            return Vec::new();
+        };
+        let mut scores: HashMap<&PathBuf, usize> = HashMap::new();
+
+        let tags: HashSet<String> = page.tags.iter().map(|t| t.to_lowercase()).collect();
+        for tag in &tags {
+            for other in self.tags.get(tag).into_iter().flatten() {
+                *scores.entry(other).or_default() += 1;
+            }
+        }
+
+        if let Some(targets) = self.link_graph.get(path) {
+            for (other, other_targets) in &self.link_graph {
+                let shared = other_targets
+                    .keys()
+                    .filter(|target| targets.contains_key(*target))
+                    .count();
+                if shared > 0 {
+                    *scores.entry(other).or_default() += shared;
+                }
+            }
+        }
+
+        let mut related: Vec<(PageHeader, usize)> = scores
+            .into_iter()
+            .filter(|(other, _)| other.as_path() != path)
+            .filter_map(|(other, score)| {
+                self.pages.get(other).map(|p| {
+                    (
+                        PageHeader {
+                            path: p.path.clone(),
+                            title: p.title.clone(),
+                        },
+                        score,
+                    )
+                })
+            })
+            .collect();
+        related.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| nat_compare(&a.title, &b.title))
+        });
+        related
+            .into_iter()
+            .take(limit)
+            .map(|(header, _)| header)
+            .collect()
+    }
+
+    /// Returns pages ordered by the word count of their body, to find stubs worth
+    /// merging (`ascending`) or long pages worth splitting (descending).
+    ///
+    /// Pages with the same word count are ordered by title. At most `limit` pages
+    /// are returned.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_pages_by_length(&self, ascending: bool, limit: usize) -> Vec<(PageHeader, usize)> {
+        let mut pages: Vec<(PageHeader, usize)> = self
+            .pages
+            .values()
+            .map(|page| {
+                (
+                    PageHeader {
+                        path: page.path.clone(),
+                        title: page.title.clone(),
+                    },
+                    page.word_count,
+                )
+            })
+            .collect();
+
+        pages.sort_by(|(a, a_words), (b, b_words)| {
+            let by_length = if ascending {
+                a_words.cmp(b_words)
+            } else {
+                b_words.cmp(a_words)
+            };
+            by_length.then_with(|| nat_compare(&a.title, &b.title))
+        });
+        pages.truncate(limit);
+        pages
+    }
+
+    /// Groups pages by the value of a frontmatter field, for board (kanban) views.
+    ///
+    /// String values are trimmed, and numbers and booleans are used as text. A page
+    /// whose field holds an array appears under each of its values. Pages without a
+    /// usable value are grouped under [`UNSET_BOARD_COLUMN`]. Pages within each
+    /// group are sorted by title.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_pages_by_status_field(&self, field: &str) -> HashMap<String, Vec<PageHeader>> {
+        fn value_to_column(value: &serde_json::Value) -> Option<String> {
+            let text = match value {
+                serde_json::Value::String(s) => s.trim().to_string(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            (!text.is_empty()).then_some(text)
+        }
+
+        let mut board: HashMap<String, Vec<PageHeader>> = HashMap::new();
+
+        for page in self.pages.values() {
+            let mut columns: Vec<String> = match page.frontmatter.get(field) {
+                Some(serde_json::Value::Array(items)) => {
+                    items.iter().filter_map(value_to_column).collect()
+                }
+                Some(value) => value_to_column(value).into_iter().collect(),
+                None => Vec::new(),
+            };
+            columns.sort();
+            columns.dedup();
+            if columns.is_empty() {
+                columns.push(UNSET_BOARD_COLUMN.to_string());
+            }
+
+            for column in columns {
+                board.entry(column).or_default().push(PageHeader {
+                    path: page.path.clone(),
+                    title: page.title.clone(),
+                });
+            }
+        }
+
+        for pages in board.values_mut() {
+            pages.sort_by(|a, b| nat_compare(&a.title, &b.title));
+        }
+
+        board
+    }
+
+    /// Finds the pages containing `query`, ignoring case, sorted by title.
+    ///
+    /// Page bodies are read from disk. With `include_frontmatter`, the string values
+    /// in each page's frontmatter are searched too, at any depth, so infobox values
+    /// like a character's faction can be found. With `player_mode`, `:::gm` blocks
+    /// aren't searched, so results don't give away GM-only content. Each result
+    /// says where it matched.
+    #[instrument(level = "debug", skip(self))]
+    pub fn search(
+        &self,
+        query: &str,
+        include_frontmatter: bool,
+        player_mode: bool,
+    ) -> Vec<SearchResult> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Vec::new();
+        }
+
+        let mut results: Vec<SearchResult> = self
+            .pages
+            .values()
+            .filter_map(|page| {
+                let in_body = match fs::read_to_string(&page.path) {
+                    Ok(content) => {
+                        let (_, body) = parser::extract_frontmatter(&content);
+                        let body = if player_mode {
+                            parser::strip_gm_blocks(body)
+                        } else {
+                            Cow::Borrowed(body)
+                        };
+                        body.to_lowercase().contains(&needle)
+                    }
+                    Err(e) => {
+                        warn!("Failed to read {:?} for search: {}", page.path, e);
+                        false
+                    }
+                };
+                let in_frontmatter =
+                    include_frontmatter && frontmatter_contains(&page.frontmatter, &needle);
+                (in_body || in_frontmatter).then(|| SearchResult {
+                    page: PageHeader {
+                        path: page.path.clone(),
+                        title: page.title.clone(),
+                    },
+                    in_body,
+                    in_frontmatter,
+                })
+            })
+            .collect();
+
+        results.sort_by(|a, b| nat_compare(&a.page.title, &b.page.title));
+        results
+    }
+
+    /// Finds all pages whose vault-relative path matches a glob pattern.
+    ///
+    /// Paths are matched with forward slashes, so patterns are portable across
+    /// platforms. A `*` does not cross folder boundaries, while `**` does, so
+    /// `lore/**/*.md` matches pages at any depth under `lore`. Matching is
+    /// case-sensitive. The results are sorted by path.
+    #[instrument(level = "debug", skip(self))]
+    pub fn find_pages_by_glob(&self, pattern: &str) -> Result<Vec<PageHeader>> {
+        let root = self
+            .root_path
+            .as_ref()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let pattern = Pattern::new(pattern.trim())?;
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        let mut matches: Vec<PageHeader> = self
+            .pages
+            .values()
+            .filter(|page| {
+                page.path.strip_prefix(root).is_ok_and(|relative| {
+                    let relative = relative.to_string_lossy().replace('\\', "/");
+                    pattern.matches_with(&relative, options)
+                })
+            })
+            .map(|page| PageHeader {
+                path: page.path.clone(),
+                title: page.title.clone(),
+            })
+            .collect();
+
+        matches.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(matches)
+    }
+
+    /// Generates a hierarchical file tree representation of the vault.
+    ///
+    /// # Returns
+    /// `Result<FileNode>` representing the root of the file tree
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_file_tree(&self) -> Result<FileNode> {
+        let root = self
+            .root_path
+            .as_ref()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let root_name = root
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let hidden_dirs: Vec<PathBuf> = if self.settings.hide_asset_folders {
+            self.settings
+                .asset_folders
+                .iter()
+                .map(|folder| root.join(folder.trim_matches('/')))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self::build_tree_recursive(
+            root,
+            &root_name,
+            &self.settings.extra_file_extensions,
+            &hidden_dirs,
+        )
+    }
+
+    /// Recursively builds the file tree structure. Besides folders, pages and images,
+    /// files with one of the `extra_extensions` are included. Folders in `hidden_dirs`
+    /// are skipped along with everything inside them.
+    #[instrument(level = "debug", skip(path, name))]
+    fn build_tree_recursive(
+        path: &Path,
+        name: &str,
+        extra_extensions: &[String],
+        hidden_dirs: &[PathBuf],
+    ) -> Result<FileNode> {
+        // Determine the file type first.
+        let file_type = if path.is_dir() {
+            FileType::Directory
+        } else if is_image_file(path) {
+            FileType::Image
+        } else if is_markdown_file(path) {
+            FileType::Markdown
+        } else {
+            FileType::Other
+        };
+
+        let mut children = if file_type == FileType::Directory {
+            Some(Vec::new())
+        } else {
+            None
+        };
+
+        if file_type == FileType::Directory {
+            if let Some(children_vec) = children.as_mut() {
+                for entry in fs::read_dir(path)? {
+                    let entry = entry?;
+                    let child_path = entry.path();
+
+                    if let Some(file_name) = child_path.file_name().and_then(|n| n.to_str()) {
+                        // Skip hidden files
+                        if file_name.starts_with('.') {
+                            continue;
+                        }
+                        if child_path.is_dir() && hidden_dirs.contains(&child_path) {
+                            continue;
+                        }
+                        if child_path.is_dir()
+                            || is_markdown_file(&child_path)
+                            || is_image_file(&child_path)
+                            || has_extra_extension(&child_path, extra_extensions)
+                        {
+                            children_vec.push(Self::build_tree_recursive(
+                                &child_path,
+                                file_name,
+                                extra_extensions,
+                                hidden_dirs,
+                            )?);
+                        }
+                    }
+                }
+
+                // Sort children: directories first (based on Ord impl), then alphanumerically by name.
+                children_vec.sort_by(|a, b| {
+                    a.file_type
+                        .cmp(&b.file_type)
+                        .then_with(|| nat_compare(&a.name, &b.name))
+                });
+            }
+        }
+
+        let name = if file_type == FileType::Markdown {
+            file_stem_string(path)
+        } else {
+            name.to_string()
+        };
+
+        Ok(FileNode {
+            name,
+            path: path.to_path_buf(),
+            file_type,
+            children,
+        })
+    }
+
+    /// Returns a list of all directory paths in the vault.
+    pub fn get_all_directory_paths(&self) -> Result<Vec<PathBuf>> {
+        let root_node = self.get_file_tree()?;
+        let mut dirs = Vec::new();
+        // Add the root directory itself
+        dirs.push(root_node.path.clone());
+        Self::collect_dirs_recursive(&root_node, &mut dirs);
+        Ok(dirs)
+    }
+
+    /// Helper function to recursively collect directory paths.
+    fn collect_dirs_recursive(node: &FileNode, dirs: &mut Vec<PathBuf>) {
+        if let Some(children) = &node.children {
+            for child in children {
+                if child.file_type == FileType::Directory {
+                    dirs.push(child.path.clone());
+                    Self::collect_dirs_recursive(child, dirs);
+                }
+            }
+        }
+    }
+
+    /// Finds all broken links in the vault and aggregates them by target.
+    #[instrument(level = "debug", skip(self))]
+    pub fn get_all_broken_links(&self) -> Result<Vec<BrokenLink>> {
+        let mut broken_links_map: HashMap<String, HashSet<PageHeader>> = HashMap::new();
+
+        // Iterate through all pages and their outgoing links
+        for (source_path, page) in &self.pages {
+            for link in &page.links {
+                // A link is broken if it cannot be resolved by the indexer.
+                if self.resolve_link(link, source_path).is_none() {
+                    let source_header = PageHeader {
+                        path: source_path.clone(),
+                        title: page.title.clone(),
+                    };
+                    // Add the source page to the set for this broken target.
+                    broken_links_map
+                        .entry(link.target.clone())
+                        .or_default()
+                        .insert(source_header);
+                }
+            }
+        }
+
+        // Convert the map into the final Vec<BrokenLink> structure for the frontend.
+        let mut result: Vec<BrokenLink> = broken_links_map
+            .into_iter()
+            .map(|(target, sources_set)| {
+                let mut sources: Vec<PageHeader> = sources_set.into_iter().collect();
+                // Sort the source pages by title using natural ordering.
+                sources.sort_by(|a, b| nat_compare(&a.title, &b.title));
+                BrokenLink { target, sources }
+            })
+            .collect();
+
+        // Sort the final list of broken links by their target name using natural ordering.
+        result.sort_by(|a, b| nat_compare(&a.target, &b.target));
+
+        Ok(result)
+    }
+
+    /// Returns the targets of a single page's outgoing links that don't resolve,
+    /// in the order they first appear. This is much cheaper than
+    /// `get_all_broken_links` when only one page is of interest.
+    ///
+    /// Returns an empty list if the page isn't indexed.
+    pub fn get_broken_links_for_page(&self, path: &Path) -> Vec<String> {
+        let Some(page) = self.pages.get(path) else {
+            return Vec::new();
+        };
+        let mut seen = HashSet::new();
+        page.links
+            .iter()
+            .filter(|link| self.resolve_link(link, path).is_none())
+            .filter(|link| seen.insert(link.target.clone()))
+            .map(|link| link.target.clone())
+            .collect()
+    }
+}
+
+/// A tag in the outline built by `Indexer::export_tags_opml`, with its nested tags
+/// and its pages.
+#[derive(Default)]
+struct TagOutline {
+    label: String,
+    children: Vec<TagOutline>,
+    pages: Vec<PageHeader>,
+}
+
+impl TagOutline {
+    /// Returns the nested tag named `label`, adding it if it doesn't exist yet.
+    fn child(&mut self, label: &str) -> &mut TagOutline {
+        let index = match self
+            .children
+            .iter()
+            .position(|child| child.label.eq_ignore_ascii_case(label))
+        {
+            Some(index) => index,
+            None => {
+                self.children.push(TagOutline {
+                    label: label.to_string(),
+                    ..Default::default()
+                });
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    /// Appends this tag as an OPML `<outline>` element, indented to `depth`.
+    fn write_opml(&self, opml: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let text = html_escape::encode_double_quoted_attribute(&self.label);
+        opml.push_str(&format!("{indent}<outline text=\"{text}\">\n"));
+        for child in &self.children {
+            child.write_opml(opml, depth + 1);
+        }
+        for page in &self.pages {
+            let title = html_escape::encode_double_quoted_attribute(&page.title);
+            opml.push_str(&format!("{indent}  <outline text=\"{title}\"/>\n"));
+        }
+        opml.push_str(&format!("{indent}</outline>\n"));
+    }
+}
+
+/// Compares two manifests from `Indexer::get_manifest`, reporting the pages
+/// added, removed and modified between the `old` and the `new` one.
+pub fn diff_manifests(
+    old: &HashMap<PathBuf, String>,
+    new: &HashMap<PathBuf, String>,
+) -> ManifestDiff {
+    let mut diff = ManifestDiff::default();
+    for (path, hash) in new {
+        match old.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(old_hash) if old_hash != hash => diff.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .keys()
+        .filter(|path| !new.contains_key(*path))
+        .cloned()
+        .collect();
+
+    diff.added.sort();
+    diff.removed.sort();
+    diff.modified.sort();
+    diff
+}
+
+/// Creates the index entry for a file whose frontmatter couldn't be parsed. It has
+/// no metadata, but its content is still hashed so that manifests notice edits.
+fn unparsed_page(path: &Path) -> Page {
+    let content = fs::read(path).unwrap_or_default();
+    Page {
+        path: path.to_path_buf(),
+        title: file_stem_string(path),
+        tags: HashSet::new(),
+        links: Vec::new(),
+        backlinks: HashSet::new(),
+        frontmatter: serde_json::Value::Null,
+        word_count: 0,
+        image_refs: Vec::new(),
+        external_links: Vec::new(),
+        content_hash: content_hash(&String::from_utf8_lossy(&content)),
+        parse_failed: true,
+        modified: fs::metadata(path).and_then(|m| m.modified()).ok(),
+        excerpt: None,
+    }
+}
+
+/// Returns `true` if the file at `path` has one of `extensions`, ignoring case and
+/// any leading dot they are written with.
+fn has_extra_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            extensions.iter().any(|extra| {
+                extra
+                    .trim()
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(ext)
+            })
+        })
+}
+
+/// Returns `true` if any string value in the frontmatter, at any depth, contains
+/// `needle`, which must be lowercase.
+fn frontmatter_contains(value: &serde_json::Value, needle: &str) -> bool {
+    match value {
+        serde_json::Value::String(s) => s.to_lowercase().contains(needle),
+        serde_json::Value::Array(items) => {
+            items.iter().any(|item| frontmatter_contains(item, needle))
+        }
+        serde_json::Value::Object(map) => map.values().any(|v| frontmatter_contains(v, needle)),
+        // Other types like Number, Bool, Null are skipped.
+        _ => false,
+    }
+}
+
+/// Writes a link back out as wikilink syntax, e.g. `![[Vex#Past|the rogue]]`.
+fn link_text(link: &Link) -> String {
+    let mut text = String::new();
+    if link.embed {
+        text.push('!');
+    }
+    text.push_str("[[");
+    text.push_str(&link.target);
+    if let Some(section) = &link.section {
+        text.push('#');
+        text.push_str(section);
+    }
+    if let Some(alias) = &link.alias {
+        text.push('|');
+        text.push_str(alias);
+    }
+    text.push_str("]]");
+    text
+}
+
+/// Returns the stable id in a page's frontmatter, if it has one. String ids are
+/// trimmed; numeric ids are used as text.
+fn page_uid(frontmatter: &serde_json::Value) -> Option<String> {
+    UID_KEYS.iter().find_map(|key| match frontmatter.get(key)? {
+        serde_json::Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+/// Counts the leading path components two folders have in common.
+fn shared_depth(a: &Path, b: &Path) -> usize {
+    a.components()
+        .zip(b.components())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{IMAGES_DIR_NAME, VAULT_DATA_DIR_NAME};
+    use crate::events::FileEvent;
+    use std::{collections::HashSet, fs, path::PathBuf};
+    use tempfile::tempdir;
+
+    /// Helper function to set up a temporary vault with some files
+    fn setup_test_vault() -> (tempfile::TempDir, PathBuf, PathBuf, PathBuf) {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        let page1_path = root.join("Page One.md");
+        fs::write(
+            &page1_path,
+            r#"---
+title: "First Page"
+tags: ["alpha", "beta"]
+---
+This page links to [[Page Two]].
+"#,
+        )
+        .unwrap();
+
+        let page2_path = root.join("Page Two.md");
+        fs::write(
+            &page2_path,
+            r#"---
+title: "Second Page"
+tags: ["beta", "gamma"]
+---
+This page links back to [[Page One]] and also to [[Page Three|a different name]].
+"#,
+        )
+        .unwrap();
+
+        let page3_path = root.join("Page Three.md");
+        fs::write(
+            &page3_path,
+            r#"---
+title: "Third Page"
+tags: ["gamma"]
+---
+No outgoing links here.
+"#,
+        )
+        .unwrap();
+
+        (dir, page1_path, page2_path, page3_path)
+    }
+
+    #[test]
+    fn test_indexer_scan_vault() {
+        let (_dir, page1_path, page2_path, page3_path) = setup_test_vault();
+        let root = _dir.path();
+        let backups = root.join(VAULT_DATA_DIR_NAME).join("backups");
+        fs::create_dir_all(&backups).unwrap();
+        fs::write(backups.join("20240305T100000.000.md"), "A backup").unwrap();
+        let mut indexer = Indexer::new(root);
+
+        indexer.scan_vault(root).unwrap();
+
+        // Test pages count; backups in the data folder are not pages.
+        assert_eq!(indexer.pages.len(), 3);
+
+        // Test tags
+        assert_eq!(indexer.tags.len(), 3);
+        assert_eq!(
+            indexer.tags.get("alpha").unwrap(),
+            &HashSet::from([page1_path.clone()])
+        );
+        assert_eq!(
+            indexer.tags.get("beta").unwrap(),
+            &HashSet::from([page1_path.clone(), page2_path.clone()])
+        );
+        assert_eq!(
+            indexer.tags.get("gamma").unwrap(),
+            &HashSet::from([page2_path.clone(), page3_path.clone()])
+        );
+
+        // Test link graph and backlinks
+        let page1 = indexer.pages.get(&page1_path).unwrap();
+        let page2 = indexer.pages.get(&page2_path).unwrap();
+        let page3 = indexer.pages.get(&page3_path).unwrap();
+
+        // Page 1 has an outgoing link to Page 2, so Page 2 should have a backlink from Page 1.
+        assert_eq!(page1.links.len(), 1);
+        assert!(page2.backlinks.contains(&page1_path));
+
+        // Page 2 links to Page 1 and Page 3.
+        assert_eq!(page2.links.len(), 2);
+        assert!(page1.backlinks.contains(&page2_path));
+        assert!(page3.backlinks.contains(&page2_path));
+
+        // Test link resolver
+        assert_eq!(
+            indexer.resolve_link(&page1.links[0], &page1_path).unwrap(),
+            page2_path
+        );
+        assert_eq!(
+            indexer.resolve_link(&page2.links[0], &page2_path).unwrap(),
+            page1_path
+        );
+        assert_eq!(
+            indexer.resolve_link(&page2.links[1], &page2_path).unwrap(),
+            page3_path
+        );
+    }
+
+    #[test]
+    fn test_indexer_file_events() {
+        let (_dir, page1_path, page2_path, page3_path) = setup_test_vault();
+        let root = _dir.path();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        // --- Test Deletion ---
+        indexer.handle_event_and_rebuild(&FileEvent::Deleted(page1_path.clone()));
+
+        assert_eq!(indexer.pages.len(), 2);
+        assert!(!indexer.tags.contains_key("alpha")); // alpha tag should be gone
+
+        // The link from page 2 to the now-deleted page 1 will be dangling,
+        // but the backlink *from* page 1 on other pages should be removed.
+        // Let's re-fetch Page 3 to check its backlinks.
+        let page3 = indexer.pages.get(&page3_path).unwrap();
+        assert!(page3.backlinks.contains(&page2_path)); // This should still be there.
+
+        let page2_after_delete = indexer.pages.get(&page2_path).unwrap();
+        assert!(page2_after_delete.backlinks.is_empty()); // Backlink from page1 is gone.
+
+        // --- Test Creation ---
+        let new_page_path = root.join("New Page.md");
+        fs::write(
+            &new_page_path,
+            r#"---
+tags: ["new", "alpha"]
+---
+Linking to [[Page Two]]
+"#,
+        )
+        .unwrap();
+        indexer.handle_event_and_rebuild(&FileEvent::Created(new_page_path.clone()));
+
+        assert_eq!(indexer.pages.len(), 3);
+        assert!(indexer.tags.contains_key("new"));
+        assert!(indexer.tags.contains_key("alpha")); // alpha is back
+        let page2 = indexer.pages.get(&page2_path).unwrap();
+        // Page 2 should now have a backlink from New Page
+        assert!(page2.backlinks.contains(&new_page_path));
+        assert_eq!(page2.backlinks.len(), 1);
+
+        // --- Test Modification ---
+        fs::write(
+            &page3_path,
+            r#"---
+title: "Third Page Modified"
+tags: ["gamma", "modified"]
+---
+Now I link to [[Page Two]]!
+"#,
+        )
+        .unwrap();
+        indexer.handle_event_and_rebuild(&FileEvent::Modified(page3_path.clone()));
+        let page3 = indexer.pages.get(&page3_path).unwrap();
+        assert_eq!(page3.title, "Third Page Modified");
+        assert!(page3.tags.contains("modified"));
+        assert_eq!(page3.links.len(), 1);
+
+        let page2 = indexer.pages.get(&page2_path).unwrap();
+        // Page 2 should now have backlinks from both New Page and Page 3
+        assert_eq!(page2.backlinks.len(), 2);
+        assert!(page2.backlinks.contains(&new_page_path));
+        assert!(page2.backlinks.contains(&page3_path));
+    }
+
+    #[test]
+    fn test_resolve_duplicate_names_by_proximity() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let nested = root.join("north").join("towns");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join("south")).unwrap();
+
+        let north_intro = root.join("north").join("Intro.md");
+        let south_intro = root.join("south").join("Intro.md");
+        fs::write(&north_intro, "North.").unwrap();
+        fs::write(&south_intro, "South.").unwrap();
+
+        let south_page = root.join("south").join("Capital.md");
+        let town_page = nested.join("Village.md");
+        let home_page = root.join("Home.md");
+        fs::write(&south_page, "See [[Intro]].").unwrap();
+        fs::write(&town_page, "See [[Intro]].").unwrap();
+        fs::write(&home_page, "See [[Intro]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        // Same folder wins.
+        assert_eq!(
+            indexer.resolve_name("Intro", Some(&south_page)),
+            Some(south_intro.clone())
+        );
+        // Otherwise the page in the nearest ancestor folder wins.
+        assert_eq!(
+            indexer.resolve_name("intro", Some(&town_page)),
+            Some(north_intro.clone())
+        );
+        // Without a nearer candidate, the choice is stable: shallowest, then by path.
+        assert_eq!(
+            indexer.resolve_name("Intro", Some(&home_page)),
+            Some(north_intro.clone())
+        );
+        assert_eq!(indexer.resolve_target("Intro"), Some(north_intro.clone()));
+
+        // Backlinks follow the proximity-based resolution.
+        let south_backlinks = &indexer.pages[&south_intro].backlinks;
+        assert_eq!(south_backlinks, &HashSet::from([south_page]));
+        let north_backlinks = &indexer.pages[&north_intro].backlinks;
+        assert_eq!(north_backlinks, &HashSet::from([town_page, home_page]));
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        let (_dir, page1_path, _, page3_path) = setup_test_vault();
+        let root = _dir.path();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        assert_eq!(indexer.resolve_target("Page One"), Some(page1_path));
+        assert_eq!(
+            indexer.resolve_target(" page three#Some Section|Alias "),
+            Some(page3_path)
+        );
+        assert_eq!(indexer.resolve_target("Missing Page"), None);
+        assert_eq!(indexer.resolve_target(""), None);
+    }
+
+    #[test]
+    fn test_tags_are_grouped_case_insensitively() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("A.md"), "---\ntags: [Beta]\n---\n").unwrap();
+        fs::write(root.join("B.md"), "---\ntags: [beta]\n---\n").unwrap();
+        fs::write(root.join("C.md"), "---\ntags: [Beta, Alpha]\n---\n").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        // Both casings share one entry, keyed by the lowercased tag.
+        assert_eq!(indexer.tags.len(), 2);
+        assert_eq!(indexer.tags.get("beta").unwrap().len(), 3);
+        assert!(!indexer.tags.contains_key("Beta"));
+
+        // The most common casing is used as the display label.
+        let tags = indexer.get_all_tags().unwrap();
+        let labels: Vec<&str> = tags.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, vec!["Alpha", "Beta"]);
+        assert_eq!(tags[1].1.len(), 3);
+
+        assert_eq!(
+            indexer.get_tag_cooccurrence(),
+            vec![("Alpha".to_string(), "Beta".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_get_folder_stats() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let towns = root.join("Places").join("Towns");
+        fs::create_dir_all(&towns).unwrap();
+        fs::create_dir_all(root.join("images")).unwrap();
+        fs::create_dir_all(root.join(".hidden")).unwrap();
+
+        fs::write(root.join("Home.md"), "1234").unwrap();
+        fs::write(root.join("images").join("map.png"), "12").unwrap();
+        fs::write(root.join("Places").join("Places.md"), "123").unwrap();
+        fs::write(towns.join("Town.md"), "12345").unwrap();
+        fs::write(towns.join("charter.pdf"), "1").unwrap();
+        fs::write(root.join(".hidden").join("secret.md"), "ignored").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let stats = indexer.get_folder_stats().unwrap();
+
+        let find = |path: &Path| stats.iter().find(|s| s.path == path).unwrap();
+
+        let root_stat = find(root);
+        assert_eq!(stats[0].path, root);
+        assert_eq!(
+            (root_stat.pages, root_stat.images, root_stat.attachments),
+            (3, 1, 1)
+        );
+        assert_eq!(root_stat.total_bytes, 15);
+
+        let places = find(&root.join("Places"));
+        assert_eq!((places.pages, places.images, places.attachments), (2, 0, 1));
+        assert_eq!(places.total_bytes, 9);
+
+        let towns_stat = find(&towns);
+        assert_eq!((towns_stat.pages, towns_stat.attachments), (1, 1));
+        assert_eq!(towns_stat.total_bytes, 6);
+
+        assert!(!stats.iter().any(|s| s.path.ends_with(".hidden")));
+    }
+
+    #[test]
+    fn test_get_link_degree_map() {
+        let (_dir, page1_path, page2_path, page3_path) = setup_test_vault();
+        let root = _dir.path();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let degrees = indexer.get_link_degree_map();
+
+        assert_eq!(degrees.len(), 3);
+        assert_eq!(degrees[&page1_path], (1, 1));
+        assert_eq!(degrees[&page2_path], (2, 1));
+        assert_eq!(degrees[&page3_path], (0, 1));
+    }
+
+    #[test]
+    fn test_get_pages_by_length() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("Stub.md"), "---\ntitle: Stub\n---\nTodo.").unwrap();
+        fs::write(
+            root.join("Medium.md"),
+            "# Heading\n\nFour words right here.",
+        )
+        .unwrap();
+        fs::write(root.join("Long.md"), "word ".repeat(50)).unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles = |pages: Vec<(PageHeader, usize)>| -> Vec<(String, usize)> {
+            pages
+                .into_iter()
+                .map(|(p, words)| (p.title, words))
+                .collect()
+        };
+
+        assert_eq!(
+            titles(indexer.get_pages_by_length(true, 10)),
+            vec![
+                ("Stub".to_string(), 1),
+                ("Medium".to_string(), 5),
+                ("Long".to_string(), 50)
+            ]
+        );
+        assert_eq!(
+            titles(indexer.get_pages_by_length(false, 2)),
+            vec![("Long".to_string(), 50), ("Medium".to_string(), 5)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_uid() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        let tavern = root.join("Tavern.md");
+        fs::write(&vex, "---\nuid: ' npc-0042 '\nid: ignored\n---\n").unwrap();
+        fs::write(
+            &tavern,
+            "---\nid: 7\n---\nRun by [[uid:npc-0042|the owner]].",
+        )
+        .unwrap();
+        fs::write(root.join("Plain.md"), "See [[uid:missing]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        assert_eq!(indexer.resolve_uid("npc-0042"), Some(vex.clone()));
+        assert_eq!(indexer.resolve_uid("7"), Some(tavern.clone()));
+        assert_eq!(indexer.resolve_uid("ignored"), None);
+        assert_eq!(
+            indexer.resolve_target("uid:npc-0042#History"),
+            Some(vex.clone())
+        );
+
+        // Uid links count as ordinary links.
+        assert!(indexer.pages[&vex].backlinks.contains(&tavern));
+        let broken = indexer.get_all_broken_links().unwrap();
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].target, "uid:missing");
+        assert!(indexer.get_uid_collisions().is_empty());
+    }
+
+    #[test]
+    fn test_uid_collisions_are_reported() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let first = root.join("A.md");
+        let second = root.join("B.md");
+        fs::write(&first, "---\nuid: shared\n---\n").unwrap();
+        fs::write(&second, "---\nuid: shared\n---\n").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let collisions = indexer.get_uid_collisions();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].0, "shared");
+        let paths: Vec<&PathBuf> = collisions[0].1.iter().map(|p| &p.path).collect();
+        assert_eq!(paths, vec![&first, &second]);
+        // The first page in path order keeps the id.
+        assert_eq!(indexer.resolve_uid("shared"), Some(first));
+    }
+
+    #[test]
+    fn test_get_missing_images() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("images")).unwrap();
+        fs::write(root.join("images").join("map.png"), [0u8; 4]).unwrap();
+        let page_path = root.join("City.md");
+        fs::write(
+            &page_path,
+            "---\nimage: portrait.png\n---\n![[map.png|The map]] and ![[mpa.png]], ![[mpa.png]] again.\n![remote](https://example.com/x.png)",
+        )
+        .unwrap();
+        fs::write(root.join("Fine.md"), "![[map.png]]").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        assert_eq!(
+            indexer.get_missing_images_for_page(&page_path),
+            vec!["portrait.png".to_string(), "mpa.png".to_string()]
+        );
+        let all = indexer.get_all_missing_images();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].0.path, page_path);
+    }
+
+    #[test]
+    fn test_unparsed_pages_are_hashed() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let broken = root.join("Broken.md");
+        fs::write(&broken, "---\ntags: [npc\n---\nOops.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        assert!(indexer.pages[&broken].parse_failed);
+        assert!(!indexer.get_manifest()[Path::new("Broken.md")].is_empty());
+
+        fs::write(&broken, "---\ntags: [npc\n---\nOops, edited.").unwrap();
+        let mut fresh = Indexer::new(root);
+        fresh.scan_vault(root).unwrap();
+        assert_eq!(
+            indexer.compare_with(&fresh).outdated,
+            vec![PathBuf::from("Broken.md")]
+        );
+    }
+
+    #[test]
+    fn test_get_untagged_pages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("Vex.md"), "---\ntags: [npc]\n---\nA rogue.").unwrap();
+        fs::write(root.join("Harbor.md"), "---\ntitle: Harbor\n---\nA port.").unwrap();
+        fs::write(root.join("Notes.md"), "Just notes.").unwrap();
+        fs::write(root.join("Broken.md"), "---\ntags: [npc\n---\nOops.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let untagged: Vec<(String, bool)> = indexer
+            .get_untagged_pages()
+            .into_iter()
+            .map(|(page, parse_failed)| (page.title, parse_failed))
+            .collect();
+        assert_eq!(
+            untagged,
+            vec![
+                ("Broken".to_string(), true),
+                ("Harbor".to_string(), false),
+                ("Notes".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_self_linking_pages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let narcissus = root.join("Narcissus.md");
+        fs::write(&narcissus, "See [[Narcissus]], and [[Echo]].").unwrap();
+        fs::write(root.join("Echo.md"), "Pines for [[Narcissus]].").unwrap();
+        fs::write(
+            root.join("Guide.md"),
+            "Jump to [[Guide#Usage]].\n\n## Usage",
+        )
+        .unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let pages = indexer.get_self_linking_pages();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].path, narcissus);
+    }
+
+    #[test]
+    fn test_get_critical_pages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        // Two triangles, joined only through Bridge.
+        fs::write(root.join("Alpha.md"), "[[Beta]] and [[Bridge]].").unwrap();
+        fs::write(root.join("Beta.md"), "[[Bridge]].").unwrap();
+        fs::write(root.join("Bridge.md"), "Leads to [[Gamma]].").unwrap();
+        fs::write(root.join("Gamma.md"), "[[Delta]].").unwrap();
+        fs::write(root.join("Delta.md"), "Back to [[Bridge]].").unwrap();
+        // A page hanging off the second triangle.
+        fs::write(root.join("Leaf.md"), "Only knows [[Delta]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles: Vec<String> = indexer
+            .get_critical_pages()
+            .into_iter()
+            .map(|page| page.title)
+            .collect();
+        // Gamma is well connected, so removing it leaves the rest reachable.
+        assert_eq!(titles, vec!["Bridge", "Delta"]);
+    }
+
+    #[test]
+    fn test_export_tags_opml() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("Harbor.md"),
+            "---\ntags: [location/city, trade]\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Tom & Jerry's.md"),
+            "---\ntags: [location/city]\n---\n",
+        )
+        .unwrap();
+        fs::write(root.join("North.md"), "---\ntags: [location]\n---\n").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let opml = indexer.export_tags_opml().unwrap();
+
+        assert!(
+            opml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">")
+        );
+        assert!(opml.contains(
+            "    <outline text=\"location\">\n      <outline text=\"city\">\n        \
+             <outline text=\"Harbor\"/>\n        <outline text=\"Tom &amp; Jerry's\"/>\n      \
+             </outline>\n      <outline text=\"North\"/>\n    </outline>\n"
+        ));
+        assert!(opml.contains("    <outline text=\"trade\">\n      <outline text=\"Harbor\"/>"));
+    }
+
+    #[test]
+    fn test_get_all_references() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        fs::write(&vex, "# Past\nA rogue.").unwrap();
+        fs::write(root.join("Tavern.md"), "[[Vex]] drinks here.").unwrap();
+        fs::write(
+            root.join("Harbor.md"),
+            "[[vex|The rogue]] sails, see [[Vex#Past]]. ![[Vex]]",
+        )
+        .unwrap();
+        fs::write(root.join("Unrelated.md"), "[[Harbor]]").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let references = indexer.get_all_references(&vex);
+
+        let texts = |group: &[Reference]| -> Vec<(String, String)> {
+            group
+                .iter()
+                .map(|r| (r.source.title.clone(), r.text.clone()))
+                .collect()
+        };
+        assert_eq!(
+            texts(&references.direct),
+            [("Tavern".to_string(), "[[Vex]]".to_string())]
+        );
+        assert_eq!(
+            texts(&references.aliased),
+            [("Harbor".to_string(), "[[vex|The rogue]]".to_string())]
+        );
+        assert_eq!(
+            texts(&references.sections),
+            [("Harbor".to_string(), "[[Vex#Past]]".to_string())]
+        );
+        assert_eq!(
+            texts(&references.embeds),
+            [("Harbor".to_string(), "![[Vex]]".to_string())]
+        );
+
+        assert_eq!(
+            indexer.get_all_references(&root.join("Tavern.md")),
+            References::default()
+        );
+    }
+
+    #[test]
+    fn test_get_external_links() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let harbor = root.join("Harbor.md");
+        fs::write(
+            &harbor,
+            "---\nsource: https://example.com/frontmatter\n---\n\
+             See [the map](https://example.com/map \"Map\") and https://example.org/wiki/Port_(city).\n\
+             Mirror: <http://example.net/docs?page=2#top>, again https://example.org/wiki/Port_(city).\n\
+             Not links: [[Vex]], ftp://example.com, https://",
+        )
+        .unwrap();
+        fs::write(root.join("Vex.md"), "No links here.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let links: Vec<String> = indexer
+            .get_external_links()
+            .into_iter()
+            .map(|(path, url)| {
+                assert_eq!(path, harbor);
+                url
+            })
+            .collect();
+        assert_eq!(
+            links,
+            [
+                "https://example.com/map",
+                "https://example.org/wiki/Port_(city)",
+                "http://example.net/docs?page=2#top",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_with_and_without_frontmatter() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        fs::write(
+            &vex,
+            "---\ninfobox:\n  allegiance:\n    - The Crimson Hand\n---\nA rogue of the docks.",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Harbor.md"),
+            "The docks, watched by the crimson hand.",
+        )
+        .unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles = |results: Vec<SearchResult>| -> Vec<String> {
+            results.into_iter().map(|r| r.page.title).collect()
+        };
+        assert_eq!(titles(indexer.search("CRIMSON", false, false)), ["Harbor"]);
+        assert_eq!(titles(indexer.search("rogue", false, false)), ["Vex"]);
+
+        let results = indexer.search("crimson", true, false);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].page.path, vex);
+        assert!(!results[1].in_body);
+        assert!(results[1].in_frontmatter);
+        assert!(results[0].in_body && !results[0].in_frontmatter);
+
+        // Frontmatter keys aren't searched, only values.
+        assert!(indexer.search("allegiance", true, false).is_empty());
+        assert!(indexer.search("  ", true, false).is_empty());
+
+        // In player mode, GM blocks aren't searched.
+        fs::write(
+            root.join("Harbor.md"),
+            "The docks.\n\n:::gm\nWatched by the crimson hand.\n:::",
+        )
+        .unwrap();
+        assert_eq!(titles(indexer.search("crimson", false, false)), ["Harbor"]);
+        assert!(indexer.search("crimson", false, true).is_empty());
+    }
+
+    #[test]
+    fn test_diff_manifests() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("people")).unwrap();
+        fs::write(root.join("people").join("Vex.md"), "A rogue.").unwrap();
+        fs::write(root.join("Harbor.md"), "A port.").unwrap();
+        fs::write(root.join("Old Map.md"), "Lost.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let before = indexer.get_manifest();
+        assert_eq!(before.len(), 3);
+        assert!(before.contains_key(Path::new("people").join("Vex.md").as_path()));
+
+        fs::write(root.join("people").join("Vex.md"), "A reformed rogue.").unwrap();
+        fs::remove_file(root.join("Old Map.md")).unwrap();
+        fs::write(root.join("Tavern.md"), "Warm.").unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let after = indexer.get_manifest();
+
+        let diff = diff_manifests(&before, &after);
+        assert_eq!(diff.added, vec![PathBuf::from("Tavern.md")]);
+        assert_eq!(diff.removed, vec![PathBuf::from("Old Map.md")]);
+        assert_eq!(diff.modified, vec![Path::new("people").join("Vex.md")]);
+
+        assert_eq!(diff_manifests(&after, &after), ManifestDiff::default());
+    }
+
+    #[test]
+    fn test_get_pages_by_status_field() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("Idea A.md"), "---\nstatus: \" Idea \"\n---\n").unwrap();
+        fs::write(root.join("Idea B.md"), "---\nstatus: [Idea, Draft]\n---\n").unwrap();
+        fs::write(root.join("Draft C.md"), "---\nstatus: Draft\n---\n").unwrap();
+        fs::write(root.join("Loose D.md"), "No frontmatter here.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let board = indexer.get_pages_by_status_field("status");
+        let titles = |column: &str| -> Vec<String> {
+            board[column].iter().map(|p| p.title.clone()).collect()
+        };
+
+        assert_eq!(board.len(), 3);
+        assert_eq!(titles("Idea"), vec!["Idea A", "Idea B"]);
+        assert_eq!(titles("Draft"), vec!["Draft C", "Idea B"]);
+        assert_eq!(titles(UNSET_BOARD_COLUMN), vec!["Loose D"]);
+    }
+
+    #[test]
+    fn test_find_pages_by_glob() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let nested = root.join("lore").join("gods");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join("Home.md"), "").unwrap();
+        fs::write(root.join("lore").join("Creation.md"), "").unwrap();
+        fs::write(nested.join("Sun God.md"), "").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles = |pattern: &str| -> Vec<String> {
+            indexer
+                .find_pages_by_glob(pattern)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.title)
+                .collect()
+        };
+
+        // A recursive glob matches pages at any depth below the folder.
+        assert_eq!(titles("lore/**/*.md"), vec!["Creation", "Sun God"]);
+        // A single-level glob does not descend into subfolders.
+        assert_eq!(titles("lore/*.md"), vec!["Creation"]);
+        assert_eq!(titles("*.md"), vec!["Home"]);
+        assert!(titles("archive/**/*.md").is_empty());
+
+        assert!(indexer.find_pages_by_glob("lore/[").is_err());
+    }
+
+    #[test]
+    fn test_get_pages_with_min_backlinks() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("Capital.md"), "Seat of [[Queen]].").unwrap();
+        fs::write(root.join("Queen.md"), "Rules from the [[Capital]].").unwrap();
+        fs::write(root.join("Harbor.md"), "A day's ride from the [[Capital]].").unwrap();
+        fs::write(root.join("Village.md"), "Nobody links here.").unwrap();
 
-    /// Helper function to set up a temporary vault with some files
-    fn setup_test_vault() -> (tempfile::TempDir, PathBuf, PathBuf, PathBuf) {
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let hubs = indexer.get_pages_with_min_backlinks(2);
+        assert_eq!(hubs.len(), 1);
+        assert_eq!(hubs[0].0.title, "Capital");
+        assert_eq!(hubs[0].1, 2);
+
+        let counts: Vec<(String, usize)> = indexer
+            .get_pages_with_min_backlinks(1)
+            .into_iter()
+            .map(|(page, count)| (page.title, count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![("Capital".to_string(), 2), ("Queen".to_string(), 1)]
+        );
+        assert_eq!(indexer.get_pages_with_min_backlinks(0).len(), 4);
+    }
+
+    #[test]
+    fn test_get_pages_modified_since() {
         let dir = tempdir().unwrap();
         let root = dir.path();
+        let epoch = SystemTime::UNIX_EPOCH;
+        let day = std::time::Duration::from_secs(24 * 60 * 60);
+        for (name, days) in [
+            ("Old", 1),
+            ("Recent", 10),
+            ("Newest", 20),
+            ("Also Recent", 10),
+        ] {
+            let path = root.join(format!("{}.md", name));
+            fs::write(&path, "Text.").unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(epoch + day * days)
+                .unwrap();
+        }
 
-        let page1_path = root.join("Page One.md");
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles: Vec<String> = indexer
+            .get_pages_modified_since(epoch + day * 5)
+            .into_iter()
+            .map(|page| page.title)
+            .collect();
+        assert_eq!(titles, vec!["Newest", "Also Recent", "Recent"]);
+        assert!(indexer
+            .get_pages_modified_since(epoch + day * 20)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_find_pages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("Gandalf.md"), "Text.").unwrap();
+        fs::write(root.join("Morgana.md"), "Text.").unwrap();
+        fs::write(root.join("Big Gun Ready.md"), "Text.").unwrap();
         fs::write(
-            &page1_path,
-            r#"---
-title: "First Page"
-tags: ["alpha", "beta"]
----
-This page links to [[Page Two]].
-"#,
+            root.join("Mithrandir.md"),
+            "---\naliases: [Grey Pilgrim, Grey Wanderer]\n---\nText.",
         )
         .unwrap();
+        fs::write(root.join("Vex.md"), "---\naliases: The Rogue\n---\nText.").unwrap();
 
-        let page2_path = root.join("Page Two.md");
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let titles = |query| -> Vec<String> {
+            indexer
+                .fuzzy_find_pages(query)
+                .into_iter()
+                .map(|page| page.title)
+                .collect()
+        };
+
+        let found = titles("gnd");
+        assert_eq!(found[0], "Gandalf");
+        assert!(found.contains(&"Mithrandir".to_string()));
+        assert!(found.contains(&"Big Gun Ready".to_string()));
+        assert!(!found.contains(&"Morgana".to_string()));
+
+        // An exact prefix ranks above the same letters further in.
+        let found = titles("gan");
+        assert_eq!(found[0], "Gandalf");
+        assert_eq!(found.last().unwrap(), "Morgana");
+        assert_eq!(titles("pilgrim"), vec!["Mithrandir"]);
+        assert_eq!(titles("rogue"), vec!["Vex"]);
+        assert!(titles("").is_empty());
+    }
+
+    #[test]
+    fn test_get_pages_in_folder() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let places = root.join("places");
+        let docks = places.join("docks");
+        fs::create_dir_all(&docks).unwrap();
+        fs::write(root.join("Vex.md"), "Text.").unwrap();
+        fs::write(places.join("Harbor.md"), "Text.").unwrap();
+        fs::write(places.join("Abbey.md"), "Text.").unwrap();
+        fs::write(docks.join("Pier 7.md"), "Text.").unwrap();
+        fs::write(root.join("places-old.md"), "Text.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles = |recursive| -> Vec<String> {
+            indexer
+                .get_pages_in_folder(&places, recursive)
+                .into_iter()
+                .map(|page| page.title)
+                .collect()
+        };
+        assert_eq!(titles(false), vec!["Abbey", "Harbor"]);
+        assert_eq!(titles(true), vec!["Abbey", "Harbor", "Pier 7"]);
+        assert!(indexer
+            .get_pages_in_folder(&root.join("missing"), true)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_alphabetical_index() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        for name in [
+            "Vex",
+            "harbor",
+            "Hollow Keep",
+            "Harbor",
+            "7th Legion",
+            "Écu",
+            "élan",
+        ] {
+            fs::write(root.join(format!("{}.md", name)), "Text.").unwrap();
+        }
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let index: Vec<(char, Vec<String>)> = indexer
+            .get_alphabetical_index()
+            .into_iter()
+            .map(|(letter, pages)| (letter, pages.into_iter().map(|p| p.title).collect()))
+            .collect();
+        assert_eq!(
+            index,
+            vec![
+                (NON_ALPHABETIC_INDEX_GROUP, vec!["7th Legion".to_string()]),
+                (
+                    'H',
+                    vec![
+                        "Harbor".to_string(),
+                        "harbor".to_string(),
+                        "Hollow Keep".to_string()
+                    ]
+                ),
+                ('V', vec!["Vex".to_string()]),
+                ('É', vec!["Écu".to_string(), "élan".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_related_pages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
         fs::write(
-            &page2_path,
-            r#"---
-title: "Second Page"
-tags: ["beta", "gamma"]
----
-This page links back to [[Page One]] and also to [[Page Three|a different name]].
-"#,
+            &vex,
+            "---\ntags: [rogue, Harbor]\n---\nWorks the [[Docks]].",
         )
         .unwrap();
-
-        let page3_path = root.join("Page Three.md");
         fs::write(
-            &page3_path,
-            r#"---
-title: "Third Page"
-tags: ["gamma"]
----
-No outgoing links here.
-"#,
+            root.join("Mara.md"),
+            "---\ntags: [rogue, harbor]\n---\nA fence.",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Tobin.md"),
+            "---\ntags: [rogue]\n---\nA cutpurse.",
         )
         .unwrap();
+        fs::write(root.join("Gull.md"), "Sails from the [[Docks]].").unwrap();
+        fs::write(root.join("Docks.md"), "---\ntags: [place]\n---\nBusy.").unwrap();
 
-        (dir, page1_path, page2_path, page3_path)
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        let titles = |pages: Vec<PageHeader>| -> Vec<String> {
+            pages.into_iter().map(|p| p.title).collect()
+        };
+        // Mara shares two tags, Tobin one tag, and Gull links to the Docks too.
+        assert_eq!(
+            titles(indexer.get_related_pages(&vex, 10)),
+            ["Mara", "Gull", "Tobin"]
+        );
+        assert_eq!(titles(indexer.get_related_pages(&vex, 1)), ["Mara"]);
+        assert!(indexer
+            .get_related_pages(&root.join("Missing.md"), 10)
+            .is_empty());
     }
 
     #[test]
-    fn test_indexer_scan_vault() {
-        let (_dir, page1_path, page2_path, page3_path) = setup_test_vault();
-        let root = _dir.path();
-        let mut indexer = Indexer::new(root);
+    fn test_file_tree_lists_extra_file_types() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("Vex.md"), "A rogue.").unwrap();
+        fs::write(root.join("Heist.canvas"), "{\"nodes\": []}").unwrap();
+        fs::write(root.join("notes.txt"), "Not listed.").unwrap();
 
+        let mut indexer = Indexer::new(root);
         indexer.scan_vault(root).unwrap();
-
-        // Test pages count
-        assert_eq!(indexer.pages.len(), 3);
-
-        // Test tags
-        assert_eq!(indexer.tags.len(), 3);
+        let names = |tree: FileNode| -> Vec<(String, FileType)> {
+            tree.children
+                .unwrap()
+                .into_iter()
+                .map(|node| (node.name, node.file_type))
+                .collect()
+        };
         assert_eq!(
-            indexer.tags.get("alpha").unwrap(),
-            &HashSet::from([page1_path.clone()])
+            names(indexer.get_file_tree().unwrap()),
+            [("Vex".to_string(), FileType::Markdown)]
         );
+
+        indexer.settings.extra_file_extensions = vec![".Canvas".to_string()];
+        let tree = indexer.get_file_tree().unwrap();
         assert_eq!(
-            indexer.tags.get("beta").unwrap(),
-            &HashSet::from([page1_path.clone(), page2_path.clone()])
+            names(tree),
+            [
+                ("Heist.canvas".to_string(), FileType::Other),
+                ("Vex".to_string(), FileType::Markdown)
+            ]
         );
+        // Extra files are shown but not indexed as pages.
+        assert!(!indexer.pages.contains_key(&root.join("Heist.canvas")));
+    }
+
+    #[test]
+    fn test_inline_tags_are_indexed() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        fs::write(&vex, "A #Rogue from the #docks, not `#code`.").unwrap();
+        fs::write(root.join("Mara.md"), "---\ntags: [rogue]\n---\nA noble.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        assert_eq!(indexer.tags["rogue"].len(), 2);
+        assert_eq!(indexer.tags["docks"], HashSet::from([vex.clone()]));
+        assert!(!indexer.tags.contains_key("code"));
+
+        let tags = indexer.get_all_tags().unwrap();
+        let docks = tags.iter().find(|(tag, _)| tag == "docks").unwrap();
+        assert_eq!(docks.1[0].path, vex);
+    }
+
+    #[test]
+    fn test_file_tree_hides_asset_folders() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(IMAGES_DIR_NAME)).unwrap();
+        fs::write(root.join(IMAGES_DIR_NAME).join("map.png"), [0u8; 4]).unwrap();
+        fs::write(root.join("Vex.md"), "A rogue.").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let names = |tree: FileNode| -> Vec<String> {
+            tree.children
+                .unwrap()
+                .into_iter()
+                .map(|node| node.name)
+                .collect()
+        };
         assert_eq!(
-            indexer.tags.get("gamma").unwrap(),
-            &HashSet::from([page2_path.clone(), page3_path.clone()])
+            names(indexer.get_file_tree().unwrap()),
+            [IMAGES_DIR_NAME.to_string(), "Vex".to_string()]
         );
 
-        // Test link graph and backlinks
-        let page1 = indexer.pages.get(&page1_path).unwrap();
-        let page2 = indexer.pages.get(&page2_path).unwrap();
-        let page3 = indexer.pages.get(&page3_path).unwrap();
+        indexer.settings.hide_asset_folders = true;
+        assert_eq!(names(indexer.get_file_tree().unwrap()), ["Vex".to_string()]);
+    }
 
-        // Page 1 has an outgoing link to Page 2, so Page 2 should have a backlink from Page 1.
-        assert_eq!(page1.links.len(), 1);
-        assert!(page2.backlinks.contains(&page1_path));
+    #[test]
+    fn test_resolve_name_with_leading_path() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("people")).unwrap();
+        fs::create_dir_all(root.join("places")).unwrap();
+        fs::write(root.join("people").join("Vex.md"), "A rogue.").unwrap();
+        fs::write(root.join("places").join("Vex.md"), "A town.").unwrap();
+        fs::write(root.join("places").join("Harbor.md"), "A port.").unwrap();
+        fs::write(root.join("Index.md"), "[[places/Vex]] and [[people/Vex]].").unwrap();
 
-        // Page 2 links to Page 1 and Page 3.
-        assert_eq!(page2.links.len(), 2);
-        assert!(page1.backlinks.contains(&page2_path));
-        assert!(page3.backlinks.contains(&page2_path));
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
 
-        // Test link resolver
-        assert_eq!(indexer.resolve_link(&page1.links[0]).unwrap(), page2_path);
-        assert_eq!(indexer.resolve_link(&page2.links[0]).unwrap(), page1_path);
-        assert_eq!(indexer.resolve_link(&page2.links[1]).unwrap(), page3_path);
+        assert_eq!(
+            indexer.resolve_name("places/Vex", None),
+            Some(root.join("places").join("Vex.md"))
+        );
+        assert_eq!(
+            indexer.resolve_name("people/vex", None),
+            Some(root.join("people").join("Vex.md"))
+        );
+        // A stale path falls back to the name alone, if only one page has it and
+        // the folder doesn't exist.
+        assert_eq!(
+            indexer.resolve_name("archive/Harbor", None),
+            Some(root.join("places").join("Harbor.md"))
+        );
+        assert_eq!(indexer.resolve_name("archive/Vex", None), None);
+        assert_eq!(indexer.resolve_name("people/Harbor", None), None);
+        assert!(indexer.pages[&root.join("places").join("Vex.md")]
+            .backlinks
+            .contains(&root.join("Index.md")));
     }
 
     #[test]
-    fn test_indexer_file_events() {
-        let (_dir, page1_path, page2_path, page3_path) = setup_test_vault();
+    fn test_get_neighborhood() {
+        let (_dir, page1, page2, page3) = setup_test_vault();
         let root = _dir.path();
+        fs::write(root.join("Outsider.md"), "Links to [[Page Three]].").unwrap();
         let mut indexer = Indexer::new(root);
         indexer.scan_vault(root).unwrap();
 
-        // --- Test Deletion ---
-        indexer.handle_event_and_rebuild(&FileEvent::Deleted(page1_path.clone()));
-
-        assert_eq!(indexer.pages.len(), 2);
-        assert!(!indexer.tags.contains_key("alpha")); // alpha tag should be gone
+        // Page One links to Page Two, and Page Two links back to it and to Page Three.
+        let graph = indexer.get_neighborhood(&page1);
+        let nodes: Vec<&str> = graph.nodes.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(nodes, ["First Page", "Second Page"]);
+        assert_eq!(graph.edges.len(), 2);
+
+        let graph = indexer.get_neighborhood(&page3);
+        let nodes: Vec<&str> = graph.nodes.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(nodes, ["Third Page", "Outsider", "Second Page"]);
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.source == page2 && e.target == page3));
+        // Links between neighbors are included too.
+        assert!(graph.edges.iter().all(|e| e.target == page3));
 
-        // The link from page 2 to the now-deleted page 1 will be dangling,
-        // but the backlink *from* page 1 on other pages should be removed.
-        // Let's re-fetch Page 3 to check its backlinks.
-        let page3 = indexer.pages.get(&page3_path).unwrap();
-        assert!(page3.backlinks.contains(&page2_path)); // This should still be there.
+        assert_eq!(
+            indexer.get_neighborhood(&root.join("Missing.md")),
+            GraphData::default()
+        );
+    }
 
-        let page2_after_delete = indexer.pages.get(&page2_path).unwrap();
-        assert!(page2_after_delete.backlinks.is_empty()); // Backlink from page1 is gone.
+    #[test]
+    fn test_get_tag_counts() {
+        let (_dir, ..) = setup_test_vault();
+        let root = _dir.path();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
 
-        // --- Test Creation ---
-        let new_page_path = root.join("New Page.md");
-        fs::write(
-            &new_page_path,
-            r#"---
-tags: ["new", "alpha"]
----
-Linking to [[Page Two]]
-"#,
-        )
-        .unwrap();
-        indexer.handle_event_and_rebuild(&FileEvent::Created(new_page_path.clone()));
+        assert_eq!(
+            indexer.get_tag_counts(),
+            vec![
+                ("alpha".to_string(), 1),
+                ("beta".to_string(), 2),
+                ("gamma".to_string(), 2),
+            ]
+        );
+    }
 
-        assert_eq!(indexer.pages.len(), 3);
-        assert!(indexer.tags.contains_key("new"));
-        assert!(indexer.tags.contains_key("alpha")); // alpha is back
-        let page2 = indexer.pages.get(&page2_path).unwrap();
-        // Page 2 should now have a backlink from New Page
-        assert!(page2.backlinks.contains(&new_page_path));
-        assert_eq!(page2.backlinks.len(), 1);
+    #[test]
+    fn test_get_tag_cooccurrence() {
+        let (_dir, ..) = setup_test_vault();
+        let root = _dir.path();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
 
-        // --- Test Modification ---
-        fs::write(
-            &page3_path,
-            r#"---
-title: "Third Page Modified"
-tags: ["gamma", "modified"]
----
-Now I link to [[Page Two]]!
-"#,
-        )
-        .unwrap();
-        indexer.handle_event_and_rebuild(&FileEvent::Modified(page3_path.clone()));
-        let page3 = indexer.pages.get(&page3_path).unwrap();
-        assert_eq!(page3.title, "Third Page Modified");
-        assert!(page3.tags.contains("modified"));
-        assert_eq!(page3.links.len(), 1);
+        let pairs = indexer.get_tag_cooccurrence();
 
-        let page2 = indexer.pages.get(&page2_path).unwrap();
-        // Page 2 should now have backlinks from both New Page and Page 3
-        assert_eq!(page2.backlinks.len(), 2);
-        assert!(page2.backlinks.contains(&new_page_path));
-        assert!(page2.backlinks.contains(&page3_path));
+        // Page One has alpha/beta and Page Two has beta/gamma; Page Three has a single tag.
+        assert_eq!(
+            pairs,
+            vec![
+                ("alpha".to_string(), "beta".to_string(), 1),
+                ("beta".to_string(), "gamma".to_string(), 1),
+            ]
+        );
     }
 
     #[test]
@@ -706,4 +3110,49 @@ Now I link to [[Page Two]]!
         assert_eq!(missing_page.sources.len(), 1);
         assert_eq!(missing_page.sources[0].path, page1_path);
     }
+
+    #[test]
+    fn test_get_broken_links_for_page() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        let page1_path = root.join("Page One.md");
+        fs::write(
+            &page1_path,
+            "[[Page Two]], [[Missing Page]], [[Lost Page|lost]] and [[Missing Page]] again.",
+        )
+        .unwrap();
+        fs::write(root.join("Page Two.md"), "Links to [[Elsewhere]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        assert_eq!(
+            indexer.get_broken_links_for_page(&page1_path),
+            vec!["Missing Page".to_string(), "Lost Page".to_string()]
+        );
+        assert!(indexer
+            .get_broken_links_for_page(&root.join("Not Indexed.md"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_name_with_slash_stays_broken() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("AC")).unwrap();
+        fs::write(root.join("AC").join("Highway.md"), "A road.").unwrap();
+        fs::write(root.join("DC.md"), "A city.").unwrap();
+        let bands = root.join("Bands.md");
+        fs::write(&bands, "Loud: [[AC/DC]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+
+        assert_eq!(indexer.resolve_name("AC/DC", None), None);
+        assert_eq!(
+            indexer.get_broken_links_for_page(&bands),
+            vec!["AC/DC".to_string()]
+        );
+    }
 }
@@ -0,0 +1,84 @@
+//! External link checking.
+//!
+//! Requests each web URL found in the vault to find links that no longer resolve.
+//! Requests run concurrently, but at most `MAX_CONCURRENT_LINK_CHECKS` at a time.
+
+use crate::{
+    config::{LINK_CHECK_TIMEOUT, MAX_CONCURRENT_LINK_CHECKS},
+    error::Result,
+    models::DeadLink,
+};
+use reqwest::{Client, StatusCode};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+use tokio::sync::Semaphore;
+use tracing::{info, instrument, warn};
+
+/// Checks each link and returns the ones that are dead, in the order given.
+///
+/// Each distinct URL is requested once, however many pages use it. A link is dead
+/// if the request fails or times out, or the server answers with an error status.
+#[instrument(skip(links))]
+pub async fn find_dead_links(links: Vec<(PathBuf, String)>) -> Result<Vec<DeadLink>> {
+    let client = Client::builder().timeout(LINK_CHECK_TIMEOUT).build()?;
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_LINK_CHECKS));
+    let urls: BTreeSet<String> = links.iter().map(|(_, url)| url.clone()).collect();
+    info!("Checking {} external links", urls.len());
+
+    let handles: Vec<_> = urls
+        .into_iter()
+        .map(|url| {
+            let client = client.clone();
+            let permits = permits.clone();
+            tauri::async_runtime::spawn(async move {
+                // The semaphore is never closed, so acquiring a permit can't fail.
+                let _permit = permits.acquire_owned().await;
+                let failure = check_url(&client, &url).await;
+                (url, failure)
+            })
+        })
+        .collect();
+
+    let mut failures = HashMap::new();
+    for handle in handles {
+        match handle.await {
+            Ok((url, Some(reason))) => {
+                failures.insert(url, reason);
+            }
+            Ok((_, None)) => {}
+            Err(e) => warn!("A link check task failed: {}", e),
+        }
+    }
+
+    Ok(links
+        .into_iter()
+        .filter_map(|(path, url)| {
+            let reason = failures.get(&url)?.clone();
+            Some(DeadLink { path, url, reason })
+        })
+        .collect())
+}
+
+/// Requests `url` and returns why it is dead, or `None` if it responded.
+async fn check_url(client: &Client, url: &str) -> Option<String> {
+    let status = match client.head(url).send().await {
+        // Some servers don't answer `HEAD` requests, so those are retried with `GET`.
+        Ok(response)
+            if matches!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ) =>
+        {
+            match client.get(url).send().await {
+                Ok(response) => response.status(),
+                Err(e) => return Some(e.to_string()),
+            }
+        }
+        Ok(response) => response.status(),
+        Err(e) => return Some(e.to_string()),
+    };
+    (status.is_client_error() || status.is_server_error()).then(|| status.to_string())
+}
@@ -13,14 +13,17 @@ use tauri::Manager; // Required for the app handle and runtime scope management.
 use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
 use world::World;
 
+mod backup;
 mod commands;
 mod config;
 mod error;
 mod events;
+mod exporter;
 mod fonts;
 mod importer;
 mod indexer;
 mod licensing;
+mod link_checker;
 mod mediawiki_importer;
 mod models;
 mod parser;
@@ -28,8 +31,10 @@ mod renderer;
 mod sanitizer;
 mod template;
 mod utils;
+mod validation;
 mod watcher;
 mod wikilink;
+mod word_count;
 mod world;
 mod writer;
 
@@ -89,36 +94,98 @@ fn main() {
         // Register all our `#[tauri::command]` functions.
         .invoke_handler(tauri::generate_handler![
             commands::get_vault_path,
+            commands::get_active_vault,
             commands::initialize_vault,
             commands::get_all_tags,
+            commands::get_tag_counts,
+            commands::get_tag_cooccurrence,
+            commands::export_tags_opml,
+            commands::get_link_degrees,
+            commands::get_hub_pages,
+            commands::get_pages_modified_since,
+            commands::get_alphabetical_index,
+            commands::get_pages_by_length,
+            commands::get_untagged_pages,
+            commands::get_all_references,
+            commands::get_self_linking_pages,
+            commands::get_critical_pages,
+            commands::get_manifest,
+            commands::diff_manifests,
+            commands::verify_index,
+            commands::get_page_missing_images,
+            commands::get_all_missing_images,
+            commands::get_external_links,
+            commands::check_external_links,
+            commands::record_word_count_snapshot,
+            commands::get_word_count_history,
+            commands::get_board,
+            commands::search,
+            commands::fuzzy_find_pages,
+            commands::find_pages_by_glob,
             commands::render_page_preview,
+            commands::render_section_preview,
             commands::build_page_view,
             commands::write_page_content,
             commands::get_file_tree,
             commands::create_new_file,
             commands::create_new_folder,
+            commands::ensure_images_dir,
+            commands::save_image,
             commands::rename_path,
+            commands::rename_image,
             commands::delete_path,
+            commands::merge_pages,
+            commands::get_backups,
+            commands::restore_backup,
             commands::move_path,
+            commands::move_tagged_to_folder,
+            commands::rename_and_move,
             commands::open_in_explorer,
             commands::get_all_directory_paths,
+            commands::get_folder_stats,
+            commands::get_folder_pages,
             commands::is_pandoc_installed,
             commands::download_pandoc,
+            commands::cancel_pandoc_download,
             commands::import_docx_files,
             commands::import_docx_from_folder,
+            commands::import_text_files,
             commands::import_mediawiki_dump,
+            commands::convert_wikitext,
             commands::render_markdown,
+            commands::get_render_settings,
+            commands::set_render_settings,
+            commands::get_index_settings,
+            commands::set_index_settings,
+            commands::rename_frontmatter_key,
+            commands::normalize_line_endings,
+            commands::prettify_links,
+            commands::get_file_settings,
+            commands::set_file_settings,
+            commands::get_validation_settings,
+            commands::set_validation_settings,
+            commands::validate_frontmatter,
             commands::get_linux_install_type,
             commands::get_license_status,
             commands::verify_and_store_license,
             commands::get_image_as_base64,
             commands::get_app_usage_days,
             commands::duplicate_page,
+            commands::materialize_backlinks,
+            commands::format_page_link,
+            commands::split_page_by_heading,
+            commands::export_static_site,
+            commands::export_tagged,
             commands::list_templates,
             commands::read_template,
             commands::write_template,
             commands::delete_template,
             commands::get_all_broken_links,
+            commands::get_page_broken_links,
+            commands::get_page_excerpt,
+            commands::resolve_wikilink,
+            commands::resolve_uid,
+            commands::get_uid_collisions,
             commands::get_user_fonts,
         ])
         .run(tauri::generate_context!())
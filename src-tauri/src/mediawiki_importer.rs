@@ -3,9 +3,10 @@
 //! This module handles the conversion of a MediaWiki XML dump into a
 //! collection of Markdown files suitable for a Chronicler vault.
 
-use crate::config::IMAGES_DIR_NAME;
 use crate::error::{ChroniclerError, Result};
 use crate::importer::get_pandoc_executable_path;
+use crate::utils::ensure_images_dir;
+use crate::wikilink::format_link;
 use crate::writer::atomic_write;
 use quick_xml::events::Event;
 use quick_xml::Reader;
@@ -54,6 +55,24 @@ static PANDOC_LINK_RE: LazyLock<Regex> =
 /// simply cleans it up by replacing it with an empty string.
 static BR_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)br\s*/").unwrap());
 
+/// Matches a MediaWiki `<ref>` citation, either with content (`<ref name="a">...</ref>`)
+/// or self-closing (`<ref name="a" />`). Captures the attributes in group 1 and the
+/// content in group 2. `<references />` is not matched.
+static REF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<ref(\s[^>]*?)?(?:/>|>(.*?)</ref\s*>)").unwrap());
+
+/// Extracts the `name` attribute from a `<ref>` tag's attributes, quoted or unquoted.
+static REF_NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)name\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s/>]+))"#).unwrap());
+
+/// Matches an innermost MediaWiki template (one containing no other templates).
+static INNER_TEMPLATE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{([^{}]*)\}\}").unwrap());
+
+/// Matches a Markdown footnote definition line as emitted by Pandoc (e.g., `[^1]: Text`).
+static FOOTNOTE_DEF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[\^([^\]]+)\]:\s*(.*)$").unwrap());
+
 #[derive(Debug, Default)]
 struct PageData {
     title: String,
@@ -293,19 +312,222 @@ async fn process_page(
         );
     }
 
-    // 8. Convert MediaWiki image links to HTML `<img>` tags BEFORE Pandoc.
+    // 8. Convert the remaining wikitext to Markdown using Pandoc.
+    let pandoc_exe = get_pandoc_executable_path(app_handle)?;
+    let markdown = convert_body_to_markdown(&wikitext, &page.title, &pandoc_exe)?;
+
+    // 9. Assemble the final file content and write it to disk.
+    write_markdown_file(output_dir, &page.title, frontmatter, &markdown)
+}
+
+/// Converts a wikitext snippet to Markdown without reading or writing any files.
+///
+/// The snippet goes through the same conversion as pages in a dump: category links
+/// are collected, images become `<img>` tags and citations become footnotes. Images
+/// are not downloaded. Any categories are returned as `tags` in a frontmatter block
+/// at the top of the Markdown.
+pub fn convert_wikitext(text: &str, pandoc_exe: &Path) -> Result<String> {
+    let mut wikitext = text.to_string();
+    let mut tags = HashSet::new();
+    extract_categories(&mut wikitext, &mut tags);
+
+    let markdown = convert_body_to_markdown(&wikitext, "pasted wikitext", pandoc_exe)?;
+    if tags.is_empty() {
+        return Ok(markdown.trim().to_string());
+    }
+
+    let mut tags: Vec<String> = tags.into_iter().collect();
+    tags.sort();
+    let frontmatter = HashMap::from([(
+        "tags".to_string(),
+        Value::Sequence(tags.into_iter().map(Value::String).collect()),
+    )]);
+    assemble_page(&frontmatter, &markdown)
+}
+
+/// Converts the body wikitext of a page to Markdown.
+///
+/// The pre-Pandoc transformations run in a fixed order so that `<ref>` citations
+/// reach Pandoc intact and come out as Markdown footnotes.
+fn convert_body_to_markdown(wikitext: &str, title: &str, pandoc_exe: &Path) -> Result<String> {
+    let prepared = prepare_wikitext_for_pandoc(wikitext);
+    let markdown = run_pandoc(pandoc_exe, &prepared, title)?;
+    Ok(convert_links_to_wikilinks(tidy_footnotes(&markdown)))
+}
+
+/// Applies all pre-Pandoc cleanup to the body wikitext.
+fn prepare_wikitext_for_pandoc(wikitext: &str) -> String {
+    // 1. Convert MediaWiki image links to HTML `<img>` tags BEFORE Pandoc.
     //    This preserves layout information and allows our renderer to handle the final conversion.
-    wikitext = convert_mediawiki_images_to_html(&wikitext);
+    let with_images = convert_mediawiki_images_to_html(wikitext);
 
-    // 9. Use a regex to remove all leftover "br /" tags.
-    let cleaned_wikitext = BR_TAG_RE.replace_all(&wikitext, "");
+    // 2. Normalize citations so that Pandoc turns every `<ref>` into a footnote.
+    let with_refs = normalize_refs(&with_images);
 
-    // 10. Convert the remaining wikitext to Markdown using Pandoc.
-    let mut markdown = convert_with_pandoc(&cleaned_wikitext, &page.title, app_handle)?;
-    markdown = convert_links_to_wikilinks(markdown);
+    // 3. Remove leftover "br /" tags, leaving citation content untouched.
+    strip_br_tags_outside_refs(&with_refs)
+}
 
-    // 11. Assemble the final file content and write it to disk.
-    write_markdown_file(output_dir, &page.title, frontmatter, &markdown)
+/// Rewrites `<ref>` tags into a form Pandoc converts reliably.
+///
+/// Pandoc drops templates (such as `{{cite web}}`) and cannot reuse named refs,
+/// so templates inside a ref are flattened to plain text and self-closing
+/// `<ref name="a" />` tags are replaced with the content of the matching named ref.
+fn normalize_refs(wikitext: &str) -> String {
+    // First pass: collect the content of every named ref for later reuse.
+    let mut named_refs: HashMap<String, String> = HashMap::new();
+    for caps in REF_RE.captures_iter(wikitext) {
+        if let (Some(name), Some(content)) = (ref_name(&caps), caps.get(2)) {
+            if !content.as_str().trim().is_empty() {
+                named_refs
+                    .entry(name)
+                    .or_insert_with(|| content.as_str().to_string());
+            }
+        }
+    }
+
+    // Second pass: replace each ref with a plain `<ref>` holding flattened content.
+    REF_RE
+        .replace_all(wikitext, |caps: &Captures| {
+            let content = caps
+                .get(2)
+                .map(|m| m.as_str().to_string())
+                .filter(|c| !c.trim().is_empty())
+                .or_else(|| ref_name(caps).and_then(|name| named_refs.get(&name).cloned()));
+
+            match content {
+                Some(content) => format!("<ref>{}</ref>", flatten_templates(content.trim())),
+                // An empty ref would become an empty footnote, so drop it.
+                None => String::new(),
+            }
+        })
+        .to_string()
+}
+
+/// Returns the `name` attribute of a captured `<ref>` tag, if any.
+fn ref_name(caps: &Captures) -> Option<String> {
+    let attrs = caps.get(1)?.as_str();
+    let name_caps = REF_NAME_RE.captures(attrs)?;
+    (1..=3)
+        .find_map(|i| name_caps.get(i))
+        .map(|m| m.as_str().trim().to_string())
+}
+
+/// Replaces every template in the text with a plain-text rendering of it,
+/// working from the innermost templates outwards so nested templates are kept.
+fn flatten_templates(text: &str) -> String {
+    let mut result = text.to_string();
+    loop {
+        let next = INNER_TEMPLATE_RE
+            .replace_all(&result, |caps: &Captures| template_to_text(&caps[1]))
+            .to_string();
+        if next == result {
+            return result;
+        }
+        result = next;
+    }
+}
+
+/// Renders a citation-style template as readable wikitext.
+///
+/// Known citation fields are combined as `author, [url title], work, date`.
+/// Templates without any of these fields fall back to their positional values,
+/// except `{{lang|code|text}}`, which is just its text.
+fn template_to_text(content: &str) -> String {
+    let params = split_template_params(content);
+    if params
+        .first()
+        .is_some_and(|name| name.trim().eq_ignore_ascii_case("lang"))
+    {
+        return params.get(2).map_or("", |text| text.trim()).to_string();
+    }
+    let mut named: HashMap<String, String> = HashMap::new();
+    let mut positional = Vec::new();
+    for param in params.iter().skip(1) {
+        match param.split_once('=') {
+            Some((key, value)) => {
+                named.insert(key.trim().to_lowercase(), value.trim().to_string());
+            }
+            None if !param.trim().is_empty() => positional.push(param.trim().to_string()),
+            None => {}
+        }
+    }
+
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|k| named.get(*k))
+            .find(|v| !v.is_empty())
+            .cloned()
+    };
+
+    let author = field(&["author", "authors"]).or_else(|| {
+        let last = field(&["last", "last1"])?;
+        Some(match field(&["first", "first1"]) {
+            Some(first) => format!("{} {}", first, last),
+            None => last,
+        })
+    });
+    let title = match (field(&["url"]), field(&["title"])) {
+        (Some(url), Some(title)) => Some(format!("[{} {}]", url, title)),
+        (Some(url), None) => Some(url),
+        (None, title) => title,
+    };
+    let work = field(&["work", "website", "journal", "newspaper", "publisher"]);
+    let date = field(&["date", "year"]);
+
+    let parts: Vec<String> = [author, title, work, date].into_iter().flatten().collect();
+    if parts.is_empty() {
+        positional.join(", ")
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Removes leftover "br /" tags from everything except the content of `<ref>` tags,
+/// where the pattern can legitimately occur (e.g., in citation URLs).
+fn strip_br_tags_outside_refs(wikitext: &str) -> String {
+    let mut result = String::with_capacity(wikitext.len());
+    let mut last_end = 0;
+    for m in REF_RE.find_iter(wikitext) {
+        result.push_str(&BR_TAG_RE.replace_all(&wikitext[last_end..m.start()], ""));
+        result.push_str(m.as_str());
+        last_end = m.end();
+    }
+    result.push_str(&BR_TAG_RE.replace_all(&wikitext[last_end..], ""));
+    result
+}
+
+/// Tidies the footnotes Pandoc generates from `<ref>` tags.
+///
+/// Trailing whitespace is trimmed from definitions, and consecutive footnote
+/// definitions are grouped into a single block without blank lines in between.
+fn tidy_footnotes(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            // Skip a blank line sitting between two footnote definitions.
+            let prev_is_def = output.last().is_some_and(|l| FOOTNOTE_DEF_RE.is_match(l));
+            let next_is_def = lines
+                .get(i + 1)
+                .is_some_and(|l| FOOTNOTE_DEF_RE.is_match(l));
+            if prev_is_def && next_is_def {
+                continue;
+            }
+        }
+
+        match FOOTNOTE_DEF_RE.captures(line) {
+            Some(caps) => output.push(format!("[^{}]: {}", &caps[1], caps[2].trim_end())),
+            None => output.push(line.to_string()),
+        }
+    }
+
+    let mut result = output.join("\n");
+    if markdown.ends_with('\n') {
+        result.push('\n');
+    }
+    result
 }
 
 // --- Helper Functions for process_page ---
@@ -456,14 +678,23 @@ fn write_markdown_file(
     frontmatter: HashMap<String, Value>,
     markdown_body: &str,
 ) -> Result<PathBuf> {
-    let yaml_header = serde_yaml::to_string(&frontmatter)?;
-    let final_content = format!("---\n{}---\n\n{}", yaml_header, markdown_body.trim());
+    let final_content = assemble_page(&frontmatter, markdown_body)?;
     let filename = format!("{}.md", clean_filename(title));
     let filepath = output_dir.join(filename);
     atomic_write(&filepath, &final_content)?;
     Ok(filepath)
 }
 
+/// Joins YAML frontmatter and a Markdown body into the content of a page.
+fn assemble_page(frontmatter: &HashMap<String, Value>, markdown_body: &str) -> Result<String> {
+    let yaml_header = serde_yaml::to_string(frontmatter)?;
+    Ok(format!(
+        "---\n{}---\n\n{}",
+        yaml_header,
+        markdown_body.trim()
+    ))
+}
+
 // --- General Purpose Utility Functions ---
 
 /// Converts MediaWiki image syntax like `[[File:Foo.jpg|thumb|left|250px|Caption]]`
@@ -579,8 +810,7 @@ async fn download_image(image_name: &str, domain: &str, output_dir: &Path) -> Re
     // THEN, replace spaces with underscores for the final filename.
     let final_name = base_name.replace(' ', "_");
 
-    let image_dir = output_dir.join(IMAGES_DIR_NAME);
-    fs::create_dir_all(&image_dir)?;
+    let image_dir = ensure_images_dir(output_dir)?;
     let final_path = image_dir.join(&final_name);
 
     if final_path.exists() {
@@ -629,6 +859,27 @@ async fn download_image(image_name: &str, domain: &str, output_dir: &Path) -> Re
 /// An infobox parser that correctly handles nested brackets.
 fn parse_infobox(content: &str) -> (Option<String>, HashMap<String, String>) {
     let mut data = HashMap::new();
+    let params = split_template_params(content);
+
+    // The first parameter is the infobox name/type.
+    let infobox_name = params.first().map(|s| s.trim().to_string());
+
+    // Process the rest of the parameters (key=value pairs).
+    for param in params.iter().skip(1) {
+        if let Some((key, value)) = param.split_once('=') {
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_string();
+            if !key.is_empty() {
+                data.insert(key, value);
+            }
+        }
+    }
+    (infobox_name, data)
+}
+
+/// Splits the content of a template on `|`, ignoring pipes inside nested
+/// links or templates. The first element is the template name.
+fn split_template_params(content: &str) -> Vec<&str> {
     let mut nested_level = 0; // Handles nested templates/links
     let mut last_split = 0;
     let mut params = Vec::new();
@@ -650,27 +901,11 @@ fn parse_infobox(content: &str) -> (Option<String>, HashMap<String, String>) {
         }
     }
     params.push(&content[last_split..]);
-
-    // The first parameter is the infobox name/type.
-    let infobox_name = params.first().map(|s| s.trim().to_string());
-
-    // Process the rest of the parameters (key=value pairs).
-    for param in params.iter().skip(1) {
-        if let Some((key, value)) = param.split_once('=') {
-            let key = key.trim().to_lowercase();
-            let value = value.trim().to_string();
-            if !key.is_empty() {
-                data.insert(key, value);
-            }
-        }
-    }
-    (infobox_name, data)
+    params
 }
 
 /// Calls Pandoc to convert MediaWiki text to Markdown.
-fn convert_with_pandoc(text: &str, title: &str, app_handle: &AppHandle) -> Result<String> {
-    let pandoc_exe = get_pandoc_executable_path(app_handle)?;
-
+fn run_pandoc(pandoc_exe: &Path, text: &str, title: &str) -> Result<String> {
     let mut process = Command::new(pandoc_exe)
         .arg("--from=mediawiki")
         .arg("--to=gfm")
@@ -713,13 +948,8 @@ fn convert_links_to_wikilinks(md: String) -> String {
 
             let clean_target = target.replace('_', " ");
 
-            // If the link text and cleaned target are the same, create a simple wikilink
-            if text == clean_target {
-                format!("[[{}]]", clean_target)
-            } else {
-                // Otherwise, create an aliased wikilink
-                format!("[[{}|{}]]", clean_target, text)
-            }
+            // The link text only becomes an alias if it differs from the target.
+            format_link(&clean_target, Some(text))
         })
         .to_string()
 }
@@ -742,3 +972,90 @@ fn clean_filename(title: &str) -> String {
 fn normalize_tag(tag: &str) -> String {
     tag.trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIKITEXT_WITH_REFS: &str = r#"The city was founded in 1554.<ref name="founding">{{cite web|url=https://example.com.br/history|title=City History|website=Example}}</ref> It grew quickly.<ref name="founding" /><br />
+== Notes ==
+<references />"#;
+
+    #[test]
+    fn test_refs_survive_pre_pandoc_cleanup() {
+        let prepared = prepare_wikitext_for_pandoc(WIKITEXT_WITH_REFS);
+
+        // The citation template is flattened and its URL is not mangled by the `br /` cleanup.
+        let expected_ref = "<ref>[https://example.com.br/history City History], Example</ref>";
+        assert_eq!(prepared.matches(expected_ref).count(), 2);
+
+        // The `br /` artifact outside the refs is still removed.
+        assert!(!prepared.contains("br /"));
+        assert!(prepared.contains("<references />"));
+    }
+
+    #[test]
+    fn test_nested_templates_in_refs_are_flattened() {
+        let prepared =
+            normalize_refs("Text<ref>{{cite book|title={{lang|fr|Le Livre}}|year=1901}}</ref>");
+        assert_eq!(prepared, "Text<ref>Le Livre, 1901</ref>");
+    }
+
+    #[test]
+    fn test_empty_self_closing_ref_is_dropped() {
+        let prepared = normalize_refs("Text<ref name=\"unknown\" /> more.");
+        assert_eq!(prepared, "Text more.");
+    }
+
+    #[test]
+    fn test_tidy_footnotes() {
+        let markdown = "Body.[^1] More.[^2]\n\n[^1]: First note.   \n\n[^2]: Second note.\n";
+        assert_eq!(
+            tidy_footnotes(markdown),
+            "Body.[^1] More.[^2]\n\n[^1]: First note.\n[^2]: Second note.\n"
+        );
+    }
+
+    #[test]
+    #[ignore = "needs Pandoc on the PATH"]
+    fn test_ref_is_converted_to_footnote() {
+        let pandoc = PathBuf::from("pandoc");
+        let markdown = convert_body_to_markdown(WIKITEXT_WITH_REFS, "City", &pandoc).unwrap();
+
+        assert!(markdown.contains("[^1]"));
+        assert!(markdown.contains("[^1]: [City History](https://example.com.br/history), Example"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_convert_wikitext_snippet_without_pandoc() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A stand-in for Pandoc that passes the prepared wikitext through unchanged.
+        let dir = tempfile::tempdir().unwrap();
+        let pandoc = dir.path().join("pandoc");
+        fs::write(&pandoc, "#!/bin/sh\ncat\n").unwrap();
+        fs::set_permissions(&pandoc, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let wikitext = "Founded in 1554.<ref>{{cite book|title={{lang|fr|Le Livre}}}}</ref>\n\
+                        [[Category:Cities]]\n[[Category:Ports]]\n";
+        let markdown = convert_wikitext(wikitext, &pandoc).unwrap();
+
+        assert!(markdown.starts_with("---\ntags:\n- Cities\n- Ports\n---\n"));
+        assert!(markdown.contains("Founded in 1554.<ref>Le Livre</ref>"));
+        assert!(!markdown.contains("Category:"));
+    }
+
+    #[test]
+    #[ignore = "needs Pandoc on the PATH"]
+    fn test_convert_wikitext_snippet() {
+        let pandoc = PathBuf::from("pandoc");
+        let wikitext = "== History ==\nFounded by [[Queen Mara|the queen]].\n[[Category:Cities]]\n";
+        let markdown = convert_wikitext(wikitext, &pandoc).unwrap();
+
+        assert!(markdown.starts_with("---\ntags:\n- Cities\n---\n"));
+        assert!(markdown.contains("## History"));
+        assert!(markdown.contains("[[Queen Mara|the queen]]"));
+        assert!(!markdown.contains("Category:"));
+    }
+}
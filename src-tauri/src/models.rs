@@ -7,6 +7,7 @@ use serde_json::Value;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 /// A custom serialization function for `PathBuf` that guarantees forward slashes.
 ///
@@ -46,6 +47,9 @@ pub struct Link {
     /// The optional alias (display text) of the link.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alias: Option<String>,
+    /// Whether the link embeds its target, as in `![[Page]]`.
+    #[serde(default)]
+    pub embed: bool,
     /// The position of the link in the source file.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position: Option<LinkPosition>,
@@ -73,6 +77,30 @@ pub struct Page {
     /// `serde_json::Value` is used to allow for flexible, unstructured data,
     /// which is perfect for user-defined infoboxes.
     pub frontmatter: serde_json::Value,
+    /// The number of words in the page body, excluding the frontmatter.
+    #[serde(default)]
+    pub word_count: usize,
+    /// The local images the page uses, as written: image embeds in the body and
+    /// the paths in its `image` and `banner` fields. Remote images are left out.
+    #[serde(default)]
+    pub image_refs: Vec<String>,
+    /// The web URLs in the page body, from Markdown links and bare URLs.
+    #[serde(default)]
+    pub external_links: Vec<String>,
+    /// A hash of the file's full content, used to tell whether it has changed.
+    #[serde(default)]
+    pub content_hash: String,
+    /// Whether the file couldn't be parsed (e.g., malformed frontmatter), in which
+    /// case the page is a placeholder with no metadata.
+    #[serde(default)]
+    pub parse_failed: bool,
+    /// When the file was last modified, if the file system reports it.
+    #[serde(default)]
+    pub modified: Option<SystemTime>,
+    /// A short plain-text excerpt of the page, kept so link previews don't have
+    /// to read the file again.
+    #[serde(default)]
+    pub excerpt: Option<String>,
 }
 
 /// Represents the category of a node in the file system tree.
@@ -87,6 +115,9 @@ pub enum FileType {
     Markdown,
     /// A supported image file (e.g., `.png`, `.jpg`).
     Image,
+    /// A file with one of the extra extensions in the index settings, such as a
+    /// `.canvas` whiteboard. It is listed in the tree and opened with the OS.
+    Other,
 }
 
 /// Implements partial ordering for `FileType`.
@@ -102,7 +133,7 @@ impl PartialOrd for FileType {
 /// Implements total ordering for `FileType` to define a custom sort order.
 ///
 /// This implementation ensures that `Directory` variants are always considered
-/// "less than" file variants (`Markdown`, `Image`), causing them to appear
+/// "less than" file variants (`Markdown`, `Image`, `Other`), causing them to appear
 /// first when a list of `FileNode`s is sorted.
 impl Ord for FileType {
     fn cmp(&self, other: &Self) -> Ordering {
@@ -146,6 +177,28 @@ pub struct Backlink {
     #[serde(serialize_with = "serialize_pathbuf_as_web_str")]
     pub path: PathBuf,
     pub count: usize,
+    /// The alias of the first aliased link from the source page, if any. Only set
+    /// when `show_backlink_aliases` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// A link between two pages in a graph, weighted by how many links there are.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct GraphEdge {
+    #[serde(serialize_with = "serialize_pathbuf_as_web_str")]
+    pub source: PathBuf,
+    #[serde(serialize_with = "serialize_pathbuf_as_web_str")]
+    pub target: PathBuf,
+    /// The number of links from the source page to the target page.
+    pub weight: usize,
+}
+
+/// A set of pages and the links between them, ready to be drawn as a graph.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Default)]
+pub struct GraphData {
+    pub nodes: Vec<PageHeader>,
+    pub edges: Vec<GraphEdge>,
 }
 
 /// Represents a single entry in the Table of Contents.
@@ -172,6 +225,18 @@ pub struct RenderedPage {
     pub html_after_toc: String,
     /// The generated Table of Contents for the page.
     pub toc: Vec<TocEntry>,
+    /// The widest the body should be shown, in pixels, from the render settings.
+    pub max_content_width: Option<u32>,
+    /// How many of the page's task list items (`- [ ]`, `- [x]`) are done, or `None`
+    /// if it has none.
+    pub tasks: Option<TaskProgress>,
+}
+
+/// The number of checked task list items on a page, out of all of them.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct TaskProgress {
+    pub completed: usize,
+    pub total: usize,
 }
 
 /// A comprehensive data structure for the file view. This is a "View Model"
@@ -182,6 +247,10 @@ pub struct FullPageData {
     pub raw_content: String,
     pub rendered_page: RenderedPage,
     pub backlinks: Vec<Backlink>,
+    /// Pages that share tags or link targets with this page, most related first.
+    pub related: Vec<PageHeader>,
+    /// The page with the pages it links to and those linking to it, if requested.
+    pub local_graph: Option<GraphData>,
 }
 
 /// Represents a broken link report, aggregating all pages that link to a non-existent target.
@@ -192,3 +261,115 @@ pub struct BrokenLink {
     /// A list of all pages that contain a link to this target.
     pub sources: Vec<PageHeader>,
 }
+
+/// A page matching a full-text search, with where the text was found.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SearchResult {
+    pub page: PageHeader,
+    /// Whether the text was found in the page body.
+    pub in_body: bool,
+    /// Whether the text was found in a frontmatter value. Always `false` unless
+    /// frontmatter was searched.
+    pub in_frontmatter: bool,
+}
+
+/// An external link that couldn't be reached when checked.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DeadLink {
+    /// The page containing the link.
+    pub path: PathBuf,
+    /// The URL as written.
+    pub url: String,
+    /// Why the link counts as dead: the HTTP status, or the request error.
+    pub reason: String,
+}
+
+/// A non-blocking issue found in a page's frontmatter.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ValidationAdvisory {
+    /// The page the issue was found in.
+    pub page: PageHeader,
+    /// The frontmatter key the issue concerns.
+    pub field: String,
+    /// A human-readable description of the issue.
+    pub message: String,
+}
+
+/// The pages that differ between two vault manifests. Paths are vault-relative
+/// and sorted.
+#[derive(Debug, Clone, Serialize, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    /// Pages in the newer manifest only.
+    pub added: Vec<PathBuf>,
+    /// Pages in the older manifest only.
+    pub removed: Vec<PathBuf>,
+    /// Pages in both manifests whose content hash changed.
+    pub modified: Vec<PathBuf>,
+}
+
+/// The differences between the in-memory index and a fresh scan of the vault, as
+/// found by `World::verify_index`. Paths are vault-relative and sorted.
+#[derive(Debug, Clone, Serialize, Default, PartialEq, Eq)]
+pub struct IndexReport {
+    /// Indexed pages whose files no longer exist.
+    pub missing_on_disk: Vec<PathBuf>,
+    /// Pages on disk that aren't indexed.
+    pub missing_from_index: Vec<PathBuf>,
+    /// Indexed pages whose files have changed since they were indexed.
+    pub outdated: Vec<PathBuf>,
+    /// Pages whose indexed links or backlinks differ from those of a fresh scan.
+    pub link_mismatches: Vec<PathBuf>,
+    /// Whether the index was replaced by the fresh scan to repair it.
+    pub repaired: bool,
+}
+
+impl IndexReport {
+    /// Returns `true` if no discrepancies were found.
+    pub fn is_consistent(&self) -> bool {
+        self.missing_on_disk.is_empty()
+            && self.missing_from_index.is_empty()
+            && self.outdated.is_empty()
+            && self.link_mismatches.is_empty()
+    }
+}
+
+/// A link to a page, as written on the page containing it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Reference {
+    /// The page containing the link.
+    pub source: PageHeader,
+    /// The link as written, e.g. `[[Vex#Past|the rogue]]`.
+    pub text: String,
+}
+
+/// Every link to a page, grouped by how it refers to the page. Each link is in
+/// exactly one group: embeds first, then section links, then aliased links, and
+/// the remaining plain links are direct.
+#[derive(Debug, Clone, Serialize, Default, PartialEq, Eq)]
+pub struct References {
+    /// Plain links by name, e.g. `[[Vex]]`.
+    pub direct: Vec<Reference>,
+    /// Links with display text, e.g. `[[Vex|the rogue]]`.
+    pub aliased: Vec<Reference>,
+    /// Links to a section of the page, e.g. `[[Vex#Past]]`.
+    pub sections: Vec<Reference>,
+    /// Embeds of the page, e.g. `![[Vex]]`.
+    pub embeds: Vec<Reference>,
+}
+
+/// Aggregate storage statistics for a single folder in the vault.
+///
+/// All counts and sizes include the contents of nested subfolders.
+#[derive(Debug, Clone, Serialize, Default, PartialEq, Eq)]
+pub struct FolderStat {
+    #[serde(serialize_with = "serialize_pathbuf_as_web_str")]
+    pub path: PathBuf,
+    /// The number of Markdown pages.
+    pub pages: usize,
+    /// The number of supported image files.
+    pub images: usize,
+    /// The number of any other files.
+    pub attachments: usize,
+    /// The combined size of all files, in bytes.
+    pub total_bytes: u64,
+}
@@ -2,24 +2,76 @@
 //!
 //! Extracts metadata, links, and frontmatter from files.
 
-use crate::config::MAX_FILE_SIZE;
+use crate::config::{IndexSettings, DEFAULT_EXCERPT_LENGTH, DEFAULT_TAG_KEY, MAX_FILE_SIZE};
 use crate::error::{ChroniclerError, Result};
 use crate::models::Page;
-use crate::wikilink::extract_wikilinks;
+use crate::utils::content_hash;
+use crate::wikilink::{extract_wikilinks, WIKILINK_IMAGE_RE, WIKILINK_RE};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use std::sync::LazyLock;
 use tracing::instrument;
 
+/// The line that opens a GM-only block. The block runs until a line containing only `:::`.
+pub const GM_BLOCK_OPEN: &str = ":::gm";
+
+/// The prefix of the line that opens a collapsible block, e.g. `:::details Secrets`.
+/// The rest of the line is used as the block's summary.
+pub const DETAILS_BLOCK_OPEN: &str = ":::details";
+
+/// The line that closes the innermost open `:::` block.
+pub const CONTAINER_BLOCK_CLOSE: &str = ":::";
+
+/// Matches Markdown image syntax: `![alt](path)` or `![alt](path "title")`.
+/// Captures: 1: the image path
+pub static MARKDOWN_IMAGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"!\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#).unwrap());
+
+/// Matches an `http` or `https` URL, in a Markdown link or written out bare. The
+/// URL ends at whitespace or a character that closes it in Markdown, such as the
+/// `)` of a link or the `>` of an autolink. Balanced parentheses, as in
+/// `https://en.wikipedia.org/wiki/Mercury_(planet)`, are kept.
+static EXTERNAL_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?://(?:[^\s<>()\[\]"'`|]|\([^\s<>()\[\]"'`|]*\))+"#).unwrap()
+});
+
+/// Spoiler regex pattern.
+/// Captures: 1: content
+/// Format: ||content||
+pub static SPOILER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    // The `.*?` is a non-greedy match to correctly handle multiple spoilers on one line.
+    Regex::new(r"\|\|(.*?)\|\|").unwrap()
+});
+
+/// An inline `#tag` in body text, or a wikilink or HTML tag to skip over. A tag
+/// starts with a letter and must follow whitespace or `(`, so the `#` in a URL or
+/// an HTML entity isn't one.
+/// Captures: 1: a wikilink or HTML tag, 2: the text before the `#`, 3: the tag
+/// Format: A rogue from the #docks.
+pub static HASHTAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\[\[.*?\]\]|<[^>]*>)|(^|[\s(])#(\p{L}[\p{L}\p{N}_/-]*)").unwrap()
+});
+
+/// Frontmatter keys whose values are used as a page's excerpt, in order of preference.
+pub const EXCERPT_KEYS: &[&str] = &["summary", "description"];
+
+/// Frontmatter keys whose values are image paths.
+pub const FRONTMATTER_IMAGE_KEYS: &[&str] = &["image", "banner"];
+
 /// Parses a single Markdown file to extract its metadata (frontmatter, tags, links).
 ///
 /// # Arguments
 /// * `path` - The path to the Markdown file to parse.
+/// * `settings` - Which frontmatter keys hold the page's tags and title.
 ///
 /// # Returns
 /// A `Result` containing the parsed `Page` or a `ChroniclerError`.
 #[instrument(skip(path), fields(path = %path.display()), level = "debug", ret(level = "debug"))]
-pub fn parse_file(path: &Path) -> Result<Page> {
+pub fn parse_file(path: &Path, settings: &IndexSettings) -> Result<Page> {
     // Check file size limit
     let metadata = fs::metadata(path)?;
     if metadata.len() > MAX_FILE_SIZE {
@@ -31,15 +83,19 @@ pub fn parse_file(path: &Path) -> Result<Page> {
     }
 
     let content = fs::read_to_string(path)?;
-    let (frontmatter_str, _markdown_body) = extract_frontmatter(&content);
+    let (frontmatter_str, markdown_body) = extract_frontmatter(&content);
 
     // Parse frontmatter
     let frontmatter = parse_frontmatter(frontmatter_str, path)?;
 
     // Extract metadata
-    let tags = extract_tags_from_frontmatter(&frontmatter);
+    let mut tags = extract_tags_from_frontmatter(&frontmatter, &settings.tag_keys);
+    tags.extend(extract_inline_tags(markdown_body));
     let links = extract_wikilinks(&content);
-    let title = extract_title(&frontmatter, path);
+    let title = extract_title(&frontmatter, path, &settings.title_keys);
+    let image_refs = extract_image_refs(&frontmatter, markdown_body);
+    let external_links = extract_external_links(markdown_body);
+    let excerpt = extract_excerpt(&frontmatter, markdown_body, DEFAULT_EXCERPT_LENGTH);
 
     Ok(Page {
         path: path.to_path_buf(),
@@ -48,32 +104,175 @@ pub fn parse_file(path: &Path) -> Result<Page> {
         links,
         backlinks: HashSet::new(),
         frontmatter,
+        word_count: count_words(markdown_body),
+        image_refs,
+        external_links,
+        content_hash: content_hash(&content),
+        parse_failed: false,
+        modified: metadata.modified().ok(),
+        excerpt,
     })
 }
 
+/// Collects the local images a page uses, as written: the paths in its `image`
+/// and `banner` frontmatter fields, then its image embeds (`![[...]]` and
+/// `![alt](...)`) in order of appearance. Remote and `data:` images are skipped.
+pub fn extract_image_refs(frontmatter: &serde_json::Value, body: &str) -> Vec<String> {
+    let mut sources: Vec<String> = Vec::new();
+    for key in FRONTMATTER_IMAGE_KEYS {
+        match frontmatter.get(key) {
+            Some(serde_json::Value::String(s)) => sources.push(s.trim().to_string()),
+            Some(serde_json::Value::Array(items)) => sources.extend(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .map(|s| s.trim().to_string()),
+            ),
+            _ => {}
+        }
+    }
+    sources.extend(
+        WIKILINK_IMAGE_RE
+            .captures_iter(body)
+            .chain(MARKDOWN_IMAGE_RE.captures_iter(body))
+            .map(|caps| caps[1].trim().to_string()),
+    );
+
+    sources.retain(|src| !src.is_empty() && !src.contains("://") && !src.starts_with("data:"));
+    sources
+}
+
+/// Collects the web (`http` and `https`) URLs in a page body, from Markdown links
+/// and bare URLs alike, in order of appearance and without duplicates. Punctuation
+/// ending a sentence after a bare URL isn't part of it.
+pub fn extract_external_links(body: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    EXTERNAL_URL_RE
+        .find_iter(body)
+        .map(|m| {
+            m.as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '_'])
+        })
+        .filter(|url| !url.ends_with("//"))
+        .filter(|url| seen.insert(*url))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns a short plain-text excerpt of a page for search results and cards.
+///
+/// The page's `summary` or `description` frontmatter field is used as written if
+/// present. Otherwise the excerpt is taken from the first paragraph of the body,
+/// with wikilinks reduced to their display text, images and spoilers left out,
+/// and cut at a word boundary to at most `max_length` characters.
+pub fn extract_excerpt(
+    frontmatter: &serde_json::Value,
+    body: &str,
+    max_length: usize,
+) -> Option<String> {
+    let from_frontmatter = EXCERPT_KEYS.iter().find_map(|key| {
+        let value = frontmatter.get(key)?.as_str()?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    });
+    if from_frontmatter.is_some() {
+        return from_frontmatter;
+    }
+
+    let body = strip_gm_blocks(body);
+    let mut paragraph: Option<String> = None;
+    for event in Parser::new(&body) {
+        match (event, paragraph.as_mut()) {
+            (Event::Start(Tag::Paragraph), None) => paragraph = Some(String::new()),
+            (Event::Text(text) | Event::Code(text), Some(buffer)) => buffer.push_str(&text),
+            (Event::SoftBreak | Event::HardBreak, Some(buffer)) => buffer.push(' '),
+            (Event::End(TagEnd::Paragraph), Some(buffer)) => {
+                let text = plain_excerpt_text(buffer);
+                if !text.is_empty() {
+                    return Some(truncate_at_word(&text, max_length));
+                }
+                // A paragraph holding only an image or a spoiler is skipped.
+                paragraph = None;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Strips custom syntax from paragraph text and collapses its whitespace.
+fn plain_excerpt_text(text: &str) -> String {
+    let text = WIKILINK_IMAGE_RE.replace_all(text, "");
+    let text = SPOILER_RE.replace_all(&text, "");
+    let text = WIKILINK_RE.replace_all(&text, |caps: &regex::Captures| {
+        let alias = caps.get(3).map(|m| m.as_str());
+        alias.unwrap_or(&caps[1]).trim().to_string()
+    });
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Shortens text to at most `max_length` characters, cutting at the last word
+/// boundary and adding an ellipsis if anything was removed.
+fn truncate_at_word(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    // Leave room for the ellipsis.
+    let cut = text
+        .char_indices()
+        .nth(max_length.saturating_sub(1))
+        .map_or(text.len(), |(i, _)| i);
+    let head = &text[..cut];
+    let head = match head.rfind(' ') {
+        Some(space) if space > 0 => &head[..space],
+        _ => head,
+    };
+    format!("{}…", head.trim_end_matches([',', ';', ':', '.', ' ']))
+}
+
+/// Counts the words in a Markdown body.
+///
+/// A word is any whitespace-separated run containing at least one letter or
+/// digit, so bare Markdown markers such as `#`, `-` or `|` are not counted.
+pub fn count_words(body: &str) -> usize {
+    body.split_whitespace()
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .count()
+}
+
 /// Extracts YAML frontmatter from markdown content.
 ///
 /// This function is Unicode-safe and handles multibyte characters correctly.
 ///
 /// Returns a tuple `(frontmatter, body)`, where `frontmatter` is the raw string
 /// content between the `---` delimiters, or an empty string if none is found.
+/// Both `\n` and `\r\n` line endings are accepted.
 pub fn extract_frontmatter(content: &str) -> (&str, &str) {
     // Must start with frontmatter delimiter
-    let Some(after_opening) = content.strip_prefix("---\n") else {
+    let Some(after_opening) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
         return ("", content);
     };
 
-    // Find closing delimiter
-    let Some(closing_pos) = after_opening.find("\n---") else {
-        return ("", content);
+    // Find closing delimiter, which directly follows the opening one in an empty block
+    let (frontmatter, body_start) = if let Some(rest) = after_opening.strip_prefix("---") {
+        ("", rest)
+    } else {
+        let Some(closing_pos) = after_opening.find("\n---") else {
+            return ("", content);
+        };
+        let frontmatter = &after_opening[..closing_pos];
+        (
+            frontmatter.strip_suffix('\r').unwrap_or(frontmatter),
+            &after_opening[closing_pos + 4..],
+        )
     };
 
-    let frontmatter = &after_opening[..closing_pos];
-    let body_start = after_opening[closing_pos..].strip_prefix("\n---").unwrap();
-
     // Closing delimiter must be followed by newline, EOF, or only whitespace
-    if body_start.is_empty() || body_start.starts_with('\n') {
-        let body = body_start.strip_prefix('\n').unwrap_or(body_start);
+    let body = body_start.strip_prefix('\r').unwrap_or(body_start);
+    if body.is_empty() || body.starts_with('\n') {
+        let body = body.strip_prefix('\n').unwrap_or(body);
         return (frontmatter, body);
     }
 
@@ -92,23 +291,99 @@ pub fn parse_frontmatter(frontmatter_str: &str, path: &Path) -> Result<serde_jso
     })
 }
 
-/// Extracts tags from frontmatter.
-fn extract_tags_from_frontmatter(frontmatter: &serde_json::Value) -> HashSet<String> {
-    frontmatter
-        .get("tags")
-        .and_then(|v| v.as_array())
-        .into_iter()
-        .flatten()
-        .filter_map(|tag| tag.as_str())
+/// Extracts tags from frontmatter, merging the values of the `tags` key and every
+/// key in `tag_keys`.
+///
+/// Each key may hold a list of tags or a single tag. Tags are trimmed and a leading
+/// `#` is dropped, so `#Lore` and `Lore` are the same tag.
+fn extract_tags_from_frontmatter(
+    frontmatter: &serde_json::Value,
+    tag_keys: &[String],
+) -> HashSet<String> {
+    std::iter::once(DEFAULT_TAG_KEY)
+        .chain(tag_keys.iter().map(String::as_str))
+        .filter_map(|key| frontmatter.get(key))
+        .flat_map(|value| match value {
+            serde_json::Value::Array(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+            serde_json::Value::String(tag) => vec![tag.as_str()],
+            _ => Vec::new(),
+        })
+        .map(|tag| tag.trim().trim_start_matches('#').trim())
+        .filter(|tag| !tag.is_empty())
         .map(String::from)
         .collect()
 }
 
-/// Determines the page title from frontmatter or filename.
-fn extract_title(frontmatter: &serde_json::Value, path: &Path) -> String {
-    frontmatter
-        .get("title")
-        .and_then(|v| v.as_str())
+/// Returns the tag named by a [`HASHTAG_RE`] match, without a trailing `/` or `-`,
+/// which are taken as punctuation. Hex colours like `fff`, `fafafa` or `ffffff80`
+/// aren't tags. A four or eight letter value is only taken as a colour (with alpha)
+/// if it has a digit or a single repeated colour letter, like `fffa`, so hex words
+/// like `dead` or `cafe` are still tags.
+pub fn hashtag_name(raw: &str) -> Option<&str> {
+    let tag = raw.trim_end_matches(['/', '-']);
+    let is_hex = tag.chars().all(|c| c.is_ascii_hexdigit());
+    let is_hex_colour = is_hex
+        && match tag.len() {
+            3 | 6 => true,
+            4 | 8 => {
+                let colour = &tag.as_bytes()[..tag.len() / 4 * 3];
+                tag.chars().any(|c| c.is_ascii_digit())
+                    || colour.iter().all(|b| b.eq_ignore_ascii_case(&colour[0]))
+            }
+            _ => false,
+        };
+    (!is_hex_colour).then_some(tag)
+}
+
+/// Collects the inline `#tags` written in a Markdown body. Tags in headings, links,
+/// code and wikilinks are skipped, the same as when the body is rendered.
+pub fn extract_inline_tags(body: &str) -> HashSet<String> {
+    let mut tags = HashSet::new();
+    let mut collect = |text: &mut String| {
+        for caps in HASHTAG_RE.captures_iter(text) {
+            if let Some(tag) = caps.get(3).and_then(|raw| hashtag_name(raw.as_str())) {
+                tags.insert(tag.to_string());
+            }
+        }
+        text.clear();
+    };
+
+    // Consecutive text events are joined so a split before a `#` isn't taken as
+    // the start of a line.
+    let mut text = String::new();
+    let mut skip_depth = 0usize;
+    for event in Parser::new(body) {
+        match event {
+            Event::Text(t) if skip_depth == 0 => text.push_str(&t),
+            Event::Text(_) => {}
+            Event::Start(
+                Tag::Heading { .. } | Tag::Link { .. } | Tag::Image { .. } | Tag::CodeBlock(_),
+            ) => {
+                collect(&mut text);
+                skip_depth += 1;
+            }
+            Event::End(TagEnd::Heading(_) | TagEnd::Link | TagEnd::Image | TagEnd::CodeBlock) => {
+                collect(&mut text);
+                skip_depth = skip_depth.saturating_sub(1);
+            }
+            _ => collect(&mut text),
+        }
+    }
+    collect(&mut text);
+    tags
+}
+
+/// Determines the page title from the first of `title_keys` with a text value in
+/// the frontmatter, or else from the filename.
+pub fn extract_title(
+    frontmatter: &serde_json::Value,
+    path: &Path,
+    title_keys: &[String],
+) -> String {
+    title_keys
+        .iter()
+        .filter_map(|key| frontmatter.get(key)?.as_str())
+        .find(|title| !title.trim().is_empty())
         .map(String::from)
         .unwrap_or_else(|| {
             path.file_stem()
@@ -118,12 +393,225 @@ fn extract_title(frontmatter: &serde_json::Value, path: &Path) -> String {
         })
 }
 
+/// The part a line of Markdown plays in `:::` container blocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockLine<'a> {
+    /// `:::gm`, which opens a GM-only block.
+    GmOpen,
+    /// `:::details`, which opens a collapsible block, with the text after it.
+    DetailsOpen(&'a str),
+    /// `:::`, which closes the innermost open block.
+    Close,
+    /// Any other line, including the lines of code blocks and a `:::` with no
+    /// block open.
+    Content,
+}
+
+/// Splits `markdown` into lines, keeping their line breaks, each with the part it
+/// plays in `:::` container blocks. Fences inside code blocks are left alone.
+pub fn container_block_lines(markdown: &str) -> impl Iterator<Item = (&str, BlockLine<'_>)> {
+    let mut code_fence: Option<&str> = None;
+    let mut depth = 0usize;
+    markdown.split_inclusive('\n').map(move |line| {
+        let trimmed = line.trim();
+        let kind = if let Some(fence) = code_fence {
+            if trimmed.starts_with(fence) {
+                code_fence = None;
+            }
+            BlockLine::Content
+        } else if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            code_fence = Some(fence);
+            BlockLine::Content
+        } else if trimmed == GM_BLOCK_OPEN {
+            depth += 1;
+            BlockLine::GmOpen
+        } else if let Some(summary) = details_block_summary(trimmed) {
+            depth += 1;
+            BlockLine::DetailsOpen(summary)
+        } else if trimmed == CONTAINER_BLOCK_CLOSE && depth > 0 {
+            depth -= 1;
+            BlockLine::Close
+        } else {
+            BlockLine::Content
+        };
+        (line, kind)
+    })
+}
+
+/// Returns the text after `:::details` on a line that opens a details block, which
+/// may be empty, or `None` if the line doesn't open one.
+fn details_block_summary(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix(DETAILS_BLOCK_OPEN)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+/// Removes `:::gm` blocks from a Markdown body, along with the fence lines of any
+/// other `:::` blocks, whose content is kept. An unclosed GM block runs to the end
+/// of the body.
+pub fn strip_gm_blocks(body: &str) -> Cow<'_, str> {
+    if !body.contains(CONTAINER_BLOCK_CLOSE) {
+        return Cow::Borrowed(body);
+    }
+    let mut output = String::with_capacity(body.len());
+    let mut open_blocks: Vec<BlockLine> = Vec::new();
+    for (line, kind) in container_block_lines(body) {
+        match kind {
+            BlockLine::GmOpen | BlockLine::DetailsOpen(_) => open_blocks.push(kind),
+            BlockLine::Close => {
+                open_blocks.pop();
+            }
+            BlockLine::Content if !open_blocks.contains(&BlockLine::GmOpen) => {
+                output.push_str(line)
+            }
+            BlockLine::Content => {}
+        }
+    }
+    Cow::Owned(output)
+}
+
+/// A section of a Markdown body introduced by a heading.
+///
+/// All offsets are byte offsets into the body the section was found in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingSection {
+    /// The plain text of the heading.
+    pub title: String,
+    /// The offset at which the heading line begins.
+    pub start: usize,
+    /// The offset just past the heading line, where the section's content begins.
+    pub content_start: usize,
+    /// The offset at which the next heading of the same or a higher level begins,
+    /// or the end of the body.
+    pub end: usize,
+}
+
+/// Finds every section introduced by a heading of exactly `level` (1-6).
+///
+/// A section runs until the next heading of the same or a higher level, so
+/// deeper subheadings stay inside it. Headings inside code blocks are ignored
+/// because the Markdown parser does not report them as headings.
+pub fn find_heading_sections(body: &str, level: u8) -> Vec<HeadingSection> {
+    let mut sections: Vec<HeadingSection> = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (event, range) in Parser::new(body).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level: l, .. }) => {
+                let l = l as u8;
+                if l <= level {
+                    // Any heading at or above the split level closes the open section.
+                    if let Some(last) = sections.last_mut() {
+                        if last.end == body.len() {
+                            last.end = range.start;
+                        }
+                    }
+                }
+                if l == level {
+                    current = Some((range.start, String::new()));
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, title)) = current.as_mut() {
+                    title.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((start, title)) = current.take() {
+                    // The heading range may stop before the line break; skip past it.
+                    let content_start = if body[..range.end].ends_with('\n') {
+                        range.end
+                    } else {
+                        body[range.end..]
+                            .find('\n')
+                            .map_or(body.len(), |i| range.end + i + 1)
+                    };
+                    sections.push(HeadingSection {
+                        title: title.trim().to_string(),
+                        start,
+                        content_start,
+                        end: body.len(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    sections
+}
+
+/// Finds the first section, at any heading level, whose heading matches `section`.
+///
+/// `section` may be the heading's text, as in `[[Page#Some Heading]]`, or its
+/// anchor slug (`some-heading`); both are compared by slug.
+pub fn find_section(body: &str, section: &str) -> Option<HeadingSection> {
+    find_sections(body, section).into_iter().next()
+}
+
+/// Finds every section, at any heading level, whose heading matches `section` as
+/// in [`find_section`], in the order they appear.
+pub fn find_sections(body: &str, section: &str) -> Vec<HeadingSection> {
+    let wanted = slug::slugify(section);
+    let mut found: Vec<HeadingSection> = (1..=6)
+        .flat_map(|level| find_heading_sections(body, level))
+        .filter(|found| slug::slugify(&found.title) == wanted)
+        .collect();
+    found.sort_by_key(|found| found.start);
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use super::*; // Import everything from the parent module (parser)
     use std::collections::HashSet;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_extract_excerpt() {
+        let frontmatter =
+            serde_json::json!({ "description": "A port town.", "summary": " Vex's home. " });
+        assert_eq!(
+            extract_excerpt(&frontmatter, "Body text.", 200).as_deref(),
+            Some("Vex's home.")
+        );
+
+        let body = "# Harbor\n\n![[harbor.png]]\n\nThe [[Harbor Town|harbor]] is run by\n[[Vex]], \
+                    ||a spy||who `trades` quietly.\n\nSecond paragraph.";
+        assert_eq!(
+            extract_excerpt(&serde_json::Value::Null, body, 200).as_deref(),
+            Some("The harbor is run by Vex, who trades quietly.")
+        );
+        assert_eq!(
+            extract_excerpt(&serde_json::Value::Null, body, 24).as_deref(),
+            Some("The harbor is run by…")
+        );
+        assert_eq!(
+            extract_excerpt(&serde_json::Value::Null, "# Only a heading", 200),
+            None
+        );
+
+        // GM blocks are left out, and other blocks' fences aren't text.
+        let body =
+            ":::gm\nThe harbor master is a spy.\n:::\n\n:::details Rumours\nShips vanish.\n:::";
+        assert_eq!(
+            extract_excerpt(&serde_json::Value::Null, body, 200).as_deref(),
+            Some("Ships vanish.")
+        );
+    }
+
+    #[test]
+    fn test_strip_gm_blocks() {
+        let body = "Intro.\n:::gm\nSecret.\n:::details Nested\nAlso secret.\n:::\n:::\n\
+                    :::details More\nShown.\n:::\n```\n:::gm\n```\n:::\n:::gm\nUnclosed.";
+        assert_eq!(
+            strip_gm_blocks(body),
+            "Intro.\nShown.\n```\n:::gm\n```\n:::\n"
+        );
+    }
+
     #[test]
     fn test_parse_file_with_full_frontmatter() -> Result<()> {
         let content = r#"---
@@ -138,7 +626,7 @@ Hello, this is the body. It contains a [[Link To Another Page]].
         let file_path = dir.path().join("test_page.md");
         fs::write(&file_path, content).unwrap();
 
-        let page = parse_file(&file_path).unwrap();
+        let page = parse_file(&file_path, &IndexSettings::default()).unwrap();
 
         assert_eq!(page.title, "My Test Page");
         assert_eq!(
@@ -152,6 +640,83 @@ Hello, this is the body. It contains a [[Link To Another Page]].
         Ok(())
     }
 
+    #[test]
+    fn test_parse_file_with_additional_tag_keys() {
+        let content = "---\ntags: [Character]\ncategories:\n  - \"#Lore\"\n  - ' Noble Houses '\nkeywords: ignored\n---\nBody";
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("tagged.md");
+        fs::write(&file_path, content).unwrap();
+
+        let settings = IndexSettings {
+            tag_keys: vec!["tags".to_string(), "categories".to_string()],
+            ..Default::default()
+        };
+        let page = parse_file(&file_path, &settings).unwrap();
+        assert_eq!(
+            page.tags,
+            HashSet::from([
+                "Character".to_string(),
+                "Lore".to_string(),
+                "Noble Houses".to_string()
+            ])
+        );
+
+        // By default only `tags` is read.
+        let page = parse_file(&file_path, &IndexSettings::default()).unwrap();
+        assert_eq!(page.tags, HashSet::from(["Character".to_string()]));
+
+        // `tags` is read even when the configured keys leave it out.
+        let settings = IndexSettings {
+            tag_keys: vec!["categories".to_string()],
+            ..Default::default()
+        };
+        let page = parse_file(&file_path, &settings).unwrap();
+        assert!(page.tags.contains("Character"));
+        assert!(page.tags.contains("Lore"));
+    }
+
+    #[test]
+    fn test_parse_file_with_inline_tags() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("Vex.md");
+        let content = "# Vex #heading\n\nA #rogue from the #docks/east, dyed #fff.\n\n\
+                       Left for #dead near #c0ffee and #ABCDEF sails.\n\n\
+                       See [[Page|#alias]], [#linked](https://example.com/#top) and `#code`.\n\n\
+                       ```\n#fenced\n```";
+        fs::write(&file_path, content).unwrap();
+
+        let page = parse_file(&file_path, &IndexSettings::default()).unwrap();
+        assert_eq!(
+            page.tags,
+            HashSet::from([
+                "rogue".to_string(),
+                "docks/east".to_string(),
+                "dead".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_file_with_title_keys() {
+        let dir = tempdir().unwrap();
+        let named = dir.path().join("char_042.md");
+        let untitled = dir.path().join("char_043.md");
+        fs::write(&named, "---\ntitle: Ignored\nname: Vex\n---\nBody").unwrap();
+        fs::write(&untitled, "---\nname: ''\nlabel: 7\n---\nBody").unwrap();
+
+        let settings = IndexSettings {
+            title_keys: vec!["name".to_string(), "title".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(parse_file(&named, &settings).unwrap().title, "Vex");
+        // Keys without text are skipped, falling back to the file name.
+        assert_eq!(parse_file(&untitled, &settings).unwrap().title, "char_043");
+
+        // By default only `title` is read.
+        let page = parse_file(&named, &IndexSettings::default()).unwrap();
+        assert_eq!(page.title, "Ignored");
+    }
+
     #[test]
     fn test_parse_file_no_frontmatter() -> Result<()> {
         let content = r#"
@@ -162,7 +727,7 @@ It just has a [[Simple Link]].
         let file_path = dir.path().join("no_frontmatter.md");
         fs::write(&file_path, content).unwrap();
 
-        let page = parse_file(&file_path).unwrap();
+        let page = parse_file(&file_path, &IndexSettings::default()).unwrap();
 
         // Title should fall back to the file stem
         assert_eq!(page.title, "no_frontmatter");
@@ -1,23 +1,35 @@
 //! Markdown and Wikilink rendering engine.
 
-use crate::config::IMAGES_DIR_NAME;
+use crate::config::{RenderSettings, MAX_RELATED_PAGES};
 use crate::error::ChroniclerError;
-use crate::models::{Backlink, FullPageData, TocEntry};
+use crate::models::{Backlink, FullPageData, TaskProgress, TocEntry};
 use crate::sanitizer;
-use crate::wikilink::WIKILINK_RE;
-use crate::{error::Result, indexer::Indexer, models::RenderedPage, parser};
+use crate::utils::{self, file_stem_string};
+use crate::wikilink::{format_link, WIKILINK_IMAGE_RE, WIKILINK_RE};
+use crate::{
+    error::Result,
+    indexer::{Indexer, UID_LINK_PREFIX},
+    models::RenderedPage,
+    parser::{self, BlockLine, DETAILS_BLOCK_OPEN, GM_BLOCK_OPEN, HASHTAG_RE, SPOILER_RE},
+};
 use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use html_escape::decode_html_entities;
 use parking_lot::RwLock;
-use path_clean::PathClean;
 use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
-use pulldown_cmark::{html, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
-use regex::{Captures, Regex};
+use pulldown_cmark::{
+    html, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
+};
+use regex::{Captures, Regex, RegexBuilder};
 use serde_json::{Map, Value};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock};
+use tracing::warn;
 
 // A character set for percent-encoding that ensures slashes and colons are encoded.
 // This matches the behavior of the frontend `convertFileSrc` function.
@@ -27,24 +39,238 @@ const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
     .remove(b'.')
     .remove(b'~');
 
-/// Spoiler regex pattern.
-/// Captures: 1: content
-/// Format: ||content||
-static SPOILER_RE: LazyLock<Regex> = LazyLock::new(|| {
-    // The `.*?` is a non-greedy match to correctly handle multiple spoilers on one line.
-    Regex::new(r"\|\|(.*?)\|\|").unwrap()
-});
-
 /// HTML img tag regex pattern.
 /// Captures: 1: src attribute content
 /// Used to find and replace local image paths with asset URLs.
 static IMG_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<img src="([^"]+)""#).unwrap());
 
-/// Wikilink Image regex pattern.
-/// Captures: 1: target/filename, 2: alias/alt-text
-/// Format: ![[filename.png|alt text]]
-static WIKILINK_IMAGE_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r#"!\[\[([^\|\]]+)(?:\|([^\]]+))?\]\]"#).unwrap());
+/// Sanitized audio and video player tag regex pattern.
+/// Captures: 1: the tag name, 2: src attribute content
+/// Used like `IMG_TAG_RE` to point embedded media at asset URLs.
+static MEDIA_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<(audio|video) controls="" src="([^"]+)""#).unwrap());
+
+/// Highlight regex pattern.
+/// Captures: 1: content
+/// Format: ==content==
+///
+/// The content can't start or end with a space, so comparisons like `a == b == c`
+/// aren't highlighted. The `.*?` is non-greedy to handle several highlights on a line.
+static HIGHLIGHT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"==([^=\s](?:.*?[^=\s])?)==").unwrap());
+
+/// The size given as the last `|` part of an image embed, e.g. `![[map.png|300]]`
+/// or `![[map.png|Map|300x200]]`.
+/// Captures: 1: width, 2: height
+static IMAGE_SIZE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(\d+)(?:\s*x\s*(\d+))?\s*$").unwrap());
+
+/// A block reference marker at the end of a paragraph or list item's text.
+/// Captures: 1: the block id
+/// Format: Some paragraph. ^abc123
+static BLOCK_ID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)\^([A-Za-z0-9-]+)$").unwrap());
+
+/// Same-page section link regex pattern.
+/// Captures: 1: section, 2: alias
+/// Format: [[#Section|alias]]
+static SECTION_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[#([^\[\]\|#]+)(?:\|([^\[\]]+))?\]\]").unwrap());
+
+/// Abbreviation definition regex pattern, one per line.
+/// Captures: 1: the abbreviation, 2: its expansion
+/// Format: *[HTML]: HyperText Markup Language
+static ABBREVIATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s{0,3}\*\[([^\[\]]+)\]:\s*(.*?)\s*$").unwrap());
+
+/// A standard Markdown image whose destination contains a space, which CommonMark
+/// only accepts inside angle brackets.
+/// Captures: 1: the opening `![alt](`, 2: the destination and any title
+/// Format: ![alt](images/my map.png)
+static SPACED_IMAGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(!\[[^\]\n]*\]\()([^()<>\n]*[ \t][^()<>\n]*)\)").unwrap());
+
+/// Splits an image's destination from a quoted title.
+/// Captures: 1: the destination, 2: the title with its quotes
+/// Format: images/my map.png "A title"
+static IMAGE_TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^(.*?)[ \t]+("[^"]*"|'[^']*')$"#).unwrap());
+
+/// Matches a table or an image in rendered HTML, for wrapping wide elements.
+/// Captures: 1: a whole table, 2: an image's `width` attribute, if it has one
+static WIDE_ELEMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)(<table\b.*?</table>)|<img\b[^>]*?(?:\bwidth="(\d+)"[^>]*)?>"#).unwrap()
+});
+
+/// The marker opening a callout, on the first line of a blockquote.
+/// Captures: 1: the callout type, 2: `-` or `+` if the callout is foldable
+/// Format: [!note]- Optional title
+static CALLOUT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[!([A-Za-z][\w-]*)\]([+-])?[ \t]*").unwrap());
+
+/// The class of the element wrapped around wide content so it scrolls sideways.
+const SCROLL_WRAP_CLASS: &str = "scroll-wrap";
+
+/// Matches an HTML tag, so that text can be processed without touching markup.
+static HTML_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Matches an HTML character reference, like `&amp;` or `&#39;`.
+static HTML_ENTITY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(?:#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);").unwrap());
+
+/// The maximum number of pages followed through nested `![[Page]]` embeds.
+const MAX_EMBED_DEPTH: usize = 4;
+
+/// The maximum number of templates followed through chained `extends` keys.
+/// This guards against overly deep or accidentally cyclic template chains.
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// Frontmatter keys whose values are shown as human-readable dates when enabled.
+const DATE_KEYS: &[&str] = &["date", "created", "updated"];
+
+/// The summary shown for a collapsible block opened without a title.
+const DEFAULT_DETAILS_SUMMARY: &str = "Details";
+
+/// The prefix of a definition line in definition-list shorthand, e.g. `: A red fruit.`
+/// The space is required so lines like `:smile:` aren't mistaken for definitions.
+const DEFINITION_MARKER: &str = ": ";
+
+/// The info string of a fenced code block holding a mermaid diagram.
+const MERMAID_LANGUAGE: &str = "mermaid";
+
+/// The prefixes that make the last line of a blockquote its attribution, e.g. `— Author`.
+const ATTRIBUTION_MARKERS: &[&str] = &["—", "--", "- "];
+
+/// The HTML that closes a definition list, used to merge adjacent lists.
+const DEFINITION_LIST_CLOSE: &str = "</dl>\n";
+
+/// The prefix of an inline code span rendered as keyboard keys, e.g. `` `kbd:Ctrl+C` ``.
+const KBD_CODE_PREFIX: &str = "kbd:";
+
+/// The opening of an inline footnote, e.g. `^[A note.]`.
+const INLINE_FOOTNOTE_OPEN: &str = "^[";
+
+/// The prefix of the labels generated for inline footnotes.
+const INLINE_FOOTNOTE_LABEL: &str = "inline-note-";
+
+/// The delimiter of display math. A block of math opens and closes with a line
+/// holding only `$$`, or sits on one line as `$$...$$`.
+const MATH_BLOCK_DELIMITER: &str = "$$";
+
+/// Marks where an extracted math span or block goes back into the rendered HTML.
+/// An HTML comment passes through Markdown parsing and custom syntax untouched.
+/// Captures: 1: the index of the math
+static MATH_PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<!--math:(\d+)-->").unwrap());
+
+/// The callout types rendered as callouts; a blockquote opened with any other
+/// `[!type]` stays a plain blockquote.
+const CALLOUT_TYPES: &[&str] = &[
+    "note",
+    "abstract",
+    "summary",
+    "tldr",
+    "info",
+    "todo",
+    "tip",
+    "hint",
+    "important",
+    "success",
+    "check",
+    "done",
+    "question",
+    "help",
+    "faq",
+    "warning",
+    "caution",
+    "attention",
+    "failure",
+    "fail",
+    "missing",
+    "danger",
+    "error",
+    "bug",
+    "example",
+    "quote",
+    "cite",
+    "lore",
+];
+
+/// The kinds of `:::` container blocks understood by the renderer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContainerBlock {
+    Gm,
+    Details,
+}
+
+/// Per-render information about the content being rendered.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderContext<'a> {
+    /// The path of the page being rendered, if known. Links whose name matches
+    /// several pages resolve to the one nearest this page.
+    pub source: Option<&'a Path>,
+    /// Renders links for a standalone HTML export rather than the app: page links
+    /// get real relative `href`s to the exported `.html` files, and section links
+    /// (`[[Page#Section]]`, `[[#Section]]`) point at the heading's anchor.
+    pub export: bool,
+    /// Renders only the prose for a distraction-free reading view: the frontmatter
+    /// isn't processed (it is returned as `Null`), no TOC is returned, and the whole
+    /// body is returned as `html_after_toc`.
+    pub focus: bool,
+    /// The pages this content is embedded in through `![[Page]]` embeds, outermost
+    /// first. Used to stop pages from embedding each other endlessly.
+    pub embedded_in: &'a [PathBuf],
+    /// Prefixed to the IDs of headings and blocks. Content embedded with `![[Page]]`
+    /// gets one, e.g. `embed-2-`, so its IDs don't repeat those of the page around
+    /// it; it is empty for anything rendered on its own.
+    pub id_prefix: &'a str,
+    /// The deepest heading level listed in the TOC, e.g. `3` for H1 to H3. Deeper
+    /// headings still get anchor IDs and count towards the numbering. All levels
+    /// are listed when `None`.
+    pub toc_depth: Option<u32>,
+}
+
+/// The terms defined by the glossary page, for linking them on rendered pages.
+struct Glossary {
+    /// The name the glossary page is linked by.
+    page_name: String,
+    /// Maps each lowercased term to the heading defining it.
+    headings: HashMap<String, String>,
+    /// Matches any of the terms as a whole word, ignoring case.
+    re: Regex,
+}
+
+impl Glossary {
+    /// Turns the first use of each term in a piece of Markdown text into a link to
+    /// the term's heading in the glossary, unless it's in `linked` already. Terms
+    /// inside existing wikilinks are left alone.
+    fn link_terms(&self, text: &str, linked: &mut HashSet<String>) -> String {
+        let mut link_term = |caps: &Captures| {
+            let term = &caps[0];
+            let key = term.to_lowercase();
+            match self.headings.get(&key) {
+                Some(heading) if linked.insert(key) => {
+                    format_link(&format!("{}#{}", self.page_name, heading), Some(term))
+                }
+                _ => term.to_string(),
+            }
+        };
+
+        let mut output = String::with_capacity(text.len());
+        let mut last = 0;
+        for link in WIKILINK_RE.find_iter(text) {
+            output.push_str(
+                &self
+                    .re
+                    .replace_all(&text[last..link.start()], &mut link_term),
+            );
+            output.push_str(link.as_str());
+            last = link.end();
+        }
+        output.push_str(&self.re.replace_all(&text[last..], &mut link_term));
+        output
+    }
+}
 
 /// A struct responsible for rendering Markdown content.
 #[derive(Debug)]
@@ -52,6 +278,16 @@ pub struct Renderer {
     indexer: Arc<RwLock<Indexer>>,
     // The vault path is needed to resolve relative image paths.
     vault_path: PathBuf,
+    /// User-configurable rendering options.
+    settings: RenderSettings,
+    /// The directory holding page templates, used to resolve `extends` in frontmatter.
+    templates_dir: Option<PathBuf>,
+    /// Rendered pages by path, with the hash of the content they were rendered from,
+    /// so flipping back to a page doesn't render it again.
+    render_cache: RwLock<HashMap<PathBuf, (String, RenderedPage)>>,
+    /// The TOC entries of pages that links point into, by path, so `[[Page#Heading]]`
+    /// links don't read and parse the target page once per link.
+    heading_cache: RwLock<HashMap<PathBuf, Vec<TocEntry>>>,
 }
 
 /// Determines the MIME type of a file based on its extension.
@@ -84,7 +320,66 @@ impl Renderer {
         Self {
             indexer,
             vault_path,
+            settings: RenderSettings::default(),
+            templates_dir: None,
+            render_cache: RwLock::new(HashMap::new()),
+            heading_cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the directory from which `extends` templates are loaded.
+    pub fn set_templates_dir(&mut self, templates_dir: PathBuf) {
+        self.templates_dir = Some(templates_dir);
+        self.render_cache.get_mut().clear();
+    }
+
+    /// Returns the current render settings.
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// Replaces the render settings used for subsequent renders.
+    pub fn set_settings(&mut self, settings: RenderSettings) {
+        self.settings = settings;
+        self.render_cache.get_mut().clear();
+        self.heading_cache.get_mut().clear();
+    }
+
+    /// Drops the cached render of the page at `path`, and of every page whose render
+    /// mentions it, such as pages linking to or embedding it. A change to the glossary
+    /// page can link a new term on any page, so it drops every cached render.
+    pub fn invalidate(&self, path: &Path) {
+        if self.is_glossary_page(path) {
+            self.clear_cache();
+            return;
         }
+        let web_path = path_to_web_str(path);
+        self.heading_cache.write().remove(path);
+        self.render_cache.write().retain(|cached_path, (_, page)| {
+            cached_path != path
+                && !page.html_before_toc.contains(&web_path)
+                && !page.html_after_toc.contains(&web_path)
+                && !page.processed_frontmatter.to_string().contains(&web_path)
+        });
+    }
+
+    /// Drops every cached render, for changes that can affect any page, like a new
+    /// page turning broken links into working ones.
+    pub fn clear_cache(&self) {
+        self.render_cache.write().clear();
+        self.heading_cache.write().clear();
+    }
+
+    /// Returns whether `path` is the configured glossary page, as a page at `path`
+    /// would resolve the name.
+    fn is_glossary_page(&self, path: &Path) -> bool {
+        self.settings
+            .glossary_page
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .and_then(|name| self.indexer.read().resolve_name(name, Some(path)))
+            .is_some_and(|glossary| glossary == path)
     }
 
     /// Resolves a potentially relative image path to an absolute path within the vault.
@@ -92,15 +387,8 @@ impl Renderer {
     /// This helper centralizes the logic for handling image paths. It correctly
     /// handles both absolute paths and relative paths, which are assumed to be
     /// inside the vault's "images" subdirectory.
-    fn resolve_image_path(&self, path_str: &str) -> PathBuf {
-        let path = Path::new(path_str);
-        let resolved_path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            // Assumes relative paths start from the vault's "images" directory.
-            self.vault_path.join(IMAGES_DIR_NAME).join(path)
-        };
-        resolved_path.clean()
+    pub fn resolve_image_path(&self, path_str: &str) -> PathBuf {
+        utils::resolve_image_path(&self.vault_path, path_str)
     }
 
     /// Processes an image source path, returning a correctly formatted Tauri v2 asset URL.
@@ -129,11 +417,23 @@ impl Renderer {
 
     /// Processes an image source path, returning a Base64 Data URL.
     /// It resolves both absolute and relative paths before encoding.
+    ///
+    /// Images larger than the `max_embed_image_bytes` setting are not embedded,
+    /// since a huge Data URL bloats the page and slows rendering. An asset URL is
+    /// returned for them instead.
     pub fn convert_image_path_to_data_url(&self, path_str: &str) -> String {
         let absolute_path = self.resolve_image_path(path_str);
 
+        if let Ok(metadata) = fs::metadata(&absolute_path) {
+            if metadata.len() > self.settings.max_embed_image_bytes {
+                return self.convert_image_path_to_asset_url(path_str);
+            }
+        }
+
         if let Ok(data) = fs::read(&absolute_path) {
-            let mime_type = get_mime_type(path_str);
+            // The resolved path is used, since the written one may lack an extension.
+            let resolved_name = absolute_path.to_string_lossy();
+            let mime_type = get_mime_type(&resolved_name);
             let encoded = general_purpose::STANDARD.encode(data);
             format!("data:{};base64,{}", mime_type, encoded)
         } else {
@@ -142,6 +442,19 @@ impl Renderer {
         }
     }
 
+    /// Converts a frontmatter image path into a `src` the frontend can display.
+    ///
+    /// Applies the hybrid logic: absolute paths are outside Tauri's asset protocol
+    /// scope, so they use the secure Base64 fallback, while relative (in-vault)
+    /// paths use the performant asset protocol.
+    fn resolve_frontmatter_image_src(&self, path_str: &str) -> String {
+        if Path::new(path_str).is_absolute() {
+            self.convert_image_path_to_data_url(path_str)
+        } else {
+            self.convert_image_path_to_asset_url(path_str)
+        }
+    }
+
     /// Processes the `image` field from the frontmatter, which can be a single
     /// string or a list of strings, preparing it for the frontend.
     ///
@@ -154,18 +467,7 @@ impl Renderer {
         let mut image_absolute_paths = Vec::new();
 
         let mut process_single_image = |path_str: &str| {
-            let path = Path::new(path_str);
-
-            // Apply the hybrid logic: use the best method based on the path type.
-            let image_src = if path.is_absolute() {
-                // For absolute paths, use the secure Base64 fallback. This is necessary
-                // because the path is outside Tauri's asset protocol scope.
-                self.convert_image_path_to_data_url(path_str)
-            } else {
-                // For relative (in-vault) paths, use the performant asset protocol.
-                self.convert_image_path_to_asset_url(path_str)
-            };
-            image_srcs.push(Value::String(image_src));
+            image_srcs.push(Value::String(self.resolve_frontmatter_image_src(path_str)));
 
             // Also resolve the absolute path for the frontend to use (e.g., for an "open file" button).
             let absolute_path = self.resolve_image_path(path_str);
@@ -196,6 +498,34 @@ impl Renderer {
         );
     }
 
+    /// Processes the `banner` field from the frontmatter, a single wide image
+    /// displayed above the page content, separate from the infobox images.
+    ///
+    /// Adds `banner` (the displayable src) and `banner_path` (the absolute path).
+    /// Nothing is added if the value is not a string or the file does not exist.
+    fn process_banner_image(&self, map: &mut Map<String, Value>, banner_value: &Value) {
+        let Some(path_str) = banner_value.as_str().map(str::trim) else {
+            return;
+        };
+        if path_str.is_empty() {
+            return;
+        }
+
+        let absolute_path = self.resolve_image_path(path_str);
+        if !absolute_path.is_file() {
+            return;
+        }
+
+        map.insert(
+            "banner".to_string(),
+            Value::String(self.resolve_frontmatter_image_src(path_str)),
+        );
+        map.insert(
+            "banner_path".to_string(),
+            Value::String(absolute_path.to_string_lossy().to_string()),
+        );
+    }
+
     /// A post-processing step that finds all standard HTML `<img src="...">` tags
     /// in a block of rendered HTML and converts their `src` paths.
     fn process_body_image_tags(&self, html: &str) -> String {
@@ -203,6 +533,10 @@ impl Renderer {
             .replace_all(html, |caps: &Captures| {
                 // 1. Get the path, which might have both HTML and URL encoding
                 let encoded_path_str = &caps[1];
+                // Images from embedded pages have been processed already.
+                if encoded_path_str.starts_with("data:") || encoded_path_str.contains("://") {
+                    return caps[0].to_string();
+                }
 
                 // 2. Decode the path string
                 let html_decoded_path = decode_html_entities(encoded_path_str);
@@ -228,6 +562,22 @@ impl Renderer {
             .to_string()
     }
 
+    /// Points the `src` of the sanitized audio and video players in the body at the
+    /// media files through the asset protocol. Media is never embedded as a Data
+    /// URL, since the files are often large.
+    fn process_body_media_tags(&self, html: &str) -> String {
+        MEDIA_TAG_RE
+            .replace_all(html, |caps: &Captures| {
+                let path = decode_html_entities(&caps[2]);
+                format!(
+                    r#"<{} controls="" src="{}""#,
+                    &caps[1],
+                    self.convert_image_path_to_asset_url(&path)
+                )
+            })
+            .to_string()
+    }
+
     /// Renders a string of Markdown to HTML, but strips the outer `<p>` tags.
     /// This is useful for rendering inline content like in infobox fields.
     fn render_inline_markdown(&self, markdown: &str) -> String {
@@ -251,9 +601,9 @@ impl Renderer {
 
     /// Processes a single string value from the frontmatter, rendering any custom syntax
     /// (wikilinks, spoilers, image tags) into final HTML.
-    fn render_frontmatter_string_as_html(&self, text: &str) -> String {
+    fn render_frontmatter_string_as_html(&self, text: &str, ctx: RenderContext) -> String {
         // 1. Process custom syntax first (wikilinks, spoilers, etc.)
-        let with_custom_syntax = self.render_custom_syntax_in_string(text);
+        let with_custom_syntax = self.render_custom_syntax_in_string(text, ctx);
 
         // 2. Render standard Markdown on the result of step 1.
         let with_markdown = self.render_inline_markdown(&with_custom_syntax);
@@ -262,10 +612,166 @@ impl Renderer {
         self.process_body_image_tags(&with_markdown)
     }
 
+    /// Returns the rows of a tabular frontmatter field: a non-empty list whose items
+    /// are all objects. Returns `None` for any other value.
+    fn tabular_rows(value: &Value) -> Option<Vec<&Map<String, Value>>> {
+        let rows: Vec<&Map<String, Value>> = value
+            .as_array()?
+            .iter()
+            .map(Value::as_object)
+            .collect::<Option<_>>()?;
+        (!rows.is_empty()).then_some(rows)
+    }
+
+    /// Renders the rows of a tabular frontmatter field as an HTML table.
+    ///
+    /// The columns are the rows' keys in order of first appearance, and a row
+    /// without a key gets an empty cell. String cells are rendered like other
+    /// frontmatter strings; columns holding only numbers are right-aligned.
+    fn render_frontmatter_table(
+        &self,
+        rows: Vec<&Map<String, Value>>,
+        ctx: RenderContext,
+    ) -> String {
+        let mut columns: Vec<&str> = Vec::new();
+        for row in &rows {
+            for key in row.keys() {
+                if !columns.contains(&key.as_str()) {
+                    columns.push(key);
+                }
+            }
+        }
+        let align = |column: &str| {
+            let numeric = rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .all(Value::is_number);
+            if numeric {
+                " align=\"right\""
+            } else {
+                ""
+            }
+        };
+
+        let mut html = String::from("<table class=\"frontmatter-table\">\n<thead>\n<tr>");
+        for column in &columns {
+            let _ = write!(
+                html,
+                "<th{}>{}</th>",
+                align(column),
+                html_escape::encode_text(column)
+            );
+        }
+        html.push_str("</tr>\n</thead>\n<tbody>\n");
+        for row in &rows {
+            html.push_str("<tr>");
+            for column in &columns {
+                let cell = match row.get(*column) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => self.render_frontmatter_string_as_html(s, ctx),
+                    Some(other) => html_escape::encode_text(&other.to_string()).into_owned(),
+                };
+                let _ = write!(html, "<td{}>{}</td>", align(column), cell);
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>");
+        html
+    }
+
+    /// Returns the human-readable form of a date-like frontmatter field, if date
+    /// humanizing is enabled, the key is one of `DATE_KEYS`, and the value is a date.
+    fn humanize_date_field(&self, key: &str, value: &Value) -> Option<String> {
+        if !self.settings.humanize_dates || !DATE_KEYS.contains(&key) {
+            return None;
+        }
+        self.humanize_date(value.as_str()?)
+    }
+
+    /// Formats an ISO date (`2024-03-05`) or date-time (`2024-03-05T10:00:00`,
+    /// optionally with an offset) using the configured date format.
+    ///
+    /// Returns `None` if the value isn't a date or the format string is invalid.
+    fn humanize_date(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .or_else(|| {
+                DateTime::parse_from_rfc3339(value)
+                    .ok()
+                    .map(|dt| dt.date_naive())
+            })
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+                    .ok()
+                    .map(|dt| dt.date())
+            })?;
+
+        // An invalid format string makes `Display` fail, so write it fallibly.
+        let mut formatted = String::new();
+        write!(formatted, "{}", date.format(&self.settings.date_format)).ok()?;
+        Some(formatted)
+    }
+
+    /// Merges fields from the template named by the frontmatter's `extends` key.
+    ///
+    /// The page's own values always win; the template only supplies fields the page
+    /// omits. Templates may themselves use `extends`, forming a chain. The template's
+    /// `title` is never inherited, and the `extends` key itself is removed. This only
+    /// affects the rendered output; the page file is not modified.
+    fn apply_frontmatter_extends(&self, frontmatter: &mut Value) {
+        let Value::Object(map) = frontmatter else {
+            return;
+        };
+
+        let mut next = map.remove("extends");
+        let Some(templates_dir) = &self.templates_dir else {
+            return;
+        };
+
+        let mut visited = HashSet::new();
+        while let Some(Value::String(name)) = next.take() {
+            let name = name.trim();
+            let name = name.strip_suffix(".md").unwrap_or(name);
+            // Only plain template names are allowed, never paths.
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                warn!("Ignoring invalid template name in `extends`: {:?}", name);
+                break;
+            }
+            if !visited.insert(name.to_lowercase()) || visited.len() > MAX_EXTENDS_DEPTH {
+                warn!(
+                    "Stopping `extends` chain at repeated or too-deep template {:?}",
+                    name
+                );
+                break;
+            }
+
+            let path = templates_dir.join(format!("{}.md", name));
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Could not read template {:?} for `extends`: {}", path, e);
+                    break;
+                }
+            };
+            let (template_fm, _) = parser::extract_frontmatter(&content);
+            let Ok(Value::Object(mut template_map)) = parser::parse_frontmatter(template_fm, &path)
+            else {
+                break;
+            };
+
+            next = template_map.remove("extends");
+            template_map.remove("title");
+            for (key, value) in template_map {
+                map.entry(key).or_insert(value);
+            }
+        }
+    }
+
     /// Takes a parsed serde_json::Value representing the frontmatter, sanitizes it,
     /// and recursively processes all string fields to render custom syntax. This
     /// function modifies the `Value` in place.
-    fn process_frontmatter(&self, frontmatter: &mut Value) {
+    fn process_frontmatter(&self, frontmatter: &mut Value, ctx: RenderContext) {
         sanitizer::sanitize_json_values(frontmatter);
 
         if let Value::Object(map) = frontmatter {
@@ -281,15 +787,26 @@ impl Renderer {
                     // This function will add the 'images' and 'image_paths' keys
                     // to our new `processed_map` at the correct position.
                     self.process_infobox_images(&mut processed_map, &value);
+                } else if key == "banner" {
+                    // The banner is resolved like `image`, but kept under its own keys.
+                    self.process_banner_image(&mut processed_map, &value);
+                } else if let Some(date) = self.humanize_date_field(&key, &value) {
+                    // Date-like keys holding an ISO date are shown in a human-readable format.
+                    processed_map.insert(key, Value::String(date));
+                } else if let Some(rows) = Self::tabular_rows(&value) {
+                    // A list of objects is shown as a table rather than inline values.
+                    processed_map
+                        .insert(key, Value::String(self.render_frontmatter_table(rows, ctx)));
                 } else {
                     // For all other keys, process them and insert into the new map.
                     let mut new_value = value;
                     if let Value::String(s) = &new_value {
-                        new_value = Value::String(self.render_frontmatter_string_as_html(s));
+                        new_value = Value::String(self.render_frontmatter_string_as_html(s, ctx));
                     } else if let Value::Array(arr) = &mut new_value {
                         for item in arr.iter_mut() {
                             if let Value::String(s) = item {
-                                *item = Value::String(self.render_frontmatter_string_as_html(s));
+                                *item =
+                                    Value::String(self.render_frontmatter_string_as_html(s, ctx));
                             }
                         }
                     }
@@ -303,9 +820,25 @@ impl Renderer {
     }
 
     /// Processes raw markdown content into a structured, rendered page object.
+    ///
+    /// Links are resolved without knowing which page the content belongs to; use
+    /// [`Renderer::render_page_with_context`] when the page's path is known.
     pub fn render_page_preview(&self, content: &str) -> Result<RenderedPage> {
+        self.render_page_with_context(content, RenderContext::default())
+    }
+
+    /// Processes raw markdown content into a structured, rendered page object,
+    /// resolving ambiguous links relative to the page described by `ctx`.
+    pub fn render_page_with_context(
+        &self,
+        content: &str,
+        ctx: RenderContext,
+    ) -> Result<RenderedPage> {
         // 1. Separate and parse the frontmatter.
         let (frontmatter_str, body) = parser::extract_frontmatter(content);
+        if ctx.focus {
+            return Ok(self.render_focus_page(frontmatter_str, body, ctx));
+        }
         let mut frontmatter_json = match parser::parse_frontmatter(frontmatter_str, Path::new("")) {
             Ok(fm) => fm,
             Err(e) => {
@@ -320,11 +853,23 @@ impl Renderer {
             }
         };
 
+        // Merge in any fields inherited from an `extends` template.
+        self.apply_frontmatter_extends(&mut frontmatter_json);
+
+        // The raw title is needed to detect a duplicate H1, before the frontmatter
+        // fields are rendered to HTML.
+        let duplicate_title = if self.settings.suppress_duplicate_title {
+            Some(self.title_from_frontmatter(&frontmatter_json, ctx.source))
+        } else {
+            None
+        };
+
         // 2. Sanitize and render all fields within the frontmatter.
-        self.process_frontmatter(&mut frontmatter_json);
+        self.process_frontmatter(&mut frontmatter_json, ctx);
 
         // 3. Render the main body content to HTML, correctly handling custom syntax.
-        let (html_before_toc, html_after_toc, toc) = self.render_body_to_html_with_toc(body);
+        let (html_before_toc, html_after_toc, toc) =
+            self.render_body_to_html_with_toc(body, duplicate_title.as_deref(), ctx);
 
         // 4. Return the complete structure.
         Ok(RenderedPage {
@@ -332,136 +877,751 @@ impl Renderer {
             html_before_toc,
             html_after_toc,
             toc,
+            max_content_width: self.settings.max_content_width,
+            tasks: self.count_tasks(body),
         })
     }
 
-    /// Replaces all custom syntax (spoilers and wikilinks) in a string with valid HTML.
-    fn render_custom_syntax_in_string(&self, text: &str) -> String {
-        // 1. Process spoilers first.
-        let with_spoilers = SPOILER_RE.replace_all(text, |caps: &Captures| {
-            format!("<span class=\"spoiler\">{}</span>", &caps[1])
-        });
-
-        // 2. Process image wikilinks ![[...]] into <img> tags.
-        let with_images = WIKILINK_IMAGE_RE.replace_all(&with_spoilers, |caps: &Captures| {
-            let path_str = caps.get(1).map_or("", |m| m.as_str()).trim();
-            let alt_text = caps.get(2).map_or(path_str, |m| m.as_str().trim());
+    /// Works out a page's title from its frontmatter the way the index does, using
+    /// the configured title keys and falling back to the name of `source`, so it
+    /// matches the indexed title even before unsaved changes are indexed.
+    fn title_from_frontmatter(&self, frontmatter: &Value, source: Option<&Path>) -> String {
+        let indexer = self.indexer.read();
+        parser::extract_title(
+            frontmatter,
+            source.unwrap_or(Path::new("")),
+            &indexer.settings.title_keys,
+        )
+    }
 
-            // Generate the simple <img> tag with the given path.
-            // This will then be handled by the `process_body_image_tags` post-processor.
-            format!(
-                r#"<img src="{}" alt="{}">"#,
-                // Use the normalized path directly as the src
-                path_str,
-                html_escape::encode_double_quoted_attribute(alt_text)
-            )
-        });
+    /// Returns the indexed title of the page at `path`, or its file name if it isn't
+    /// indexed.
+    pub fn page_title(&self, path: &Path) -> String {
+        self.indexer
+            .read()
+            .pages
+            .get(path)
+            .map_or_else(|| file_stem_string(path), |page| page.title.clone())
+    }
 
-        // 3. Finally, process standard wikilinks [[...]] on the remaining text.
-        let indexer = self.indexer.read();
-        WIKILINK_RE
-            .replace_all(&with_images, |caps: &Captures| {
-                let target = caps.get(1).map_or("", |m| m.as_str()).trim();
-                let alias = caps.get(3).map(|m| m.as_str().trim()).unwrap_or(target);
-                let normalized_target = target.to_lowercase();
+    /// Renders just the body of a page for focus mode, skipping the infobox and TOC.
+    fn render_focus_page(
+        &self,
+        frontmatter_str: &str,
+        body: &str,
+        ctx: RenderContext,
+    ) -> RenderedPage {
+        // The frontmatter is only needed to spot a heading that repeats the title.
+        let duplicate_title = if self.settings.suppress_duplicate_title {
+            parser::parse_frontmatter(frontmatter_str, Path::new(""))
+                .ok()
+                .map(|fm| self.title_from_frontmatter(&fm, ctx.source))
+        } else {
+            None
+        };
+        let (html_before_toc, html_after_toc, _) =
+            self.render_body_to_html_with_toc(body, duplicate_title.as_deref(), ctx);
 
-                if let Some(path) = indexer.link_resolver.get(&normalized_target) {
-                    let web_path = path_to_web_str(path);
-                    format!(
-                        "<a href=\"#\" class=\"internal-link\" data-path=\"{}\">{}</a>",
-                        web_path, alias
-                    )
-                } else {
-                    format!(
-                        "<a href=\"#\" class=\"internal-link broken\" data-target=\"{}\">{}</a>",
-                        target, // Use the original target name for creation
-                        alias
-                    )
-                }
-            })
-            .to_string()
+        RenderedPage {
+            processed_frontmatter: Value::Null,
+            html_before_toc: String::new(),
+            html_after_toc: html_before_toc + &html_after_toc,
+            toc: Vec::new(),
+            max_content_width: self.settings.max_content_width,
+            tasks: None,
+        }
     }
 
-    /// Extracts the display text from wikilinks within a string, leaving other text intact.
-    /// For example, "[[Page|Alias]] (extra)" becomes "Alias (extra)".
-    fn extract_display_text_from_wikilinks(&self, text: &str) -> String {
-        WIKILINK_RE
-            .replace_all(text, |caps: &Captures| {
-                // Use the alias (capture group 3) if it exists, otherwise use the target (capture group 1).
-                let alias = caps.get(3).map(|m| m.as_str().trim());
-                let target = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
-                alias.unwrap_or(target).to_string()
-            })
-            .to_string()
+    /// Counts the checked task list items in a page body, at any nesting depth, out
+    /// of all of them. Items in GM-only blocks don't count in player mode, since
+    /// they aren't shown. Returns `None` if there are no task list items.
+    fn count_tasks(&self, body: &str) -> Option<TaskProgress> {
+        let body = self.render_container_blocks(body);
+        let mut progress = TaskProgress {
+            completed: 0,
+            total: 0,
+        };
+        for event in Parser::new_ext(&body, Options::ENABLE_TASKLISTS) {
+            if let Event::TaskListMarker(checked) = event {
+                progress.total += 1;
+                progress.completed += usize::from(checked);
+            }
+        }
+        (progress.total > 0).then_some(progress)
     }
 
-    /// Renders Markdown body content to HTML, processing custom wikilinks, and generating a TOC.
+    /// Resolves `:::gm` and `:::details` ... `:::` container blocks in a Markdown body.
     ///
-    /// The function splits the resulting HTML at the first header, allowing the frontend
-    /// to inject the Table of Contents between any introductory content and the main body.
+    /// A GM block's content is normally wrapped in `<div class="gm-only">` so it can
+    /// be styled as GM-only; in player mode the block is removed entirely. A details
+    /// block becomes a collapsible `<details>` element whose summary is the text after
+    /// `:::details`. Blocks can be nested, and each `:::` closes the innermost open
+    /// block. The content between the markers is left as Markdown, so it is rendered
+    /// like the rest of the page.
     ///
-    /// ## Behavior
+    /// Fences inside code blocks are left alone, and an unclosed block runs to the end
+    /// of the body so an unfinished edit can't leak GM content to players.
+    fn render_container_blocks<'a>(&self, markdown: &'a str) -> Cow<'a, str> {
+        if !markdown.contains(GM_BLOCK_OPEN) && !markdown.contains(DETAILS_BLOCK_OPEN) {
+            return Cow::Borrowed(markdown);
+        }
+        let player_mode = self.settings.player_mode;
+
+        let mut output = String::with_capacity(markdown.len());
+        let mut open_blocks: Vec<ContainerBlock> = Vec::new();
+
+        for (line, kind) in parser::container_block_lines(markdown) {
+            let hidden = player_mode && open_blocks.contains(&ContainerBlock::Gm);
+            match kind {
+                BlockLine::GmOpen => {
+                    open_blocks.push(ContainerBlock::Gm);
+                    if !player_mode {
+                        output.push_str("<div class=\"gm-only\">\n\n");
+                    }
+                }
+                BlockLine::DetailsOpen(summary) => {
+                    open_blocks.push(ContainerBlock::Details);
+                    if !hidden {
+                        let summary = if summary.is_empty() {
+                            DEFAULT_DETAILS_SUMMARY
+                        } else {
+                            summary
+                        };
+                        output.push_str(&format!(
+                            "<details>\n<summary>{}</summary>\n\n",
+                            html_escape::encode_text(summary)
+                        ));
+                    }
+                }
+                BlockLine::Close => {
+                    let block = open_blocks.pop();
+                    if !hidden {
+                        output.push_str(Self::container_block_end(block));
+                    }
+                }
+                BlockLine::Content => {
+                    if !hidden {
+                        output.push_str(line);
+                    }
+                }
+            }
+        }
+
+        // Close any blocks left open, innermost first.
+        while !open_blocks.is_empty() {
+            let hidden = player_mode && open_blocks.contains(&ContainerBlock::Gm);
+            let block = open_blocks.pop();
+            if !hidden {
+                output.push_str(Self::container_block_end(block));
+            }
+        }
+        Cow::Owned(output)
+    }
+
+    /// Returns the HTML that closes a container block.
+    fn container_block_end(block: Option<ContainerBlock>) -> &'static str {
+        match block {
+            Some(ContainerBlock::Gm) => "\n</div>\n\n",
+            Some(ContainerBlock::Details) => "\n</details>\n\n",
+            None => "",
+        }
+    }
+
+    /// Rewrites inline footnotes (`^[A note.]`) as reference footnotes, appending
+    /// their definitions to the end of the document. Each gets a label that isn't
+    /// already used in the document.
     ///
-    /// This function implements a specific set of rules for rendering `[[wikilinks]]`:
+    /// Fenced code blocks and inline code spans are left untouched. An inline
+    /// footnote must close on the line it opens, and may contain wikilinks.
+    fn expand_inline_footnotes(markdown: &str) -> Cow<'_, str> {
+        if !markdown.contains(INLINE_FOOTNOTE_OPEN) {
+            return Cow::Borrowed(markdown);
+        }
+
+        let mut output = String::with_capacity(markdown.len());
+        let mut definitions = String::new();
+        let mut next_id = 1;
+        let mut code_fence: Option<&str> = None;
+
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some(fence) = code_fence {
+                if trimmed.starts_with(fence) {
+                    code_fence = None;
+                }
+                output.push_str(line);
+                continue;
+            }
+            if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+                code_fence = Some(fence);
+                output.push_str(line);
+                continue;
+            }
+
+            let mut rest = line;
+            while let Some((before, note, after)) = Self::next_inline_footnote(rest) {
+                let label = loop {
+                    let label = format!("{}{}", INLINE_FOOTNOTE_LABEL, next_id);
+                    next_id += 1;
+                    if !markdown.contains(&format!("[^{}]", label)) {
+                        break label;
+                    }
+                };
+                output.push_str(before);
+                output.push_str(&format!("[^{}]", label));
+                definitions.push_str(&format!("\n[^{}]: {}\n", label, note.trim()));
+                rest = after;
+            }
+            output.push_str(rest);
+        }
+
+        if definitions.is_empty() {
+            return Cow::Borrowed(markdown);
+        }
+        if !output.ends_with('\n') {
+            output.push('\n');
+        }
+        output.push_str(&definitions);
+        Cow::Owned(output)
+    }
+
+    /// Wraps the destinations of standard Markdown images that contain spaces in
+    /// angle brackets, so `![alt](images/my map.png)` is parsed as an image rather
+    /// than left as text. A quoted title after the destination is kept.
     ///
-    /// 1.  **Block-Level Code**: Wikilinks ARE processed inside fenced (```) and indented code blocks.
-    /// 2.  **Inline Code**: Wikilinks are NOT processed inside inline (` `) code and the literal `[[...]]` syntax is preserved.
-    /// 3.  **All Other Text**: Wikilinks are processed as normal.
+    /// Fenced code blocks and inline code spans are left untouched.
+    fn bracket_spaced_image_paths(markdown: &str) -> Cow<'_, str> {
+        if !SPACED_IMAGE_RE.is_match(markdown) {
+            return Cow::Borrowed(markdown);
+        }
+
+        let mut output = String::with_capacity(markdown.len());
+        let mut code_fence: Option<&str> = None;
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some(fence) = code_fence {
+                if trimmed.starts_with(fence) {
+                    code_fence = None;
+                }
+                output.push_str(line);
+                continue;
+            }
+            if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+                code_fence = Some(fence);
+                output.push_str(line);
+                continue;
+            }
+
+            let bracketed = SPACED_IMAGE_RE.replace_all(line, |caps: &Captures| {
+                let whole = &caps[0];
+                // An odd number of backticks before the image means it's in a code span.
+                let in_code = line[..caps.get(0).map_or(0, |m| m.start())]
+                    .matches('`')
+                    .count()
+                    % 2
+                    == 1;
+                let inner = caps[2].trim();
+                let (destination, title) = match IMAGE_TITLE_RE.captures(inner) {
+                    Some(parts) => (parts.get(1).map_or("", |m| m.as_str()), parts.get(2)),
+                    None => (inner, None),
+                };
+                if in_code || !destination.contains([' ', '\t']) {
+                    return whole.to_string();
+                }
+                match title {
+                    Some(title) => format!("{}<{}> {})", &caps[1], destination, title.as_str()),
+                    None => format!("{}<{}>)", &caps[1], destination),
+                }
+            });
+            output.push_str(&bracketed);
+        }
+        Cow::Owned(output)
+    }
+
+    /// Returns the index just past the code span opened by the backtick run at
+    /// `start`, or past the run itself if no matching run closes it.
+    fn skip_code_span(line: &str, start: usize) -> usize {
+        let bytes = line.as_bytes();
+        let run = bytes[start..].iter().take_while(|&&b| b == b'`').count();
+        let fence = &line[start..start + run];
+        let mut search = start + run;
+        while let Some(offset) = line[search..].find(fence) {
+            let end = search + offset + run;
+            if bytes.get(end) != Some(&b'`') {
+                return end;
+            }
+            search = end + bytes[end..].iter().take_while(|&&b| b == b'`').count();
+        }
+        start + run
+    }
+
+    /// Returns `true` if `line`, without its indentation, starts a list item.
+    fn is_list_item(line: &str) -> bool {
+        let marker = line.trim_start_matches(|c: char| c.is_ascii_digit());
+        let ordered = marker.len() < line.len();
+        let rest = if ordered {
+            marker.strip_prefix(['.', ')'])
+        } else {
+            marker.strip_prefix(['-', '*', '+'])
+        };
+        rest.is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+    }
+
+    /// Replaces the math in `markdown` with placeholders, returning the new Markdown
+    /// and the HTML of each piece of math, to be put back by `restore_math`.
     ///
-    /// ## Table of Contents Generation
+    /// Inline math is written `$...$`. The opening `$` must be followed by a
+    /// non-space character, and the closing one preceded by one and not followed by
+    /// a digit, so prices like `$5 and $10` aren't taken as math. Display math uses
+    /// `$$` lines. The TeX is kept as written, HTML-escaped, in a `math-inline` span
+    /// or `math-block` div for the frontend to typeset.
     ///
-    /// A preliminary pass is made over the Markdown to extract all headers (`<h1>` to `<h6>`).
-    /// For each header, it generates:
-    /// - A hierarchical number (e.g., "1", "1.1", "2").
-    /// - A unique, URL-friendly `id` (a "slug") for anchor linking. Duplicate header
-    ///   text is handled by appending a counter to the slug (e.g., `my-header`, `my-header-1`).
-    ///
-    /// ## Implementation Details
-    ///
-    /// The `pulldown-cmark` parser emits a stream of events. A key challenge is that it may
-    /// fragment text, for example, sending `[[wikilink]]` as three separate `Text` events:
-    /// `Text("[[")`, `Text("wikilink")`, and `Text("]]")`.
-    ///
-    /// To solve this, we use a **text-buffering** (or coalescing) strategy:
-    /// - We loop through the events from the parser.
-    /// - `Text` events are collected into a temporary `text_buffer`.
-    /// - Any non-`Text` event triggers a "flush" of the buffer. Flushing involves running the
-    ///   wikilink replacement logic on the entire buffered string.
-    ///
-    /// This approach works because of how `pulldown-cmark` creates events:
-    /// - The content of **block-level code** is made of `Text` events, so it gets buffered and processed.
-    /// - **Inline code** is a single, discrete `Event::Code`, not `Text`. This event triggers a buffer
-    ///   flush and is then passed through, so its content is never processed for wikilinks.
-    ///
-    /// ## Returns
+    /// Fenced and indented code blocks and inline code spans are left untouched. A
+    /// line indented by four spaces or a tab is taken as code when it follows a
+    /// blank line or more code, unless it is inside a list.
+    fn extract_math(markdown: &str) -> (Cow<'_, str>, Vec<String>) {
+        if !markdown.contains('$') {
+            return (Cow::Borrowed(markdown), Vec::new());
+        }
+
+        let mut output = String::with_capacity(markdown.len());
+        let mut math = Vec::new();
+        let mut code_fence: Option<&str> = None;
+        // Whether the previous line was blank or indented code, and whether the last
+        // unindented line was a list item, to tell indented code from other lines.
+        let mut after_blank = true;
+        let mut in_indented_code = false;
+        let mut in_list = false;
+        // The opening line and the TeX so far of an unclosed display block.
+        let mut block: Option<(&str, String)> = None;
+        let push_block = |output: &mut String, math: &mut Vec<String>, tex: &str| {
+            output.push_str(&format!("<!--math:{}-->\n", math.len()));
+            math.push(format!(
+                "<div class=\"math-block\">{}</div>\n",
+                html_escape::encode_text(tex.trim())
+            ));
+        };
+
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some((_, tex)) = block.as_mut() {
+                if trimmed == MATH_BLOCK_DELIMITER {
+                    push_block(&mut output, &mut math, tex);
+                    block = None;
+                } else {
+                    tex.push_str(line);
+                }
+                continue;
+            }
+            if let Some(fence) = code_fence {
+                if trimmed.starts_with(fence) {
+                    code_fence = None;
+                }
+                output.push_str(line);
+                continue;
+            }
+            if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+                code_fence = Some(fence);
+                output.push_str(line);
+                continue;
+            }
+
+            if trimmed.is_empty() {
+                after_blank = true;
+                output.push_str(line);
+                continue;
+            }
+            let indented = line.starts_with('\t') || line.starts_with("    ");
+            in_indented_code = indented && !in_list && (after_blank || in_indented_code);
+            after_blank = false;
+            if in_indented_code {
+                output.push_str(line);
+                continue;
+            }
+            if !indented {
+                in_list = Self::is_list_item(trimmed);
+            }
+
+            if trimmed == MATH_BLOCK_DELIMITER {
+                block = Some((line, String::new()));
+                continue;
+            }
+            let one_line_block = trimmed
+                .strip_prefix(MATH_BLOCK_DELIMITER)
+                .and_then(|rest| rest.strip_suffix(MATH_BLOCK_DELIMITER))
+                .filter(|tex| !tex.trim().is_empty() && !tex.contains('$'));
+            if let Some(tex) = one_line_block {
+                push_block(&mut output, &mut math, tex);
+                continue;
+            }
+            Self::extract_inline_math(line, &mut output, &mut math);
+        }
+
+        // A display block that never closes is left as text.
+        if let Some((opening, tex)) = block {
+            output.push_str(opening);
+            output.push_str(&tex);
+        }
+
+        if math.is_empty() {
+            return (Cow::Borrowed(markdown), math);
+        }
+        (Cow::Owned(output), math)
+    }
+
+    /// Appends `line` to `output` with its inline math replaced by placeholders,
+    /// adding the math's HTML to `math`.
+    fn extract_inline_math(line: &str, output: &mut String, math: &mut Vec<String>) {
+        let bytes = line.as_bytes();
+        let mut copied = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'`' => i = Self::skip_code_span(line, i),
+                // A run of dollar signs, e.g. `$$` in running text, never opens math.
+                b'$' if bytes.get(i + 1) == Some(&b'$') => {
+                    i += bytes[i..].iter().take_while(|&&b| b == b'$').count();
+                }
+                b'$' if bytes.get(i + 1).is_some_and(|b| !b.is_ascii_whitespace()) => {
+                    let content_start = i + 1;
+                    let mut close = None;
+                    let mut j = content_start;
+                    while j < bytes.len() {
+                        match bytes[j] {
+                            b'\\' => j += 2,
+                            b'`' => j = Self::skip_code_span(line, j),
+                            b'$' if j > content_start
+                                && !bytes[j - 1].is_ascii_whitespace()
+                                && !bytes.get(j + 1).is_some_and(u8::is_ascii_digit) =>
+                            {
+                                close = Some(j);
+                                break;
+                            }
+                            _ => j += 1,
+                        }
+                    }
+                    let Some(end) = close else {
+                        i += 1;
+                        continue;
+                    };
+                    output.push_str(&line[copied..i]);
+                    output.push_str(&format!("<!--math:{}-->", math.len()));
+                    math.push(format!(
+                        "<span class=\"math-inline\">{}</span>",
+                        html_escape::encode_text(&line[content_start..end])
+                    ));
+                    i = end + 1;
+                    copied = i;
+                }
+                _ => i += 1,
+            }
+        }
+        output.push_str(&line[copied..]);
+    }
+
+    /// Puts the math taken out by `extract_math` back into rendered HTML.
+    fn restore_math(html: &str, math: &[String]) -> String {
+        if math.is_empty() {
+            return html.to_string();
+        }
+        MATH_PLACEHOLDER_RE
+            .replace_all(html, |caps: &Captures| {
+                caps[1]
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| math.get(i))
+                    .map_or_else(|| caps[0].to_string(), Clone::clone)
+            })
+            .into_owned()
+    }
+
+    /// Finds the first inline footnote in a line outside of code spans, returning
+    /// the text before it, its content, and the text after it.
+    fn next_inline_footnote(line: &str) -> Option<(&str, &str, &str)> {
+        let bytes = line.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'`' => i = Self::skip_code_span(line, i),
+                b'^' if line[i..].starts_with(INLINE_FOOTNOTE_OPEN) => {
+                    let content_start = i + INLINE_FOOTNOTE_OPEN.len();
+                    let mut depth = 1;
+                    for (offset, byte) in bytes[content_start..].iter().enumerate() {
+                        match byte {
+                            b'[' => depth += 1,
+                            b']' => {
+                                depth -= 1;
+                                if depth == 0 {
+                                    let end = content_start + offset;
+                                    return Some((
+                                        &line[..i],
+                                        &line[content_start..end],
+                                        &line[end + 1..],
+                                    ));
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    return None;
+                }
+                _ => i += 1,
+            }
+        }
+        None
+    }
+
+    /// Replaces all custom syntax (spoilers, highlights and wikilinks) in a string
+    /// with valid HTML.
+    fn render_custom_syntax_in_string(&self, text: &str, ctx: RenderContext) -> String {
+        // 1. Process spoilers first, then highlights, which may sit inside them.
+        let with_spoilers = SPOILER_RE.replace_all(text, |caps: &Captures| {
+            format!("<span class=\"spoiler\">{}</span>", &caps[1])
+        });
+        let with_highlights = HIGHLIGHT_RE.replace_all(&with_spoilers, |caps: &Captures| {
+            format!("<mark>{}</mark>", &caps[1])
+        });
+
+        // 2. Process image wikilinks ![[...]] into <img> tags.
+        let with_images = WIKILINK_IMAGE_RE.replace_all(&with_highlights, |caps: &Captures| {
+            let path_str = caps.get(1).map_or("", |m| m.as_str()).trim();
+            // Pages embedded within running text are linked rather than embedded.
+            if self.resolve_page_embed(path_str, ctx).is_some() {
+                return caps[0][1..].to_string();
+            }
+            // Audio and video get a player; its path is resolved after sanitizing.
+            let tag = if utils::is_audio_file(Path::new(path_str)) {
+                Some("audio")
+            } else if utils::is_video_file(Path::new(path_str)) {
+                Some("video")
+            } else {
+                None
+            };
+            if let Some(tag) = tag {
+                return format!(
+                    r#"<{0} controls src="{1}" class="embedded-media"></{0}>"#,
+                    tag,
+                    html_escape::encode_double_quoted_attribute(path_str)
+                );
+            }
+
+            // A trailing `|300` or `|300x200` sizes the image; the rest is alt text.
+            let alias = caps.get(2).map_or("", |m| m.as_str());
+            let (alt, last) = alias.rsplit_once('|').unwrap_or(("", alias));
+            let (alt_text, size) = match IMAGE_SIZE_RE.captures(last) {
+                Some(size) => (alt.trim(), Some(size)),
+                None => (alias.trim(), None),
+            };
+            let alt_text = if alt_text.is_empty() {
+                path_str
+            } else {
+                alt_text
+            };
+            let mut size_attrs = String::new();
+            if let Some(size) = size {
+                size_attrs.push_str(&format!(" width=\"{}\"", &size[1]));
+                if let Some(height) = size.get(2) {
+                    size_attrs.push_str(&format!(" height=\"{}\"", height.as_str()));
+                }
+            }
+
+            // Generate the simple <img> tag with the given path.
+            // This will then be handled by the `process_body_image_tags` post-processor.
+            format!(
+                r#"<img src="{}" alt="{}"{}>"#,
+                // Use the normalized path directly as the src
+                path_str,
+                html_escape::encode_double_quoted_attribute(alt_text),
+                size_attrs
+            )
+        });
+
+        // 3. In exports, same-page section links [[#...]] become plain anchors.
+        let with_sections = if ctx.export {
+            SECTION_LINK_RE.replace_all(&with_images, |caps: &Captures| {
+                let section = caps[1].trim();
+                let alias = caps.get(2).map_or(section, |m| m.as_str().trim());
+                format!("<a href=\"#{}\">{}</a>", slug::slugify(section), alias)
+            })
+        } else {
+            with_images
+        };
+
+        // 4. Finally, process standard wikilinks [[...]] on the remaining text.
+        let indexer = self.indexer.read();
+        WIKILINK_RE
+            .replace_all(&with_sections, |caps: &Captures| {
+                let target = caps.get(1).map_or("", |m| m.as_str()).trim();
+                let section = caps.get(2).map(|m| m.as_str().trim());
+                let alias = caps.get(3).map(|m| m.as_str().trim()).unwrap_or(target);
+
+                if let Some(path) = indexer.resolve_name(target, ctx.source) {
+                    let web_path = path_to_web_str(&path);
+                    let href = if ctx.export {
+                        self.export_href(ctx.source, &path, section)
+                    } else {
+                        "#".to_string()
+                    };
+                    // A bare `[[uid:...]]` link shows the page's name rather than its id.
+                    let label = if caps.get(3).is_none() && target.starts_with(UID_LINK_PREFIX) {
+                        Cow::Owned(file_stem_string(&path))
+                    } else {
+                        Cow::Borrowed(alias)
+                    };
+                    // A `^id` section refers to a block; any other section is only
+                    // kept if the page really has such a heading.
+                    let section_attr = match section {
+                        Some(section) if section.starts_with('^') => format!(
+                            " data-block=\"{}\"",
+                            html_escape::encode_double_quoted_attribute(&section[1..])
+                        ),
+                        Some(section) => self
+                            .section_anchor(&indexer, &path, section)
+                            .map_or(String::new(), |id| format!(" data-section=\"{}\"", id)),
+                        None => String::new(),
+                    };
+                    // Excerpts never include GM blocks, so they are safe in player mode.
+                    let preview_attr = if self.settings.link_previews {
+                        indexer
+                            .pages
+                            .get(&path)
+                            .and_then(|page| page.excerpt.as_deref())
+                            .map_or(String::new(), |excerpt| {
+                                format!(
+                                    " data-preview=\"{}\"",
+                                    html_escape::encode_double_quoted_attribute(excerpt)
+                                )
+                            })
+                    } else {
+                        String::new()
+                    };
+                    format!(
+                        "<a href=\"{}\" class=\"internal-link\" data-path=\"{}\"{}{}>{}</a>",
+                        href, web_path, section_attr, preview_attr, label
+                    )
+                } else {
+                    let suggestion = if self.settings.suggest_broken_links {
+                        indexer
+                            .suggest_page_name(target)
+                            .map_or(String::new(), |name| {
+                                format!(
+                                    " data-suggestion=\"{}\"",
+                                    html_escape::encode_double_quoted_attribute(&name)
+                                )
+                            })
+                    } else {
+                        String::new()
+                    };
+                    format!(
+                        "<a href=\"#\" class=\"internal-link broken\" data-target=\"{}\"{}>{}</a>",
+                        target, // Use the original target name for creation
+                        suggestion,
+                        alias
+                    )
+                }
+            })
+            .to_string()
+    }
+
+    /// Returns the anchor ID of the heading in the page at `path` that `section`
+    /// names, as its TOC entry has it, or `None` if there is no such heading.
     ///
-    /// A tuple `(html_before_toc, html_after_toc, toc)` where:
-    /// - `html_before_toc`: Rendered HTML of all content *before* the first header.
-    /// - `html_after_toc`: Rendered HTML of all content *from* the first header onwards.
-    /// - `toc`: A `Vec<TocEntry>` representing the structured Table of Contents.
+    /// A section matches a heading by its text, or by its ID, so the later of two
+    /// headings with the same text can be linked to as `[[Page#Notes-1]]`.
+    fn section_anchor(&self, indexer: &Indexer, path: &Path, section: &str) -> Option<String> {
+        if !self.heading_cache.read().contains_key(path) {
+            let toc = self.page_headings(indexer, path)?;
+            self.heading_cache.write().insert(path.to_path_buf(), toc);
+        }
+        let cache = self.heading_cache.read();
+        let toc = cache.get(path)?;
+        // Headings are compared by the same slug as their IDs, so a heading with a
+        // link in it matches its displayed text.
+        let wanted = slug::slugify(section);
+        toc.iter()
+            .find(|entry| slug::slugify(&entry.text) == wanted)
+            .or_else(|| toc.iter().find(|entry| entry.id == wanted))
+            .map(|entry| entry.id.clone())
+    }
+
+    /// Reads the page at `path` and returns its TOC entries as rendering it would,
+    /// without rendering anything else.
+    fn page_headings(&self, indexer: &Indexer, path: &Path) -> Option<Vec<TocEntry>> {
+        let content = fs::read_to_string(path).ok()?;
+        let (_, body) = parser::extract_frontmatter(&content);
+        let markdown = self.render_container_blocks(body);
+        let (markdown, _) = Self::extract_math(&markdown);
+        let mut events: Vec<Event> = Parser::new_ext(&markdown, Self::markdown_options()).collect();
+        if self.settings.suppress_duplicate_title {
+            let title = indexer
+                .pages
+                .get(path)
+                .map_or_else(|| file_stem_string(path), |page| page.title.clone());
+            Self::strip_leading_title_heading(&mut events, &title);
+        }
+        Some(self.build_toc(&events, ""))
+    }
+
+    /// Builds the relative `href` from the exported page for `source` to the exported
+    /// page for `target`, with the section's heading anchor if one is given.
     ///
-    fn render_body_to_html_with_toc(&self, markdown: &str) -> (String, String, Vec<TocEntry>) {
-        // --- 1. Initial Setup ---
+    /// Exported pages mirror the vault's folders with a `.html` extension, so the
+    /// link climbs out of the source's folder and back down to the target.
+    fn export_href(&self, source: Option<&Path>, target: &Path, section: Option<&str>) -> String {
+        let depth = source
+            .and_then(|s| s.strip_prefix(&self.vault_path).ok())
+            .map_or(0, |rel| rel.components().count().saturating_sub(1));
+        let target = target.strip_prefix(&self.vault_path).unwrap_or(target);
+
+        let mut href = "../".repeat(depth);
+        let segments: Vec<String> = target
+            .with_extension("html")
+            .components()
+            .map(|c| utf8_percent_encode(&c.as_os_str().to_string_lossy(), ENCODE_SET).to_string())
+            .collect();
+        href.push_str(&segments.join("/"));
+        if let Some(section) = section {
+            href.push('#');
+            href.push_str(&slug::slugify(section));
+        }
+        href
+    }
+
+    /// Extracts the display text from wikilinks within a string, leaving other text intact.
+    /// For example, "[[Page|Alias]] (extra)" becomes "Alias (extra)".
+    fn extract_display_text_from_wikilinks(&self, text: &str) -> String {
+        WIKILINK_RE
+            .replace_all(text, |caps: &Captures| {
+                // Use the alias (capture group 3) if it exists, otherwise use the target (capture group 1).
+                let alias = caps.get(3).map(|m| m.as_str().trim());
+                let target = caps.get(1).map(|m| m.as_str().trim()).unwrap_or("");
+                alias.unwrap_or(target).to_string()
+            })
+            .to_string()
+    }
 
-        // Standard pulldown-cmark options to enable features like tables and strikethrough.
+    /// Standard pulldown-cmark options to enable features like tables and strikethrough.
+    fn markdown_options() -> Options {
         let mut options = Options::empty();
         options.insert(Options::ENABLE_STRIKETHROUGH);
         options.insert(Options::ENABLE_TABLES);
         options.insert(Options::ENABLE_FOOTNOTES);
+        options.insert(Options::ENABLE_TASKLISTS);
+        options
+    }
 
-        // Create the event stream parser from the raw Markdown string.
-        let parser = Parser::new_ext(markdown, options);
-        // We collect events first to allow for a multi-pass approach.
-        let events: Vec<Event> = parser.into_iter().collect();
-
-        // --- Pass 1: Extract Headers and Generate TOC data ---
+    /// Builds the TOC entries for the headings in `events`, numbering them and giving
+    /// each a unique anchor ID (a repeated heading gets `-1`, `-2`, ... appended).
+    fn build_toc(&self, events: &[Event], id_prefix: &str) -> Vec<TocEntry> {
         let mut toc = Vec::new();
         let mut header_text_buffer = String::new();
         let mut current_level: Option<HeadingLevel> = None;
         let mut counters = [0; 6]; // For H1 to H6
         let mut unique_ids = HashMap::new();
 
-        for event in &events {
+        for event in events {
             if let Event::Start(Tag::Heading { level, .. }) = event {
                 current_level = Some(*level);
                 header_text_buffer.clear();
@@ -499,16 +1659,113 @@ impl Renderer {
                         number,
                         text: display_text,
                         level: level as u32,
-                        id: slug,
+                        id: format!("{}{}", id_prefix, slug),
                     });
                 }
             } else if current_level.is_some() {
-                if let Event::Text(text) | Event::Code(text) = event {
+                if let Event::Code(text) = event {
+                    header_text_buffer.push_str(text.strip_prefix(KBD_CODE_PREFIX).unwrap_or(text));
+                } else if let Event::Text(text) = event {
                     header_text_buffer.push_str(text);
                 }
             }
         }
 
+        toc
+    }
+
+    /// Renders Markdown body content to HTML, processing custom wikilinks, and generating a TOC.
+    ///
+    /// The function splits the resulting HTML at the first header, allowing the frontend
+    /// to inject the Table of Contents between any introductory content and the main body.
+    ///
+    /// ## Behavior
+    ///
+    /// This function implements a specific set of rules for rendering `[[wikilinks]]`:
+    ///
+    /// 1.  **Block-Level Code**: Wikilinks ARE processed inside fenced (```) and indented code blocks.
+    /// 2.  **Inline Code**: Wikilinks are NOT processed inside inline (` `) code and the literal `[[...]]` syntax is preserved.
+    /// 3.  **All Other Text**: Wikilinks are processed as normal.
+    ///
+    /// ## Table of Contents Generation
+    ///
+    /// A preliminary pass is made over the Markdown to extract all headers (`<h1>` to `<h6>`).
+    /// For each header, it generates:
+    /// - A hierarchical number (e.g., "1", "1.1", "2").
+    /// - A unique, URL-friendly `id` (a "slug") for anchor linking. Duplicate header
+    ///   text is handled by appending a counter to the slug (e.g., `my-header`, `my-header-1`).
+    ///
+    /// ## Implementation Details
+    ///
+    /// The `pulldown-cmark` parser emits a stream of events. A key challenge is that it may
+    /// fragment text, for example, sending `[[wikilink]]` as three separate `Text` events:
+    /// `Text("[[")`, `Text("wikilink")`, and `Text("]]")`.
+    ///
+    /// To solve this, we use a **text-buffering** (or coalescing) strategy:
+    /// - We loop through the events from the parser.
+    /// - `Text` events are collected into a temporary `text_buffer`.
+    /// - Any non-`Text` event triggers a "flush" of the buffer. Flushing involves running the
+    ///   wikilink replacement logic on the entire buffered string.
+    ///
+    /// This approach works because of how `pulldown-cmark` creates events:
+    /// - The content of **block-level code** is made of `Text` events, so it gets buffered and processed.
+    /// - **Inline code** is a single, discrete `Event::Code`, not `Text`. This event triggers a buffer
+    ///   flush and is then passed through, so its content is never processed for wikilinks.
+    ///
+    /// ## Returns
+    ///
+    /// A tuple `(html_before_toc, html_after_toc, toc)` where:
+    /// - `html_before_toc`: Rendered HTML of all content *before* the first header.
+    /// - `html_after_toc`: Rendered HTML of all content *from* the first header onwards.
+    /// - `toc`: A `Vec<TocEntry>` representing the structured Table of Contents.
+    ///
+    /// If `duplicate_title` is given and the body starts with an H1 whose text is
+    /// exactly that title, the heading is omitted from both the HTML and the TOC.
+    ///
+    fn render_body_to_html_with_toc(
+        &self,
+        markdown: &str,
+        duplicate_title: Option<&str>,
+        ctx: RenderContext,
+    ) -> (String, String, Vec<TocEntry>) {
+        // --- 1. Initial Setup ---
+
+        // Container blocks are resolved before parsing, so in player mode the content
+        // of GM-only blocks never reaches the event stream (or the TOC) at all.
+        let markdown = self.render_container_blocks(markdown);
+        // Math is taken out before anything else, so none of its TeX is read as
+        // Markdown or custom syntax, and put back once the HTML is rendered.
+        let (markdown, math) = Self::extract_math(&markdown);
+        // Inline footnotes become reference footnotes, so pulldown-cmark numbers and
+        // renders them like any other.
+        let markdown = Self::expand_inline_footnotes(&markdown);
+        let markdown = Self::bracket_spaced_image_paths(&markdown);
+        // Abbreviation definitions are taken out of the text and applied to it instead.
+        let (markdown, abbreviations) = Self::extract_abbreviations(&markdown);
+        let abbreviation_re = Self::abbreviation_regex(&abbreviations);
+        let glossary = self.load_glossary(ctx.source);
+        // The terms already linked on this page, and how many headings, links or code
+        // blocks the text being flushed is inside; terms and #tags there aren't linked.
+        let glossary_linked = RefCell::new(HashSet::new());
+        let glossary_skip_depth = Cell::new(0usize);
+
+        // Create the event stream parser from the raw Markdown string.
+        let parser = Parser::new_ext(&markdown, Self::markdown_options());
+        // We collect events first to allow for a multi-pass approach.
+        let mut events: Vec<Event> = parser.into_iter().collect();
+
+        if let Some(title) = duplicate_title {
+            Self::strip_leading_title_heading(&mut events, title);
+        }
+        Self::convert_definition_lists(&mut events);
+        Self::convert_callouts(&mut events);
+        self.convert_page_embeds(&mut events, ctx);
+        Self::convert_blockquote_attributions(&mut events);
+        Self::convert_block_ids(&mut events, ctx.id_prefix);
+
+        // --- Pass 1: Extract Headers and Generate TOC data ---
+        let mut toc = self.build_toc(&events, ctx.id_prefix);
+
         // --- Pass 2: Process Events for HTML Rendering ---
         let mut events_before_toc = Vec::new();
         let mut events_after_toc = Vec::new();
@@ -516,6 +1773,8 @@ impl Renderer {
         let mut text_buffer = String::new();
         let mut found_first_header = false;
         let mut header_idx = 0;
+        // The source of the mermaid diagram being collected, while inside one.
+        let mut mermaid_source: Option<String> = None;
 
         // --- 2a. The Flushing Closure ---
         // This closure contains the logic to process the contents of `text_buffer`.
@@ -526,9 +1785,24 @@ impl Renderer {
                 return;
             }
 
+            let text = match &glossary {
+                Some(glossary) if glossary_skip_depth.get() == 0 => {
+                    Cow::Owned(glossary.link_terms(buffer, &mut glossary_linked.borrow_mut()))
+                }
+                _ => Cow::Borrowed(buffer.as_str()),
+            };
+            let text = if glossary_skip_depth.get() == 0 {
+                Cow::Owned(Self::link_hashtags(&text))
+            } else {
+                text
+            };
+
             // Process all custom syntax on the buffer and push the result as a single HTML event.
             // This is more efficient than splitting the text into multiple events.
-            let final_html = self.render_custom_syntax_in_string(buffer);
+            let mut final_html = self.render_custom_syntax_in_string(&text, ctx);
+            if let Some(re) = &abbreviation_re {
+                final_html = Self::apply_abbreviations(&final_html, re, &abbreviations);
+            }
             events.push(Event::Html(final_html.into()));
 
             // Reset the buffer so it's ready for the next block of text.
@@ -543,7 +1817,54 @@ impl Renderer {
                 &mut events_before_toc
             };
 
+            // Mermaid diagrams are passed through untouched, so that wikilink and
+            // spoiler syntax can't mangle the diagram source.
+            if let Some(source) = mermaid_source.as_mut() {
+                match event {
+                    Event::Text(text) => source.push_str(&text),
+                    Event::End(TagEnd::CodeBlock) => {
+                        current_event_list.push(Event::Html(
+                            format!(
+                                "<div class=\"mermaid\">{}</div>\n",
+                                html_escape::encode_text(source)
+                            )
+                            .into(),
+                        ));
+                        mermaid_source = None;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            // The depth changes once the text before this event has been flushed.
+            let next_skip_depth = match &event {
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))
+                    if Self::is_mermaid_fence(info) =>
+                {
+                    glossary_skip_depth.get()
+                }
+                Event::Start(
+                    Tag::Heading { .. } | Tag::Link { .. } | Tag::Image { .. } | Tag::CodeBlock(_),
+                ) => glossary_skip_depth.get() + 1,
+                Event::End(
+                    TagEnd::Heading(_) | TagEnd::Link | TagEnd::Image | TagEnd::CodeBlock,
+                ) => glossary_skip_depth.get().saturating_sub(1),
+                _ => glossary_skip_depth.get(),
+            };
+
             match event {
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref info)))
+                    if Self::is_mermaid_fence(info) =>
+                {
+                    flush_text_buffer(&mut text_buffer, current_event_list);
+                    mermaid_source = Some(String::new());
+                }
+                Event::Code(code) if code.starts_with(KBD_CODE_PREFIX) => {
+                    flush_text_buffer(&mut text_buffer, current_event_list);
+                    let keys = &code[KBD_CODE_PREFIX.len()..];
+                    current_event_list.push(Event::InlineHtml(Self::render_kbd(keys).into()));
+                }
                 // If the event is text, add it to our buffer. Don't process it yet.
                 Event::Text(text) => {
                     text_buffer.push_str(&text);
@@ -553,7 +1874,7 @@ impl Renderer {
                     // First, flush any pending text to maintain order.
                     flush_text_buffer(&mut text_buffer, current_event_list);
                     // Now, process the HTML content itself for our custom syntax.
-                    let processed_html = self.render_custom_syntax_in_string(&html_content);
+                    let processed_html = self.render_custom_syntax_in_string(&html_content, ctx);
                     // Push the processed HTML back into the event stream.
                     current_event_list.push(Event::Html(processed_html.into()));
                 }
@@ -563,8 +1884,8 @@ impl Renderer {
                     found_first_header = true;
 
                     // Get the pre-calculated ID for this header from our TOC data.
-                    let id = toc
-                        .get(header_idx)
+                    let entry = toc.get(header_idx);
+                    let id = entry
                         .map_or_else(|| CowStr::from(""), |entry| CowStr::from(entry.id.clone()));
                     header_idx += 1;
                     // Now that we've found the header, all subsequent events go to the 'after' list.
@@ -574,6 +1895,13 @@ impl Renderer {
                         classes: vec![],
                         attrs: vec![],
                     }));
+                    // The number is taken from the TOC entry, so the two always agree.
+                    if let Some(entry) = entry.filter(|_| self.settings.number_headings) {
+                        events_after_toc.push(Event::InlineHtml(
+                            format!("<span class=\"heading-number\">{}</span> ", entry.number)
+                                .into(),
+                        ));
+                    }
                 }
                 // If the event is *anything else* (an end tag, code event, etc.),
                 // it also signals the end of our consecutive text block.
@@ -584,6 +1912,7 @@ impl Renderer {
                     current_event_list.push(event);
                 }
             }
+            glossary_skip_depth.set(next_skip_depth);
         }
         // It's possible for the markdown to end with text, leaving content in the buffer.
         // This final flush ensures that last bit of text gets processed.
@@ -603,38 +1932,930 @@ impl Renderer {
         let mut html_after = String::new();
         html::push_html(&mut html_after, events_after_toc.into_iter());
 
+        let html_before = Self::restore_math(&html_before, &math);
+        let html_after = Self::restore_math(&html_after, &math);
+
         // --- 5. Sanitize HTML ---
         // Sanitize the raw rendered HTML to remove any malicious user-written
         // tags (like <script>) or attributes (like onerror) and prevent XSS.
         let sanitized_before = sanitizer::sanitize_html(&html_before);
         let sanitized_after = sanitizer::sanitize_html(&html_after);
 
-        // --- 6. Post-Processing for Embedded Images ---
-        // Now that the HTML is safe, find the remaining <img> tags and convert
-        // their local src paths to asset URLs.
-        let final_before = self.process_body_image_tags(&sanitized_before);
-        let final_after = self.process_body_image_tags(&sanitized_after);
+        // --- 6. Post-Processing for Embedded Images and Media ---
+        // Now that the HTML is safe, find the remaining <img>, <audio> and <video>
+        // tags and convert their local src paths to asset URLs.
+        let mut final_before =
+            self.process_body_media_tags(&self.process_body_image_tags(&sanitized_before));
+        let mut final_after =
+            self.process_body_media_tags(&self.process_body_image_tags(&sanitized_after));
+
+        // --- 7. Wrap Wide Elements ---
+        // The wrappers are added after sanitizing, since they are our own markup.
+        // Embedded content is wrapped along with the page it is embedded in.
+        if let Some(max_width) = self
+            .settings
+            .max_content_width
+            .filter(|_| ctx.id_prefix.is_empty())
+        {
+            final_before = Self::wrap_wide_elements(&final_before, max_width);
+            final_after = Self::wrap_wide_elements(&final_after, max_width);
+        }
+
+        // Every heading needed its entry to get its ID and number; only now are the
+        // ones too deep for the TOC dropped.
+        if let Some(depth) = ctx.toc_depth {
+            toc.retain(|entry| entry.level <= depth);
+        }
 
         (final_before, final_after, toc)
     }
 
-    /// Renders a full Markdown string to an HTML string using pulldown-cmark.
-    /// This function handles only standard Markdown syntax and does not process
-    /// any custom syntax like wikilinks.
-    fn render_markdown_to_html(&self, markdown: &str) -> String {
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_FOOTNOTES);
+    /// Renders keyboard keys joined by `+`, e.g. `Ctrl+C`, as `<kbd>` elements. A
+    /// literal plus key can be written at the end, as in `Ctrl++`.
+    fn render_kbd(keys: &str) -> String {
+        let (keys, plus_key) = match keys.strip_suffix("++") {
+            Some(rest) => (rest, true),
+            None => (keys, false),
+        };
+        keys.split('+')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .chain(plus_key.then_some("+"))
+            .map(|key| format!("<kbd>{}</kbd>", html_escape::encode_text(key)))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
 
-        let parser = Parser::new_ext(markdown, options);
-        let mut html_output = String::new();
-        html::push_html(&mut html_output, parser);
+    /// Removes abbreviation definitions (`*[HTML]: HyperText Markup Language`)
+    /// from the Markdown, returning the remaining text and the definitions.
+    /// Definitions inside fenced code blocks are left alone.
+    fn extract_abbreviations(markdown: &str) -> (Cow<'_, str>, Vec<(String, String)>) {
+        if !markdown.contains("*[") {
+            return (Cow::Borrowed(markdown), Vec::new());
+        }
 
-        html_output
-    }
+        let mut output = String::with_capacity(markdown.len());
+        let mut abbreviations = Vec::new();
+        let mut code_fence: Option<&str> = None;
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if let Some(fence) = code_fence {
+                if trimmed.starts_with(fence) {
+                    code_fence = None;
+                }
+            } else if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f))
+            {
+                code_fence = Some(fence);
+            } else if let Some(caps) = ABBREVIATION_RE.captures(line.trim_end_matches(['\r', '\n']))
+            {
+                let term = caps[1].trim();
+                if !term.is_empty() {
+                    abbreviations.push((term.to_string(), caps[2].to_string()));
+                    continue;
+                }
+            }
+            output.push_str(line);
+        }
 
-    /// Renders a string of pure Markdown to a `RenderedPage` object containing only HTML.
+        if abbreviations.is_empty() {
+            (Cow::Borrowed(markdown), abbreviations)
+        } else {
+            (Cow::Owned(output), abbreviations)
+        }
+    }
+
+    /// Builds a regex matching any of the abbreviations as a whole word, longest
+    /// first so that e.g. `HTML5` wins over `HTML`. Rendered text mixes plain text
+    /// with escaped HTML, like a link's label, so each term is matched both as
+    /// written and HTML-escaped, e.g. `R&D` and `R&amp;D`.
+    fn abbreviation_regex(abbreviations: &[(String, String)]) -> Option<Regex> {
+        let mut terms: Vec<&str> = Vec::new();
+        let escaped: Vec<Cow<str>> = abbreviations
+            .iter()
+            .map(|(term, _)| html_escape::encode_text(term))
+            .collect();
+        for ((term, _), escaped) in abbreviations.iter().zip(&escaped) {
+            terms.push(term);
+            if escaped != term {
+                terms.push(escaped);
+            }
+        }
+        Self::whole_word_regex(terms, false)
+    }
+
+    /// Builds a regex matching any of the terms as a whole word, longest first.
+    fn whole_word_regex(mut terms: Vec<&str>, case_insensitive: bool) -> Option<Regex> {
+        if terms.is_empty() {
+            return None;
+        }
+        terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+        let alternatives: Vec<String> = terms
+            .iter()
+            .map(|term| {
+                // `\b` only works next to word characters, so terms like `C++` are
+                // matched without a boundary on that side.
+                let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+                format!(
+                    "{}{}{}",
+                    if word(term.chars().next()) { r"\b" } else { "" },
+                    regex::escape(term),
+                    if word(term.chars().last()) { r"\b" } else { "" }
+                )
+            })
+            .collect();
+        RegexBuilder::new(&alternatives.join("|"))
+            .case_insensitive(case_insensitive)
+            .build()
+            .ok()
+    }
+
+    /// Reads the terms defined by the glossary page set in the render settings: the
+    /// text of each heading below the top level. Returns `None` if no glossary is
+    /// set, it has no terms, or the page being rendered is the glossary itself.
+    fn load_glossary(&self, source: Option<&Path>) -> Option<Glossary> {
+        let name = self.settings.glossary_page.as_deref()?.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let path = self.indexer.read().resolve_name(name, source)?;
+        if source == Some(path.as_path()) {
+            return None;
+        }
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read glossary page {:?}: {}", path, e);
+                return None;
+            }
+        };
+
+        let (_, body) = parser::extract_frontmatter(&content);
+        // In player mode, terms defined inside GM blocks are left out with the blocks.
+        let body = self.render_container_blocks(body);
+        let mut headings = HashMap::new();
+        let mut heading: Option<String> = None;
+        for event in Parser::new(&body) {
+            match event {
+                Event::Start(Tag::Heading { level, .. }) if level != HeadingLevel::H1 => {
+                    heading = Some(String::new());
+                }
+                Event::Text(text) | Event::Code(text) => {
+                    if let Some(heading) = heading.as_mut() {
+                        heading.push_str(&text);
+                    }
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    let Some(heading) = heading.take() else {
+                        continue;
+                    };
+                    let term = heading.trim();
+                    // A term has to fit in the section and alias of a wikilink.
+                    if !term.is_empty() && !term.contains(['[', ']', '|', '#']) {
+                        headings
+                            .entry(term.to_lowercase())
+                            .or_insert_with(|| term.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let re = Self::whole_word_regex(headings.values().map(String::as_str).collect(), true)?;
+        Some(Glossary {
+            page_name: file_stem_string(&path),
+            headings,
+            re,
+        })
+    }
+
+    /// Turns each inline `#tag` in a run of text into a link to the tag, as the
+    /// parser finds them for the index. Anything inside a wikilink or an HTML tag
+    /// is left alone.
+    fn link_hashtags(text: &str) -> String {
+        HASHTAG_RE
+            .replace_all(text, |caps: &Captures| {
+                if caps.get(1).is_some() {
+                    return caps[0].to_string();
+                }
+                let before = &caps[2];
+                let Some(tag) = parser::hashtag_name(&caps[3]) else {
+                    return caps[0].to_string();
+                };
+                let rest = &caps[3][tag.len()..];
+                format!(
+                    "{}<a class=\"tag\" data-tag=\"{}\">#{}</a>{}",
+                    before,
+                    html_escape::encode_double_quoted_attribute(tag),
+                    tag,
+                    rest
+                )
+            })
+            .into_owned()
+    }
+
+    /// Wraps each abbreviation in the text of an HTML fragment in an `<abbr>` element
+    /// titled with its expansion. Tags and their attributes are left untouched, and so
+    /// are character references: `amp` isn't found in `&amp;`.
+    fn apply_abbreviations(html: &str, re: &Regex, abbreviations: &[(String, String)]) -> String {
+        let wrap = |text: &str| {
+            let entities: Vec<_> = HTML_ENTITY_RE.find_iter(text).collect();
+            re.replace_all(text, |caps: &Captures| {
+                let whole = caps.get(0).unwrap();
+                let term = whole.as_str();
+                // A match cutting into a reference is part of it, not a term.
+                let cuts_entity = entities.iter().any(|e| {
+                    e.start() < whole.end()
+                        && whole.start() < e.end()
+                        && (e.start() < whole.start() || whole.end() < e.end())
+                });
+                if cuts_entity {
+                    return term.to_string();
+                }
+                let title = abbreviations
+                    .iter()
+                    .rev()
+                    .find(|(t, _)| t == term || html_escape::encode_text(t) == term)
+                    .map_or("", |(_, title)| title.as_str());
+                format!(
+                    "<abbr title=\"{}\">{}</abbr>",
+                    html_escape::encode_double_quoted_attribute(title),
+                    term
+                )
+            })
+            .into_owned()
+        };
+
+        let mut output = String::with_capacity(html.len());
+        let mut last = 0;
+        for tag in HTML_TAG_RE.find_iter(html) {
+            output.push_str(&wrap(&html[last..tag.start()]));
+            output.push_str(tag.as_str());
+            last = tag.end();
+        }
+        output.push_str(&wrap(&html[last..]));
+        output
+    }
+
+    /// Wraps every table, and every image with a `width` above `max_width`, in a
+    /// `scroll-wrap` element so it scrolls sideways rather than breaking the layout.
+    /// A table's width isn't known until it's shown, so all are wrapped.
+    ///
+    /// Images sit inside paragraphs, where a `<div>` isn't allowed, so they get a
+    /// `<span>` that the stylesheet displays as a block.
+    fn wrap_wide_elements(html: &str, max_width: u32) -> String {
+        WIDE_ELEMENT_RE
+            .replace_all(html, |caps: &Captures| {
+                let is_table = caps.get(1).is_some();
+                let is_wide = caps
+                    .get(2)
+                    .and_then(|width| width.as_str().parse::<u32>().ok())
+                    .is_some_and(|width| width > max_width);
+                if is_table {
+                    format!("<div class=\"{SCROLL_WRAP_CLASS}\">{}</div>", &caps[0])
+                } else if is_wide {
+                    format!("<span class=\"{SCROLL_WRAP_CLASS}\">{}</span>", &caps[0])
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .into_owned()
+    }
+
+    /// Returns `true` if a fenced code block's info string marks a mermaid diagram.
+    fn is_mermaid_fence(info: &str) -> bool {
+        info.split_whitespace()
+            .next()
+            .is_some_and(|lang| lang.eq_ignore_ascii_case(MERMAID_LANGUAGE))
+    }
+
+    /// Removes the first heading from the event stream if it is the very first
+    /// event, is an H1, and its text exactly matches the given title.
+    fn strip_leading_title_heading(events: &mut Vec<Event>, title: &str) {
+        if !matches!(
+            events.first(),
+            Some(Event::Start(Tag::Heading {
+                level: HeadingLevel::H1,
+                ..
+            }))
+        ) {
+            return;
+        }
+
+        let Some(end_idx) = events
+            .iter()
+            .position(|e| matches!(e, Event::End(TagEnd::Heading(_))))
+        else {
+            return;
+        };
+
+        let heading_text: String = events[1..end_idx]
+            .iter()
+            .filter_map(|e| match e {
+                Event::Text(text) | Event::Code(text) => Some(text.as_ref()),
+                _ => None,
+            })
+            .collect();
+
+        if heading_text.trim() == title.trim() {
+            events.drain(..=end_idx);
+        }
+    }
+
+    /// Converts definition-list shorthand into `<dl>` markup.
+    ///
+    /// A paragraph is a definition list when its first line is a term, its last line
+    /// is a definition starting with `: `, and every line is one or the other:
+    ///
+    /// ```text
+    /// Apple
+    /// : A red fruit.
+    /// ```
+    ///
+    /// Lists separated only by blank lines are merged into one `<dl>`, so each term
+    /// can be its own paragraph. The inline events of each line are kept as they
+    /// are, so wikilinks and formatting in terms and definitions still render.
+    fn convert_definition_lists(events: &mut Vec<Event>) {
+        let mut output = Vec::with_capacity(events.len());
+        let mut source = std::mem::take(events).into_iter();
+
+        while let Some(event) = source.next() {
+            if !matches!(event, Event::Start(Tag::Paragraph)) {
+                output.push(event);
+                continue;
+            }
+            // Paragraphs don't nest, so the next paragraph end closes this one.
+            let inner: Vec<Event> = source
+                .by_ref()
+                .take_while(|e| !matches!(e, Event::End(TagEnd::Paragraph)))
+                .collect();
+
+            let Some(lines) = Self::definition_list_lines(&inner) else {
+                output.push(event);
+                output.extend(inner);
+                output.push(Event::End(TagEnd::Paragraph));
+                continue;
+            };
+
+            let continues_list = matches!(
+                output.last(),
+                Some(Event::Html(html)) if html.as_ref() == DEFINITION_LIST_CLOSE
+            );
+            if continues_list {
+                output.pop();
+            } else {
+                output.push(Event::Html("<dl>\n".into()));
+            }
+            for line in lines {
+                let is_definition = Self::is_definition_line(line);
+                output.push(Event::Html(
+                    if is_definition { "<dd>" } else { "<dt>" }.into(),
+                ));
+                for (i, event) in line.iter().enumerate() {
+                    match event {
+                        Event::Text(text) if i == 0 && is_definition => {
+                            let rest = &text[DEFINITION_MARKER.len()..];
+                            if !rest.is_empty() {
+                                output.push(Event::Text(CowStr::from(rest.to_string())));
+                            }
+                        }
+                        _ => output.push(event.clone()),
+                    }
+                }
+                output.push(Event::Html(
+                    if is_definition { "</dd>\n" } else { "</dt>\n" }.into(),
+                ));
+            }
+            output.push(Event::Html(DEFINITION_LIST_CLOSE.into()));
+        }
+
+        *events = output;
+    }
+
+    /// Splits a paragraph's inline events into lines if they form a definition list,
+    /// or returns `None` if they don't.
+    fn definition_list_lines<'a, 'e>(inner: &'a [Event<'e>]) -> Option<Vec<&'a [Event<'e>]>> {
+        let lines: Vec<&[Event]> = inner
+            .split(|e| matches!(e, Event::SoftBreak | Event::HardBreak))
+            .collect();
+        let (first, last) = (lines.first()?, lines.last()?);
+        if lines.len() < 2 || Self::is_definition_line(first) || !Self::is_definition_line(last) {
+            return None;
+        }
+        Some(lines)
+    }
+
+    /// Returns `true` if a line of inline events starts with the definition marker.
+    fn is_definition_line(line: &[Event]) -> bool {
+        matches!(line.first(), Some(Event::Text(text)) if text.starts_with(DEFINITION_MARKER))
+    }
+
+    /// Replaces each paragraph holding nothing but a page embed, `![[Page]]` or
+    /// `![[Page#Section]]`, with the embedded page. The page is already rendered, so
+    /// it is inserted as inline HTML, which pass 2 passes through untouched.
+    fn convert_page_embeds(&self, events: &mut Vec<Event>, ctx: RenderContext) {
+        let mut embeds = 0;
+        let mut i = 0;
+        while i < events.len() {
+            if !matches!(events[i], Event::Start(Tag::Paragraph)) {
+                i += 1;
+                continue;
+            }
+            // Paragraphs don't nest, so the next paragraph end closes this one.
+            let Some(end) = events[i..]
+                .iter()
+                .position(|e| matches!(e, Event::End(TagEnd::Paragraph)))
+                .map(|offset| i + offset)
+            else {
+                break;
+            };
+            let text: Option<String> = events[i + 1..end]
+                .iter()
+                .map(|e| match e {
+                    Event::Text(text) => Some(text.as_ref()),
+                    _ => None,
+                })
+                .collect();
+            let embed = text.as_deref().map(str::trim).and_then(|text| {
+                let caps = WIKILINK_IMAGE_RE.captures(text)?;
+                let whole = caps.get(0)?;
+                if whole.start() != 0 || whole.end() != text.len() {
+                    return None;
+                }
+                self.resolve_page_embed(&caps[1], ctx)
+            });
+
+            match embed {
+                Some((path, section)) => {
+                    embeds += 1;
+                    let id_prefix = format!("{}embed-{}-", ctx.id_prefix, embeds);
+                    let html = self.render_transclusion(&path, section.as_deref(), &id_prefix, ctx);
+                    events.splice(i..=end, [Event::InlineHtml(html.into())]);
+                    i += 1;
+                }
+                None => i = end + 1,
+            }
+        }
+    }
+
+    /// Resolves the target of an `![[...]]` embed to a page and the section of it to
+    /// embed, if any. Returns `None` if the target is an image or names no page.
+    fn resolve_page_embed(
+        &self,
+        target: &str,
+        ctx: RenderContext,
+    ) -> Option<(PathBuf, Option<String>)> {
+        let (name, section) = match target.split_once('#') {
+            Some((name, section)) => (name.trim(), Some(section.trim().to_string())),
+            None => (target.trim(), None),
+        };
+        if name.is_empty() || utils::is_image_file(Path::new(name)) {
+            return None;
+        }
+        let path = self.indexer.read().resolve_name(name, ctx.source)?;
+        Some((path, section))
+    }
+
+    /// Renders the body of the page at `path`, or only the given section of it, as a
+    /// `transclusion` block whose heading and block IDs start with `id_prefix`. The
+    /// page's frontmatter isn't rendered. A section inside a GM block is marked
+    /// `gm-only`, or not found in player mode.
+    ///
+    /// A page that would end up embedding itself, directly or through other pages,
+    /// and embeds nested more than `MAX_EMBED_DEPTH` deep are replaced by a notice.
+    fn render_transclusion(
+        &self,
+        path: &Path,
+        section: Option<&str>,
+        id_prefix: &str,
+        ctx: RenderContext,
+    ) -> String {
+        let web_path = path_to_web_str(path);
+        let name = html_escape::encode_text(&file_stem_string(path)).to_string();
+        let notice = |message: String| {
+            format!(
+                "<div class=\"transclusion broken\" data-path=\"{}\">{}</div>\n",
+                web_path, message
+            )
+        };
+
+        if ctx.source == Some(path) || ctx.embedded_in.iter().any(|p| p == path) {
+            return notice(format!("Circular embed of {}", name));
+        }
+        if ctx.embedded_in.len() >= MAX_EMBED_DEPTH {
+            return notice(format!("Embeds nested too deeply to show {}", name));
+        }
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read embedded page {:?}: {}", path, e);
+                return notice(format!("Could not read {}", name));
+            }
+        };
+        let (_, body) = parser::extract_frontmatter(&content);
+        let (body, in_gm_block) = match section {
+            Some(section) => match self.find_visible_section(body, section) {
+                Some((markdown, in_gm_block)) => (Cow::Owned(markdown), in_gm_block),
+                None => {
+                    let section = html_escape::encode_text(section);
+                    return notice(format!("No section \"{}\" in {}", section, name));
+                }
+            },
+            None => (Cow::Borrowed(body), false),
+        };
+
+        let mut embedded_in = ctx.embedded_in.to_vec();
+        embedded_in.extend(ctx.source.map(Path::to_path_buf));
+        let inner_ctx = RenderContext {
+            source: Some(path),
+            embedded_in: &embedded_in,
+            id_prefix,
+            ..ctx
+        };
+        let (html_before, html_after, _) =
+            self.render_body_to_html_with_toc(&body, None, inner_ctx);
+        let class = if in_gm_block {
+            "transclusion gm-only"
+        } else {
+            "transclusion"
+        };
+        format!(
+            "<div class=\"{}\" data-path=\"{}\">\n{}{}</div>\n",
+            class, web_path, html_before, html_after
+        )
+    }
+
+    /// Gives paragraphs and list items ending in a `^id` marker that `id`, so
+    /// `[[Page#^id]]` links can point at them. The marker itself isn't shown, and may
+    /// also sit on the block's last line of its own. The id is written after
+    /// `id_prefix`, which is empty unless the content is embedded.
+    ///
+    /// Only the first block with a given id gets it; later ones lose their marker
+    /// and a warning is logged.
+    fn convert_block_ids(events: &mut Vec<Event>, id_prefix: &str) {
+        let mut seen = HashSet::new();
+        // The starts of the paragraphs and list items the current event is inside.
+        let mut open: Vec<usize> = Vec::new();
+        let mut i = 0;
+        while i < events.len() {
+            match &events[i] {
+                Event::Start(Tag::Paragraph | Tag::Item) => open.push(i),
+                Event::End(end @ (TagEnd::Paragraph | TagEnd::Item)) => {
+                    let is_item = matches!(end, TagEnd::Item);
+                    let start = open.pop();
+                    let marker = match (start, &events[i - 1]) {
+                        (Some(start), Event::Text(text)) if start < i - 1 => {
+                            BLOCK_ID_RE.captures(text).map(|caps| {
+                                let visible = text[..caps.get(0).unwrap().start()].trim_end();
+                                (start, caps[1].to_string(), visible.to_string())
+                            })
+                        }
+                        _ => None,
+                    };
+                    if let Some((start, id, visible)) = marker {
+                        if seen.insert(id.clone()) {
+                            let tag = if is_item { "li" } else { "p" };
+                            events[start] =
+                                Event::Html(format!("<{} id=\"{}{}\">", tag, id_prefix, id).into());
+                            events[i] = Event::Html(format!("</{}>\n", tag).into());
+                        } else {
+                            warn!("Block id ^{} is used more than once; keeping the first", id);
+                        }
+
+                        if visible.is_empty() {
+                            // A marker on a line of its own leaves a line break behind.
+                            let from = if matches!(events[i - 2], Event::SoftBreak) {
+                                i - 2
+                            } else {
+                                i - 1
+                            };
+                            events.drain(from..i);
+                            i = from;
+                        } else {
+                            events[i - 1] = Event::Text(visible.into());
+                        }
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Converts blockquotes opened with a `[!type]` marker into callout boxes:
+    ///
+    /// ```text
+    /// > [!warning] Here be dragons
+    /// > The northern pass is not safe.
+    /// ```
+    ///
+    /// The rest of the marker's line is the title, which defaults to the type.
+    /// A `-` or `+` after the marker makes the callout foldable, collapsed or
+    /// expanded by default. Callouts can be nested, and a blockquote with an
+    /// unknown type is left as it is.
+    fn convert_callouts(events: &mut Vec<Event>) {
+        let mut source = std::mem::take(events).into_iter().peekable();
+        let mut output = Vec::new();
+        // The markup closing each open blockquote, or `None` for a plain one.
+        let mut closers: Vec<Option<&str>> = Vec::new();
+
+        while let Some(event) = source.next() {
+            match event {
+                Event::Start(Tag::BlockQuote) => {
+                    if !matches!(source.peek(), Some(Event::Start(Tag::Paragraph))) {
+                        closers.push(None);
+                        output.push(event);
+                        continue;
+                    }
+                    let paragraph_start = source.next().unwrap();
+                    // Text can be split around brackets, so the marker may span events.
+                    let mut first_line: Vec<Event> = Vec::new();
+                    while let Some(Event::Text(_)) = source.peek() {
+                        first_line.push(source.next().unwrap());
+                    }
+                    let text: String = first_line
+                        .iter()
+                        .filter_map(|e| match e {
+                            Event::Text(text) => Some(text.as_ref()),
+                            _ => None,
+                        })
+                        .collect();
+
+                    let callout = CALLOUT_RE.captures(&text).and_then(|caps| {
+                        let kind = caps[1].to_lowercase();
+                        CALLOUT_TYPES.contains(&kind.as_str()).then(|| {
+                            let fold = caps.get(2).map(|m| m.as_str());
+                            (kind, fold, text[caps[0].len()..].to_string())
+                        })
+                    });
+                    let Some((kind, fold, title_start)) = callout else {
+                        closers.push(None);
+                        output.push(event);
+                        output.push(paragraph_start);
+                        output.extend(first_line);
+                        continue;
+                    };
+
+                    let mut title: Vec<Event> = Vec::new();
+                    if !title_start.is_empty() {
+                        title.push(Event::Text(title_start.into()));
+                    }
+                    // The title runs to the end of the marker's line.
+                    let mut body_follows = false;
+                    for e in source.by_ref() {
+                        match e {
+                            Event::SoftBreak | Event::HardBreak => {
+                                body_follows = true;
+                                break;
+                            }
+                            Event::End(TagEnd::Paragraph) => break,
+                            e => title.push(e),
+                        }
+                    }
+                    if title.is_empty() {
+                        let mut chars = kind.chars();
+                        let default = chars
+                            .next()
+                            .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                            .unwrap_or_default();
+                        title.push(Event::Text(default.into()));
+                    }
+
+                    let (open, title_tag, close) = match fold {
+                        Some("-") => ("<details class", "summary", "</div>\n</details>\n"),
+                        Some(_) => ("<details open class", "summary", "</div>\n</details>\n"),
+                        None => ("<div class", "div", "</div>\n</div>\n"),
+                    };
+                    output.push(Event::Html(
+                        format!(
+                            "{}=\"callout callout-{}\">\n<{} class=\"callout-title\">",
+                            open, kind, title_tag
+                        )
+                        .into(),
+                    ));
+                    output.extend(title);
+                    output.push(Event::Html(
+                        format!("</{}>\n<div class=\"callout-content\">\n", title_tag).into(),
+                    ));
+                    if body_follows {
+                        output.push(paragraph_start);
+                    }
+                    closers.push(Some(close));
+                }
+                Event::End(TagEnd::BlockQuote) => match closers.pop().flatten() {
+                    Some(close) => output.push(Event::Html(close.into())),
+                    None => output.push(event),
+                },
+                _ => output.push(event),
+            }
+        }
+
+        *events = output;
+    }
+
+    /// Wraps the attribution line that ends a blockquote in `<cite>`:
+    ///
+    /// ```text
+    /// > The sea remembers every ship.
+    /// > — Captain Vex
+    /// ```
+    ///
+    /// The attribution is the last line of the quote's last paragraph when it starts
+    /// with one of the `ATTRIBUTION_MARKERS`. A `- Author` line is parsed as a list,
+    /// so a quote ending in a one-item list right after a paragraph counts too. The
+    /// line's inline events are kept, so wikilinks in an attribution still render.
+    fn convert_blockquote_attributions(events: &mut Vec<Event>) {
+        // Walking backwards keeps the indices still to be visited valid after a splice.
+        let mut i = events.len();
+        while i > 1 {
+            i -= 1;
+            if !matches!(events[i], Event::End(TagEnd::BlockQuote)) {
+                continue;
+            }
+            let attribution = match events[i - 1] {
+                Event::End(TagEnd::Paragraph) => Self::paragraph_attribution(events, i - 1),
+                Event::End(TagEnd::List(false)) => Self::list_attribution(events, i - 1),
+                _ => None,
+            };
+            if let Some((start, replacement)) = attribution {
+                events.splice(start..i, replacement);
+                i = start;
+            }
+        }
+    }
+
+    /// If the paragraph ending at `end` ends with an attribution line, returns where
+    /// the paragraph starts and the events to replace it with.
+    fn paragraph_attribution<'e>(
+        events: &[Event<'e>],
+        end: usize,
+    ) -> Option<(usize, Vec<Event<'e>>)> {
+        // Paragraphs don't nest, so the last paragraph start opens this one.
+        let start = events[..end]
+            .iter()
+            .rposition(|e| matches!(e, Event::Start(Tag::Paragraph)))?;
+        let inner = &events[start + 1..end];
+        let line_start = inner
+            .iter()
+            .rposition(|e| matches!(e, Event::SoftBreak | Event::HardBreak))
+            .map_or(0, |line_break| line_break + 1);
+        let line = &inner[line_start..];
+        if !Self::is_attribution_line(line) {
+            return None;
+        }
+
+        let mut replacement = Vec::new();
+        if line_start > 0 {
+            replacement.push(Event::Start(Tag::Paragraph));
+            replacement.extend_from_slice(&inner[..line_start - 1]);
+            replacement.push(Event::End(TagEnd::Paragraph));
+        }
+        Self::push_cite(&mut replacement, line.iter().cloned());
+        Some((start, replacement))
+    }
+
+    /// If the list ending at `end` is a single `- Author` item following a paragraph,
+    /// returns where the list starts and the events to replace it with.
+    fn list_attribution<'e>(events: &[Event<'e>], end: usize) -> Option<(usize, Vec<Event<'e>>)> {
+        let start = events[..end]
+            .iter()
+            .rposition(|e| matches!(e, Event::Start(Tag::List(None))))?;
+        if start < 1
+            || !matches!(events[start - 1], Event::End(TagEnd::Paragraph))
+            || !matches!(events[start + 1], Event::Start(Tag::Item))
+            || !matches!(events[end - 1], Event::End(TagEnd::Item))
+        {
+            return None;
+        }
+        // The item must be a single line of inline content, not nested blocks.
+        let inner = &events[start + 2..end - 1];
+        let is_inline = inner.iter().all(|e| match e {
+            Event::Start(tag) => matches!(
+                tag,
+                Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. }
+            ),
+            Event::End(tag) => matches!(
+                tag,
+                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link
+            ),
+            Event::SoftBreak | Event::HardBreak => false,
+            _ => true,
+        });
+        if inner.is_empty() || !is_inline {
+            return None;
+        }
+
+        let mut replacement = Vec::new();
+        let line = std::iter::once(Event::Text("- ".into())).chain(inner.iter().cloned());
+        Self::push_cite(&mut replacement, line);
+        Some((start, replacement))
+    }
+
+    /// Returns `true` if a line of inline events starts with an attribution marker.
+    fn is_attribution_line(line: &[Event]) -> bool {
+        matches!(
+            line.first(),
+            Some(Event::Text(text)) if ATTRIBUTION_MARKERS.iter().any(|m| text.starts_with(m))
+        )
+    }
+
+    fn push_cite<'e>(events: &mut Vec<Event<'e>>, line: impl Iterator<Item = Event<'e>>) {
+        events.push(Event::Html("<cite>".into()));
+        events.extend(line);
+        events.push(Event::Html("</cite>\n".into()));
+    }
+
+    /// Renders a full Markdown string to an HTML string using pulldown-cmark.
+    /// This function handles only standard Markdown syntax and does not process
+    /// any custom syntax like wikilinks.
+    fn render_markdown_to_html(&self, markdown: &str) -> String {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_FOOTNOTES);
+
+        let parser = Parser::new_ext(markdown, options);
+        let mut html_output = String::new();
+        html::push_html(&mut html_output, parser);
+
+        html_output
+    }
+
+    /// Renders one section of the page at `path`, from its heading up to the next
+    /// heading of the same or a higher level, for previewing `[[Page#Section]]`
+    /// links. `section` may be the heading's text or its anchor slug.
+    pub fn render_section_preview(&self, path: &Path, section: &str) -> Result<String> {
+        let content = fs::read_to_string(path)?;
+        let (_, body) = parser::extract_frontmatter(&content);
+        let (markdown, in_gm_block) =
+            self.find_visible_section(body, section).ok_or_else(|| {
+                ChroniclerError::InvalidArgument(format!("no section {:?} in {:?}", section, path))
+            })?;
+
+        let ctx = RenderContext {
+            source: Some(path),
+            ..Default::default()
+        };
+        let (html_before, html_after, _) = self.render_body_to_html_with_toc(&markdown, None, ctx);
+        Ok(if in_gm_block {
+            format!(
+                "<div class=\"gm-only\">\n{}{}</div>\n",
+                html_before, html_after
+            )
+        } else {
+            html_before + &html_after
+        })
+    }
+
+    /// Finds the first section of `body` whose heading matches `section`, as found
+    /// by [`parser::find_sections`], and returns its Markdown and whether the heading
+    /// is inside a GM block. The `:::` lines closing blocks the heading is inside are
+    /// left out, since their opening lines are too. In player mode, headings inside
+    /// GM blocks aren't matched.
+    fn find_visible_section(&self, body: &str, section: &str) -> Option<(String, bool)> {
+        parser::find_sections(body, section)
+            .into_iter()
+            .find_map(|found| {
+                let mut markdown = String::new();
+                let mut in_gm_block = false;
+                // The offsets of the lines opening the blocks the current line is in.
+                let mut open_blocks: Vec<(usize, BlockLine)> = Vec::new();
+                let mut offset = 0;
+                for (line, kind) in parser::container_block_lines(body) {
+                    let start = offset;
+                    offset += line.len();
+                    if start >= found.end {
+                        break;
+                    }
+                    // The line the heading starts on.
+                    if start <= found.start && found.start < offset {
+                        in_gm_block = open_blocks
+                            .iter()
+                            .any(|(_, kind)| *kind == BlockLine::GmOpen);
+                    }
+                    match kind {
+                        BlockLine::GmOpen | BlockLine::DetailsOpen(_) => {
+                            open_blocks.push((start, kind))
+                        }
+                        BlockLine::Close => {
+                            let opened = open_blocks.pop().map_or(0, |(opened, _)| opened);
+                            if opened < found.start {
+                                continue;
+                            }
+                        }
+                        BlockLine::Content => {}
+                    }
+                    if offset > found.start {
+                        markdown.push_str(line);
+                    }
+                }
+                if in_gm_block && self.settings.player_mode {
+                    return None;
+                }
+                Some((markdown, in_gm_block))
+            })
+    }
+
+    /// Renders a string of pure Markdown to a `RenderedPage` object containing only HTML.
     /// This command is used for rendering content that should not have wikilinks processed,
     /// such as the help file.
     pub fn render_markdown(&self, markdown: &str) -> Result<RenderedPage> {
@@ -644,18 +2865,44 @@ impl Renderer {
             html_before_toc: rendered_html,
             html_after_toc: String::new(),
             toc: vec![],
+            max_content_width: None,
+            tasks: None,
         })
     }
 
     /// Fetches all data for a given page path and returns a `FullPageData`
     /// object suitable for displaying in the main file view. This includes
-    /// raw content, rendered content, and backlink information.
-    pub fn build_page_view(&self, path: &str) -> Result<FullPageData> {
+    /// raw content, rendered content, and backlink information, plus the page's
+    /// local link graph if `include_local_graph` is set.
+    pub fn build_page_view(&self, path: &str, include_local_graph: bool) -> Result<FullPageData> {
         let raw_content = fs::read_to_string(path)?;
-        let rendered_page = self.render_page_preview(&raw_content)?;
+        let page_path = Path::new(path);
+        // A cached render is only reused if the content hasn't changed since.
+        let hash = utils::content_hash(&raw_content);
+        let cached = self
+            .render_cache
+            .read()
+            .get(page_path)
+            .filter(|(cached_hash, _)| *cached_hash == hash)
+            .map(|(_, page)| page.clone());
+        let rendered_page = match cached {
+            Some(page) => page,
+            None => {
+                let page = self.render_page_with_context(
+                    &raw_content,
+                    RenderContext {
+                        source: Some(page_path),
+                        ..Default::default()
+                    },
+                )?;
+                self.render_cache
+                    .write()
+                    .insert(page_path.to_path_buf(), (hash, page.clone()));
+                page
+            }
+        };
 
         let indexer = self.indexer.read();
-        let page_path = Path::new(path);
 
         let page = indexer
             .pages
@@ -667,17 +2914,23 @@ impl Renderer {
             .iter()
             .filter_map(|backlink_path| {
                 indexer.pages.get(backlink_path).map(|p| {
-                    // Get the count of links from the source (backlink_path) to the target (page_path)
-                    let count = indexer
+                    // Get the links from the source (backlink_path) to the target (page_path)
+                    let links = indexer
                         .link_graph
                         .get(backlink_path)
                         .and_then(|targets| targets.get(page_path))
-                        .map_or(0, |links| links.len());
+                        .map_or(&[][..], |links| links.as_slice());
+                    let alias = if self.settings.show_backlink_aliases {
+                        links.iter().find_map(|link| link.alias.clone())
+                    } else {
+                        None
+                    };
 
                     Backlink {
                         title: p.title.clone(),
                         path: p.path.clone(),
-                        count,
+                        count: links.len(),
+                        alias,
                     }
                 })
             })
@@ -686,10 +2939,15 @@ impl Renderer {
         // Sort backlinks alphabetically by title (case-insensitive)
         backlinks.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
 
+        let related = indexer.get_related_pages(page_path, MAX_RELATED_PAGES);
+        let local_graph = include_local_graph.then(|| indexer.get_neighborhood(page_path));
+
         Ok(FullPageData {
             raw_content,
             rendered_page,
             backlinks,
+            related,
+            local_graph,
         })
     }
 }
@@ -697,6 +2955,7 @@ impl Renderer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::events::FileEvent;
     use crate::indexer::Indexer;
     use parking_lot::RwLock;
     use serde_json::json;
@@ -730,7 +2989,7 @@ mod tests {
     fn test_render_custom_syntax_in_string() {
         let (renderer, page1_path) = setup_renderer();
         let content = "Link to [[Page One]] and a ||spoiler||.";
-        let rendered = renderer.render_custom_syntax_in_string(content);
+        let rendered = renderer.render_custom_syntax_in_string(content, RenderContext::default());
 
         let expected_path_str = path_to_web_str(&page1_path);
         let expected = format!(
@@ -741,6 +3000,61 @@ mod tests {
         assert_eq!(rendered, expected);
     }
 
+    #[test]
+    fn test_link_previews() {
+        let (mut renderer, page1_path) = setup_renderer();
+        let link = |renderer: &Renderer| {
+            renderer.render_custom_syntax_in_string("[[Page One]]", RenderContext::default())
+        };
+        assert!(!link(&renderer).contains("data-preview"));
+
+        renderer.set_settings(RenderSettings {
+            link_previews: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            link(&renderer),
+            format!(
+                "<a href=\"#\" class=\"internal-link\" data-path=\"{}\" data-preview=\"content\">Page One</a>",
+                path_to_web_str(&page1_path)
+            )
+        );
+        let page = renderer.render_page_preview("See [[Page One]].").unwrap();
+        assert!(page.html_before_toc.contains("data-preview=\"content\""));
+
+        // Overlong previews are cut short by the sanitizer.
+        let long = format!("<a data-preview=\"{}\">x</a>", "a".repeat(400));
+        let preview = format!(
+            "data-preview=\"{}…\"",
+            "a".repeat(sanitizer::MAX_PREVIEW_LENGTH)
+        );
+        assert!(sanitizer::sanitize_html(&long).contains(&preview));
+    }
+
+    #[test]
+    fn test_link_previews_in_player_mode() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("Old Fort.md"),
+            ":::gm\nThe fort is a trap.\n:::\n\nA quiet ruin on the coast.",
+        )
+        .unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let mut renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+        renderer.set_settings(RenderSettings {
+            link_previews: true,
+            player_mode: true,
+            ..Default::default()
+        });
+
+        let html =
+            renderer.render_custom_syntax_in_string("[[Old Fort]]", RenderContext::default());
+        assert!(html.contains("data-preview=\"A quiet ruin on the coast.\""));
+        assert!(!html.contains("trap"));
+    }
+
     #[test]
     fn test_frontmatter_markdown_rendering() {
         let (renderer, page1_path) = setup_renderer();
@@ -796,12 +3110,78 @@ Body
     }
 
     #[test]
-    fn test_render_page_preview_with_malformed_yaml() {
-        let (renderer, _) = setup_renderer();
-        let content = "---\ntitle: Test\ninvalid yaml: here:\n---\nBody.";
-        let result = renderer.render_page_preview(content).unwrap();
+    fn test_broken_link_suggestions() {
+        let (mut renderer, _) = setup_renderer();
+        let content = "See [[Page Onee]] and [[Something Else Entirely]].";
 
-        // Check that the frontmatter contains the error object
+        let page = renderer.render_page_preview(content).unwrap();
+        assert!(!page.html_before_toc.contains("data-suggestion"));
+
+        renderer.set_settings(RenderSettings {
+            suggest_broken_links: true,
+            ..Default::default()
+        });
+        let page = renderer.render_page_preview(content).unwrap();
+        assert!(page.html_before_toc.contains(
+            "<a href=\"#\" class=\"internal-link broken\" data-target=\"Page Onee\" data-suggestion=\"Page One\">Page Onee</a>"
+        ));
+        // Nothing is close to the second link, so it gets no suggestion.
+        assert_eq!(page.html_before_toc.matches("data-suggestion").count(), 1);
+    }
+
+    #[test]
+    fn test_render_page_in_focus_mode() {
+        let (renderer, _) = setup_renderer();
+        let content =
+            "---\ntitle: Test\nrelation: '[[Page One]]'\n---\nIntro with [[Page One]].\n\n## Section\n\nMore prose.";
+
+        let focus_ctx = RenderContext {
+            focus: true,
+            ..Default::default()
+        };
+        let result = renderer
+            .render_page_with_context(content, focus_ctx)
+            .unwrap();
+        let full = renderer.render_page_preview(content).unwrap();
+
+        assert_eq!(result.processed_frontmatter, Value::Null);
+        assert!(result.toc.is_empty());
+        assert!(result.html_before_toc.is_empty());
+        assert_eq!(
+            result.html_after_toc,
+            format!("{}{}", full.html_before_toc, full.html_after_toc)
+        );
+        assert!(result.html_after_toc.contains(">Page One</a>"));
+        assert!(result
+            .html_after_toc
+            .contains("<h2 id=\"section\">Section</h2>"));
+    }
+
+    #[test]
+    fn test_frontmatter_table() {
+        let (renderer, page1_path) = setup_renderer();
+        let content = "---\ntitle: Ledger\nroster:\n  - name: '[[Page One]]'\n    level: 3\n  - name: Vex\n    level: 12\n    note: <b>Rogue</b>\nallies: [Vex, Bram]\n---\nBody.";
+
+        let result = renderer.render_page_preview(content).unwrap();
+        let expected = format!(
+            "<table class=\"frontmatter-table\">\n<thead>\n<tr><th>name</th><th align=\"right\">level</th><th>note</th></tr>\n</thead>\n<tbody>\n<tr><td><a href=\"#\" class=\"internal-link\" data-path=\"{}\">Page One</a></td><td align=\"right\">3</td><td></td></tr>\n<tr><td>Vex</td><td align=\"right\">12</td><td><b>Rogue</b></td></tr>\n</tbody>\n</table>",
+            path_to_web_str(&page1_path)
+        );
+        assert_eq!(result.processed_frontmatter["roster"], expected);
+        // Lists of plain values keep the usual infobox behavior.
+        assert_eq!(
+            result.processed_frontmatter["allies"],
+            json!(["Vex", "Bram"])
+        );
+    }
+
+    #[test]
+    fn test_render_page_preview_with_malformed_yaml() {
+        let (renderer, _) = setup_renderer();
+        let content = "---\ntitle: Test\ninvalid yaml: here:\n---\nBody.";
+        let result = renderer.render_page_preview(content).unwrap();
+
+        // Check that the frontmatter contains the error object
         assert_eq!(
             result.processed_frontmatter["error"],
             json!("YAML Parse Error")
@@ -812,6 +3192,79 @@ Body
         assert_eq!(result.html_before_toc, "<p>Body.</p>\n");
     }
 
+    #[test]
+    fn test_ambiguous_links_resolve_by_proximity() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("north")).unwrap();
+        fs::create_dir_all(root.join("south")).unwrap();
+        let north_intro = root.join("north").join("Intro.md");
+        let south_intro = root.join("south").join("Intro.md");
+        fs::write(&north_intro, "North.").unwrap();
+        fs::write(&south_intro, "South.").unwrap();
+        let south_page = root.join("south").join("Capital.md");
+        fs::write(&south_page, "See [[Intro]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let view = renderer
+            .build_page_view(south_page.to_str().unwrap(), false)
+            .unwrap();
+        let expected = format!("data-path=\"{}\"", path_to_web_str(&south_intro));
+        assert!(view.rendered_page.html_before_toc.contains(&expected));
+
+        // Without the page's path, the stable flat choice is used instead.
+        let preview = renderer.render_page_preview("See [[Intro]].").unwrap();
+        let expected = format!("data-path=\"{}\"", path_to_web_str(&north_intro));
+        assert!(preview.html_before_toc.contains(&expected));
+    }
+
+    #[test]
+    fn test_backlinks_show_link_aliases() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let bob = root.join("Bob.md");
+        fs::write(&bob, "The butler.").unwrap();
+        fs::write(
+            root.join("Manor.md"),
+            "Greeted by [[Bob]], then [[Bob|The Butler]].",
+        )
+        .unwrap();
+        fs::write(root.join("Kitchen.md"), "Run by [[Bob]].").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let mut renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let aliases = |renderer: &Renderer| -> Vec<(String, Option<String>)> {
+            let view = renderer
+                .build_page_view(bob.to_str().unwrap(), false)
+                .unwrap();
+            view.backlinks
+                .into_iter()
+                .map(|backlink| (backlink.title, backlink.alias))
+                .collect()
+        };
+        assert_eq!(
+            aliases(&renderer),
+            vec![("Kitchen".to_string(), None), ("Manor".to_string(), None)]
+        );
+
+        renderer.set_settings(RenderSettings {
+            show_backlink_aliases: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            aliases(&renderer),
+            vec![
+                ("Kitchen".to_string(), None),
+                ("Manor".to_string(), Some("The Butler".to_string())),
+            ]
+        );
+    }
+
     #[test]
     fn test_render_page_preview_no_frontmatter() {
         let (renderer, _) = setup_renderer();
@@ -867,7 +3320,8 @@ Case 3: Inline with single backticks `[[Page One]]`.
 A normal link for comparison: [[Page One]].
 "#;
 
-        let (body_html, _, _) = renderer.render_body_to_html_with_toc(content);
+        let (body_html, _, _) =
+            renderer.render_body_to_html_with_toc(content, None, RenderContext::default());
         let expected_path_str = path_to_web_str(&page1_path);
 
         // The expected HTML now asserts that wikilinks ARE rendered inside
@@ -888,7 +3342,8 @@ A normal link for comparison: [[Page One]].
 A normal link to [[Page One]].
 A spoiler with a ||secret [[link]] inside||.
 "#;
-        let (body_html, _, _) = renderer.render_body_to_html_with_toc(content);
+        let (body_html, _, _) =
+            renderer.render_body_to_html_with_toc(content, None, RenderContext::default());
         let page1_path_str = path_to_web_str(&page1_path);
         let link_path_str = path_to_web_str(&link_path);
 
@@ -901,6 +3356,49 @@ A spoiler with a ||secret [[link]] inside||.
         assert_eq!(body_html, expected_html);
     }
 
+    #[test]
+    fn test_math() {
+        let (renderer, _) = setup_renderer();
+        let content = "Energy is $E=mc^2$, and $a<b$ stays $\\$x$.\n\
+                       It costs $5 and $10, or `$x$` in code.\n\
+                       $$\n\\sum_{i=1}^n [[i]] ==i==\n\n\\frac{a}{b}\n$$\n\
+                       $$x^2$$\n\n\
+                       ```\n$$\n$y$\n```\n\n    \
+                       cost $x$\n\n\
+                       - a list item\n\n    with $z$ math";
+        let (body_html, _, _) =
+            renderer.render_body_to_html_with_toc(content, None, RenderContext::default());
+
+        assert!(body_html.starts_with(
+            "<p>Energy is <span class=\"math-inline\">E=mc^2</span>, and <span class=\"math-inline\">a&lt;b</span>"
+        ));
+        assert!(body_html.contains("stays <span class=\"math-inline\">\\$x</span>.\n"));
+        assert!(body_html.contains("It costs $5 and $10, or <code>$x$</code> in code.</p>\n"));
+        assert!(body_html.contains(
+            "<div class=\"math-block\">\\sum_{i=1}^n [[i]] ==i==\n\n\\frac{a}{b}</div>\n\n<div class=\"math-block\">x^2</div>\n"
+        ));
+        assert!(body_html.contains("<pre><code>$$\n$y$\n</code></pre>"));
+        assert!(body_html.contains("<pre><code>cost $x$\n</code></pre>"));
+        assert!(body_html.contains("with <span class=\"math-inline\">z</span> math"));
+        assert_eq!(body_html.matches("math-").count(), 6);
+    }
+
+    #[test]
+    fn test_highlights() {
+        let (renderer, page1_path) = setup_renderer();
+        let content = "==Vex== owes ==[[Page One]]== a favor, and ||==never==|| pays.\n\n\
+                       a == b == c, `==code==`, and ====.";
+        let (body_html, _, _) =
+            renderer.render_body_to_html_with_toc(content, None, RenderContext::default());
+
+        let expected_html = format!(
+            "<p><mark>Vex</mark> owes <mark><a href=\"#\" class=\"internal-link\" data-path=\"{}\">Page One</a></mark> a favor, and <span class=\"spoiler\"><mark>never</mark></span> pays.</p>\n\
+             <p>a == b == c, <code>==code==</code>, and ====.</p>\n",
+            path_to_web_str(&page1_path)
+        );
+        assert_eq!(body_html, expected_html);
+    }
+
     #[test]
     fn test_toc_generation_and_html_split() {
         let (renderer, _) = setup_renderer();
@@ -944,6 +3442,32 @@ Final text.
             .contains("<h1 id=\"header-2\">Header 2</h1>"));
     }
 
+    #[test]
+    fn test_toc_depth() {
+        let (renderer, _) = setup_renderer();
+        let content = "# Harbor\n## Docks\n### Pier\n#### Crate\n## Market\n";
+        let ctx = RenderContext {
+            toc_depth: Some(2),
+            ..Default::default()
+        };
+        let result = renderer.render_page_with_context(content, ctx).unwrap();
+
+        let toc: Vec<(&str, &str)> = result
+            .toc
+            .iter()
+            .map(|entry| (entry.number.as_str(), entry.text.as_str()))
+            .collect();
+        assert_eq!(
+            toc,
+            vec![("1", "Harbor"), ("1.1", "Docks"), ("1.2", "Market")]
+        );
+        // Headings left out of the TOC can still be linked to.
+        assert!(result.html_after_toc.contains("<h3 id=\"pier\">Pier</h3>"));
+        assert!(result
+            .html_after_toc
+            .contains("<h4 id=\"crate\">Crate</h4>"));
+    }
+
     #[test]
     fn test_toc_with_duplicate_headers() {
         let (renderer, _) = setup_renderer();
@@ -970,4 +3494,1128 @@ Final text.
         );
         assert!(result.html_after_toc.is_empty());
     }
+
+    #[test]
+    fn test_image_without_extension_resolves_to_existing_file() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let images_dir = root.join("images");
+        fs::create_dir_all(&images_dir).unwrap();
+        fs::write(images_dir.join("portrait.png"), b"portrait").unwrap();
+
+        let indexer = Arc::new(RwLock::new(Indexer::new(root)));
+        let renderer = Renderer::new(indexer, root.to_path_buf());
+
+        let result = renderer
+            .render_page_preview("---\nimage: portrait\n---\nBody")
+            .unwrap();
+        let fm = &result.processed_frontmatter;
+        assert_eq!(
+            fm["image_paths"],
+            json!([images_dir.join("portrait.png").to_string_lossy()])
+        );
+        assert!(fm["images"][0].as_str().unwrap().ends_with("portrait.png"));
+        assert!(renderer
+            .convert_image_path_to_data_url("portrait")
+            .starts_with("data:image/png;base64,"));
+
+        // Without a matching file, the path is left as written.
+        let result = renderer
+            .render_page_preview("---\nimage: nobody\n---\nBody")
+            .unwrap();
+        assert_eq!(
+            result.processed_frontmatter["image_paths"],
+            json!([images_dir.join("nobody").to_string_lossy()])
+        );
+    }
+
+    #[test]
+    fn test_banner_is_processed_separately_from_image() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let images_dir = root.join("images");
+        fs::create_dir_all(&images_dir).unwrap();
+        fs::write(images_dir.join("portrait.png"), b"portrait").unwrap();
+        fs::write(images_dir.join("banner.png"), b"banner").unwrap();
+
+        let indexer = Arc::new(RwLock::new(Indexer::new(root)));
+        let renderer = Renderer::new(indexer, root.to_path_buf());
+
+        let content = "---\nimage: portrait.png\nbanner: banner.png\n---\nBody";
+        let result = renderer.render_page_preview(content).unwrap();
+        let fm = &result.processed_frontmatter;
+
+        assert_eq!(
+            fm["image_paths"],
+            json!([images_dir.join("portrait.png").to_string_lossy()])
+        );
+        assert_eq!(
+            fm["banner_path"],
+            json!(images_dir.join("banner.png").to_string_lossy())
+        );
+        assert!(fm["banner"].as_str().unwrap().contains("banner.png"));
+        assert!(!fm["images"][0].as_str().unwrap().contains("banner.png"));
+        assert!(fm.get("image").is_none());
+
+        // A banner pointing at a missing file is skipped entirely.
+        let missing = "---\nbanner: missing.png\n---\nBody";
+        let result = renderer.render_page_preview(missing).unwrap();
+        assert!(result.processed_frontmatter.get("banner").is_none());
+        assert!(result.processed_frontmatter.get("banner_path").is_none());
+    }
+
+    #[test]
+    fn test_suppress_duplicate_title_heading() {
+        let (mut renderer, _) = setup_renderer();
+        let matching = "---\ntitle: The Keep\n---\n# The Keep\nIntro.\n## History\n";
+        let non_matching = "---\ntitle: The Keep\n---\n# The Old Keep\nIntro.\n";
+
+        // Off by default: the heading is rendered as usual.
+        let result = renderer.render_page_preview(matching).unwrap();
+        assert!(result
+            .html_after_toc
+            .contains("<h1 id=\"the-keep\">The Keep</h1>"));
+        assert_eq!(result.toc.len(), 2);
+
+        renderer.set_settings(RenderSettings {
+            suppress_duplicate_title: true,
+            ..Default::default()
+        });
+
+        let result = renderer.render_page_preview(matching).unwrap();
+        assert!(!result.html_before_toc.contains("<h1"));
+        assert!(!result.html_after_toc.contains("<h1"));
+        assert_eq!(result.html_before_toc.trim(), "<p>Intro.</p>");
+        assert_eq!(result.toc.len(), 1);
+        assert_eq!(result.toc[0].text, "History");
+        assert_eq!(result.toc[0].number, "1");
+
+        let result = renderer.render_page_preview(non_matching).unwrap();
+        assert!(result
+            .html_after_toc
+            .contains("<h1 id=\"the-old-keep\">The Old Keep</h1>"));
+        assert_eq!(result.toc.len(), 1);
+
+        // The title comes from the configured title keys, as in the index.
+        renderer.indexer.write().settings.title_keys = vec!["name".to_string()];
+        let result = renderer
+            .render_page_preview("---\nname: The Keep\n---\n# The Keep\nIntro.\n")
+            .unwrap();
+        assert!(!result.html_after_toc.contains("<h1"));
+        let result = renderer.render_page_preview(matching).unwrap();
+        assert!(result.html_after_toc.contains("<h1"));
+    }
+
+    #[test]
+    fn test_frontmatter_extends_template() {
+        let (mut renderer, _) = setup_renderer();
+        let templates = tempdir().unwrap();
+        fs::write(
+            templates.path().join("Character.md"),
+            "---\ntitle: Template Title\nstatus: alive\nrace: human\n---\nTemplate body",
+        )
+        .unwrap();
+        renderer.set_templates_dir(templates.path().to_path_buf());
+
+        let content = "---\ntitle: Aria\nextends: Character\nrace: elf\n---\nBody";
+        let fm = renderer
+            .render_page_preview(content)
+            .unwrap()
+            .processed_frontmatter;
+
+        // The template supplies the omitted field, but the page's own values win.
+        assert_eq!(fm["status"], "alive");
+        assert_eq!(fm["race"], "elf");
+        assert_eq!(fm["title"], "Aria");
+        assert!(fm.get("extends").is_none());
+
+        // A missing template leaves the page's own fields untouched.
+        let content = "---\nextends: Missing\nrace: elf\n---\nBody";
+        let fm = renderer
+            .render_page_preview(content)
+            .unwrap()
+            .processed_frontmatter;
+        assert_eq!(fm["race"], "elf");
+        assert!(fm.get("status").is_none());
+    }
+
+    #[test]
+    fn test_oversized_images_are_not_embedded() {
+        let (mut renderer, _) = setup_renderer();
+        let dir = tempdir().unwrap();
+        let small = dir.path().join("small.png");
+        let large = dir.path().join("large.png");
+        fs::write(&small, [0u8; 8]).unwrap();
+        fs::write(&large, [0u8; 64]).unwrap();
+
+        renderer.set_settings(RenderSettings {
+            max_embed_image_bytes: 16,
+            ..Default::default()
+        });
+
+        let small_src = renderer.convert_image_path_to_data_url(small.to_str().unwrap());
+        assert!(small_src.starts_with("data:image/png;base64,"));
+
+        let large_src = renderer.convert_image_path_to_data_url(large.to_str().unwrap());
+        assert!(!large_src.starts_with("data:"));
+        assert_eq!(
+            large_src,
+            renderer.convert_image_path_to_asset_url(large.to_str().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_humanize_frontmatter_dates() {
+        let (mut renderer, _) = setup_renderer();
+        let content =
+            "---\ndate: 2024-03-05\nupdated: sometime last spring\nborn: 2024-03-05\n---\nBody";
+
+        // Off by default: dates are left untouched.
+        let fm = renderer
+            .render_page_preview(content)
+            .unwrap()
+            .processed_frontmatter;
+        assert_eq!(fm["date"], "2024-03-05");
+
+        renderer.set_settings(RenderSettings {
+            humanize_dates: true,
+            ..Default::default()
+        });
+        let fm = renderer
+            .render_page_preview(content)
+            .unwrap()
+            .processed_frontmatter;
+        assert_eq!(fm["date"], "March 5, 2024");
+        // Values that aren't dates, and keys that aren't date-like, stay as-is.
+        assert_eq!(fm["updated"], "sometime last spring");
+        assert_eq!(fm["born"], "2024-03-05");
+
+        renderer.set_settings(RenderSettings {
+            humanize_dates: true,
+            date_format: "%d/%m/%Y".to_string(),
+            ..Default::default()
+        });
+        let fm = renderer
+            .render_page_preview(content)
+            .unwrap()
+            .processed_frontmatter;
+        assert_eq!(fm["date"], "05/03/2024");
+    }
+
+    #[test]
+    fn test_gm_blocks_in_gm_and_player_mode() {
+        let (mut renderer, _) = setup_renderer();
+        let content = "Public intro.\n\n:::gm\n## Twist\n\nThe **king** is a [[Page One|traitor]].\n:::\n\n```\n:::gm\n```\n\nPublic outro.";
+
+        // GM mode (the default): the block is rendered and marked as GM-only.
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(html.contains("<div class=\"gm-only\">"));
+        assert!(html.contains("<strong>king</strong>"));
+        assert!(html.contains(">traitor</a>"));
+        assert!(page.toc.iter().any(|entry| entry.text == "Twist"));
+        // A fence marker inside a code block is not treated as a GM block.
+        assert!(html.contains("<code>:::gm"));
+
+        // Player mode: the block is stripped from the output, not just hidden.
+        renderer.set_settings(RenderSettings {
+            player_mode: true,
+            ..Default::default()
+        });
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(!html.contains("gm-only"));
+        assert!(!html.contains("king"));
+        assert!(!html.contains("traitor"));
+        assert!(page.toc.is_empty());
+        assert!(html.contains("Public intro."));
+        assert!(html.contains("Public outro."));
+    }
+
+    #[test]
+    fn test_details_blocks() {
+        let (mut renderer, _) = setup_renderer();
+        let content = ":::details The <Secret> History\nSee [[Page One]] for more.\n\nA second paragraph.\n\n:::gm\n:::details\nGM notes.\n:::\n:::\n:::\n\nAfter.";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(html.contains("<summary>The &lt;Secret&gt; History</summary>"));
+        assert!(html.contains(">Page One</a>"));
+        assert!(html.contains("<p>A second paragraph.</p>"));
+        // A nested block without a title gets the default summary.
+        assert!(html.contains("<summary>Details</summary>"));
+        assert_eq!(html.matches("</details>").count(), 2);
+        assert!(html.contains("<p>After.</p>"));
+
+        // In player mode the details block inside the GM block is stripped with it.
+        renderer.set_settings(RenderSettings {
+            player_mode: true,
+            ..Default::default()
+        });
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(!html.contains("GM notes."));
+        assert!(!html.contains("<summary>Details</summary>"));
+        assert_eq!(html.matches("</details>").count(), 1);
+        assert!(html.contains("<p>After.</p>"));
+    }
+
+    #[test]
+    fn test_uid_links() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        fs::write(&vex, "---\nuid: npc-0042\n---\n").unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let rendered = renderer.render_custom_syntax_in_string(
+            "[[uid:npc-0042]] and [[uid:npc-0042|the rogue]]",
+            RenderContext::default(),
+        );
+        let path = path_to_web_str(&vex);
+        assert_eq!(
+            rendered,
+            format!(
+                "<a href=\"#\" class=\"internal-link\" data-path=\"{0}\">Vex</a> and <a href=\"#\" class=\"internal-link\" data-path=\"{0}\">the rogue</a>",
+                path
+            )
+        );
+    }
+
+    #[test]
+    fn test_mermaid_fences() {
+        let (renderer, _) = setup_renderer();
+        let content = "```mermaid\ngraph TD\n  A[[Page One]] --> B\n```\n\n```\nplain code\n```";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        assert_eq!(
+            page.html_before_toc,
+            "<div class=\"mermaid\">graph TD\n  A[[Page One]] --&gt; B\n</div>\n<pre><code>plain code\n</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_kbd_shorthand() {
+        let (renderer, _) = setup_renderer();
+        let markdown = "Copy with `kbd:Ctrl+C`, zoom with `kbd:Ctrl++`. Plain `code` stays.";
+
+        let (html, _, _) =
+            renderer.render_body_to_html_with_toc(markdown, None, Default::default());
+
+        assert_eq!(
+            html,
+            "<p>Copy with <kbd>Ctrl</kbd>+<kbd>C</kbd>, zoom with <kbd>Ctrl</kbd>+<kbd>+</kbd>. \
+             Plain <code>code</code> stays.</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        let (renderer, _) = setup_renderer();
+        let markdown = "The HTML spec, not HTMLish, links to [[Page One]].\n\n\
+                        *[HTML]: HyperText \"Markup\" Language\n\n\
+                        ```\n*[CODE]: Not a definition\n```\n";
+
+        let (html, _, _) =
+            renderer.render_body_to_html_with_toc(markdown, None, Default::default());
+
+        assert!(html.contains(
+            "The <abbr title=\"HyperText &quot;Markup&quot; Language\">HTML</abbr> spec, not HTMLish"
+        ));
+        assert!(!html.contains("*[HTML]"));
+        assert!(html.contains("*[CODE]: Not a definition"));
+        assert_eq!(html.matches("<abbr").count(), 1);
+
+        // Terms with characters that get escaped are still found, and terms that
+        // look like parts of character references don't break them.
+        let markdown = "R&D funds the lab & the amp, see [[Page One|R&D notes]].\n\n\
+                        *[R&D]: Research and development\n*[amp]: Amplifier\n";
+        let (html, _, _) =
+            renderer.render_body_to_html_with_toc(markdown, None, Default::default());
+        assert!(html.contains("<abbr title=\"Research and development\">R&amp;D</abbr> funds"));
+        assert!(html.contains("the lab &amp; the <abbr title=\"Amplifier\">amp</abbr>,"));
+        assert!(html.contains(">R&amp;D</abbr> notes</a>"));
+        assert_eq!(html.matches("<abbr").count(), 3);
+    }
+
+    #[test]
+    fn test_inline_footnotes() {
+        let (renderer, _temp_dir) = setup_renderer();
+        let markdown = "Vex fled north.^[See [[Harbor|the harbor]] for details.] \
+                        Code like `a^[0]` is left alone.\n\n\
+                        ```\nlet b = c^[1];\n```\n";
+
+        let (html, _, _) =
+            renderer.render_body_to_html_with_toc(markdown, None, Default::default());
+
+        assert!(html.contains("<a href=\"#inline-note-1\">1</a></sup>"));
+        assert!(html.contains(">the harbor</a> for details."));
+        assert!(html.contains("<code>a^[0]</code>"));
+        assert!(html.contains("let b = c^[1];"));
+        assert_eq!(html.matches("footnote-definition\"").count(), 1);
+    }
+
+    #[test]
+    fn test_definition_lists() {
+        let (renderer, _) = setup_renderer();
+        let content = "Apple\n: A *red* fruit.\n\nBanana\n: A yellow fruit.\n: See [[Page One]].\n\nNote: this stays a paragraph.\n:smile: So does this.";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(html.starts_with(
+            "<dl>\n<dt>Apple</dt>\n<dd>A <em>red</em> fruit.</dd>\n<dt>Banana</dt>\n<dd>A yellow fruit.</dd>\n<dd>See <a"
+        ));
+        assert_eq!(html.matches("<dl>").count(), 1);
+        assert!(html.contains(">Page One</a>.</dd>\n</dl>"));
+        assert!(html.contains("<p>Note: this stays a paragraph.\n:smile: So does this.</p>"));
+    }
+
+    #[test]
+    fn test_blockquote_attributions() {
+        let (renderer, _) = setup_renderer();
+        let content = "> The sea remembers.\n>\n> Every ship.\n> — [[Page One]], *Logbook*\n\n\
+                       > Short.\n> -- Anon\n\n> Listed.\n> - Someone\n\n> Just a quote.\n> No attribution.";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(html.contains(
+            "<p>The sea remembers.</p>\n<p>Every ship.</p>\n<cite>— <a href=\"#\" class=\"internal-link\""
+        ));
+        assert!(html.contains(">Page One</a>, <em>Logbook</em></cite>\n</blockquote>"));
+        assert!(html.contains("<p>Short.</p>\n<cite>-- Anon</cite>\n</blockquote>"));
+        assert!(html.contains("<p>Listed.</p>\n<cite>- Someone</cite>\n</blockquote>"));
+        assert!(html.contains("<p>Just a quote.\nNo attribution.</p>\n</blockquote>"));
+        assert_eq!(html.matches("<cite>").count(), 3);
+    }
+
+    #[test]
+    fn test_callouts() {
+        let (renderer, _) = setup_renderer();
+        let content = "> [!warning] Here be [[Page One|dragons]]\n> The pass is *not* safe.\n\n\
+                       > [!LORE]-\n> Hidden history.\n>\n> > [!tip]+ Inner\n> > Nested.\n\n\
+                       > [!unknown] Stays a quote.\n\n> [!note]";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+        assert!(html.starts_with(
+            "<div class=\"callout callout-warning\">\n<div class=\"callout-title\">Here be <a"
+        ));
+        assert!(html.contains(
+            ">dragons</a></div>\n<div class=\"callout-content\">\n<p>The pass is <em>not</em> safe.</p>\n</div>\n</div>"
+        ));
+        assert!(html.contains(
+            "<details class=\"callout callout-lore\">\n<summary class=\"callout-title\">Lore</summary>\n<div class=\"callout-content\">\n<p>Hidden history.</p>\n\
+             <details open=\"\" class=\"callout callout-tip\">\n<summary class=\"callout-title\">Inner</summary>\n<div class=\"callout-content\">\n<p>Nested.</p>\n</div>\n</details>\n</div>\n</details>"
+        ));
+        assert!(html.contains("<blockquote>\n<p>[!unknown] Stays a quote.</p>\n</blockquote>"));
+        assert!(html.contains(
+            "<div class=\"callout callout-note\">\n<div class=\"callout-title\">Note</div>\n<div class=\"callout-content\">\n</div>\n</div>"
+        ));
+    }
+
+    #[test]
+    fn test_block_ids() {
+        let (renderer, page1_path) = setup_renderer();
+        let content = "The pact was sealed at dawn. ^pact\n\n\
+                       A second paragraph\nwith its id below.\n^second\n\n\
+                       - Bring the *map* ^map-item\n- No id here\n\n\
+                       Same id again. ^pact\n\n\
+                       Code `^kept` stays. And 2^10 too.";
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = page.html_before_toc;
+
+        assert!(html.contains("<p id=\"pact\">The pact was sealed at dawn.</p>"));
+        assert!(html.contains("<p id=\"second\">A second paragraph\nwith its id below.</p>"));
+        assert!(html.contains("<li id=\"map-item\">Bring the <em>map</em></li>"));
+        assert!(html.contains("<li>No id here</li>"));
+        // A repeated id keeps its first block; the marker still disappears.
+        assert!(html.contains("<p>Same id again.</p>"));
+        assert!(html.contains("<code>^kept</code> stays. And 2^10 too."));
+
+        let link = renderer.render_custom_syntax_in_string(
+            "[[Page One#^pact|the pact]]",
+            RenderContext::default(),
+        );
+        assert_eq!(
+            link,
+            format!(
+                "<a href=\"#\" class=\"internal-link\" data-path=\"{}\" data-block=\"pact\">the pact</a>",
+                path_to_web_str(&page1_path)
+            )
+        );
+        let page = renderer.render_page_preview("[[Page One#^pact]]").unwrap();
+        assert!(page.html_before_toc.contains("data-block=\"pact\""));
+    }
+
+    #[test]
+    fn test_task_lists() {
+        let (renderer, _) = setup_renderer();
+        let content = "- [x] Find the map\n- [ ] Reach the keep\n    - [x] Cross the river\n    - [ ] Bribe the guard\n\
+                       - Talk to Vex\n\n```\n- [x] Not a task\n```";
+        let page = renderer.render_page_preview(content).unwrap();
+
+        assert_eq!(
+            page.tasks,
+            Some(TaskProgress {
+                completed: 2,
+                total: 4
+            })
+        );
+        let html = page.html_before_toc;
+        assert!(html.contains(
+            "<li><input disabled=\"\" type=\"checkbox\" checked=\"\">\nFind the map</li>"
+        ));
+        assert!(html.contains("<li><input disabled=\"\" type=\"checkbox\">\nBribe the guard</li>"));
+
+        // Inputs written by hand become read-only checkboxes too.
+        let page = renderer
+            .render_page_preview(
+                "Password: <input type=\"password\" name=\"pw\"> <input type=\"submit\">",
+            )
+            .unwrap();
+        assert!(page.html_before_toc.contains(
+            "Password: <input type=\"checkbox\" disabled=\"\"> <input type=\"checkbox\" disabled=\"\">"
+        ));
+
+        let page = renderer.render_page_preview("- Just a list").unwrap();
+        assert_eq!(page.tasks, None);
+    }
+
+    #[test]
+    fn test_inline_hashtags() {
+        let (renderer, _) = setup_renderer();
+        let content = "# Vex #rogue\n\n\
+                       #character from the #docks/east (see #lore-notes). Dyed #fff,\n\
+                       filed under issue #42, at https://example.com/#top, and [[Page One|#alias]].\n\n\
+                       Painted #c0ffee, #ABCDEF, #fafafa, #fffa or #ffffff80 by the #dead at the #cafe.\n\n\
+                       Code `#kept` stays.\n\n```\n#fenced\n```";
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+
+        assert!(html.contains(
+            "<p><a class=\"tag\" data-tag=\"character\">#character</a> from the \
+             <a class=\"tag\" data-tag=\"docks/east\">#docks/east</a> (see \
+             <a class=\"tag\" data-tag=\"lore-notes\">#lore-notes</a>). Dyed #fff,"
+        ));
+        assert!(html.contains("issue #42, at https://example.com/#top, and"));
+        assert!(html.contains(">#alias</a>."));
+        // Hex colours are left alone, but hex words like #dead are still tags.
+        assert!(html.contains(
+            "Painted #c0ffee, #ABCDEF, #fafafa, #fffa or #ffffff80 by the \
+             <a class=\"tag\" data-tag=\"dead\">#dead</a> at the \
+             <a class=\"tag\" data-tag=\"cafe\">#cafe</a>."
+        ));
+        assert!(html.contains("Vex #rogue</h1>"));
+        assert!(html.contains("<code>#kept</code>"));
+        assert!(html.contains("<code>#fenced\n</code>"));
+        assert_eq!(html.matches("class=\"tag\"").count(), 5);
+    }
+
+    #[test]
+    fn test_media_embeds() {
+        let (renderer, _) = setup_renderer();
+        let page = renderer
+            .render_page_preview("![[theme.mp3]]\n\n![[cutscenes/Scene One.MP4]]\n\n![[map.png]]")
+            .unwrap();
+        let html = page.html_before_toc;
+
+        assert!(html.contains(&format!(
+            "<audio controls=\"\" src=\"{}\" class=\"embedded-media\"></audio>",
+            renderer.convert_image_path_to_asset_url("theme.mp3")
+        )));
+        assert!(html.contains(&format!(
+            "<video controls=\"\" src=\"{}\" class=\"embedded-media\"></video>",
+            renderer.convert_image_path_to_asset_url("cutscenes/Scene One.MP4")
+        )));
+        assert!(html.contains("<img src="));
+    }
+
+    #[test]
+    fn test_image_embed_sizes() {
+        let (renderer, _) = setup_renderer();
+        let img = |attrs: &str| {
+            format!(
+                "<img src=\"{}\" class=\"embedded-image\" {}>",
+                renderer.convert_image_path_to_asset_url("map.png"),
+                attrs
+            )
+        };
+
+        for (content, attrs) in [
+            ("![[map.png|300]]", r#"alt="map.png" width="300""#),
+            (
+                "![[map.png|300x200]]",
+                r#"alt="map.png" width="300" height="200""#,
+            ),
+            (
+                "![[map.png|World Map|300]]",
+                r#"alt="World Map" width="300""#,
+            ),
+            ("![[map.png|World Map]]", r#"alt="World Map""#),
+            ("![[map.png|Map of 1200]]", r#"alt="Map of 1200""#),
+            ("![[map.png]]", r#"alt="map.png""#),
+        ] {
+            let page = renderer.render_page_preview(content).unwrap();
+            assert!(
+                page.html_before_toc.contains(&img(attrs)),
+                "{}",
+                page.html_before_toc
+            );
+        }
+    }
+
+    #[test]
+    fn test_image_paths_with_spaces() {
+        let (renderer, _) = setup_renderer();
+        let expected = format!(
+            "<img src=\"{}\" class=\"embedded-image\"",
+            renderer.convert_image_path_to_asset_url("images/my map.png")
+        );
+
+        for content in [
+            "![Map](images/my map.png)",
+            "![Map](images/my%20map.png)",
+            "![Map](<images/my map.png>)",
+            "![Map](images/my map.png \"The north\")",
+        ] {
+            let page = renderer.render_page_preview(content).unwrap();
+            assert!(page.html_before_toc.contains(&expected), "{}", content);
+        }
+
+        let page = renderer
+            .render_page_preview("`![Map](images/my map.png)`\n\n```\n![Map](a b.png)\n```")
+            .unwrap();
+        assert!(!page.html_before_toc.contains("<img"));
+        assert!(page
+            .html_before_toc
+            .contains("<code>![Map](images/my map.png)</code>"));
+
+        // Absolute paths are embedded as data, once the spaces are decoded.
+        let dir = tempdir().unwrap();
+        let image = dir.path().join("my map.png");
+        fs::write(&image, "png").unwrap();
+        let absolute = image.to_string_lossy();
+        for content in [
+            format!("![Map]({})", absolute),
+            format!("![Map]({})", absolute.replace(' ', "%20")),
+        ] {
+            let page = renderer.render_page_preview(&content).unwrap();
+            assert!(page
+                .html_before_toc
+                .contains("<img src=\"data:image/png;base64,cG5n\""));
+        }
+    }
+
+    #[test]
+    fn test_page_embeds() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let history = root.join("History.md");
+        let ouroboros = root.join("Ouroboros.md");
+        fs::write(
+            &history,
+            "---\ntitle: A History\n---\n# Origins\nForged in ||fire||.\n# Today\nRuled by [[Mara]].",
+        )
+        .unwrap();
+        fs::write(&ouroboros, "Tail.\n\n![[Serpent]]").unwrap();
+        fs::write(root.join("Serpent.md"), "Head.\n\n![[Ouroboros]]").unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let render = |content: &str, source: Option<&Path>| {
+            let ctx = RenderContext {
+                source,
+                ..Default::default()
+            };
+            let page = renderer.render_page_with_context(content, ctx).unwrap();
+            format!("{}{}", page.html_before_toc, page.html_after_toc)
+        };
+
+        let html = render(
+            "![[History]]\n\n![[History#Today|now]]\n\n![[map.png]]",
+            None,
+        );
+        let history_div = format!(
+            "<div class=\"transclusion\" data-path=\"{}\">\n",
+            path_to_web_str(&history)
+        );
+        assert!(html.starts_with(&format!(
+            "{}<h1 id=\"embed-1-origins\">Origins</h1>\n<p>Forged in <span class=\"spoiler\">fire</span>.</p>",
+            history_div
+        )));
+        assert!(!html.contains("title"));
+        assert_eq!(html.matches(&history_div).count(), 2);
+        // Each embed's heading IDs are its own.
+        assert!(html.contains("<h1 id=\"embed-1-today\">Today</h1>"));
+        assert!(html.contains("<h1 id=\"embed-2-today\">Today</h1>"));
+        assert!(html.contains("class=\"internal-link broken\" data-target=\"Mara\""));
+        assert!(html.contains("<img src=\"asset://"));
+
+        // A page embedded in running text is linked instead.
+        let html = render("See ![[History]] for more.", None);
+        assert!(html.contains("See <a href=\"#\" class=\"internal-link\""));
+        assert!(!html.contains("transclusion"));
+
+        // Pages embedding each other stop at the page being rendered.
+        let html = render(&fs::read_to_string(&ouroboros).unwrap(), Some(&ouroboros));
+        assert!(html.contains("<p>Head.</p>"));
+        assert!(html.contains(&format!(
+            "<div class=\"transclusion broken\" data-path=\"{}\">Circular embed of Ouroboros</div>",
+            path_to_web_str(&ouroboros)
+        )));
+    }
+
+    #[test]
+    fn test_page_embeds_of_gm_sections_and_wide_elements() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("Vex.md"),
+            "# Vex\n:::gm\n## Secrets\nA spy.\n:::\n## Stats\n| HP | AC |\n|----|----|\n| 9 | 14 |\n",
+        )
+        .unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let mut renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+        renderer.set_settings(RenderSettings {
+            max_content_width: Some(800),
+            ..Default::default()
+        });
+        let render = |renderer: &Renderer, content: &str| {
+            let page = renderer
+                .render_page_with_context(content, RenderContext::default())
+                .unwrap();
+            format!("{}{}", page.html_before_toc, page.html_after_toc)
+        };
+
+        let html = render(&renderer, "![[Vex#Secrets]]");
+        assert!(html.starts_with("<div class=\"transclusion gm-only\""));
+        assert!(html.contains("A spy."));
+        assert!(!html.contains(":::"));
+
+        // Tables in embeds are wrapped once, by the page around them.
+        let html = render(&renderer, "![[Vex#Stats]]");
+        assert_eq!(html.matches("scroll-wrap").count(), 1);
+
+        renderer.set_settings(RenderSettings {
+            player_mode: true,
+            ..Default::default()
+        });
+        let html = render(&renderer, "![[Vex#Secrets]]");
+        assert!(html.contains("No section \"Secrets\" in Vex"));
+        assert!(!html.contains("A spy."));
+    }
+
+    #[test]
+    fn test_glossary_terms_link_once() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let glossary_path = root.join("Glossary.md");
+        fs::write(
+            &glossary_path,
+            "# Glossary\n## Ley Line\nA river of mana.\n## Mana\nMagic energy.",
+        )
+        .unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let mut renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+        renderer.set_settings(RenderSettings {
+            glossary_page: Some("Glossary".to_string()),
+            ..Default::default()
+        });
+
+        let content = "## Mana\n\nThe ley line carries mana. More mana flows at [[Glossary|the mana well]].\n\n\
+                       `mana` in code, [mana](https://example.com), and MANA again.";
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = page.html_after_toc;
+        let glossary_link = format!("data-path=\"{}\"", path_to_web_str(&glossary_path));
+
+        assert!(html.contains("<h2 id=\"mana\">Mana</h2>"));
+        assert!(html.contains(&format!(
+            "The <a href=\"#\" class=\"internal-link\" {} data-section=\"ley-line\">ley line</a> carries \
+             <a href=\"#\" class=\"internal-link\" {} data-section=\"mana\">mana</a>. More mana flows",
+            glossary_link, glossary_link
+        )));
+        assert!(html.contains(">the mana well</a>"));
+        assert!(html.contains("<code>mana</code>"));
+        assert!(html.contains("and MANA again."));
+        assert_eq!(html.matches(&glossary_link).count(), 3);
+
+        // The glossary itself isn't linked to itself.
+        let view = renderer
+            .build_page_view(glossary_path.to_str().unwrap(), false)
+            .unwrap();
+        assert!(!view.rendered_page.html_after_toc.contains("internal-link"));
+    }
+
+    #[test]
+    fn test_glossary_gm_terms_in_player_mode() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("Glossary.md"),
+            "## Mana\nMagic energy.\n\n:::gm\n## Voidstone\nThe cult's secret.\n:::\n",
+        )
+        .unwrap();
+
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let mut renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+        let content = "The voidstone hums with mana.";
+        let linked = |renderer: &Renderer| {
+            renderer
+                .render_page_preview(content)
+                .unwrap()
+                .html_before_toc
+                .matches("data-section=")
+                .count()
+        };
+
+        renderer.set_settings(RenderSettings {
+            glossary_page: Some("Glossary".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(linked(&renderer), 2);
+
+        // Players only get the terms they can read in the glossary.
+        renderer.set_settings(RenderSettings {
+            glossary_page: Some("Glossary".to_string()),
+            player_mode: true,
+            ..Default::default()
+        });
+        let html = renderer
+            .render_page_preview(content)
+            .unwrap()
+            .html_before_toc;
+        assert!(html.contains("The voidstone hums with <a "));
+        assert!(!html.contains("data-section=\"voidstone\""));
+        assert_eq!(linked(&renderer), 1);
+    }
+
+    #[test]
+    fn test_render_cache() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        let harbor = root.join("Harbor.md");
+        fs::write(&vex, "Lives in [[Harbor]], owes [[Morrow]].").unwrap();
+        fs::write(&harbor, "A port.").unwrap();
+        let indexer = Arc::new(RwLock::new(Indexer::new(root)));
+        indexer.write().scan_vault(root).unwrap();
+        let renderer = Renderer::new(indexer.clone(), root.to_path_buf());
+        let view = |renderer: &Renderer| {
+            renderer
+                .build_page_view(vex.to_str().unwrap(), false)
+                .unwrap()
+                .rendered_page
+                .html_before_toc
+        };
+
+        let first = view(&renderer);
+        assert!(first.contains("data-target=\"Morrow\""));
+
+        // A new page isn't seen until the cache is cleared.
+        let morrow = root.join("Morrow.md");
+        fs::write(&morrow, "A noble.").unwrap();
+        indexer
+            .write()
+            .handle_event_and_rebuild(&FileEvent::Created(morrow.clone()));
+        assert_eq!(view(&renderer), first);
+        renderer.clear_cache();
+        assert!(!view(&renderer).contains("broken"));
+
+        // Editing the page itself is noticed from its content.
+        fs::write(&vex, "Left [[Harbor]].").unwrap();
+        assert!(view(&renderer).starts_with("<p>Left "));
+
+        // Invalidating a page drops the pages linking to it, but not others.
+        renderer
+            .build_page_view(harbor.to_str().unwrap(), false)
+            .unwrap();
+        renderer.invalidate(&harbor);
+        assert!(renderer.render_cache.read().is_empty());
+        view(&renderer);
+        renderer.invalidate(&morrow);
+        assert!(renderer.render_cache.read().contains_key(&vex));
+
+        // The glossary page can add a term to any page, so it drops everything.
+        let glossary = root.join("Glossary.md");
+        fs::write(&glossary, "## Harbor\nA safe port.").unwrap();
+        indexer
+            .write()
+            .handle_event_and_rebuild(&FileEvent::Created(glossary.clone()));
+        let mut renderer = Renderer::new(indexer.clone(), root.to_path_buf());
+        renderer.set_settings(RenderSettings {
+            glossary_page: Some("Glossary".to_string()),
+            ..Default::default()
+        });
+        view(&renderer);
+        renderer.invalidate(&morrow);
+        assert!(renderer.render_cache.read().contains_key(&vex));
+        renderer.invalidate(&glossary);
+        assert!(renderer.render_cache.read().is_empty());
+    }
+
+    #[test]
+    fn test_wide_elements_are_wrapped() {
+        let (mut renderer, _) = setup_renderer();
+        let content = "A paragraph.\n\n| Name | Role |\n|---|---|\n| Vex | Rogue |";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        assert!(!page.html_before_toc.contains("scroll-wrap"));
+        assert_eq!(page.max_content_width, None);
+
+        renderer.set_settings(RenderSettings {
+            max_content_width: Some(700),
+            ..Default::default()
+        });
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = page.html_before_toc;
+        assert!(html.starts_with("<p>A paragraph.</p>\n<div class=\"scroll-wrap\"><table>"));
+        assert!(html.contains("</tbody></table></div>"));
+        assert_eq!(html.matches("scroll-wrap").count(), 1);
+        assert_eq!(page.max_content_width, Some(700));
+
+        // Only images wider than the content are wrapped.
+        assert_eq!(
+            Renderer::wrap_wide_elements(
+                "<img src=\"a.png\" width=\"1200\"><img src=\"b.png\" width=\"300\"><img src=\"c.png\">",
+                700
+            ),
+            "<span class=\"scroll-wrap\"><img src=\"a.png\" width=\"1200\"></span><img src=\"b.png\" width=\"300\"><img src=\"c.png\">"
+        );
+        // An image keeps its paragraph valid HTML.
+        let page = renderer
+            .render_page_preview("A map: <img src=\"map.png\" width=\"1200\">")
+            .unwrap();
+        let html = page.html_before_toc;
+        assert!(html.starts_with("<p>A map: <span class=\"scroll-wrap\"><img "));
+        assert!(html.ends_with("width=\"1200\"></span></p>\n"));
+    }
+
+    #[test]
+    fn test_number_headings() {
+        let (mut renderer, _) = setup_renderer();
+        let content = "# Rules\n## Combat\n### Initiative\n## Magic\n# Appendix";
+
+        let page = renderer.render_page_preview(content).unwrap();
+        assert!(!page.html_after_toc.contains("heading-number"));
+        assert!(page
+            .html_after_toc
+            .contains("<h3 id=\"initiative\">Initiative</h3>"));
+
+        renderer.set_settings(RenderSettings {
+            number_headings: true,
+            ..Default::default()
+        });
+        let page = renderer.render_page_preview(content).unwrap();
+        let html = &page.html_after_toc;
+        for entry in &page.toc {
+            assert!(html.contains(&format!(
+                "<span class=\"heading-number\">{}</span> {}</h{}>",
+                entry.number, entry.text, entry.level
+            )));
+        }
+        assert!(html.contains(
+            "<h3 id=\"initiative\"><span class=\"heading-number\">1.1.1</span> Initiative</h3>"
+        ));
+        assert!(html.contains("<span class=\"heading-number\">1.2</span> Magic</h2>"));
+        assert!(html.contains("<span class=\"heading-number\">2</span> Appendix</h1>"));
+        // The numbers aren't part of the headings' IDs or TOC text.
+        assert_eq!(page.toc[1].id, "combat");
+        assert_eq!(page.toc[1].text, "Combat");
+    }
+
+    #[test]
+    fn test_render_section_preview() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let page_path = root.join("Vex.md");
+        fs::write(root.join("link.md"), "").unwrap();
+        fs::write(
+            &page_path,
+            "---\ntitle: Vex\n---\n# Vex\nA rogue.\n## Early Life\nBorn in [[link]].\n\
+             ### Childhood\nStreet urchin.\n## Later Years\nRetired.\n",
+        )
+        .unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let html = renderer
+            .render_section_preview(&page_path, "early-life")
+            .unwrap();
+        assert!(html.starts_with("<h2 id=\"early-life\">Early Life</h2>\n<p>Born in <a"));
+        assert!(html.contains("class=\"internal-link\""));
+        assert!(html.contains("Street urchin."));
+        assert!(!html.contains("A rogue."));
+        assert!(!html.contains("Retired."));
+
+        // The heading text works as well as its slug.
+        assert_eq!(
+            renderer
+                .render_section_preview(&page_path, "Early Life")
+                .unwrap(),
+            html
+        );
+        assert!(renderer
+            .render_section_preview(&page_path, "Missing")
+            .is_err());
+    }
+
+    #[test]
+    fn test_render_section_preview_in_gm_block() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let page_path = root.join("Vex.md");
+        fs::write(
+            &page_path,
+            "# Vex\nA rogue.\n:::gm\n## Secrets\nA spy for the crown.\n:::\n\
+             ## Public\nKnown to all.\n",
+        )
+        .unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let mut renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        // The section keeps the GM block it starts in.
+        let html = renderer
+            .render_section_preview(&page_path, "Secrets")
+            .unwrap();
+        assert_eq!(
+            html,
+            "<div class=\"gm-only\">\n<h2 id=\"secrets\">Secrets</h2>\n<p>A spy for the crown.</p>\n</div>\n"
+        );
+
+        // In player mode it can't be previewed at all.
+        renderer.set_settings(RenderSettings {
+            player_mode: true,
+            ..Default::default()
+        });
+        assert!(renderer
+            .render_section_preview(&page_path, "Secrets")
+            .is_err());
+        assert!(renderer
+            .render_section_preview(&page_path, "Public")
+            .unwrap()
+            .contains("Known to all."));
+    }
+
+    #[test]
+    fn test_section_anchors_in_export_mode() {
+        let (renderer, _) = setup_renderer();
+        let root = renderer.vault_path.clone();
+        let source = root.join("lore").join("Current.md");
+        let content =
+            "Jump to [[#Old Wars|the wars]] or read [[Page One#The Founding]].\n\n## Old Wars";
+
+        let export_ctx = RenderContext {
+            source: Some(&source),
+            export: true,
+            ..Default::default()
+        };
+        let page = renderer
+            .render_page_with_context(content, export_ctx)
+            .unwrap();
+        let html = format!("{}{}", page.html_before_toc, page.html_after_toc);
+
+        // Same-page section links become anchors matching the heading's TOC id.
+        assert_eq!(page.toc[0].id, "old-wars");
+        assert!(html.contains(r##"<a href="#old-wars">the wars</a>"##));
+        // Cross-page links point at the exported file, relative to this page.
+        assert!(html.contains(r#"href="../Page%20One.html#the-founding""#));
+
+        // Outside export mode, the in-app link format is unchanged.
+        let page = renderer
+            .render_page_with_context(
+                content,
+                RenderContext {
+                    source: Some(&source),
+                    export: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(page
+            .html_before_toc
+            .contains(r##"<a href="#" class="internal-link""##));
+        assert!(page.html_before_toc.contains("[[#Old Wars|the wars]]"));
+    }
+
+    #[test]
+    fn test_section_links_carry_heading_id() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        fs::write(
+            &vex,
+            "---\ntitle: Vex\n---\n# Vex\n## Early Life\nBorn poor.\n## Ties to [[Vex|Herself]]\n",
+        )
+        .unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let html = renderer.render_custom_syntax_in_string(
+            "[[Vex#Early Life|her youth]], [[Vex#ties-to-herself]] and [[Vex#Old Age]]",
+            RenderContext::default(),
+        );
+        let web_path = path_to_web_str(&vex);
+        assert_eq!(
+            html,
+            format!(
+                "<a href=\"#\" class=\"internal-link\" data-path=\"{0}\" data-section=\"early-life\">her youth</a>, \
+                 <a href=\"#\" class=\"internal-link\" data-path=\"{0}\" data-section=\"ties-to-herself\">Vex</a> and \
+                 <a href=\"#\" class=\"internal-link\" data-path=\"{0}\">Vex</a>",
+                web_path
+            )
+        );
+        // The attribute survives sanitizing.
+        let page = renderer
+            .render_page_preview("See [[Vex#Early Life]].")
+            .unwrap();
+        assert!(page.html_before_toc.contains("data-section=\"early-life\""));
+    }
+
+    #[test]
+    fn test_section_links_match_toc_ids_of_repeated_headings() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let vex = root.join("Vex.md");
+        fs::write(&vex, "## Notes\n## Notes\n## Notes 1\n").unwrap();
+        let mut indexer = Indexer::new(root);
+        indexer.scan_vault(root).unwrap();
+        let renderer = Renderer::new(Arc::new(RwLock::new(indexer)), root.to_path_buf());
+
+        let toc = renderer
+            .render_page_preview(&fs::read_to_string(&vex).unwrap())
+            .unwrap()
+            .toc;
+        let ids: Vec<&str> = toc.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, ["notes", "notes-1", "notes-1-1"]);
+
+        // A section names the first heading with its text, and the link carries the
+        // same deduplicated ID as that heading's TOC entry.
+        let html = renderer.render_custom_syntax_in_string(
+            "[[Vex#Notes]] [[Vex#Notes 1]]",
+            RenderContext::default(),
+        );
+        assert!(html.contains("data-section=\"notes\""));
+        assert!(html.contains("data-section=\"notes-1-1\""));
+
+        // The headings are cached until the page is invalidated.
+        fs::write(&vex, "## Notes 1\n").unwrap();
+        let html =
+            renderer.render_custom_syntax_in_string("[[Vex#Notes 1]]", RenderContext::default());
+        assert!(html.contains("data-section=\"notes-1-1\""));
+        renderer.invalidate(&vex);
+        let html =
+            renderer.render_custom_syntax_in_string("[[Vex#Notes 1]]", RenderContext::default());
+        assert!(html.contains("data-section=\"notes-1\""));
+    }
 }
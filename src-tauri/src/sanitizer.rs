@@ -5,13 +5,34 @@
 
 use ammonia::Builder;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
+
+/// The most characters kept in a link's `data-preview` attribute. Longer previews
+/// are cut short with an ellipsis.
+pub const MAX_PREVIEW_LENGTH: usize = 300;
+
+/// Cuts an overlong `data-preview` attribute down to [`MAX_PREVIEW_LENGTH`]
+/// characters. Every other attribute is kept as it is.
+fn limit_preview_length<'u>(
+    element: &str,
+    attribute: &str,
+    value: &'u str,
+) -> Option<Cow<'u, str>> {
+    if element != "a" || attribute != "data-preview" {
+        return Some(value.into());
+    }
+    match value.char_indices().nth(MAX_PREVIEW_LENGTH) {
+        Some((cut, _)) => Some(format!("{}…", value[..cut].trim_end()).into()),
+        None => Some(value.into()),
+    }
+}
 
 /// Cleans user-provided HTML, removing potentially dangerous tags and attributes
 /// to prevent XSS attacks.
 pub fn sanitize_html(dirty_html: &str) -> String {
     Builder::new()
         .link_rel(None) // Do not add rel="noopener noreferrer" to links.
+        .attribute_filter(limit_preview_length)
         .tags(HashSet::from([
             "figure",
             "img",
@@ -54,16 +75,49 @@ pub fn sanitize_html(dirty_html: &str) -> String {
             "summary", // Summary for the details element
             "kbd",     // Keyboard input
             "abbr",    // Abbreviation
+            "cite",    // Attribution of a blockquote
+            "mark",    // Highlighted text
+            "div",     // Block containers, e.g. GM-only sections, mermaid diagrams and math
+            "audio",   // Embedded audio player
+            "video",   // Embedded video player
+            "source",  // Alternative media sources
+            "input",   // Task list checkboxes
         ]))
         .add_tag_attributes("img", &["src", "data", "alt", "style", "width", "height"])
+        .add_tag_attributes("audio", &["controls", "src", "class"])
+        .add_tag_attributes("video", &["controls", "src", "class"])
+        .add_tag_attributes("source", &["src", "type"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        // Only read-only task checkboxes are rendered, whatever input was written.
+        .set_tag_attribute_value("input", "type", "checkbox")
+        .set_tag_attribute_value("input", "disabled", "")
         .add_tag_attributes("figure", &["style"])
         .add_tag_attributes("figcaption", &["style"])
-        .add_tag_attributes("a", &["href", "title", "class", "data-path", "data-target"])
+        .add_tag_attributes(
+            "a",
+            &[
+                "href",
+                "title",
+                "class",
+                "data-path",
+                "data-target",
+                "data-suggestion",
+                "data-section",
+                "data-block",
+                "data-tag",
+                "data-preview",
+            ],
+        )
         .add_tag_attributes("span", &["class"])
-        .add_tag_attributes("details", &["open"])
+        .add_tag_attributes("div", &["class", "data-path"]) // Embedded pages link to their source
+        .add_tag_attributes("details", &["open", "class"]) // Foldable callouts have a class
+        .add_tag_attributes("summary", &["class"])
         .add_tag_attributes("abbr", &["title"]) // Allow title for abbreviations
         .add_tag_attributes("th", &["style", "align"]) // Allow table header alignment
         .add_tag_attributes("td", &["style", "align"]) // Allow table cell alignment
+        // Allow 'id' attribute on blocks with a `^id` marker for block links.
+        .add_tag_attributes("p", &["id"])
+        .add_tag_attributes("li", &["id"])
         // Allow 'id' attribute on all heading tags for TOC linking.
         .add_tag_attributes("h1", &["id"])
         .add_tag_attributes("h2", &["id"])
@@ -24,7 +24,7 @@ const TEMPLATE_DIR: &str = "templates";
 ///
 /// # Arguments
 /// * `app_handle` - The Tauri application handle, used to get the app's config path.
-fn get_templates_dir(app_handle: &AppHandle) -> Result<PathBuf> {
+pub fn get_templates_dir(app_handle: &AppHandle) -> Result<PathBuf> {
     let config_dir = app_handle.path().app_config_dir()?;
     let dir = config_dir.join(TEMPLATE_DIR);
     if !dir.exists() {
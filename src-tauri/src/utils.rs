@@ -2,11 +2,28 @@
 //!
 //! Common helpers used across modules.
 
-use std::path::Path;
+use crate::config::IMAGES_DIR_NAME;
+use path_clean::PathClean;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 /// A list of common image file extensions.
 const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
 
+/// Audio file extensions the webview can play.
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg", "m4a", "flac"];
+
+/// Video file extensions the webview can play.
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm", "mov"];
+
+fn has_extension_in(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+}
+
 /// Helper function to check if a path points to a Markdown file.
 pub fn is_markdown_file(path: &Path) -> bool {
     path.extension()
@@ -15,10 +32,17 @@ pub fn is_markdown_file(path: &Path) -> bool {
 
 /// Checks if a path points to a supported image file.
 pub fn is_image_file(path: &Path) -> bool {
-    path.extension()
-        .and_then(|s| s.to_str())
-        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-        .unwrap_or(false)
+    has_extension_in(path, IMAGE_EXTENSIONS)
+}
+
+/// Checks if a path points to a supported audio file.
+pub fn is_audio_file(path: &Path) -> bool {
+    has_extension_in(path, AUDIO_EXTENSIONS)
+}
+
+/// Checks if a path points to a supported video file.
+pub fn is_video_file(path: &Path) -> bool {
+    has_extension_in(path, VIDEO_EXTENSIONS)
 }
 
 /// Extracts the file stem from a path and returns it as a clean String.
@@ -29,3 +53,259 @@ pub fn file_stem_string(path: &Path) -> String {
         .to_string_lossy()
         .to_string()
 }
+
+/// Returns a hash of a file's content as 16 hex digits, using 64-bit FNV-1a.
+///
+/// Unlike the standard library's hasher, the result is stable across releases,
+/// so hashes can be saved and compared later.
+pub fn content_hash(content: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let hash = content.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Returns the Levenshtein distance between two strings: the number of single
+/// character insertions, deletions or substitutions needed to turn one into the other.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The score for each character of a fuzzy query found in the candidate.
+const FUZZY_MATCH_SCORE: i32 = 16;
+/// The extra score for a character matched right after the previous one.
+const FUZZY_CONSECUTIVE_BONUS: i32 = 8;
+/// The extra score for a character matched at the start of a word.
+const FUZZY_WORD_START_BONUS: i32 = 8;
+/// The extra score for matching the candidate's first character.
+const FUZZY_PREFIX_BONUS: i32 = 8;
+/// The score lost for each candidate character skipped between two matches.
+const FUZZY_GAP_PENALTY: i32 = 1;
+
+/// Scores how well `query` fuzzily matches `candidate`, ignoring case, like a
+/// quick switcher would: every query character must appear in the candidate in
+/// order, but not necessarily next to each other. Returns `None` if it doesn't
+/// match.
+///
+/// Runs of consecutive characters, matches at the start of words and at the start
+/// of the candidate score higher, and gaps between matches lower, so `gnd` ranks
+/// "Gandalf" above "Big Gun Ready". The best placement of the query is found, not
+/// just the leftmost one.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let original: Vec<char> = candidate.chars().collect();
+    // Lowercasing char by char keeps the positions aligned with `original`.
+    let lower: Vec<char> = original
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    if query.is_empty() || query.len() > lower.len() {
+        return None;
+    }
+
+    let bonus = |j: usize| -> i32 {
+        if j == 0 {
+            return FUZZY_PREFIX_BONUS + FUZZY_WORD_START_BONUS;
+        }
+        let previous = original[j - 1];
+        let word_start =
+            !previous.is_alphanumeric() || (previous.is_lowercase() && original[j].is_uppercase());
+        if word_start {
+            FUZZY_WORD_START_BONUS
+        } else {
+            0
+        }
+    };
+
+    // `best[j]` is the best score of the query so far with its last character
+    // matched at position `j`, if it can be matched there at all.
+    let mut best: Vec<Option<i32>> = lower
+        .iter()
+        .enumerate()
+        .map(|(j, &c)| (c == query[0]).then(|| FUZZY_MATCH_SCORE + bonus(j)))
+        .collect();
+    for &q in &query[1..] {
+        let mut next = vec![None; lower.len()];
+        // The best score of an earlier match further back, less the gap since.
+        let mut carried: Option<i32> = None;
+        for j in 1..lower.len() {
+            let adjacent = best[j - 1];
+            if lower[j] == q {
+                let from_adjacent = adjacent.map(|s| s + FUZZY_CONSECUTIVE_BONUS);
+                let from_gap = carried;
+                next[j] = from_adjacent
+                    .max(from_gap)
+                    .map(|s| s + FUZZY_MATCH_SCORE + bonus(j));
+            }
+            carried = carried
+                .map(|s| s - FUZZY_GAP_PENALTY)
+                .max(adjacent.map(|s| s - FUZZY_GAP_PENALTY));
+        }
+        best = next;
+    }
+    best.into_iter().flatten().max()
+}
+
+/// Checks whether `path` lies inside `root`.
+///
+/// Both paths are canonicalized first, so `..` components and symlinks cannot be
+/// used to escape the root. A path that doesn't exist yet is judged by its parent
+/// folder; if neither can be resolved, the path is treated as outside.
+pub fn is_within_root(root: &Path, path: &Path) -> bool {
+    let Ok(root) = root.canonicalize() else {
+        return false;
+    };
+    let resolved = path
+        .canonicalize()
+        .or_else(|e| match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => parent.canonicalize().map(|p| p.join(name)),
+            _ => Err(e),
+        });
+    resolved.is_ok_and(|resolved| resolved.starts_with(&root))
+}
+
+/// Checks whether `path` is, or lies inside, a hidden file or folder below `root`,
+/// such as the vault's `.chronicler` data folder. Folders above `root` don't count.
+pub fn is_hidden_in(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+    })
+}
+
+/// Resolves an image path as written in a page to an absolute path.
+///
+/// Absolute paths are used as they are; relative paths are resolved from the
+/// vault's images folder. A path written without an extension, like `portrait`,
+/// resolves to the first existing file with a known image extension, as wikis
+/// commonly allow. If there is none, the path is returned without one.
+pub fn resolve_image_path(vault_root: &Path, path_str: &str) -> PathBuf {
+    let path = Path::new(path_str);
+    let resolved = if path.is_absolute() {
+        path.clean()
+    } else {
+        vault_root.join(IMAGES_DIR_NAME).join(path).clean()
+    };
+    if resolved.extension().is_some() || resolved.is_file() {
+        return resolved;
+    }
+    IMAGE_EXTENSIONS
+        .iter()
+        .map(|ext| resolved.with_extension(ext))
+        .find(|candidate| candidate.is_file())
+        .unwrap_or(resolved)
+}
+
+/// Creates the images folder inside `vault_root` if it doesn't exist yet and
+/// returns its path. Does nothing if the folder is already there.
+pub fn ensure_images_dir(vault_root: &Path) -> io::Result<PathBuf> {
+    let images_dir = vault_root.join(IMAGES_DIR_NAME);
+    fs::create_dir_all(&images_dir)?;
+    Ok(images_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("Ærin", "Aerin"), 2);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("gnd", "Gandalf").is_some());
+        assert!(fuzzy_score("GND", "gandalf").is_some());
+        assert_eq!(fuzzy_score("gdn", "Gandalf"), None);
+        assert_eq!(fuzzy_score("", "Gandalf"), None);
+
+        // A prefix beats the same letters further in, and a run beats a scatter.
+        assert!(fuzzy_score("gan", "Gandalf") > fuzzy_score("gan", "Morgana"));
+        assert!(fuzzy_score("gnd", "Gandalf") > fuzzy_score("gnd", "Big Gun Ready"));
+        // Word starts count, including in camel case.
+        assert!(fuzzy_score("hk", "Hollow Keep") > fuzzy_score("hk", "Hulk"));
+        assert!(fuzzy_score("hk", "HollowKeep") > fuzzy_score("hk", "Hulk"));
+    }
+
+    #[test]
+    fn test_resolve_image_path_without_extension() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let images = root.join(IMAGES_DIR_NAME);
+        fs::create_dir_all(images.join("maps")).unwrap();
+        fs::write(images.join("portrait.webp"), b"").unwrap();
+        fs::write(images.join("portrait.jpg"), b"").unwrap();
+        fs::write(images.join("maps").join("coast.png"), b"").unwrap();
+
+        // Extensions are tried in a fixed order.
+        assert_eq!(
+            resolve_image_path(root, "portrait"),
+            images.join("portrait.jpg")
+        );
+        assert_eq!(
+            resolve_image_path(root, "maps/coast"),
+            images.join("maps").join("coast.png")
+        );
+        assert_eq!(resolve_image_path(root, "missing"), images.join("missing"));
+        assert_eq!(
+            resolve_image_path(root, "portrait.png"),
+            images.join("portrait.png")
+        );
+    }
+
+    #[test]
+    fn test_is_within_root() {
+        let dir = tempdir().unwrap();
+        let vault = dir.path().join("vault");
+        fs::create_dir_all(vault.join("lore")).unwrap();
+        fs::write(vault.join("lore").join("Page.md"), "").unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        // In-vault paths, existing or not.
+        assert!(is_within_root(&vault, &vault.join("lore").join("Page.md")));
+        assert!(is_within_root(&vault, &vault.join("lore").join("New.md")));
+        assert!(is_within_root(&vault, &vault));
+
+        // Out-of-vault paths.
+        assert!(!is_within_root(&vault, &dir.path().join("secret.txt")));
+        assert!(!is_within_root(&vault, Path::new("/")));
+
+        // Traversal out of the vault through `..` is caught.
+        let traversal = vault.join("lore").join("..").join("..").join("secret.txt");
+        assert!(!is_within_root(&vault, &traversal));
+        let inside = vault.join("lore").join("..").join("lore").join("Page.md");
+        assert!(is_within_root(&vault, &inside));
+    }
+
+    #[test]
+    fn test_is_hidden_in() {
+        let vault = Path::new("/home/gm/.campaigns/Vault");
+        assert!(is_hidden_in(
+            vault,
+            &vault.join(".chronicler").join("backups").join("Page.md")
+        ));
+        assert!(is_hidden_in(vault, &vault.join("lore").join(".draft.md")));
+        // The hidden folder above the vault doesn't hide its pages.
+        assert!(!is_hidden_in(vault, &vault.join("lore").join("Page.md")));
+    }
+}
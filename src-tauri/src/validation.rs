@@ -0,0 +1,380 @@
+//! Frontmatter validation.
+//!
+//! Checks page frontmatter against user-configured rules and for keys that are
+//! set more than once. The results are advisories only: they are reported to the
+//! user but never stop a page from being saved or indexed.
+
+use crate::{
+    config::{FieldLengthLimit, TypeTagCheck, ValidationSettings},
+    models::{Page, PageHeader, ValidationAdvisory},
+    parser::extract_frontmatter,
+};
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::PathBuf,
+};
+
+/// The frontmatter key holding a page's type, used to scope limits.
+pub const PAGE_TYPE_KEY: &str = "type";
+
+/// The frontmatter key reported for advisories about a page's tags.
+const TAGS_FIELD: &str = "tags";
+
+/// Returns a page's `type` field, trimmed, if it is a non-empty string.
+fn page_type(page: &Page) -> Option<&str> {
+    page.frontmatter
+        .get(PAGE_TYPE_KEY)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Orders advisories by page path, then by field.
+pub fn sort_advisories(advisories: &mut [ValidationAdvisory]) {
+    advisories.sort_by(|a, b| {
+        a.page
+            .path
+            .cmp(&b.page.path)
+            .then_with(|| a.field.cmp(&b.field))
+    });
+}
+
+/// Runs every check enabled in `settings` on the indexed frontmatter, ordering the
+/// advisories by page path, then by field. The duplicate-key check reads the files
+/// themselves, so it is left to [`check_duplicate_keys`].
+pub fn validate_pages(
+    pages: &HashMap<PathBuf, Page>,
+    settings: &ValidationSettings,
+) -> Vec<ValidationAdvisory> {
+    let mut advisories = check_field_lengths(pages, &settings.field_length_limits);
+    if let Some(check) = settings.type_tag_check {
+        advisories.extend(check_type_tags(pages, check));
+    }
+    sort_advisories(&mut advisories);
+    advisories
+}
+
+/// The top-level keys of a YAML mapping in the order they are written, repeats
+/// included. The values are skipped.
+struct TopLevelKeys(Vec<String>);
+
+impl<'de> Deserialize<'de> for TopLevelKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeysVisitor;
+
+        impl<'de> Visitor<'de> for KeysVisitor {
+            type Value = TopLevelKeys;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a mapping")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut keys = Vec::new();
+                while let Some((key, IgnoredAny)) = map.next_entry::<serde_yaml::Value, _>()? {
+                    let key = match key {
+                        serde_yaml::Value::String(key) => key,
+                        other => serde_yaml::to_string(&other)
+                            .unwrap_or_default()
+                            .trim()
+                            .to_string(),
+                    };
+                    keys.push(key);
+                }
+                Ok(TopLevelKeys(keys))
+            }
+        }
+
+        deserializer.deserialize_map(KeysVisitor)
+    }
+}
+
+/// Returns the top-level keys that appear more than once in a frontmatter block,
+/// with how often each appears, in the order they are first written.
+///
+/// The normal parser keeps only the last value of a repeated key, so a mistake like
+/// two `title:` lines goes unnoticed. Frontmatter that isn't a valid YAML mapping
+/// has no duplicates.
+pub fn find_duplicate_keys(frontmatter: &str) -> Vec<(String, usize)> {
+    let Ok(TopLevelKeys(keys)) = serde_yaml::from_str(frontmatter) else {
+        return Vec::new();
+    };
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for key in keys {
+        match counts.iter_mut().find(|(seen, _)| *seen == key) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((key, 1)),
+        }
+    }
+    counts.retain(|(_, count)| *count > 1);
+    counts
+}
+
+/// Flags pages whose frontmatter sets the same key more than once. Each page's
+/// file is read again, since the index only keeps the parsed values; pages that
+/// can't be read are skipped. Advisories are ordered by page path, then by field.
+///
+/// The pages are given as headers so the index doesn't have to stay locked while
+/// their files are read.
+pub fn check_duplicate_keys(pages: &[PageHeader]) -> Vec<ValidationAdvisory> {
+    let mut advisories = Vec::new();
+
+    for page in pages {
+        let Ok(content) = fs::read_to_string(&page.path) else {
+            continue;
+        };
+        let (frontmatter, _) = extract_frontmatter(&content);
+        for (key, count) in find_duplicate_keys(frontmatter) {
+            advisories.push(ValidationAdvisory {
+                page: page.clone(),
+                message: format!(
+                    "`{}` is set {} times; only the last value is used.",
+                    key, count
+                ),
+                field: key,
+            });
+        }
+    }
+
+    sort_advisories(&mut advisories);
+    advisories
+}
+
+/// Flags frontmatter string fields that are longer than their configured limit.
+///
+/// Lengths are counted in characters. A limit with a `page_type` only applies to
+/// pages whose `type` field matches it. Advisories are ordered by page path, then
+/// by field.
+pub fn check_field_lengths(
+    pages: &HashMap<PathBuf, Page>,
+    limits: &[FieldLengthLimit],
+) -> Vec<ValidationAdvisory> {
+    let mut advisories = Vec::new();
+    if limits.is_empty() {
+        return advisories;
+    }
+
+    for page in pages.values() {
+        let page_type = page_type(page);
+
+        for limit in limits {
+            let applies = limit.page_type.as_deref().is_none_or(|wanted| {
+                page_type.is_some_and(|actual| actual.eq_ignore_ascii_case(wanted.trim()))
+            });
+            if !applies {
+                continue;
+            }
+            let Some(value) = page.frontmatter.get(&limit.field).and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let length = value.chars().count();
+            if length > limit.max_length {
+                advisories.push(ValidationAdvisory {
+                    page: PageHeader {
+                        title: page.title.clone(),
+                        path: page.path.clone(),
+                    },
+                    field: limit.field.clone(),
+                    message: format!(
+                        "`{}` is {} characters long; the suggested maximum is {}.",
+                        limit.field, length, limit.max_length
+                    ),
+                });
+            }
+        }
+    }
+
+    sort_advisories(&mut advisories);
+    advisories
+}
+
+/// Flags pages whose `type` field and tags disagree, in the direction `check` asks
+/// for. Types and tags are compared case-insensitively.
+///
+/// With [`TypeTagCheck::TagNeedsType`], a tag only counts as a type tag if some page
+/// in the vault has it as its `type`, so ordinary tags are never flagged. Advisories
+/// are ordered by page path, then by field.
+pub fn check_type_tags(
+    pages: &HashMap<PathBuf, Page>,
+    check: TypeTagCheck,
+) -> Vec<ValidationAdvisory> {
+    let types: HashSet<String> = pages
+        .values()
+        .filter_map(page_type)
+        .map(str::to_lowercase)
+        .collect();
+    let mut advisories = Vec::new();
+
+    for page in pages.values() {
+        let page_type = page_type(page);
+        let tags: HashSet<String> = page.tags.iter().map(|t| t.to_lowercase()).collect();
+        let header = || PageHeader {
+            title: page.title.clone(),
+            path: page.path.clone(),
+        };
+
+        if matches!(check, TypeTagCheck::TypeNeedsTag | TypeTagCheck::Both) {
+            if let Some(t) = page_type.filter(|t| !tags.contains(&t.to_lowercase())) {
+                advisories.push(ValidationAdvisory {
+                    page: header(),
+                    field: PAGE_TYPE_KEY.to_string(),
+                    message: format!("The type is `{}`, but the page isn't tagged `#{}`.", t, t),
+                });
+            }
+        }
+
+        if matches!(check, TypeTagCheck::TagNeedsType | TypeTagCheck::Both) {
+            let mut type_tags: Vec<&String> = tags
+                .iter()
+                .filter(|tag| types.contains(*tag))
+                .filter(|tag| page_type.is_none_or(|t| !t.eq_ignore_ascii_case(tag)))
+                .collect();
+            type_tags.sort();
+            for tag in type_tags {
+                let message = match page_type {
+                    Some(t) => format!("The page is tagged `#{}`, but its type is `{}`.", tag, t),
+                    None => format!("The page is tagged `#{}`, but has no type.", tag),
+                };
+                advisories.push(ValidationAdvisory {
+                    page: header(),
+                    field: TAGS_FIELD.to_string(),
+                    message,
+                });
+            }
+        }
+    }
+
+    sort_advisories(&mut advisories);
+    advisories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+    use tempfile::tempdir;
+
+    fn page(name: &str, frontmatter: serde_json::Value) -> (PathBuf, Page) {
+        tagged_page(name, frontmatter, &[])
+    }
+
+    fn tagged_page(name: &str, frontmatter: serde_json::Value, tags: &[&str]) -> (PathBuf, Page) {
+        let path = PathBuf::from(format!("/vault/{}.md", name));
+        let page = Page {
+            path: path.clone(),
+            title: name.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            links: Vec::new(),
+            backlinks: HashSet::new(),
+            frontmatter,
+            word_count: 0,
+            image_refs: Vec::new(),
+            external_links: Vec::new(),
+            content_hash: String::new(),
+            parse_failed: false,
+            modified: None,
+            excerpt: None,
+        };
+        (path, page)
+    }
+
+    #[test]
+    fn test_field_length_limits() {
+        let pages = HashMap::from([
+            page(
+                "Long",
+                json!({"type": "Character", "description": "x".repeat(21), "motto": "ok"}),
+            ),
+            page(
+                "Short",
+                json!({"type": "character", "description": "Brief."}),
+            ),
+            page(
+                "Place",
+                json!({"type": "location", "description": "y".repeat(40)}),
+            ),
+        ]);
+        let limits = vec![
+            FieldLengthLimit {
+                field: "description".to_string(),
+                max_length: 20,
+                page_type: Some("character".to_string()),
+            },
+            FieldLengthLimit {
+                field: "motto".to_string(),
+                max_length: 2,
+                page_type: None,
+            },
+        ];
+
+        let advisories = check_field_lengths(&pages, &limits);
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].page.title, "Long");
+        assert_eq!(advisories[0].field, "description");
+        assert!(advisories[0].message.contains("21 characters"));
+    }
+
+    #[test]
+    fn test_type_tag_mismatches() {
+        let pages = HashMap::from([
+            tagged_page("Vex", json!({"type": "character"}), &["rogue"]),
+            tagged_page("Mara", json!({"type": "Character"}), &["character"]),
+            tagged_page("Docks", json!({}), &["character", "place"]),
+            tagged_page("Tavern", json!({"type": "location"}), &["location"]),
+        ]);
+
+        let advisories = check_type_tags(&pages, TypeTagCheck::TypeNeedsTag);
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].page.title, "Vex");
+        assert_eq!(advisories[0].field, "type");
+
+        // `place` isn't anyone's type, so only the `character` tag is flagged.
+        let advisories = check_type_tags(&pages, TypeTagCheck::TagNeedsType);
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].page.title, "Docks");
+        assert_eq!(advisories[0].field, "tags");
+        assert!(advisories[0].message.contains("#character"));
+
+        assert_eq!(check_type_tags(&pages, TypeTagCheck::Both).len(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        assert_eq!(
+            find_duplicate_keys("title: Vex\ntags: [rogue]\ntitle: Mara\ntitle: Tam"),
+            [("title".to_string(), 3)]
+        );
+        // Repeats inside nested mappings are left to the YAML parser.
+        assert!(find_duplicate_keys("stats:\n  str: 8\ndex: 14").is_empty());
+        assert!(find_duplicate_keys("not: [valid").is_empty());
+
+        let dir = tempdir().unwrap();
+        let mut pages = Vec::new();
+        for (name, content) in [
+            (
+                "Vex",
+                "---\ntitle: Vex\ntype: character\ntitle: Mara\n---\nA rogue.",
+            ),
+            ("Docks", "---\ntitle: Docks\ntype: location\n---\nA place."),
+        ] {
+            let path = dir.path().join(format!("{}.md", name));
+            fs::write(&path, content).unwrap();
+            pages.push(PageHeader {
+                title: name.to_string(),
+                path,
+            });
+        }
+
+        let advisories = check_duplicate_keys(&pages);
+        assert_eq!(advisories.len(), 1);
+        assert_eq!(advisories[0].page.title, "Vex");
+        assert_eq!(advisories[0].field, "title");
+        assert!(advisories[0].message.contains("2 times"));
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     config::{DEBOUNCE_INTERVAL, DEFAULT_EVENT_CHANNEL_CAPACITY},
     error::Result,
     events::FileEvent,
-    utils::{is_image_file, is_markdown_file},
+    utils::{is_hidden_in, is_image_file, is_markdown_file},
 };
 use notify_debouncer_full::{
     new_debouncer,
@@ -79,6 +79,7 @@ impl Watcher {
     pub fn start(&mut self, root_path: &Path) -> Result<()> {
         // Clone the sender for use in the callback closure
         let event_sender = self.event_sender.clone();
+        let root = root_path.to_path_buf();
 
         // Create the debouncer with our event publishing callback
         let mut debouncer = new_debouncer(
@@ -86,7 +87,7 @@ impl Watcher {
             None,
             move |result: DebounceEventResult| match result {
                 Ok(events) => {
-                    handle_debounced_events(&event_sender, events);
+                    handle_debounced_events(&event_sender, &root, events);
                 }
                 Err(errors) => {
                     for error in errors {
@@ -141,14 +142,17 @@ impl Drop for Watcher {
 ///
 /// This function processes raw filesystem events from the debouncer, converts them
 /// to our standardized `FileEvent` format, and publishes them to subscribers.
-/// It processes both markdown and image files, ignoring temporary files.
+/// It processes both markdown and image files, ignoring temporary files and
+/// anything in hidden folders, like the vault's data folder.
 ///
 /// # Arguments
 /// * `event_sender` - The broadcast sender to publish events to
+/// * `root` - The watched root directory
 /// * `events` - Raw debounced events from the filesystem watcher
 #[instrument(level = "debug", skip(event_sender, events))]
 fn handle_debounced_events(
     event_sender: &broadcast::Sender<FileEvent>,
+    root: &Path,
     events: Vec<DebouncedEvent>,
 ) {
     for event in events {
@@ -229,6 +233,17 @@ fn handle_debounced_events(
 
         // Publish each file event to subscribers
         for file_event in file_events {
+            // Moving a file into a hidden folder takes it out of the vault.
+            let file_event = match file_event {
+                FileEvent::Renamed { from, to } if is_hidden_in(root, &to) => {
+                    FileEvent::Deleted(from)
+                }
+                file_event => file_event,
+            };
+            if is_hidden_in(root, file_event.path()) {
+                continue;
+            }
+
             info!(
                 "Publishing file event: {} - {:?}",
                 file_event.event_type(),
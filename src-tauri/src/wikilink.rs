@@ -2,9 +2,13 @@
 //!
 //! Extracts wikilinks from text, creating Link structs
 
-use crate::models::{Link, LinkPosition};
+use crate::{
+    config::LinkStyle,
+    models::{Link, LinkPosition},
+    utils::file_stem_string,
+};
 use regex::Regex;
-use std::sync::LazyLock;
+use std::{path::Path, sync::LazyLock};
 
 /// Shared wikilink regex pattern.
 /// Captures: 1: target, 2: section (optional), 3: alias (optional)
@@ -13,6 +17,12 @@ pub static WIKILINK_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"\[\[([^\[\]\|#]+)(?:#([^\[\]\|#]+))?(?:\|([^\[\]]+))?\]\]").unwrap()
 });
 
+/// Wikilink Image regex pattern.
+/// Captures: 1: target/filename, 2: alias/alt-text, which may end in a size
+/// Format: ![[filename.png|alt text]] or ![[filename.png|alt text|300x200]]
+pub static WIKILINK_IMAGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"!\[\[([^\|\]]+)(?:\|([^\]]+))?\]\]"#).unwrap());
+
 /// A helper to convert a byte offset to a 1-based line and column number.
 fn offset_to_line_col(content: &str, byte_offset: usize) -> LinkPosition {
     let mut line = 1;
@@ -54,16 +64,49 @@ pub fn extract_wikilinks(content: &str) -> Vec<Link> {
                 .to_string();
             let section = cap.get(2).map(|m| m.as_str().trim().to_string());
             let alias = cap.get(3).map(|m| m.as_str().trim().to_string());
+            let embed = content[..offset].ends_with('!');
             Link {
                 target,
                 section,
                 alias,
+                embed,
                 position,
             }
         })
         .collect()
 }
 
+/// Writes a wikilink to `target`, with `alias` as its text unless the alias is
+/// empty or the same as the target.
+pub fn format_link(target: &str, alias: Option<&str>) -> String {
+    match alias
+        .map(str::trim)
+        .filter(|a| !a.is_empty() && *a != target)
+    {
+        Some(alias) => format!("[[{}|{}]]", target, alias),
+        None => format!("[[{}]]", target),
+    }
+}
+
+/// Writes a wikilink to the page at `path` in the given style. `title` is only
+/// used by [`LinkStyle::Aliased`].
+pub fn format_page_link(path: &Path, title: &str, vault_root: &Path, style: LinkStyle) -> String {
+    match style {
+        LinkStyle::Bare => format_link(&file_stem_string(path), None),
+        LinkStyle::Aliased => format_link(&file_stem_string(path), Some(title)),
+        LinkStyle::Path => {
+            // Links always use forward slashes, whatever the platform.
+            let relative = path.strip_prefix(vault_root).unwrap_or(path);
+            let target: Vec<String> = relative
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            format_link(&target.join("/"), None)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*; // Import everything from the parent module
@@ -79,10 +122,11 @@ This file tests various link formats.
 - A link in the middle of a sentence [[Fifth Page]] like this.
 - A link with extra whitespace: [[  Whitespace Test | Alias  ]]
 - A link with a whitespacey section: [[Test#  My Section  ]]
+- An embedded page: ![[Sixth Page]]
 "#;
         let links = extract_wikilinks(content);
 
-        assert_eq!(links.len(), 7);
+        assert_eq!(links.len(), 8);
 
         // --- Standard Link ---
         assert_eq!(
@@ -91,6 +135,7 @@ This file tests various link formats.
                 target: "Target Page".to_string(),
                 section: None,
                 alias: None,
+                embed: false,
                 position: Some(LinkPosition {
                     line: 3,
                     column: 20
@@ -105,6 +150,7 @@ This file tests various link formats.
                 target: "Another Page".to_string(),
                 section: None,
                 alias: Some("Display Text".to_string()),
+                embed: false,
                 position: Some(LinkPosition {
                     line: 4,
                     column: 25
@@ -119,6 +165,7 @@ This file tests various link formats.
                 target: "Third Page".to_string(),
                 section: Some("Section Header".to_string()),
                 alias: None,
+                embed: false,
                 position: Some(LinkPosition {
                     line: 5,
                     column: 24
@@ -133,6 +180,7 @@ This file tests various link formats.
                 target: "Fourth Page".to_string(),
                 section: Some("Some Section".to_string()),
                 alias: Some("Alias Text".to_string()),
+                embed: false,
                 position: Some(LinkPosition {
                     line: 6,
                     column: 21
@@ -147,6 +195,7 @@ This file tests various link formats.
                 target: "Fifth Page".to_string(),
                 section: None,
                 alias: None,
+                embed: false,
                 position: Some(LinkPosition {
                     line: 7,
                     column: 38
@@ -161,6 +210,7 @@ This file tests various link formats.
                 target: "Whitespace Test".to_string(),
                 section: None,
                 alias: Some("Alias".to_string()),
+                embed: false,
                 position: Some(LinkPosition {
                     line: 8,
                     column: 33
@@ -175,11 +225,45 @@ This file tests various link formats.
                 target: "Test".to_string(),
                 section: Some("My Section".to_string()),
                 alias: None,
+                embed: false,
                 position: Some(LinkPosition {
                     line: 9,
                     column: 38
                 })
             }
         );
+
+        // --- Embed ---
+        assert_eq!(links[7].target, "Sixth Page");
+        assert!(links[7].embed);
+    }
+
+    #[test]
+    fn test_format_page_link_styles() {
+        let root = Path::new("/vault");
+        let path = root.join("people").join("char_042.md");
+
+        assert_eq!(
+            format_page_link(&path, "Vex", root, LinkStyle::Bare),
+            "[[char_042]]"
+        );
+        assert_eq!(
+            format_page_link(&path, "Vex", root, LinkStyle::Aliased),
+            "[[char_042|Vex]]"
+        );
+        assert_eq!(
+            format_page_link(&path, "Vex", root, LinkStyle::Path),
+            "[[people/char_042]]"
+        );
+        // An alias matching the file name is left out.
+        assert_eq!(
+            format_page_link(&root.join("Vex.md"), "Vex", root, LinkStyle::Aliased),
+            "[[Vex]]"
+        );
+        assert_eq!(
+            format_page_link(&root.join("Vex.md"), "Vex", root, LinkStyle::Path),
+            "[[Vex]]"
+        );
+        assert_eq!(format_link("Vex", Some(" ")), "[[Vex]]");
     }
 }
@@ -0,0 +1,154 @@
+//! Word-count history.
+//!
+//! Keeps a small JSON record of the vault's total word count, one entry per day,
+//! in the vault's data directory so writing progress can be tracked over time.
+
+use crate::{
+    config::{MAX_WORD_COUNT_HISTORY_DAYS, VAULT_DATA_DIR_NAME},
+    error::Result,
+    writer::atomic_write,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::{instrument, warn};
+
+/// The name of the history file inside the vault's data directory.
+pub const WORD_COUNT_HISTORY_FILE: &str = "word-count-history.json";
+
+/// The vault's total word count on a given day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WordCountSnapshot {
+    /// The day of the snapshot, formatted as `YYYY-MM-DD`.
+    pub date: String,
+    /// The total number of words across all pages.
+    pub words: usize,
+    /// The change since the previous snapshot (zero for the first one).
+    #[serde(default)]
+    pub delta: i64,
+}
+
+fn history_path(vault_root: &Path) -> PathBuf {
+    vault_root
+        .join(VAULT_DATA_DIR_NAME)
+        .join(WORD_COUNT_HISTORY_FILE)
+}
+
+/// Recomputes each snapshot's `delta` from the one before it.
+fn fill_deltas(history: &mut [WordCountSnapshot]) {
+    let mut previous: Option<usize> = None;
+    for snapshot in history.iter_mut() {
+        snapshot.delta = previous.map_or(0, |p| snapshot.words as i64 - p as i64);
+        previous = Some(snapshot.words);
+    }
+}
+
+/// Loads the vault's word-count history, oldest first.
+/// Returns an empty series if nothing has been recorded yet.
+pub fn load_history(vault_root: &Path) -> Result<Vec<WordCountSnapshot>> {
+    let path = history_path(vault_root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut history: Vec<WordCountSnapshot> = serde_json::from_str(&fs::read_to_string(path)?)?;
+    fill_deltas(&mut history);
+    Ok(history)
+}
+
+/// Records the vault's total word count for `date` and returns the updated series.
+///
+/// There is at most one snapshot per day: recording again on the same day replaces
+/// that day's count. Only the most recent `MAX_WORD_COUNT_HISTORY_DAYS` snapshots
+/// are kept. An unreadable history file is replaced rather than blocking the update.
+#[instrument]
+pub fn record_snapshot(
+    vault_root: &Path,
+    date: NaiveDate,
+    words: usize,
+) -> Result<Vec<WordCountSnapshot>> {
+    let mut history = load_history(vault_root).unwrap_or_else(|e| {
+        warn!("Discarding unreadable word-count history: {}", e);
+        Vec::new()
+    });
+
+    let date = date.format("%Y-%m-%d").to_string();
+    history.retain(|snapshot| snapshot.date != date);
+    history.push(WordCountSnapshot {
+        date,
+        words,
+        delta: 0,
+    });
+    // ISO dates sort chronologically as strings.
+    history.sort_by(|a, b| a.date.cmp(&b.date));
+    if history.len() > MAX_WORD_COUNT_HISTORY_DAYS {
+        let excess = history.len() - MAX_WORD_COUNT_HISTORY_DAYS;
+        history.drain(..excess);
+    }
+    fill_deltas(&mut history);
+
+    let path = history_path(vault_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write(&path, &serde_json::to_string_pretty(&history)?)?;
+
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_record_and_load_word_count_history() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        assert!(load_history(root).unwrap().is_empty());
+
+        record_snapshot(root, day(1), 1000).unwrap();
+        record_snapshot(root, day(2), 1250).unwrap();
+        // A second snapshot on the same day replaces the first.
+        record_snapshot(root, day(2), 1400).unwrap();
+
+        let history = load_history(root).unwrap();
+        assert_eq!(
+            history,
+            vec![
+                WordCountSnapshot {
+                    date: "2024-03-01".to_string(),
+                    words: 1000,
+                    delta: 0,
+                },
+                WordCountSnapshot {
+                    date: "2024-03-02".to_string(),
+                    words: 1400,
+                    delta: 400,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_count_history_is_bounded() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let start = day(1);
+        for offset in 0..(MAX_WORD_COUNT_HISTORY_DAYS as u64 + 5) {
+            let date = start + chrono::Days::new(offset);
+            record_snapshot(root, date, offset as usize).unwrap();
+        }
+
+        let history = load_history(root).unwrap();
+        assert_eq!(history.len(), MAX_WORD_COUNT_HISTORY_DAYS);
+        assert_eq!(history[0].words, 5);
+        assert_eq!(history[0].delta, 0);
+    }
+}
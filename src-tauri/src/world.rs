@@ -12,22 +12,37 @@
 //! - Providing a unified API for Tauri commands to interact with the backend.
 
 use crate::{
-    config::{self, DEBOUNCE_INTERVAL},
+    backup::{self, Backup},
+    config::{
+        self, FileSettings, IndexSettings, RenderSettings, ValidationSettings, DEBOUNCE_INTERVAL,
+        IMAGES_DIR_NAME, MIN_BACKUP_INTERVAL_MINUTES,
+    },
     error::{ChroniclerError, Result},
     events::FileEvent,
-    importer,
+    exporter, importer,
     indexer::Indexer,
-    mediawiki_importer,
-    models::{BrokenLink, FileNode, FullPageData, PageHeader, RenderedPage},
-    renderer::Renderer,
+    link_checker, mediawiki_importer,
+    models::{
+        BrokenLink, DeadLink, FileNode, FolderStat, FullPageData, IndexReport, Page, PageHeader,
+        References, RenderedPage, SearchResult, ValidationAdvisory,
+    },
+    parser,
+    renderer::{RenderContext, Renderer},
     template,
+    utils::{self, file_stem_string, is_within_root},
+    validation,
     watcher::Watcher,
+    wikilink,
+    word_count::{self, WordCountSnapshot},
     writer::Writer,
 };
+use chrono::{DateTime, Duration, Local};
 use parking_lot::{Mutex, RwLock};
 use std::{
+    collections::{BTreeSet, HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
+    time::SystemTime,
 };
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::{sync::broadcast, time::sleep};
@@ -90,24 +105,81 @@ impl World {
             tracing::error!("Failed to update asset protocol scope: {}", e);
         }
 
-        // --- 2. Perform Initial Scan on a new Indexer instance ---
-        // This is done outside of any locks to avoid blocking other operations during the scan.
-        let mut new_indexer_instance = Indexer::new(root_path);
+        // --- 2. Scan the vault and swap in the new subsystems ---
+        let config = config::load(&app_handle).unwrap_or_else(|e| {
+            error!("Failed to load settings, using defaults: {}", e);
+            config::AppConfig::default()
+        });
+        let templates_dir = match template::get_templates_dir(&app_handle) {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                error!("Failed to locate templates directory: {}", e);
+                None
+            }
+        };
+        let event_receiver = self.load_vault(
+            root_path,
+            config.index_settings,
+            config.render_settings,
+            templates_dir,
+        )?;
+
+        // --- 3. Spawn Background Event Processing Task ---
+        // The task is given its own handle to the world's state.
+        let indexer_clone = self.indexer.clone();
+        let writer_clone = self.writer.clone();
+        let renderer_clone = self.renderer.clone();
+        // Use Tauri's async runtime instead of tokio::spawn
+        tauri::async_runtime::spawn(async move {
+            Self::process_file_events(
+                app_handle,
+                indexer_clone,
+                writer_clone,
+                renderer_clone,
+                event_receiver,
+            )
+            .await;
+        });
+
+        info!(
+            "World initialized successfully for path: {}",
+            root_path.display()
+        );
+        Ok(())
+    }
+
+    /// Scans `root_path` with the given index settings, starts a watcher on it and
+    /// replaces the indexer, writer and renderer with fresh instances for the new
+    /// vault. The renderer is given its settings and templates directory before it
+    /// is swapped in, so no page is ever rendered with the defaults (which would show
+    /// GM-only content in a player-mode vault).
+    ///
+    /// This is the part of `initialize` that does not depend on the Tauri runtime.
+    ///
+    /// # Returns
+    /// A receiver for the new watcher's file events.
+    fn load_vault(
+        &self,
+        root_path: &Path,
+        index_settings: IndexSettings,
+        render_settings: RenderSettings,
+        templates_dir: Option<PathBuf>,
+    ) -> Result<broadcast::Receiver<FileEvent>> {
+        // Scanning is done outside of any locks to avoid blocking other operations.
+        let mut new_indexer_instance = Indexer::with_settings(root_path, index_settings);
         new_indexer_instance.scan_vault(root_path)?;
 
-        // --- 3. Start File Watcher ---
         let mut new_watcher = Watcher::new();
         new_watcher.start(root_path)?;
-
-        // --- 4. Subscribe to File Events ---
         let event_receiver = new_watcher.subscribe();
 
-        // --- 5. Create File System Writer and Renderer ---
         let new_writer = Writer::new();
-        // The Renderer is created here, now that we have the vault path.
-        let new_renderer = Renderer::new(self.indexer.clone(), root_path.to_path_buf());
+        let mut new_renderer = Renderer::new(self.indexer.clone(), root_path.to_path_buf());
+        new_renderer.set_settings(render_settings);
+        if let Some(dir) = templates_dir {
+            new_renderer.set_templates_dir(dir);
+        }
 
-        // --- 6. Lock and Update Shared State ---
         // The lock scope is kept as short as possible.
         {
             // The watcher is replaced. The old watcher is dropped, automatically stopping its thread.
@@ -116,25 +188,10 @@ impl World {
             // The fully scanned indexer replaces the old one.
             *self.indexer.write() = new_indexer_instance;
             *self.writer.write() = Some(new_writer);
-            // Set the newly created renderer.
             *self.renderer.write() = Some(new_renderer);
         }
 
-        // --- 7. Spawn Background Event Processing Task ---
-        // The task is given its own handle to the world's state.
-        let indexer_clone = self.indexer.clone();
-        let writer_clone = self.writer.clone();
-        // Use Tauri's async runtime instead of tokio::spawn
-        tauri::async_runtime::spawn(async move {
-            Self::process_file_events(app_handle, indexer_clone, writer_clone, event_receiver)
-                .await;
-        });
-
-        info!(
-            "World initialized successfully for path: {}",
-            root_path.display()
-        );
-        Ok(())
+        Ok(event_receiver)
     }
 
     /// Changes the vault path, saves the configuration, and re-initializes the world.
@@ -146,6 +203,127 @@ impl World {
         self.initialize(Path::new(&path), app_handle)
     }
 
+    /// Returns the root of the vault that is currently loaded, or `None` if no vault
+    /// has been initialized yet.
+    ///
+    /// Unlike the vault path stored in the configuration file, this always reflects
+    /// the vault the backend is actually serving.
+    pub fn get_active_vault(&self) -> Option<PathBuf> {
+        self.root_path.read().clone()
+    }
+
+    /// Returns the index settings currently in effect.
+    pub fn get_index_settings(&self) -> IndexSettings {
+        self.indexer.read().settings.clone()
+    }
+
+    /// Saves the index settings and re-indexes the vault so they take effect.
+    pub fn set_index_settings(
+        &self,
+        settings: IndexSettings,
+        app_handle: &AppHandle,
+    ) -> Result<()> {
+        config::set_index_settings(settings.clone(), app_handle)?;
+        self.apply_index_settings(settings)?;
+        if let Err(e) = app_handle.emit("index-updated", ()) {
+            error!("Failed to emit index-updated event: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Compares the index against a fresh scan of the vault, to catch changes made
+    /// outside the app that the watcher missed. With `repair`, an index that
+    /// disagrees is replaced by the fresh scan.
+    pub fn verify_index(&self, repair: bool) -> Result<IndexReport> {
+        let root_path = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        // The scan is done outside of any locks, as in `apply_index_settings`.
+        let settings = self.indexer.read().settings.clone();
+        let mut fresh = Indexer::with_settings(&root_path, settings);
+        fresh.scan_vault(&root_path)?;
+
+        let mut report = self.indexer.read().compare_with(&fresh);
+        if repair && !report.is_consistent() {
+            info!(
+                "Index disagreed with the vault on disk, replacing it: {:?}",
+                report
+            );
+            *self.indexer.write() = fresh;
+            if let Some(renderer) = self.renderer.read().as_ref() {
+                renderer.clear_cache();
+            }
+            report.repaired = true;
+        }
+        Ok(report)
+    }
+
+    /// Rescans the current vault with new index settings and swaps in the result.
+    fn apply_index_settings(&self, settings: IndexSettings) -> Result<()> {
+        let Some(root_path) = self.root_path.read().clone() else {
+            self.indexer.write().settings = settings;
+            return Ok(());
+        };
+        let mut new_indexer_instance = Indexer::with_settings(&root_path, settings);
+        new_indexer_instance.scan_vault(&root_path)?;
+        *self.indexer.write() = new_indexer_instance;
+        if let Some(renderer) = self.renderer.read().as_ref() {
+            renderer.clear_cache();
+        }
+        Ok(())
+    }
+
+    /// Returns the render settings currently in effect, or the saved ones while no
+    /// vault is loaded.
+    pub fn get_render_settings(&self, app_handle: &AppHandle) -> Result<RenderSettings> {
+        match self.renderer.read().as_ref() {
+            Some(renderer) => Ok(renderer.settings().clone()),
+            None => Ok(config::load(app_handle)?.render_settings),
+        }
+    }
+
+    /// Saves the render settings and applies them to the active renderer.
+    pub fn set_render_settings(
+        &self,
+        settings: RenderSettings,
+        app_handle: &AppHandle,
+    ) -> Result<()> {
+        config::set_render_settings(settings.clone(), app_handle)?;
+        if let Some(renderer) = self.renderer.write().as_mut() {
+            renderer.set_settings(settings);
+        }
+        Ok(())
+    }
+
+    /// Saves a page template. Pages that `extends` it render differently afterwards,
+    /// so every cached render is dropped.
+    pub fn write_template(
+        &self,
+        app_handle: &AppHandle,
+        name: &str,
+        content: &str,
+    ) -> Result<PathBuf> {
+        let path = template::write_template(app_handle, name, content)?;
+        self.clear_render_cache();
+        Ok(path)
+    }
+
+    /// Deletes a page template, dropping every cached render as `write_template` does.
+    pub fn delete_template(&self, path: &Path) -> Result<()> {
+        template::delete_template(path)?;
+        self.clear_render_cache();
+        Ok(())
+    }
+
+    /// Drops every cached render, if a vault is loaded.
+    fn clear_render_cache(&self) {
+        if let Some(renderer) = self.renderer.read().as_ref() {
+            renderer.clear_cache();
+        }
+    }
+
     /// Background task that collects and processes file events from the watcher.
     ///
     /// This task implements a debouncing and batching strategy. It waits for an
@@ -153,11 +331,15 @@ impl World {
     /// other events that have occurred in rapid succession. This batch is then
     /// processed by the `Indexer` in one go, preventing repeated, expensive
     /// relationship rebuilds.
-    #[instrument(level = "debug", skip(app_handle, indexer, writer, event_receiver))]
+    #[instrument(
+        level = "debug",
+        skip(app_handle, indexer, writer, renderer, event_receiver)
+    )]
     async fn process_file_events(
         app_handle: AppHandle,
         indexer: Arc<RwLock<Indexer>>,
         writer: Arc<RwLock<Option<Writer>>>,
+        renderer: Arc<RwLock<Option<Renderer>>>,
         mut event_receiver: broadcast::Receiver<FileEvent>,
     ) {
         loop {
@@ -221,12 +403,34 @@ impl World {
                 }
 
                 // --- 3. Batch Index Update ---
-                {
+                // An edit that adds or removes a title or alias can also change how
+                // links resolve, so the link names are compared across the update.
+                let names_changed = {
                     let mut index = indexer.write();
+                    let names_before = index.link_candidates.clone();
                     index.handle_event_batch(&events_batch);
+                    index.link_candidates != names_before
+                };
+
+                // --- 4. Drop Stale Renders ---
+                // A page that was added or moved can change how links on any page
+                // resolve, so those events clear the whole cache.
+                if let Some(renderer) = renderer.read().as_ref() {
+                    if names_changed {
+                        renderer.clear_cache();
+                    } else {
+                        for event in &events_batch {
+                            match event {
+                                FileEvent::Modified(path) | FileEvent::Deleted(path) => {
+                                    renderer.invalidate(path)
+                                }
+                                _ => renderer.clear_cache(),
+                            }
+                        }
+                    }
                 }
 
-                // --- 4. Notify Frontend ---
+                // --- 5. Notify Frontend ---
                 if let Err(e) = app_handle.emit("index-updated", ()) {
                     error!("Failed to emit index-updated event: {}", e);
                 }
@@ -242,16 +446,164 @@ impl World {
         self.indexer.read().get_all_tags()
     }
 
+    /// Returns each tag with the number of pages using it, sorted alphabetically.
+    pub fn get_tag_counts(&self) -> Vec<(String, usize)> {
+        self.indexer.read().get_tag_counts()
+    }
+
+    /// Returns pairs of tags that appear together, with the number of shared pages.
+    pub fn get_tag_cooccurrence(&self) -> Vec<(String, String, usize)> {
+        self.indexer.read().get_tag_cooccurrence()
+    }
+
+    /// Returns the tags, nested on `/`, and their pages as an OPML outline.
+    pub fn export_tags_opml(&self) -> Result<String> {
+        self.indexer.read().export_tags_opml()
+    }
+
+    /// Returns the `(outbound, inbound)` link counts for every page in the vault.
+    pub fn get_link_degrees(&self) -> HashMap<PathBuf, (usize, usize)> {
+        self.indexer.read().get_link_degree_map()
+    }
+
+    /// Returns the images a page uses that don't exist on disk.
+    pub fn get_page_missing_images(&self, path: &Path) -> Vec<String> {
+        self.indexer.read().get_missing_images_for_page(path)
+    }
+
+    /// Returns every page that uses missing images, with those images.
+    pub fn get_all_missing_images(&self) -> Vec<(PageHeader, Vec<String>)> {
+        self.indexer.read().get_all_missing_images()
+    }
+
+    /// Returns every web URL in the vault with the page it's in.
+    pub fn get_external_links(&self) -> Vec<(PathBuf, String)> {
+        self.indexer.read().get_external_links()
+    }
+
+    /// Requests every web URL in the vault and returns the links that are dead.
+    pub async fn check_external_links(&self) -> Result<Vec<DeadLink>> {
+        let links = self.indexer.read().get_external_links();
+        link_checker::find_dead_links(links).await
+    }
+
+    /// Returns the pages without tags, each with whether it failed to parse.
+    pub fn get_untagged_pages(&self) -> Vec<(PageHeader, bool)> {
+        self.indexer.read().get_untagged_pages()
+    }
+
+    /// Returns every link to a page, grouped by how it refers to the page.
+    pub fn get_all_references(&self, path: &Path) -> References {
+        self.indexer.read().get_all_references(path)
+    }
+
+    /// Returns the pages that link to themselves.
+    pub fn get_self_linking_pages(&self) -> Vec<PageHeader> {
+        self.indexer.read().get_self_linking_pages()
+    }
+
+    /// Returns each page's vault-relative path mapped to a hash of its content.
+    pub fn get_manifest(&self) -> HashMap<PathBuf, String> {
+        self.indexer.read().get_manifest()
+    }
+
+    /// Returns the pages whose removal would disconnect parts of the link graph.
+    pub fn get_critical_pages(&self) -> Vec<PageHeader> {
+        self.indexer.read().get_critical_pages()
+    }
+
+    /// Returns the pages with at least `min_backlinks` backlinks, most linked first.
+    pub fn get_hub_pages(&self, min_backlinks: usize) -> Vec<(PageHeader, usize)> {
+        self.indexer
+            .read()
+            .get_pages_with_min_backlinks(min_backlinks)
+    }
+
+    /// Returns the pages modified after `since`, an RFC 3339 timestamp such as
+    /// `2024-03-05T10:00:00Z`, most recently modified first.
+    pub fn get_pages_modified_since(&self, since: &str) -> Result<Vec<PageHeader>> {
+        let since = DateTime::parse_from_rfc3339(since).map_err(|e| {
+            ChroniclerError::InvalidArgument(format!("invalid timestamp {:?}: {}", since, e))
+        })?;
+        Ok(self
+            .indexer
+            .read()
+            .get_pages_modified_since(SystemTime::from(since)))
+    }
+
+    /// Returns all pages grouped by the first letter of their title.
+    pub fn get_alphabetical_index(&self) -> Vec<(char, Vec<PageHeader>)> {
+        self.indexer.read().get_alphabetical_index()
+    }
+
+    /// Returns up to `limit` pages with their word counts, shortest or longest first.
+    pub fn get_pages_by_length(&self, ascending: bool, limit: usize) -> Vec<(PageHeader, usize)> {
+        self.indexer.read().get_pages_by_length(ascending, limit)
+    }
+
+    /// Groups pages into board columns by the value of a frontmatter field.
+    pub fn get_board(&self, field: &str) -> HashMap<String, Vec<PageHeader>> {
+        self.indexer.read().get_pages_by_status_field(field)
+    }
+
+    /// Finds the pages containing some text, optionally searching frontmatter too.
+    /// GM blocks are only searched when player mode is off.
+    pub fn search(&self, query: &str, include_frontmatter: bool) -> Vec<SearchResult> {
+        let player_mode = self
+            .renderer
+            .read()
+            .as_ref()
+            .is_some_and(|renderer| renderer.settings().player_mode);
+        self.indexer
+            .read()
+            .search(query, include_frontmatter, player_mode)
+    }
+
+    /// Finds the pages whose title or an alias fuzzily matches `query`, best first.
+    pub fn fuzzy_find_pages(&self, query: &str) -> Vec<PageHeader> {
+        self.indexer.read().fuzzy_find_pages(query)
+    }
+
+    /// Finds all pages whose vault-relative path matches a glob pattern.
+    pub fn find_pages_by_glob(&self, pattern: &str) -> Result<Vec<PageHeader>> {
+        self.indexer.read().find_pages_by_glob(pattern)
+    }
+
     /// Returns the file tree structure of the vault for frontend display.
     pub fn get_file_tree(&self) -> Result<FileNode> {
         self.indexer.read().get_file_tree()
     }
 
     /// Processes raw markdown content and returns the fully rendered page data.
-    pub fn render_page_preview(&self, content: &str) -> Result<RenderedPage> {
+    /// If the page's path is given, ambiguous links resolve to the nearest page.
+    /// Only headings up to `toc_depth` are listed in the TOC.
+    pub fn render_page_preview(
+        &self,
+        content: &str,
+        path: Option<&Path>,
+        focus: bool,
+        toc_depth: u32,
+    ) -> Result<RenderedPage> {
         // This operation does not lock the renderer, only the indexer internally for link resolution.
         if let Some(renderer) = self.renderer.read().as_ref() {
-            renderer.render_page_preview(content)
+            renderer.render_page_with_context(
+                content,
+                RenderContext {
+                    source: path,
+                    focus,
+                    toc_depth: Some(toc_depth),
+                    ..Default::default()
+                },
+            )
+        } else {
+            Err(ChroniclerError::VaultNotInitialized)
+        }
+    }
+
+    /// Renders one section of a page, for previewing a `[[Page#Section]]` link.
+    pub fn render_section_preview(&self, path: &Path, section: &str) -> Result<String> {
+        if let Some(renderer) = self.renderer.read().as_ref() {
+            renderer.render_section_preview(path, section)
         } else {
             Err(ChroniclerError::VaultNotInitialized)
         }
@@ -269,67 +621,313 @@ impl World {
     }
 
     /// Fetches and renders all data required for the main file view.
-    pub fn build_page_view(&self, path: &str) -> Result<FullPageData> {
+    pub fn build_page_view(&self, path: &str, include_local_graph: bool) -> Result<FullPageData> {
         // The renderer handles its own internal locking of the indexer.
         if let Some(renderer) = self.renderer.read().as_ref() {
-            renderer.build_page_view(path)
+            renderer.build_page_view(path, include_local_graph)
         } else {
             Err(ChroniclerError::VaultNotInitialized)
         }
     }
 
+    /// Resolves a wikilink target to the path of the page it points to, if it exists.
+    pub fn resolve_wikilink(&self, target: &str) -> Option<PathBuf> {
+        self.indexer.read().resolve_target(target)
+    }
+
+    /// Resolves a page's stable id (its `uid` or `id` frontmatter field) to its path.
+    pub fn resolve_uid(&self, uid: &str) -> Option<PathBuf> {
+        self.indexer.read().resolve_uid(uid)
+    }
+
+    /// Returns the ids claimed by more than one page, with those pages.
+    pub fn get_uid_collisions(&self) -> Vec<(String, Vec<PageHeader>)> {
+        self.indexer.read().get_uid_collisions()
+    }
+
+    /// Returns storage statistics for every folder in the vault.
+    pub fn get_folder_stats(&self) -> Result<Vec<FolderStat>> {
+        self.indexer.read().get_folder_stats()
+    }
+
+    /// Records today's total word count for the vault, replacing any snapshot
+    /// already taken today, and returns the updated history.
+    pub fn record_word_count_snapshot(&self) -> Result<Vec<WordCountSnapshot>> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let total: usize = self
+            .indexer
+            .read()
+            .pages
+            .values()
+            .map(|page| page.word_count)
+            .sum();
+        word_count::record_snapshot(&root, Local::now().date_naive(), total)
+    }
+
+    /// Returns the vault's daily word-count history, oldest first.
+    pub fn get_word_count_history(&self) -> Result<Vec<WordCountSnapshot>> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        word_count::load_history(&root)
+    }
+
     /// Returns a list of all directory paths in the vault.
     pub fn get_all_directory_paths(&self) -> Result<Vec<PathBuf>> {
         self.indexer.read().get_all_directory_paths()
     }
 
     /// Converts a relative or absolute image path to a Base64 Data URL string.
+    /// The image must be inside the vault.
     pub fn get_image_as_base64(&self, path: &str) -> Result<String> {
         if let Some(renderer) = self.renderer.read().as_ref() {
+            self.ensure_within_vault(&renderer.resolve_image_path(path))?;
             Ok(renderer.convert_image_path_to_data_url(path))
         } else {
             Err(ChroniclerError::VaultNotInitialized)
         }
     }
 
+    /// Returns `true` if the path is inside the current vault, after resolving
+    /// `..` components and symlinks. Always `false` when no vault is open.
+    pub fn is_within_vault(&self, path: &Path) -> bool {
+        self.root_path
+            .read()
+            .as_deref()
+            .is_some_and(|root| is_within_root(root, path))
+    }
+
+    /// Returns an error unless the path is inside the current vault. Commands that
+    /// accept a path from the frontend use this to avoid exposing the wider file system.
+    pub fn ensure_within_vault(&self, path: &Path) -> Result<()> {
+        if self.is_within_vault(path) {
+            Ok(())
+        } else {
+            Err(ChroniclerError::PathOutsideVault(path.to_path_buf()))
+        }
+    }
+
+    /// Checks every page's frontmatter against the configured validation rules.
+    pub fn validate_frontmatter(&self, settings: &ValidationSettings) -> Vec<ValidationAdvisory> {
+        let (mut advisories, pages) = {
+            let index = self.indexer.read();
+            let pages: Vec<PageHeader> = index
+                .pages
+                .values()
+                .map(|page| PageHeader {
+                    path: page.path.clone(),
+                    title: page.title.clone(),
+                })
+                .collect();
+            (validation::validate_pages(&index.pages, settings), pages)
+        };
+        // The files are read for duplicate keys after the index lock is released, so
+        // the watcher can keep updating the index meanwhile.
+        advisories.extend(validation::check_duplicate_keys(&pages));
+        validation::sort_advisories(&mut advisories);
+        advisories
+    }
+
     /// Returns a list of all broken links in the vault.
     pub fn get_all_broken_links(&self) -> Result<Vec<BrokenLink>> {
         self.indexer.read().get_all_broken_links()
     }
 
+    /// Returns the pages in a folder, including those in its subfolders if
+    /// `recursive` is set, sorted by title.
+    pub fn get_folder_pages(&self, folder: &Path, recursive: bool) -> Vec<PageHeader> {
+        self.indexer.read().get_pages_in_folder(folder, recursive)
+    }
+
+    /// Returns the unresolved link targets of a single page.
+    pub fn get_page_broken_links(&self, path: &Path) -> Vec<String> {
+        self.indexer.read().get_broken_links_for_page(path)
+    }
+
+    /// Returns a short plain-text excerpt of a page, from its `summary` or
+    /// `description` field or else its first paragraph, capped at `max_length`
+    /// characters. Returns `None` if the page has no text to excerpt.
+    pub fn get_page_excerpt(&self, path: &Path, max_length: usize) -> Result<Option<String>> {
+        let frontmatter = self
+            .indexer
+            .read()
+            .pages
+            .get(path)
+            .map(|page| page.frontmatter.clone())
+            .ok_or_else(|| ChroniclerError::FileNotFound(path.to_path_buf()))?;
+        let content = std::fs::read_to_string(path)?;
+        let (_, body) = parser::extract_frontmatter(&content);
+        Ok(parser::extract_excerpt(&frontmatter, body, max_length))
+    }
+
     // --- Synchronous File System Operations (from UI) ---
 
     /// Writes content to a page on disk.
     /// This method doesn't need to modify the index directly, as the file watcher
     /// will detect the change and send an event.
-    pub fn write_page_content(&self, path: &str, content: &str) -> Result<()> {
+    ///
+    /// If backups are enabled, the previous content is backed up first, unless the
+    /// page was already backed up within the last `MIN_BACKUP_INTERVAL_MINUTES`.
+    pub fn write_page_content(
+        &self,
+        path: &str,
+        content: &str,
+        settings: &FileSettings,
+    ) -> Result<()> {
         let writer = self
             .writer
             .read()
             .clone()
             .ok_or(ChroniclerError::VaultNotInitialized)?;
-        writer.write_page_content(Path::new(path), content)
+        let path = Path::new(path);
+
+        if settings.backup_before_changes {
+            let root = self
+                .root_path
+                .read()
+                .clone()
+                .ok_or(ChroniclerError::VaultNotInitialized)?;
+            let now = Local::now().naive_local();
+            let interval = Duration::minutes(MIN_BACKUP_INTERVAL_MINUTES);
+            let unchanged = std::fs::read_to_string(path).is_ok_and(|old| old == content);
+            if !unchanged && !backup::has_recent_backup(&root, path, now, interval) {
+                backup::create_backup(&root, path, now)?;
+            }
+        }
+
+        writer.write_page_content(path, content)
     }
 
-    /// Creates a new markdown file, optionally using a template.
+    /// Backs up the file or folder at `path` if backups are enabled.
+    fn backup_before_change(&self, path: &Path, settings: &FileSettings) -> Result<()> {
+        if !settings.backup_before_changes {
+            return Ok(());
+        }
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        backup::create_backups_for(&root, path, Local::now().naive_local())
+    }
+
+    /// Returns the backups of a file, newest first.
+    pub fn get_backups(&self, path: &Path) -> Result<Vec<Backup>> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        backup::list_backups(&root, path)
+    }
+
+    /// Restores a file to the content of one of its backups and synchronously updates
+    /// the index. The content being replaced is backed up first. Returns the path of
+    /// the restored file.
+    pub fn restore_backup(&self, backup_path: &Path) -> Result<PathBuf> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let restored = backup::restore_backup(&root, backup_path, Local::now().naive_local())?;
+
+        let event = if self.indexer.read().pages.contains_key(&restored) {
+            FileEvent::Modified(restored.clone())
+        } else {
+            FileEvent::Created(restored.clone())
+        };
+        self.indexer.write().handle_event_and_rebuild(&event);
+        Ok(restored)
+    }
+
+    /// Writes a "Backlinks" section into a page, listing every page that links to it.
+    /// A section written earlier is replaced rather than duplicated.
+    pub fn materialize_backlinks(&self, path: PathBuf, settings: &FileSettings) -> Result<()> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let links: Vec<String> = {
+            let index = self.indexer.read();
+            let page = index
+                .pages
+                .get(&path)
+                .ok_or_else(|| ChroniclerError::FileNotFound(path.clone()))?;
+            let mut sources: Vec<&Page> = page
+                .backlinks
+                .iter()
+                .filter(|source| **source != path)
+                .filter_map(|source| index.pages.get(source))
+                .collect();
+            sources.sort_by_key(|source| file_stem_string(&source.path).to_lowercase());
+            let mut links: Vec<String> = sources
+                .into_iter()
+                .map(|source| {
+                    wikilink::format_page_link(
+                        &source.path,
+                        &source.title,
+                        &root,
+                        settings.link_style,
+                    )
+                })
+                .collect();
+            links.dedup();
+            links
+        };
+
+        writer.write_backlinks_block(&path, &links)?;
+
+        self.indexer
+            .write()
+            .handle_event_and_rebuild(&FileEvent::Modified(path));
+
+        Ok(())
+    }
+
+    /// Creates a new markdown file, optionally using a template. The page starts
+    /// with the default tags configured for the folder it is created in.
     pub fn create_new_file(
         &self,
         parent_dir: String,
         file_name: String,
         template_path: Option<String>,
+        settings: &FileSettings,
     ) -> Result<PageHeader> {
         let writer = self
             .writer
             .read()
             .clone()
             .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
 
         // Read the template content if a path is provided.
         let template_content = template_path
             .map(|p| template::read_template(Path::new(&p)))
             .transpose()?;
 
-        let page_header = writer.create_new_file(&parent_dir, &file_name, template_content)?;
+        let default_tags = Path::new(&parent_dir)
+            .strip_prefix(&root)
+            .map_or(&[][..], |folder| settings.default_tags_for(folder));
+        let page_header =
+            writer.create_new_file(&parent_dir, &file_name, template_content, default_tags)?;
 
         // For UI actions, we call the synchronous indexer method to get immediate feedback.
         self.indexer
@@ -356,27 +954,85 @@ impl World {
         Ok(())
     }
 
+    /// Creates the vault's images folder if it doesn't exist yet and returns its path,
+    /// so images can be saved into it before they are embedded.
+    pub fn ensure_images_dir(&self) -> Result<PathBuf> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let existed = root.join(IMAGES_DIR_NAME).is_dir();
+        let images_dir = utils::ensure_images_dir(&root)?;
+
+        if !existed {
+            self.indexer
+                .write()
+                .handle_event_and_rebuild(&FileEvent::FolderCreated(images_dir.clone()));
+        }
+        Ok(images_dir)
+    }
+
+    /// Saves pasted image bytes into the vault's images folder, creating it if needed.
+    ///
+    /// # Returns
+    /// The image's path relative to the images folder, ready to embed as `![[path]]`.
+    pub fn save_image(&self, bytes: &[u8], suggested_name: &str) -> Result<PathBuf> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let images_dir = self.ensure_images_dir()?;
+
+        let path = writer.save_image(&images_dir, bytes, suggested_name)?;
+        self.indexer
+            .write()
+            .handle_event_and_rebuild(&FileEvent::Created(path.clone()));
+
+        Ok(path
+            .strip_prefix(&images_dir)
+            .map(Path::to_path_buf)
+            .unwrap_or(path))
+    }
+
     /// Renames a file or folder in-place and synchronously updates the index.
     /// Returns the new path of the renamed item.
-    pub fn rename_path(&self, path: PathBuf, new_name: String) -> Result<PathBuf> {
+    ///
+    /// If `sync_title_on_rename` is set and the renamed page has a `title` frontmatter
+    /// field, the title is updated to the new name.
+    pub fn rename_path(
+        &self,
+        path: PathBuf,
+        new_name: String,
+        settings: &FileSettings,
+    ) -> Result<PathBuf> {
         let writer = self
             .writer
             .read()
             .clone()
             .ok_or(ChroniclerError::VaultNotInitialized)?;
+        self.backup_before_change(&path, settings)?;
 
         // Get necessary info from the indexer before performing the operation.
-        let backlinks = {
+        let (backlinks, has_title) = {
             let index = self.indexer.read();
-            index
-                .pages
-                .get(&path)
-                .map(|p| p.backlinks.clone())
-                .unwrap_or_default()
+            index.pages.get(&path).map_or((HashSet::new(), false), |p| {
+                (p.backlinks.clone(), p.frontmatter.get("title").is_some())
+            })
         };
 
         let new_path = writer.rename_path(&path, &new_name, &backlinks)?;
 
+        if settings.sync_title_on_rename && has_title {
+            // The rename itself has succeeded, so a failed title update is not fatal.
+            if let Err(e) =
+                writer.set_frontmatter_field(&new_path, "title", &file_stem_string(&new_path))
+            {
+                error!("Failed to update the title of {:?}: {}", new_path, e);
+            }
+        }
+
         // After the transaction succeeds, update the indexer's in-memory state.
         self.indexer
             .write()
@@ -389,6 +1045,114 @@ impl World {
         Ok(new_path)
     }
 
+    /// Renames a frontmatter key on every page that has it, e.g. `type` to `category`,
+    /// keeping each value as written. Pages that already have `new_key` are skipped
+    /// so that nothing is overwritten. Returns the number of pages changed.
+    pub fn rename_frontmatter_key(&self, old_key: &str, new_key: &str) -> Result<usize> {
+        let (old_key, new_key) = (old_key.trim(), new_key.trim());
+        for key in [old_key, new_key] {
+            if key.is_empty() || key.contains([':', '\n']) || key.starts_with(['#', '-']) {
+                return Err(ChroniclerError::InvalidArgument(format!(
+                    "`{}` is not a valid frontmatter key",
+                    key
+                )));
+            }
+        }
+        if old_key == new_key {
+            return Ok(0);
+        }
+
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let paths: Vec<PathBuf> = self
+            .indexer
+            .read()
+            .pages
+            .values()
+            .filter(|page| page.frontmatter.get(old_key).is_some())
+            .map(|page| page.path.clone())
+            .collect();
+
+        let changed = writer.rename_frontmatter_key(&paths, old_key, new_key)?;
+
+        let events: Vec<FileEvent> = changed.iter().cloned().map(FileEvent::Modified).collect();
+        self.indexer.write().handle_event_batch(&events);
+
+        Ok(changed.len())
+    }
+
+    /// Rewrites the line endings of every page to LF (`to_lf`) or CRLF, skipping pages
+    /// that already use that style, and reindexes the changed pages. Returns the
+    /// number of pages changed.
+    pub fn normalize_line_endings(&self, to_lf: bool) -> Result<usize> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let paths: Vec<PathBuf> = self.indexer.read().pages.keys().cloned().collect();
+        let changed = writer.normalize_line_endings(&paths, to_lf)?;
+
+        let events: Vec<FileEvent> = changed.iter().cloned().map(FileEvent::Modified).collect();
+        self.indexer.write().handle_event_batch(&events);
+
+        Ok(changed.len())
+    }
+
+    /// Adds the linked page's frontmatter `title` as an alias to every bare wikilink
+    /// in the vault, e.g. `[[char_042]]` to `[[char_042|Vex]]`. Links that already
+    /// have an alias, broken links, and links to pages without a title (or whose
+    /// title is the link text) are left alone. Returns the number of pages changed.
+    pub fn prettify_links(&self) -> Result<usize> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let aliases: HashMap<PathBuf, HashMap<String, String>> = {
+            let index = self.indexer.read();
+            index
+                .pages
+                .values()
+                .filter_map(|page| {
+                    let page_aliases: HashMap<String, String> = page
+                        .links
+                        .iter()
+                        .filter(|link| link.alias.is_none())
+                        .filter_map(|link| {
+                            let target = index.resolve_link(link, &page.path)?;
+                            let title = index.pages.get(&target)?.frontmatter.get("title")?;
+                            let title = title.as_str()?.trim();
+                            // An alias can't contain brackets, and one matching the
+                            // link text adds nothing.
+                            if title.is_empty()
+                                || title.contains(['[', ']'])
+                                || title == link.target
+                            {
+                                return None;
+                            }
+                            Some((link.target.to_lowercase(), title.to_string()))
+                        })
+                        .collect();
+                    (!page_aliases.is_empty()).then(|| (page.path.clone(), page_aliases))
+                })
+                .collect()
+        };
+
+        let changed = writer.add_link_aliases(&aliases)?;
+
+        let events: Vec<FileEvent> = changed.iter().cloned().map(FileEvent::Modified).collect();
+        self.indexer.write().handle_event_batch(&events);
+
+        Ok(changed.len())
+    }
+
     /// Moves a file or folder to a new directory, updating links and the index.
     /// Returns the new path of the moved item.
     pub fn move_path(&self, source_path: PathBuf, dest_dir: PathBuf) -> Result<PathBuf> {
@@ -423,13 +1187,99 @@ impl World {
         Ok(new_path)
     }
 
+    /// Moves every page with the given tag into `dest_dir`, updating links and the
+    /// index. Pages already in `dest_dir` stay where they are, and a page whose name
+    /// is taken there gets a numbered name. Returns the new paths of the moved pages.
+    pub fn move_tagged_to_folder(&self, tag: &str, dest_dir: PathBuf) -> Result<Vec<PathBuf>> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        self.ensure_within_vault(&dest_dir)?;
+
+        let pages: Vec<(PathBuf, HashSet<PathBuf>)> = {
+            let index = self.indexer.read();
+            let key = tag.trim().trim_start_matches('#').to_lowercase();
+            let mut pages: Vec<(PathBuf, HashSet<PathBuf>)> = index
+                .tags
+                .get(&key)
+                .into_iter()
+                .flatten()
+                .filter(|path| path.parent() != Some(dest_dir.as_path()))
+                .filter_map(|path| index.pages.get(path))
+                .map(|page| (page.path.clone(), page.backlinks.clone()))
+                .collect();
+            pages.sort_by(|(a, _), (b, _)| a.cmp(b));
+            pages
+        };
+        if pages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let moves = writer.move_pages(&pages, &dest_dir)?;
+
+        // The pages linking to the moved ones may have had their links renamed.
+        let linking: BTreeSet<&PathBuf> = pages
+            .iter()
+            .flat_map(|(_, backlinks)| backlinks)
+            .filter(|path| !moves.iter().any(|(from, _)| from == *path))
+            .collect();
+        let events: Vec<FileEvent> = moves
+            .iter()
+            .map(|(from, to)| FileEvent::Renamed {
+                from: from.clone(),
+                to: to.clone(),
+            })
+            .chain(linking.into_iter().cloned().map(FileEvent::Modified))
+            .collect();
+        self.indexer.write().handle_event_batch(&events);
+
+        Ok(moves.into_iter().map(|(_, to)| to).collect())
+    }
+
+    /// Renames and relocates a file or folder in one operation, updating links and
+    /// the index. The destination must be inside the vault and must not exist yet.
+    /// Returns the new path of the item.
+    pub fn rename_and_move(&self, path: PathBuf, new_full_path: PathBuf) -> Result<PathBuf> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        self.ensure_within_vault(&path)?;
+        self.ensure_within_vault(&new_full_path)?;
+
+        let backlinks = {
+            let index = self.indexer.read();
+            index
+                .pages
+                .get(&path)
+                .map(|p| p.backlinks.clone())
+                .unwrap_or_default()
+        };
+
+        let new_path = writer.rename_and_move(&path, &new_full_path, &backlinks)?;
+
+        self.indexer
+            .write()
+            .handle_event_and_rebuild(&FileEvent::Renamed {
+                from: path,
+                to: new_path.clone(),
+            });
+
+        Ok(new_path)
+    }
+
     /// Deletes a file or folder and synchronously updates the index.
-    pub fn delete_path(&self, path: PathBuf) -> Result<()> {
+    /// If backups are enabled, the deleted pages are backed up first.
+    pub fn delete_path(&self, path: PathBuf, settings: &FileSettings) -> Result<()> {
         let writer = self
             .writer
             .read()
             .clone()
             .ok_or(ChroniclerError::VaultNotInitialized)?;
+        self.backup_before_change(&path, settings)?;
 
         writer.delete_path(&path)?;
 
@@ -442,6 +1292,102 @@ impl World {
         Ok(())
     }
 
+    /// Merges the page at `source` into the page at `target`, redirects every link to
+    /// the source to the target, deletes the source, and synchronously updates the
+    /// index. Both pages are backed up first if `backup_before_changes` is enabled.
+    ///
+    /// The target's frontmatter wins: fields only the source has are added, and the
+    /// two pages' tags are combined. The source's body is appended to the target's.
+    pub fn merge_pages(
+        &self,
+        source: PathBuf,
+        target: PathBuf,
+        settings: &FileSettings,
+    ) -> Result<()> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let backlinks = {
+            let index = self.indexer.read();
+            if !index.pages.contains_key(&target) {
+                return Err(ChroniclerError::FileNotFound(target));
+            }
+            index
+                .pages
+                .get(&source)
+                .map(|page| page.backlinks.clone())
+                .ok_or_else(|| ChroniclerError::FileNotFound(source.clone()))?
+        };
+
+        self.backup_before_change(&source, settings)?;
+        self.backup_before_change(&target, settings)?;
+
+        let changed = writer.merge_pages(&source, &target, &backlinks)?;
+
+        let mut events = vec![FileEvent::Deleted(source)];
+        events.extend(changed.into_iter().map(FileEvent::Modified));
+        self.indexer.write().handle_event_batch(&events);
+        Ok(())
+    }
+
+    /// Renames an image and rewrites every embed and frontmatter reference to it,
+    /// then updates the index. Returns the image's new path.
+    pub fn rename_image(
+        &self,
+        path: PathBuf,
+        new_name: &str,
+        settings: &FileSettings,
+    ) -> Result<PathBuf> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let pages = self.indexer.read().get_pages_using_image(&path);
+        for page in &pages {
+            self.backup_before_change(page, settings)?;
+        }
+
+        let (new_path, changed) = writer.rename_image(&root, &path, new_name, &pages)?;
+
+        let mut events = vec![FileEvent::Renamed {
+            from: path,
+            to: new_path.clone(),
+        }];
+        events.extend(changed.into_iter().map(FileEvent::Modified));
+        self.indexer.write().handle_event_batch(&events);
+        Ok(new_path)
+    }
+
+    /// Formats a wikilink to the page at `path` in the configured link style.
+    pub fn format_page_link(&self, path: &Path, settings: &FileSettings) -> Result<String> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let index = self.indexer.read();
+        let page = index
+            .pages
+            .get(path)
+            .ok_or_else(|| ChroniclerError::FileNotFound(path.to_path_buf()))?;
+        Ok(wikilink::format_page_link(
+            path,
+            &page.title,
+            &root,
+            settings.link_style,
+        ))
+    }
+
     /// Duplicates a page and synchronously updates the index.
     pub fn duplicate_page(&self, path: String) -> Result<PageHeader> {
         let writer = self
@@ -460,6 +1406,79 @@ impl World {
         Ok(new_page_header)
     }
 
+    /// Splits a page into one new page per heading of the given level, replacing
+    /// each section in the original with a link to its new page.
+    /// Returns the paths of the newly created pages.
+    pub fn split_page_by_heading(&self, path: PathBuf, level: u8) -> Result<Vec<PathBuf>> {
+        let writer = self
+            .writer
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+
+        let created = writer.split_page_by_heading(&path, level)?;
+
+        let mut indexer = self.indexer.write();
+        for new_path in &created {
+            indexer.handle_event_and_rebuild(&FileEvent::Created(new_path.clone()));
+        }
+        if !created.is_empty() {
+            indexer.handle_event_and_rebuild(&FileEvent::Modified(path));
+        }
+
+        Ok(created)
+    }
+
+    // --- Export Operations ---
+
+    /// Exports every page in the vault, except those matched by the vault's
+    /// `.exportignore` file, as HTML into `output_dir`.
+    /// Returns the paths of the written files.
+    pub fn export_static_site(&self, output_dir: PathBuf) -> Result<Vec<PathBuf>> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let pages: Vec<PathBuf> = self.indexer.read().pages.keys().cloned().collect();
+
+        // The renderer takes its own read locks on the indexer while rendering.
+        let renderer = self.renderer.read();
+        let renderer = renderer
+            .as_ref()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        exporter::export_static_site(renderer, &root, &pages, &output_dir)
+    }
+
+    /// Copies every page with the given tag, and the images they use, into
+    /// `output_dir` as Markdown. Links to pages outside the exported set are reduced
+    /// to plain text. Emits `export-progress` events as pages are copied.
+    pub fn export_tagged(
+        &self,
+        tag: &str,
+        output_dir: PathBuf,
+        app_handle: &AppHandle,
+    ) -> Result<Vec<PathBuf>> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        let index = self.indexer.read();
+        let key = tag.trim().trim_start_matches('#').to_lowercase();
+        let pages: Vec<PathBuf> = index
+            .tags
+            .get(&key)
+            .map(|pages| pages.iter().cloned().collect())
+            .unwrap_or_default();
+
+        exporter::export_pages(&index, &root, &pages, &output_dir, |progress| {
+            if let Err(e) = app_handle.emit(exporter::EXPORT_PROGRESS_EVENT, progress) {
+                error!("Failed to emit export progress: {}", e);
+            }
+        })
+    }
+
     // --- Document Import Operations ---
 
     /// Converts individual docx files and adds them to the vault, then updates the index.
@@ -486,6 +1505,33 @@ impl World {
         Ok(converted_paths)
     }
 
+    /// Imports plain-text files into `dest_dir` (which must be inside the vault) as
+    /// Markdown pages, then updates the index.
+    pub fn import_text_files(
+        &self,
+        txt_paths: Vec<PathBuf>,
+        dest_dir: PathBuf,
+    ) -> Result<importer::TextImportResult> {
+        let root = self
+            .root_path
+            .read()
+            .clone()
+            .ok_or(ChroniclerError::VaultNotInitialized)?;
+        if !dest_dir.starts_with(&root) || !dest_dir.is_dir() {
+            return Err(ChroniclerError::InvalidPath(dest_dir));
+        }
+
+        let result = importer::import_text_files(&txt_paths, &dest_dir)?;
+
+        let mut indexer = self.indexer.write();
+        for path in &result.imported {
+            indexer.update_file(path);
+        }
+        indexer.rebuild_relations();
+
+        Ok(result)
+    }
+
     /// Scans a directory for .docx files, imports them, and updates the index.
     ///
     /// This method acts as a coordinator. It determines the output directory,
@@ -523,6 +1569,13 @@ impl World {
         Ok(converted_paths)
     }
 
+    /// Converts a pasted wikitext snippet to Markdown using the MediaWiki import
+    /// pipeline, without touching the vault.
+    pub fn convert_wikitext(&self, app_handle: &AppHandle, text: &str) -> Result<String> {
+        let pandoc_exe = importer::get_pandoc_executable_path(app_handle)?;
+        mediawiki_importer::convert_wikitext(text, &pandoc_exe)
+    }
+
     /// Imports a MediaWiki XML dump, converting pages to Markdown.
     pub async fn import_mediawiki_dump(
         &self,
@@ -561,3 +1614,681 @@ impl Default for World {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FolderTags;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_active_vault_reflects_loaded_root() {
+        let world = World::new();
+        assert_eq!(world.get_active_vault(), None);
+
+        // `change_vault` persists the path and then loads the vault; only the
+        // loading step decides which vault is active.
+        let first = tempdir().unwrap();
+        let second = tempdir().unwrap();
+        world
+            .load_vault(
+                first.path(),
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(world.get_active_vault(), Some(first.path().to_path_buf()));
+
+        world
+            .load_vault(
+                second.path(),
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(world.get_active_vault(), Some(second.path().to_path_buf()));
+
+        // A vault that fails to load leaves the previous one active.
+        assert!(world
+            .load_vault(
+                &second.path().join("missing"),
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None
+            )
+            .is_err());
+        assert_eq!(world.get_active_vault(), Some(second.path().to_path_buf()));
+    }
+
+    #[test]
+    fn test_loaded_renderer_has_the_render_settings() {
+        let world = World::new();
+        let vault = tempdir().unwrap();
+        let settings = RenderSettings {
+            player_mode: true,
+            ..Default::default()
+        };
+        world
+            .load_vault(vault.path(), IndexSettings::default(), settings, None)
+            .unwrap();
+
+        // The renderer is never published with the default (GM) settings.
+        assert!(world
+            .renderer
+            .read()
+            .as_ref()
+            .is_some_and(|renderer| renderer.settings().player_mode));
+    }
+
+    #[test]
+    fn test_rename_and_move_updates_backlinks() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        fs::create_dir_all(root.join("Inbox")).unwrap();
+        fs::create_dir_all(root.join("People")).unwrap();
+        let note_path = root.join("Inbox").join("Untitled.md");
+        let linker_path = root.join("Linker.md");
+        fs::write(&note_path, "A note.").unwrap();
+        fs::write(
+            &linker_path,
+            "See [[Untitled]] and [[Untitled#Past|her past]].",
+        )
+        .unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        // Destinations outside the vault or already taken are rejected.
+        let outside = tempdir().unwrap();
+        assert!(world
+            .rename_and_move(note_path.clone(), outside.path().join("Mira.md"))
+            .is_err());
+        assert!(world
+            .rename_and_move(note_path.clone(), linker_path.clone())
+            .is_err());
+        assert!(note_path.exists());
+
+        let new_path = world
+            .rename_and_move(note_path.clone(), root.join("People").join("Mira.md"))
+            .unwrap();
+
+        assert_eq!(new_path, root.join("People").join("Mira.md"));
+        assert!(new_path.exists());
+        assert!(!note_path.exists());
+        assert_eq!(
+            fs::read_to_string(&linker_path).unwrap(),
+            "See [[Mira]] and [[Mira#Past|her past]]."
+        );
+        assert!(world.indexer.read().pages.contains_key(&new_path));
+    }
+
+    #[test]
+    fn test_ensure_images_dir() {
+        let vault = tempdir().unwrap();
+        let world = World::new();
+        assert!(world.ensure_images_dir().is_err());
+        world
+            .load_vault(
+                vault.path(),
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        let images_dir = vault.path().join(IMAGES_DIR_NAME);
+        assert!(!images_dir.exists());
+        assert_eq!(world.ensure_images_dir().unwrap(), images_dir);
+        assert!(images_dir.is_dir());
+
+        // A second call leaves the existing folder and its contents alone.
+        fs::write(images_dir.join("map.png"), [0u8; 4]).unwrap();
+        assert_eq!(world.ensure_images_dir().unwrap(), images_dir);
+        assert!(images_dir.join("map.png").exists());
+    }
+
+    #[test]
+    fn test_save_image() {
+        // The smallest valid PNG: the signature followed by an IHDR chunk header.
+        const PNG_BYTES: &[u8] = &[
+            0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, b'I', b'H',
+            b'D', b'R',
+        ];
+        let vault = tempdir().unwrap();
+        let world = World::new();
+        world
+            .load_vault(
+                vault.path(),
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        // The extension comes from the detected format, not the suggested name.
+        let relative = world.save_image(PNG_BYTES, "city map.jpg").unwrap();
+        assert_eq!(relative, PathBuf::from("city_map.png"));
+        let saved = vault.path().join(IMAGES_DIR_NAME).join("city_map.png");
+        assert_eq!(fs::read(&saved).unwrap(), PNG_BYTES);
+
+        // Existing images are never overwritten.
+        let relative = world.save_image(PNG_BYTES, "city map.png").unwrap();
+        assert_eq!(relative, PathBuf::from("city_map_2.png"));
+
+        assert!(world.save_image(b"not an image", "notes.png").is_err());
+    }
+
+    #[test]
+    fn test_rename_syncs_title_when_enabled() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let page_path = root.join("Old Name.md");
+        fs::write(
+            &page_path,
+            "---\ntitle: Old Name\ntags: [npc]\n---\nBody text.",
+        )
+        .unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        let settings = FileSettings {
+            sync_title_on_rename: true,
+            ..Default::default()
+        };
+        let new_path = world
+            .rename_path(page_path, "New Name".to_string(), &settings)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&new_path).unwrap(),
+            "---\ntitle: New Name\ntags: [npc]\n---\nBody text."
+        );
+        assert_eq!(world.indexer.read().pages[&new_path].title, "New Name");
+
+        // With syncing off, the title is left alone.
+        let newer_path = world
+            .rename_path(new_path, "Newer Name".to_string(), &FileSettings::default())
+            .unwrap();
+        assert_eq!(world.indexer.read().pages[&newer_path].title, "New Name");
+    }
+
+    #[test]
+    fn test_backup_on_overwrite_and_restore() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let page_path = root.join("Vex.md");
+        fs::write(&page_path, "---\ntitle: Vex\n---\nThe original text.").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+        let path = page_path.to_str().unwrap();
+
+        // Without the setting, nothing is backed up.
+        world
+            .write_page_content(path, "First edit.", &FileSettings::default())
+            .unwrap();
+        assert!(world.get_backups(&page_path).unwrap().is_empty());
+
+        let settings = FileSettings {
+            backup_before_changes: true,
+            ..Default::default()
+        };
+        world
+            .write_page_content(path, "Second edit.", &settings)
+            .unwrap();
+        // A save soon after reuses the backup just taken.
+        world
+            .write_page_content(path, "Third edit.", &settings)
+            .unwrap();
+
+        let backups = world.get_backups(&page_path).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), "First edit.");
+
+        let restored = world.restore_backup(&backups[0].path).unwrap();
+        assert_eq!(restored, page_path);
+        assert_eq!(fs::read_to_string(&page_path).unwrap(), "First edit.");
+
+        // The content replaced by the restore was kept as well.
+        let backups = world.get_backups(&page_path).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), "Third edit.");
+    }
+
+    #[test]
+    fn test_prettify_links() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        fs::write(root.join("char_042.md"), "---\ntitle: Vex\n---\nA rogue.").unwrap();
+        fs::write(root.join("Harbor.md"), "No title here.").unwrap();
+        let tavern = root.join("Tavern.md");
+        fs::write(
+            &tavern,
+            "[[char_042]] drinks with [[char_042#Past|the rogue]] at [[Harbor]], \
+             far from [[Nowhere]]. See [[Char_042#Past]].\n![[char_042]]",
+        )
+        .unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(world.prettify_links().unwrap(), 1);
+        assert_eq!(
+            fs::read_to_string(&tavern).unwrap(),
+            "[[char_042|Vex]] drinks with [[char_042#Past|the rogue]] at [[Harbor]], \
+             far from [[Nowhere]]. See [[Char_042#Past|Vex]].\n![[char_042]]"
+        );
+        assert_eq!(
+            world.indexer.read().pages[&tavern].links[0]
+                .alias
+                .as_deref(),
+            Some("Vex")
+        );
+
+        // Running it again changes nothing.
+        assert_eq!(world.prettify_links().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_merge_pages() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let source = root.join("Vex the Bold.md");
+        let target = root.join("Vex.md");
+        let tavern = root.join("Tavern.md");
+        fs::write(
+            &source,
+            "---\ntitle: Vex the Bold\nstatus: alive\ntags: [npc, villain]\n---\n## Past\nShe fled [[Vex]]'s home.",
+        )
+        .unwrap();
+        fs::write(&target, "---\ntitle: Vex\ntags: [npc]\n---\nA rogue.").unwrap();
+        fs::write(
+            &tavern,
+            "[[Vex the Bold]] drinks with [[vex the bold#Past|her past]] and [[Vex]].",
+        )
+        .unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        world
+            .merge_pages(source.clone(), target.clone(), &FileSettings::default())
+            .unwrap();
+
+        assert!(!source.exists());
+        assert_eq!(
+            fs::read_to_string(&tavern).unwrap(),
+            "[[Vex]] drinks with [[Vex#Past|her past]] and [[Vex]]."
+        );
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "---\ntitle: Vex\ntags:\n- npc\n- villain\nstatus: alive\n---\nA rogue.\n\n## Past\nShe fled [[Vex]]'s home.\n"
+        );
+
+        let index = world.indexer.read();
+        assert!(!index.pages.contains_key(&source));
+        assert!(index.pages[&target].backlinks.contains(&tavern));
+        assert!(index.pages[&target].tags.contains("villain"));
+
+        // A page can't be merged into itself.
+        assert!(world
+            .merge_pages(target.clone(), target, &FileSettings::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_rename_image() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let images = root.join(IMAGES_DIR_NAME);
+        fs::create_dir(&images).unwrap();
+        let old_image = images.join("vex.png");
+        fs::write(&old_image, "png").unwrap();
+        let vex = root.join("Vex.md");
+        let tavern = root.join("Tavern.md");
+        fs::write(&vex, "---\nimage: vex.png\n---\nA rogue.").unwrap();
+        fs::write(&tavern, "Vex drinks here.\n\n![[vex.png|Vex]]").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        let new_image = world
+            .rename_image(old_image.clone(), "vex-portrait", &FileSettings::default())
+            .unwrap();
+
+        assert_eq!(new_image, images.join("vex-portrait.png"));
+        assert!(new_image.is_file());
+        assert!(!old_image.exists());
+        assert_eq!(
+            fs::read_to_string(&vex).unwrap(),
+            "---\nimage: vex-portrait.png\n---\nA rogue."
+        );
+        assert_eq!(
+            fs::read_to_string(&tavern).unwrap(),
+            "Vex drinks here.\n\n![[vex-portrait.png|Vex]]"
+        );
+        assert_eq!(
+            world.indexer.read().get_pages_using_image(&new_image),
+            vec![tavern, vex]
+        );
+
+        // Pages can't be renamed this way.
+        assert!(world
+            .rename_image(root.join("Vex.md"), "Vex2", &FileSettings::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_normalize_line_endings() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let crlf = root.join("Vex.md");
+        let lf = root.join("Mara.md");
+        let windows_content =
+            "---\r\ntitle: Vex\r\ntags: [rogue]\r\n---\r\nA rogue.\r\n\r\nSee [[Mara]].\r\n";
+        fs::write(&crlf, windows_content).unwrap();
+        fs::write(&lf, "A fence.\n").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(world.normalize_line_endings(true).unwrap(), 1);
+        let converted = fs::read_to_string(&crlf).unwrap();
+        assert!(!converted.contains('\r'));
+        assert_eq!(converted, windows_content.replace("\r\n", "\n"));
+        assert!(world.indexer.read().pages[&crlf].tags.contains("rogue"));
+
+        // Running it again changes nothing.
+        assert_eq!(world.normalize_line_endings(true).unwrap(), 0);
+
+        assert_eq!(world.normalize_line_endings(false).unwrap(), 2);
+        assert_eq!(fs::read_to_string(&crlf).unwrap(), windows_content);
+        assert_eq!(fs::read_to_string(&lf).unwrap(), "A fence.\r\n");
+        // The converted page keeps its frontmatter in the index.
+        let index = world.indexer.read();
+        assert_eq!(index.pages[&crlf].title, "Vex");
+        assert!(index.pages[&crlf].tags.contains("rogue"));
+    }
+
+    #[test]
+    fn test_rename_frontmatter_key_across_pages() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let vex = root.join("Vex.md");
+        let harbor = root.join("Harbor.md");
+        let clash = root.join("Clash.md");
+        let plain = root.join("Plain.md");
+        fs::write(&vex, "---\ntype: [npc, villain]\n---\nA rogue.").unwrap();
+        fs::write(&harbor, "---\ntitle: Harbor\ntype: location # port\n---\n").unwrap();
+        fs::write(&clash, "---\ntype: npc\ncategory: ally\n---\n").unwrap();
+        fs::write(&plain, "No frontmatter here.").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(world.rename_frontmatter_key("type", "category").unwrap(), 2);
+        assert_eq!(
+            fs::read_to_string(&vex).unwrap(),
+            "---\ncategory: [npc, villain]\n---\nA rogue."
+        );
+        assert_eq!(
+            fs::read_to_string(&harbor).unwrap(),
+            "---\ntitle: Harbor\ncategory: location # port\n---\n"
+        );
+        // An existing `category` is not overwritten, and other pages are untouched.
+        assert_eq!(
+            fs::read_to_string(&clash).unwrap(),
+            "---\ntype: npc\ncategory: ally\n---\n"
+        );
+        assert_eq!(fs::read_to_string(&plain).unwrap(), "No frontmatter here.");
+
+        let index = world.indexer.read();
+        assert_eq!(index.pages[&vex].frontmatter["category"][1], "villain");
+        assert!(index.pages[&vex].frontmatter.get("type").is_none());
+        drop(index);
+
+        assert!(world.rename_frontmatter_key("type", "bad: key").is_err());
+    }
+
+    #[test]
+    fn test_create_new_file_adds_folder_default_tags() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let villains = root.join("characters").join("villains");
+        fs::create_dir_all(&villains).unwrap();
+        let template = root.join("Template.md");
+        fs::write(&template, "---\ntitle: {{title}}\ntags: [npc]\n---\n").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+        let settings = FileSettings {
+            folder_tags: vec![
+                FolderTags {
+                    folder: "characters".to_string(),
+                    tags: vec!["character".to_string()],
+                },
+                FolderTags {
+                    folder: "characters/villains/".to_string(),
+                    tags: vec!["villain".to_string()],
+                },
+            ],
+            ..FileSettings::default()
+        };
+
+        let vex = world
+            .create_new_file(
+                root.join("characters").to_string_lossy().into_owned(),
+                "Vex".to_string(),
+                None,
+                &settings,
+            )
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&vex.path).unwrap(),
+            "---\ntitle: Vex\ntags:\n- character\n---\n\n"
+        );
+
+        // The deepest configured folder wins, and a template keeps its own tags.
+        let mara = world
+            .create_new_file(
+                villains.to_string_lossy().into_owned(),
+                "Mara".to_string(),
+                Some(template.to_string_lossy().into_owned()),
+                &settings,
+            )
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&mara.path).unwrap(),
+            "---\ntitle: Mara\ntags:\n- npc\n- villain\n---\n"
+        );
+
+        // Pages created elsewhere keep the default content.
+        let tavern = world
+            .create_new_file(
+                root.to_string_lossy().into_owned(),
+                "Tavern".to_string(),
+                None,
+                &settings,
+            )
+            .unwrap();
+        assert!(fs::read_to_string(&tavern.path)
+            .unwrap()
+            .contains("tags: [add, your, tags]"));
+
+        let index = world.indexer.read();
+        assert!(index.pages[&vex.path].tags.contains("character"));
+        let villain_tags = &index.pages[&mara.path].tags;
+        assert!(villain_tags.contains("villain") && villain_tags.contains("npc"));
+        assert!(!villain_tags.contains("character"));
+    }
+
+    #[test]
+    fn test_verify_index_detects_and_repairs_drift() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        fs::write(root.join("Vex.md"), "Lives in [[Harbor]].").unwrap();
+        fs::write(root.join("Harbor.md"), "A port.").unwrap();
+        fs::write(root.join("Mara.md"), "A fence.").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+        assert!(world.verify_index(false).unwrap().is_consistent());
+
+        // Changes the watcher never reported.
+        fs::remove_file(root.join("Mara.md")).unwrap();
+        fs::write(root.join("Bran.md"), "A smith.").unwrap();
+        fs::write(root.join("Vex.md"), "Left for [[Bran]].").unwrap();
+
+        let report = world.verify_index(false).unwrap();
+        assert_eq!(
+            report,
+            IndexReport {
+                missing_on_disk: vec![PathBuf::from("Mara.md")],
+                missing_from_index: vec![PathBuf::from("Bran.md")],
+                outdated: vec![PathBuf::from("Vex.md")],
+                link_mismatches: vec![PathBuf::from("Harbor.md"), PathBuf::from("Vex.md")],
+                repaired: false,
+            }
+        );
+        // Without `repair`, the index is left as it was.
+        assert!(world
+            .indexer
+            .read()
+            .pages
+            .contains_key(&root.join("Mara.md")));
+
+        assert!(world.verify_index(true).unwrap().repaired);
+        assert!(world.verify_index(false).unwrap().is_consistent());
+        assert!(world.indexer.read().pages[&root.join("Bran.md")]
+            .backlinks
+            .contains(&root.join("Vex.md")));
+    }
+
+    #[test]
+    fn test_move_tagged_to_folder() {
+        let vault = tempdir().unwrap();
+        let root = vault.path();
+        let characters = root.join("characters");
+        let old = root.join("old");
+        fs::create_dir_all(&characters).unwrap();
+        fs::create_dir_all(&old).unwrap();
+        fs::write(characters.join("Vex.md"), "A different Vex.").unwrap();
+        fs::write(
+            characters.join("Bran.md"),
+            "---\ntags: [npc]\n---\nA smith.",
+        )
+        .unwrap();
+        fs::write(root.join("Mara.md"), "---\ntags: [npc]\n---\nA fence.").unwrap();
+        fs::write(old.join("Vex.md"), "---\ntags: [NPC]\n---\nA rogue.").unwrap();
+        let tavern = old.join("Tavern.md");
+        fs::write(&tavern, "[[Vex|The rogue]] drinks with [[Mara]].").unwrap();
+
+        let world = World::new();
+        world
+            .load_vault(
+                root,
+                IndexSettings::default(),
+                RenderSettings::default(),
+                None,
+            )
+            .unwrap();
+
+        let moved = world
+            .move_tagged_to_folder("#npc", characters.clone())
+            .unwrap();
+
+        let new_vex = characters.join("Vex 2.md");
+        assert_eq!(moved, vec![characters.join("Mara.md"), new_vex.clone()]);
+        assert!(!root.join("Mara.md").exists() && !old.join("Vex.md").exists());
+        assert_eq!(
+            fs::read_to_string(characters.join("Vex.md")).unwrap(),
+            "A different Vex."
+        );
+        assert!(characters.join("Bran.md").is_file());
+        assert_eq!(
+            fs::read_to_string(&tavern).unwrap(),
+            "[[Vex 2|The rogue]] drinks with [[Mara]]."
+        );
+
+        let index = world.indexer.read();
+        assert!(!index.pages.contains_key(&old.join("Vex.md")));
+        assert!(index.pages[&new_vex].backlinks.contains(&tavern));
+        assert!(index.pages[&characters.join("Mara.md")]
+            .backlinks
+            .contains(&tavern));
+        assert_eq!(index.tags["npc"].len(), 3);
+    }
+}
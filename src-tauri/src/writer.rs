@@ -5,14 +5,16 @@
 //! deleting files and folders, ensuring data integrity through atomic writes.
 
 use crate::{
+    config::DEFAULT_TAG_KEY,
     error::{ChroniclerError, Result},
     models::PageHeader,
-    utils::{file_stem_string, is_markdown_file},
-    wikilink::WIKILINK_RE,
+    parser,
+    utils::{file_stem_string, is_image_file, is_markdown_file, resolve_image_path},
+    wikilink::{WIKILINK_IMAGE_RE, WIKILINK_RE},
 };
 use regex::Captures;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs,
     io::Write,
     path::{Path, PathBuf},
@@ -20,8 +22,13 @@ use std::{
 use tempfile::NamedTempFile;
 use tracing::{error, instrument, warn};
 
-/// Represents a required change to a single backlink file, including its original content for rollback.
-struct BacklinkUpdate {
+/// Opens the generated backlinks block written by [`Writer::write_backlinks_block`].
+pub const BACKLINKS_BLOCK_START: &str = "<!-- backlinks -->";
+/// Closes the generated backlinks block.
+pub const BACKLINKS_BLOCK_END: &str = "<!-- /backlinks -->";
+
+/// Represents a required change to a single file, including its original content for rollback.
+struct FileUpdate {
     path: PathBuf,
     old_content: String,
     new_content: String,
@@ -62,28 +69,45 @@ pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
 ///
 /// This function is a core part of the rename transaction. It processes the
 /// content of a file, finds all wikilinks pointing to `old_stem`, and replaces
-/// them with `new_stem`, preserving any sections or aliases.
+/// them with `new_stem`, preserving any sections or aliases. Links written with a
+/// leading path, like `[[people/old]]`, keep the path.
 ///
 /// # Returns
 /// - `Some(String)` if the content was changed.
 /// - `None` if no links needed to be updated.
 fn replace_wikilink_in_content(content: &str, old_stem: &str, new_stem: &str) -> Option<String> {
-    let old_stem_lower = old_stem.to_lowercase();
+    let renames = HashMap::from([(old_stem.to_lowercase(), new_stem.to_string())]);
+    replace_wikilinks_in_content(content, &renames)
+}
 
+/// Like `replace_wikilink_in_content`, but replaces the links to several pages in
+/// one pass. `renames` maps each lowercase old stem to its new stem. Since every
+/// link is rewritten at most once, a new stem may be another page's old stem.
+fn replace_wikilinks_in_content(
+    content: &str,
+    renames: &HashMap<String, String>,
+) -> Option<String> {
     // Use `replace_all` to build a new string with updated wikilinks.
     let new_content = WIKILINK_RE.replace_all(content, |caps: &Captures| {
         let target = caps.get(1).map_or("", |m| m.as_str());
+        let (prefix, name) = match target.rfind('/') {
+            Some(i) => target.split_at(i + 1),
+            None => ("", target),
+        };
         // Perform a case-insensitive comparison on the link target.
-        if target.to_lowercase() == old_stem_lower {
-            let section = caps.get(2).map_or("", |m| m.as_str());
+        if let Some(new_stem) = renames.get(&name.to_lowercase()) {
+            // The section capture excludes the `#`, so it is re-added here.
+            let section = caps
+                .get(2)
+                .map_or(String::new(), |m| format!("#{}", m.as_str()));
 
             // Check if an alias exists.
             if let Some(alias_match) = caps.get(3) {
                 // An alias is present, so include it with the pipe.
-                format!("[[{new_stem}{section}|{}]]", alias_match.as_str())
+                format!("[[{prefix}{new_stem}{section}|{}]]", alias_match.as_str())
             } else {
                 // No alias was present, so don't add a pipe.
-                format!("[[{new_stem}{section}]]")
+                format!("[[{prefix}{new_stem}{section}]]")
             }
         } else {
             // If the link doesn't match, return the original text of the match.
@@ -99,6 +123,114 @@ fn replace_wikilink_in_content(content: &str, old_stem: &str, new_stem: &str) ->
     }
 }
 
+/// Returns `content` with an alias added to each bare wikilink whose target is a
+/// key of `aliases`, e.g. `[[char_042]]` to `[[char_042|Vex]]`, or `None` if no
+/// link changed. Keys are lowercase page names. Links that already have an alias
+/// are left alone, as are embeds like `![[char_042]]`.
+fn add_link_aliases_in(content: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    let new_content = WIKILINK_RE.replace_all(content, |caps: &Captures| {
+        let whole = caps.get(0).unwrap();
+        let full_match = whole.as_str();
+        if caps.get(3).is_some() || content[..whole.start()].ends_with('!') {
+            return full_match.to_string();
+        }
+        let target = caps.get(1).map_or("", |m| m.as_str());
+        let key = target.replace('\\', "").trim().to_lowercase();
+        match aliases.get(&key) {
+            // The closing brackets are replaced so the rest of the link is kept as written.
+            Some(alias) => format!("{}|{}]]", &full_match[..full_match.len() - 2], alias),
+            None => full_match.to_string(),
+        }
+    });
+
+    if new_content != content {
+        Some(new_content.into_owned())
+    } else {
+        None
+    }
+}
+
+/// Returns `content` with each image reference that `rewrite` gives a new path for
+/// replaced by it, or `None` if nothing changed. References are the image embeds
+/// in the body (`![[...]]` and `![alt](...)`) and the values of the `image` and
+/// `banner` frontmatter fields, including list items; each is passed to `rewrite`
+/// as written.
+fn replace_image_refs_in(
+    content: &str,
+    rewrite: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let (frontmatter, body) = parser::extract_frontmatter(content);
+    let head = &content[..content.len() - body.len()];
+
+    // The path is capture 1 of both embed regexes; the rest of the match is kept.
+    let replace_embed = |caps: &Captures| {
+        let full = caps.get(0).unwrap();
+        let src = caps.get(1).unwrap();
+        match rewrite(src.as_str().trim()) {
+            Some(new_src) => format!(
+                "{}{}{}",
+                &full.as_str()[..src.start() - full.start()],
+                new_src,
+                &full.as_str()[src.end() - full.start()..]
+            ),
+            None => full.as_str().to_string(),
+        }
+    };
+    let new_body = WIKILINK_IMAGE_RE.replace_all(body, &replace_embed);
+    let new_body = parser::MARKDOWN_IMAGE_RE.replace_all(&new_body, &replace_embed);
+
+    let mut new_head = head.to_string();
+    if !frontmatter.is_empty() {
+        let mut in_image_key = false;
+        let mut lines = Vec::new();
+        for line in frontmatter.split('\n') {
+            let value = if let Some((key, value)) = line
+                .split_once(':')
+                .filter(|_| !line.starts_with(char::is_whitespace) && !line.starts_with('-'))
+            {
+                in_image_key = parser::FRONTMATTER_IMAGE_KEYS.contains(&key.trim());
+                in_image_key.then_some(value)
+            } else if in_image_key {
+                line.trim_start().strip_prefix('-')
+            } else {
+                None
+            };
+            let mut line = line.to_string();
+            if let Some(value) = value {
+                // Flow lists like `[a.png, b.png]` hold several paths on one line.
+                // Each path's own span is replaced, so `nana.png` can't hit `banana.png`.
+                let mut edits = Vec::new();
+                let mut item_start = line.len() - value.len();
+                for item in value.split(',') {
+                    let src = item
+                        .trim()
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .trim()
+                        .trim_matches(['"', '\'']);
+                    if let Some(new_src) = rewrite(src).filter(|_| !src.is_empty()) {
+                        let start = item_start + item.find(src).unwrap_or_default();
+                        edits.push((start..start + src.len(), new_src));
+                    }
+                    item_start += item.len() + 1;
+                }
+                for (span, new_src) in edits.into_iter().rev() {
+                    line.replace_range(span, &new_src);
+                }
+            }
+            lines.push(line);
+        }
+        new_head = format!(
+            "---\n{}{}",
+            lines.join("\n"),
+            &head[4 + frontmatter.len()..]
+        );
+    }
+
+    let new_content = new_head + &new_body;
+    (new_content != content).then_some(new_content)
+}
+
 impl Writer {
     /// Creates a new Writer.
     pub fn new() -> Self {
@@ -115,18 +247,74 @@ impl Writer {
         atomic_write(path, content)
     }
 
+    /// Writes a delimited "Backlinks" section listing `links`, which are formatted
+    /// wikilinks (see [`crate::wikilink::format_page_link`]), at the end of a page.
+    ///
+    /// An existing block (between [`BACKLINKS_BLOCK_START`] and [`BACKLINKS_BLOCK_END`])
+    /// is replaced in place, so running this repeatedly never duplicates it. If
+    /// `links` is empty, any existing block is removed instead.
+    #[instrument(skip(self, links))]
+    pub fn write_backlinks_block(&self, path: &Path, links: &[String]) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+
+        let block = if links.is_empty() {
+            String::new()
+        } else {
+            let items: String = links.iter().map(|link| format!("- {}\n", link)).collect();
+            format!(
+                "{}\n## Backlinks\n\n{}{}\n",
+                BACKLINKS_BLOCK_START, items, BACKLINKS_BLOCK_END
+            )
+        };
+
+        let existing = content.find(BACKLINKS_BLOCK_START).and_then(|start| {
+            content[start..]
+                .find(BACKLINKS_BLOCK_END)
+                .map(|end| (start, start + end + BACKLINKS_BLOCK_END.len()))
+        });
+
+        let new_content = match existing {
+            Some((start, end)) => {
+                let before = content[..start].trim_end();
+                let after = content[end..].trim_start_matches(['\r', '\n']);
+                let mut parts: Vec<&str> = Vec::new();
+                if !before.is_empty() {
+                    parts.push(before);
+                }
+                if !block.is_empty() {
+                    parts.push(block.trim_end());
+                }
+                if !after.is_empty() {
+                    parts.push(after.trim_end());
+                }
+                format!("{}\n", parts.join("\n\n"))
+            }
+            None if block.is_empty() => return Ok(()),
+            None if content.trim().is_empty() => block,
+            None => format!("{}\n\n{}", content.trim_end(), block),
+        };
+
+        if new_content != content {
+            atomic_write(path, &new_content)?;
+        }
+        Ok(())
+    }
+
     /// Creates a new markdown file, optionally from a template.
     ///
     /// # Arguments
     /// * `parent_dir` - The directory where the new file will be created.
     /// * `file_name` - The name of the new file (without extension).
     /// * `template_content` - Optional content from a template file.
+    /// * `default_tags` - Tags the new page starts with. They replace the default
+    ///   content's placeholder tags, or are added to the template's own tags.
     #[instrument(skip(self, template_content))]
     pub fn create_new_file(
         &self,
         parent_dir: &str,
         file_name: &str,
         template_content: Option<String>,
+        default_tags: &[String],
     ) -> Result<PageHeader> {
         let path = PathBuf::from(parent_dir).join(format!("{}.md", file_name.trim()));
 
@@ -137,6 +325,7 @@ impl Writer {
         let title = file_stem_string(&path);
 
         // Use the template content if provided, otherwise use the default.
+        let template_is_used = template_content.is_some();
         let final_content = match template_content {
             Some(content) => content.replace("{{title}}", &title),
             None => format!(
@@ -148,11 +337,81 @@ tags: [add, your, tags]
 "#
             ),
         };
+        let final_content = if default_tags.is_empty() {
+            final_content
+        } else {
+            add_default_tags_in(&final_content, default_tags, template_is_used)?
+        };
 
         atomic_write(&path, &final_content)?;
         Ok(PageHeader { title, path })
     }
 
+    /// Saves image bytes into `images_dir` under a name derived from `suggested_name`.
+    ///
+    /// The bytes must be in an image format the app can display; the file extension
+    /// is taken from the detected format rather than from `suggested_name`. Spaces
+    /// become underscores so the name can be embedded as is, and an existing file is
+    /// never overwritten: `_2`, `_3`, ... is appended to the name instead.
+    ///
+    /// # Returns
+    /// The path of the saved image.
+    #[instrument(skip(self, bytes), fields(len = bytes.len()))]
+    pub fn save_image(
+        &self,
+        images_dir: &Path,
+        bytes: &[u8],
+        suggested_name: &str,
+    ) -> Result<PathBuf> {
+        let extension = image::guess_format(bytes)
+            .ok()
+            .and_then(|format| format.extensions_str().first().copied())
+            .filter(|ext| is_image_file(Path::new(&format!("image.{}", ext))))
+            .ok_or_else(|| {
+                ChroniclerError::InvalidArgument("the data is not a supported image".to_string())
+            })?;
+
+        let stem = sanitize_file_stem(&file_stem_string(Path::new(suggested_name)))
+            .replace(char::is_whitespace, "_");
+
+        let mut temp_file = NamedTempFile::new_in(images_dir)?;
+        temp_file.write_all(bytes)?;
+
+        let mut counter = 1;
+        loop {
+            let file_name = if counter == 1 {
+                format!("{}.{}", stem, extension)
+            } else {
+                format!("{}_{}.{}", stem, counter, extension)
+            };
+            let path = images_dir.join(file_name);
+            // `persist_noclobber` fails rather than replacing a file that appeared
+            // since the name was chosen.
+            match temp_file.persist_noclobber(&path) {
+                Ok(_) => return Ok(path),
+                Err(e) if e.error.kind() == std::io::ErrorKind::AlreadyExists => {
+                    temp_file = e.file;
+                    counter += 1;
+                }
+                Err(e) => return Err(e.error.into()),
+            }
+        }
+    }
+
+    /// Sets a top-level frontmatter field to a string value.
+    ///
+    /// The frontmatter is edited as text, so other fields keep their order,
+    /// comments and formatting. A page without frontmatter gets a new block.
+    #[instrument(skip(self))]
+    pub fn set_frontmatter_field(&self, path: &Path, key: &str, value: &str) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+        let updated = set_frontmatter_field_in(&content, key, value)?;
+        if updated != content {
+            atomic_write(path, &updated)?;
+        }
+        Ok(())
+    }
+
     /// Creates a new, empty folder.
     #[instrument(skip(self))]
     pub fn create_new_folder(&self, parent_dir: &str, folder_name: &str) -> Result<PathBuf> {
@@ -229,9 +488,130 @@ tags: [add, your, tags]
         self.execute_rename_or_move(old_path, new_path, backlinks)
     }
 
+    /// Moves several pages into `dest_dir` and updates the links to them, as one
+    /// transaction. `pages` pairs each page with its backlinks.
+    ///
+    /// A page whose name is already taken in `dest_dir` is given a numbered name,
+    /// e.g. `Vex 2`, and the links to it are renamed to match. Each file linking to
+    /// the pages is written once. If anything fails, the linking files are restored
+    /// and the pages moved back.
+    ///
+    /// # Returns
+    /// The old and new path of each page.
+    #[instrument(skip(self, pages))]
+    pub fn move_pages(
+        &self,
+        pages: &[(PathBuf, HashSet<PathBuf>)],
+        dest_dir: &Path,
+    ) -> Result<Vec<(PathBuf, PathBuf)>> {
+        if !dest_dir.is_dir() {
+            return Err(ChroniclerError::InvalidPath(dest_dir.to_path_buf()));
+        }
+
+        // 1. Pick a free name in the destination for each page.
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut moves: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(pages.len());
+        for (old_path, _) in pages {
+            let stem = unique_stem(dest_dir, &file_stem_string(old_path), &taken);
+            taken.insert(stem.to_lowercase());
+            moves.push((old_path.clone(), dest_dir.join(format!("{}.md", stem))));
+        }
+
+        // 2. Work out the link renames each file needs. A page's links to itself
+        //    are found in the page at its new path.
+        let mut renames: HashMap<PathBuf, HashMap<String, String>> = HashMap::new();
+        for ((old_path, backlinks), (_, new_path)) in pages.iter().zip(&moves) {
+            let (old_stem, new_stem) = (file_stem_string(old_path), file_stem_string(new_path));
+            if old_stem == new_stem {
+                continue;
+            }
+            let new_location = |path: &PathBuf| {
+                moves
+                    .iter()
+                    .find(|(from, _)| from == path)
+                    .map_or_else(|| path.clone(), |(_, to)| to.clone())
+            };
+            for file in backlinks.iter().map(new_location).chain([new_path.clone()]) {
+                renames
+                    .entry(file)
+                    .or_default()
+                    .insert(old_stem.to_lowercase(), new_stem.clone());
+            }
+        }
+
+        // 3. Move the pages, moving them back if one can't be moved.
+        let move_back = |moved: &[(PathBuf, PathBuf)]| {
+            for (old_path, new_path) in moved.iter().rev() {
+                if let Err(e) = fs::rename(new_path, old_path) {
+                    error!(
+                        "CRITICAL: FAILED TO MOVE {:?} BACK TO {:?}: {}. Vault is now inconsistent.",
+                        new_path, old_path, e
+                    );
+                }
+            }
+        };
+        for (i, (old_path, new_path)) in moves.iter().enumerate() {
+            if let Err(e) = fs::rename(old_path, new_path) {
+                warn!(
+                    "Failed to move {:?}, moving the others back: {}",
+                    old_path, e
+                );
+                move_back(&moves[..i]);
+                return Err(e.into());
+            }
+        }
+
+        // 4. Update the links as one transaction.
+        let mut updates: Vec<FileUpdate> = Vec::new();
+        for (path, file_renames) in &renames {
+            let old_content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read {:?}, skipping link update: {}", path, e);
+                    continue;
+                }
+            };
+            if let Some(new_content) = replace_wikilinks_in_content(&old_content, file_renames) {
+                updates.push(FileUpdate {
+                    path: path.clone(),
+                    old_content,
+                    new_content,
+                });
+            }
+        }
+        if let Err(e) = write_all_or_roll_back(&updates) {
+            move_back(&moves);
+            return Err(e);
+        }
+
+        Ok(moves)
+    }
+
+    /// Renames and relocates a file or folder in a single transactional operation,
+    /// updating backlinks once for the final name.
+    ///
+    /// Unlike `rename_path`, `new_path` is the complete destination, including the
+    /// file extension. Its parent folder must already exist.
+    ///
+    /// # Returns
+    /// The new path of the file or folder.
+    #[instrument(skip(self, backlinks))]
+    pub fn rename_and_move(
+        &self,
+        old_path: &Path,
+        new_path: &Path,
+        backlinks: &HashSet<PathBuf>,
+    ) -> Result<PathBuf> {
+        if !new_path.parent().is_some_and(Path::is_dir) {
+            return Err(ChroniclerError::InvalidPath(new_path.to_path_buf()));
+        }
+
+        self.execute_rename_or_move(old_path, new_path.to_path_buf(), backlinks)
+    }
+
     /// Common logic for executing a transactional rename or move operation.
     ///
-    /// This internal function is called by both `rename_path` and `move_path`. It prepares
+    /// This internal function is called by `rename_path`, `move_path` and `rename_and_move`. It prepares
     /// the operation and invokes the transactional process.
     fn execute_rename_or_move(
         &self,
@@ -274,6 +654,10 @@ tags: [add, your, tags]
     /// This function reads each backlink file, replaces the wikilink, and writes the
     /// file back atomically. If any write fails, it attempts to roll back all
     /// previous writes in the transaction. This is the core reusable logic.
+    ///
+    /// The renamed file itself is always included, so that links it contains to
+    /// its own old name are updated too. It is read from `new_path`, since the
+    /// rename has already happened on disk.
     #[instrument(skip(self, backlinks))]
     pub fn update_backlinks_for_rename(
         &self,
@@ -289,9 +673,23 @@ tags: [add, your, tags]
         // --- 1. Prepare Phase: Read files and calculate changes in memory ---
         let old_name_stem = file_stem_string(old_path);
         let new_name_stem = file_stem_string(new_path);
-        let mut updates: Vec<BacklinkUpdate> = Vec::new();
+        let mut updates: Vec<FileUpdate> = Vec::new();
+
+        // A self-linking file appears in its own backlinks under its old path,
+        // which no longer exists, so it is mapped to the new path.
+        let files_to_update: HashSet<&Path> = backlinks
+            .iter()
+            .map(|path| {
+                if path == old_path {
+                    new_path
+                } else {
+                    path.as_path()
+                }
+            })
+            .chain(std::iter::once(new_path))
+            .collect();
 
-        for backlink_path in backlinks {
+        for backlink_path in files_to_update {
             let old_content = match fs::read_to_string(backlink_path) {
                 Ok(content) => content,
                 Err(e) => {
@@ -306,7 +704,174 @@ tags: [add, your, tags]
             if let Some(new_content) =
                 replace_wikilink_in_content(&old_content, &old_name_stem, &new_name_stem)
             {
-                updates.push(BacklinkUpdate {
+                updates.push(FileUpdate {
+                    path: backlink_path.to_path_buf(),
+                    old_content,
+                    new_content,
+                });
+            }
+        }
+
+        // --- 2. Transaction Phase: Perform all file system changes ---
+        write_all_or_roll_back(&updates)
+    }
+
+    /// Renames the top-level frontmatter key `old_key` to `new_key` in each of
+    /// `paths`, keeping its value exactly as written.
+    ///
+    /// Files without `old_key`, or that already have `new_key`, are left alone so
+    /// that no value is overwritten. All files are written as one transaction: if
+    /// any write fails, the files already changed are restored. Returns the paths
+    /// of the files that were changed.
+    #[instrument(skip(self, paths))]
+    pub fn rename_frontmatter_key(
+        &self,
+        paths: &[PathBuf],
+        old_key: &str,
+        new_key: &str,
+    ) -> Result<Vec<PathBuf>> {
+        let mut updates: Vec<FileUpdate> = Vec::new();
+        for path in paths {
+            let old_content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read {:?}, skipping key rename: {}", path, e);
+                    continue;
+                }
+            };
+            if let Some(new_content) = rename_frontmatter_key_in(&old_content, old_key, new_key) {
+                updates.push(FileUpdate {
+                    path: path.clone(),
+                    old_content,
+                    new_content,
+                });
+            }
+        }
+
+        write_all_or_roll_back(&updates)?;
+        Ok(updates.into_iter().map(|update| update.path).collect())
+    }
+
+    /// Rewrites the line endings of several files to LF (`to_lf`) or CRLF. Files
+    /// already using that style throughout are left alone.
+    ///
+    /// All files are written as one transaction: if any write fails, the files
+    /// already changed are restored. Returns the paths of the files that were changed.
+    #[instrument(skip(self, paths))]
+    pub fn normalize_line_endings(&self, paths: &[PathBuf], to_lf: bool) -> Result<Vec<PathBuf>> {
+        let mut updates: Vec<FileUpdate> = Vec::new();
+        for path in paths {
+            let old_content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read {:?}, skipping line endings: {}", path, e);
+                    continue;
+                }
+            };
+            let new_content = normalize_line_endings_in(&old_content, to_lf);
+            if new_content != old_content {
+                updates.push(FileUpdate {
+                    path: path.clone(),
+                    old_content,
+                    new_content,
+                });
+            }
+        }
+
+        write_all_or_roll_back(&updates)?;
+        Ok(updates.into_iter().map(|update| update.path).collect())
+    }
+
+    /// Adds aliases to the bare wikilinks in several files. `aliases` maps each file
+    /// to the aliases for its links, keyed by lowercase page name.
+    ///
+    /// All files are written as one transaction: if any write fails, the files
+    /// already changed are restored. Returns the paths of the files that were changed.
+    #[instrument(skip(self, aliases))]
+    pub fn add_link_aliases(
+        &self,
+        aliases: &HashMap<PathBuf, HashMap<String, String>>,
+    ) -> Result<Vec<PathBuf>> {
+        let mut updates: Vec<FileUpdate> = Vec::new();
+        for (path, page_aliases) in aliases {
+            let old_content = match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read {:?}, skipping link aliases: {}", path, e);
+                    continue;
+                }
+            };
+            if let Some(new_content) = add_link_aliases_in(&old_content, page_aliases) {
+                updates.push(FileUpdate {
+                    path: path.clone(),
+                    old_content,
+                    new_content,
+                });
+            }
+        }
+
+        write_all_or_roll_back(&updates)?;
+        Ok(updates.into_iter().map(|update| update.path).collect())
+    }
+
+    /// Merges the page at `source` into the page at `target` and deletes the source.
+    ///
+    /// The source's body is appended to the target's, and its frontmatter is merged
+    /// in with the target winning (see [`merge_page_content`]). Every link to the
+    /// source, in the target and in `backlinks`, is rewritten to point to the
+    /// target. All files are written as one transaction, and if the source can't
+    /// be deleted afterwards they are restored. Returns the paths of the changed files.
+    #[instrument(skip(self, backlinks))]
+    pub fn merge_pages(
+        &self,
+        source: &Path,
+        target: &Path,
+        backlinks: &HashSet<PathBuf>,
+    ) -> Result<Vec<PathBuf>> {
+        if source == target {
+            return Err(ChroniclerError::InvalidArgument(
+                "a page can't be merged into itself".to_string(),
+            ));
+        }
+        if !is_markdown_file(source) || !is_markdown_file(target) {
+            return Err(ChroniclerError::InvalidArgument(
+                "only pages can be merged".to_string(),
+            ));
+        }
+
+        // --- 1. Prepare Phase: Read files and calculate changes in memory ---
+        let source_stem = file_stem_string(source);
+        let target_stem = file_stem_string(target);
+        let source_content = fs::read_to_string(source)?;
+        let target_content = fs::read_to_string(target)?;
+
+        let merged = merge_page_content(&target_content, &source_content)?;
+        let merged =
+            replace_wikilink_in_content(&merged, &source_stem, &target_stem).unwrap_or(merged);
+        let mut updates = vec![FileUpdate {
+            path: target.to_path_buf(),
+            old_content: target_content,
+            new_content: merged,
+        }];
+
+        for backlink_path in backlinks {
+            if backlink_path == source || backlink_path == target {
+                continue;
+            }
+            let old_content = match fs::read_to_string(backlink_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!(
+                        "Failed to read backlink file {:?}, skipping update: {}",
+                        backlink_path, e
+                    );
+                    continue;
+                }
+            };
+            if let Some(new_content) =
+                replace_wikilink_in_content(&old_content, &source_stem, &target_stem)
+            {
+                updates.push(FileUpdate {
                     path: backlink_path.clone(),
                     old_content,
                     new_content,
@@ -315,36 +880,108 @@ tags: [add, your, tags]
         }
 
         // --- 2. Transaction Phase: Perform all file system changes ---
-        let mut successfully_updated: Vec<&BacklinkUpdate> = Vec::new();
-        for update in &updates {
-            if let Err(e) = atomic_write(&update.path, &update.new_content) {
-                // --- ROLLBACK ---
-                warn!(
-                    "Failed to write backlink file {:?}, rolling back changes. Error: {}",
-                    &update.path, e
-                );
+        write_all_or_roll_back(&updates)?;
+        if let Err(e) = fs::remove_file(source) {
+            warn!(
+                "Failed to delete merged page {:?}, rolling back: {}",
+                source, e
+            );
+            roll_back(&updates.iter().collect::<Vec<_>>());
+            return Err(e.into());
+        }
+        Ok(updates.into_iter().map(|update| update.path).collect())
+    }
 
-                // Roll back the already updated backlinks by writing their old content back.
-                for change_to_revert in successfully_updated.iter().rev() {
-                    if let Err(rollback_err) =
-                        atomic_write(&change_to_revert.path, &change_to_revert.old_content)
-                    {
-                        error!(
-                            "CRITICAL: FAILED TO ROLL BACK BACKLINK FILE {:?}: {}. Vault may be inconsistent.",
-                            &change_to_revert.path,
-                            rollback_err
-                        );
-                        // Continue trying to roll back the rest of the transaction.
-                    }
+    /// Renames an image in place and rewrites every reference to it in `pages`, as
+    /// an embed or in an `image` or `banner` frontmatter field, to the new name.
+    ///
+    /// `new_name` is the new file stem; the extension is kept. References are kept
+    /// in the form they were written, with only the file name changed. The pages are
+    /// written as one transaction, and if that fails the image is renamed back.
+    /// Returns the image's new path and the paths of the changed pages.
+    #[instrument(skip(self, pages))]
+    pub fn rename_image(
+        &self,
+        vault_root: &Path,
+        old_path: &Path,
+        new_name: &str,
+        pages: &[PathBuf],
+    ) -> Result<(PathBuf, Vec<PathBuf>)> {
+        if !is_image_file(old_path) || !old_path.is_file() {
+            return Err(ChroniclerError::InvalidArgument(format!(
+                "{:?} is not an image",
+                old_path
+            )));
+        }
+        let new_name = new_name.trim();
+        if new_name.is_empty() || new_name.contains(['/', '\\']) {
+            return Err(ChroniclerError::InvalidArgument(format!(
+                "'{}' is not a valid file name",
+                new_name
+            )));
+        }
+        let parent = old_path
+            .parent()
+            .ok_or_else(|| ChroniclerError::InvalidPath(old_path.to_path_buf()))?;
+        let mut new_path = parent.join(new_name);
+        if let Some(ext) = old_path.extension() {
+            new_path.set_extension(ext);
+        }
+        if new_path.exists() {
+            return Err(ChroniclerError::InvalidArgument(format!(
+                "{:?} already exists",
+                new_path
+            )));
+        }
+        let new_file_name = new_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| ChroniclerError::InvalidPath(new_path.clone()))?;
+
+        // --- 1. Prepare Phase: Rewrite every reference to the image in memory ---
+        // A reference is matched by where it resolves, so `portrait`, `portrait.png`
+        // and an absolute path to the same file are all updated.
+        let rewrite = |src: &str| {
+            (resolve_image_path(vault_root, src) == old_path).then(|| {
+                match src.rfind(['/', '\\']) {
+                    Some(i) => format!("{}{}", &src[..=i], new_file_name),
+                    None => new_file_name.clone(),
                 }
-                return Err(e); // Return the original error
-            } else {
-                // On success, add the update to our list for potential rollback.
-                successfully_updated.push(update);
+            })
+        };
+        let mut updates = Vec::new();
+        for page in pages {
+            let old_content = match fs::read_to_string(page) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read page {:?}, skipping update: {}", page, e);
+                    continue;
+                }
+            };
+            if let Some(new_content) = replace_image_refs_in(&old_content, rewrite) {
+                updates.push(FileUpdate {
+                    path: page.clone(),
+                    old_content,
+                    new_content,
+                });
             }
         }
 
-        Ok(())
+        // --- 2. Transaction Phase: Rename the image, then update the pages ---
+        fs::rename(old_path, &new_path)?;
+        if let Err(e) = write_all_or_roll_back(&updates) {
+            if let Err(rollback_err) = fs::rename(&new_path, old_path) {
+                error!(
+                    "CRITICAL: FAILED TO RENAME {:?} BACK TO {:?}: {}",
+                    new_path, old_path, rollback_err
+                );
+            }
+            return Err(e);
+        }
+        Ok((
+            new_path,
+            updates.into_iter().map(|update| update.path).collect(),
+        ))
     }
 
     /// Creates a duplicate of a page, finding a unique name for the new file.
@@ -383,6 +1020,364 @@ tags: [add, your, tags]
             path: new_path,
         })
     }
+
+    /// Splits a page into separate files, one per heading of the given level.
+    ///
+    /// Each section (the heading's content, including any deeper subheadings) is
+    /// written to a new page in the same folder, named after the heading. In the
+    /// original page the section is replaced by a `[[link]]` to its new page, so
+    /// the frontmatter and any introductory text stay where they were. Names that
+    /// collide with existing files get a numeric suffix.
+    ///
+    /// # Returns
+    /// The paths of the newly created pages, in document order.
+    #[instrument(skip(self))]
+    pub fn split_page_by_heading(&self, path: &Path, level: u8) -> Result<Vec<PathBuf>> {
+        if !(1..=6).contains(&level) {
+            return Err(ChroniclerError::InvalidArgument(format!(
+                "heading level must be between 1 and 6, got {}",
+                level
+            )));
+        }
+
+        let content = fs::read_to_string(path)?;
+        let parent_dir = path
+            .parent()
+            .ok_or_else(|| ChroniclerError::InvalidPath(path.to_path_buf()))?;
+
+        let (_, body) = parser::extract_frontmatter(&content);
+        let body_offset = content.len() - body.len();
+        let sections = parser::find_heading_sections(body, level);
+        if sections.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 1. Work out the new pages and the rewritten original.
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut new_pages = Vec::with_capacity(sections.len());
+        let mut rewritten = content[..body_offset].to_string();
+        let mut cursor = 0;
+
+        for section in &sections {
+            let stem = unique_stem(parent_dir, &sanitize_file_stem(&section.title), &taken);
+            taken.insert(stem.to_lowercase());
+
+            let gap = &body[cursor..section.start];
+            if !gap.is_empty() && rewritten.ends_with("]]\n") && !gap.starts_with('\n') {
+                rewritten.push('\n');
+            }
+            rewritten.push_str(gap);
+            rewritten.push_str(&format!("- [[{}]]\n", stem));
+            cursor = section.end;
+
+            let section_body = body[section.content_start..section.end].trim();
+            // The title is the heading itself, even when the file name had to be
+            // suffixed. Serializing it keeps YAML-significant characters valid.
+            let title = section.title.trim();
+            let frontmatter = serde_yaml::to_string(&serde_json::json!({ "title": title }))?;
+            let page_content = format!("---\n{}---\n\n{}\n", frontmatter, section_body);
+            new_pages.push((parent_dir.join(format!("{}.md", stem)), page_content));
+        }
+
+        let rest = &body[cursor..];
+        if !rest.is_empty() && !rest.starts_with('\n') {
+            rewritten.push('\n');
+        }
+        rewritten.push_str(rest);
+
+        // 2. Create the new pages, removing any already written if one fails.
+        let mut created: Vec<PathBuf> = Vec::with_capacity(new_pages.len());
+        for (new_path, page_content) in &new_pages {
+            if let Err(e) = atomic_write(new_path, page_content) {
+                error!("Failed to write split section {:?}: {}", new_path, e);
+                remove_created(&created);
+                return Err(e);
+            }
+            created.push(new_path.clone());
+        }
+
+        // 3. Rewrite the original page last, so a failure leaves it untouched.
+        if let Err(e) = atomic_write(path, &rewritten) {
+            error!("Failed to rewrite split page {:?}: {}", path, e);
+            remove_created(&created);
+            return Err(e);
+        }
+
+        Ok(created)
+    }
+}
+
+/// Turns a heading into a usable file stem by dropping characters that are
+/// invalid in file names or meaningful inside a wikilink.
+fn sanitize_file_stem(heading: &str) -> String {
+    let cleaned: String = heading
+        .chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '[' | ']'
+            )
+        })
+        .filter(|c| !c.is_control())
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Returns `stem`, or `stem N` for the first `N` that names neither an existing
+/// file in `dir` nor one already reserved (compared case-insensitively).
+fn unique_stem(dir: &Path, stem: &str, taken: &HashSet<String>) -> String {
+    let is_free =
+        |s: &str| !taken.contains(&s.to_lowercase()) && !dir.join(format!("{}.md", s)).exists();
+    if is_free(stem) {
+        return stem.to_string();
+    }
+    let mut counter = 2;
+    loop {
+        let candidate = format!("{} {}", stem, counter);
+        if is_free(&candidate) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+/// Returns `content` with the top-level frontmatter field `key` set to `value`.
+///
+/// An existing entry is replaced together with any indented or list lines that
+/// continue it; otherwise the entry is added at the end of the frontmatter. CRLF
+/// line endings are kept.
+fn set_frontmatter_field_in(content: &str, key: &str, value: &str) -> Result<String> {
+    set_frontmatter_entry_in(content, key, value.into())
+}
+
+/// Like [`set_frontmatter_field_in`], but sets any YAML value, such as a list.
+fn set_frontmatter_entry_in(content: &str, key: &str, value: serde_yaml::Value) -> Result<String> {
+    if content.contains("\r\n") {
+        let updated =
+            set_frontmatter_entry_in(&normalize_line_endings_in(content, true), key, value)?;
+        return Ok(normalize_line_endings_in(&updated, false));
+    }
+
+    let mut entry = serde_yaml::Mapping::new();
+    entry.insert(key.into(), value);
+    let entry = serde_yaml::to_string(&entry)?;
+
+    let (frontmatter, body) = parser::extract_frontmatter(content);
+    if body.len() == content.len() {
+        return Ok(format!("---\n{}---\n\n{}", entry, content));
+    }
+    // `frontmatter` starts right after the opening `---\n`.
+    let rest = &content[4 + frontmatter.len()..];
+    if frontmatter.is_empty() {
+        return Ok(format!("---\n{}{}", entry, rest));
+    }
+
+    let prefix = format!("{}:", key);
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    let mut skipping = false;
+    for line in frontmatter.lines() {
+        let continues = line.starts_with([' ', '\t']) || (line.starts_with('-') && line != "---");
+        if skipping && continues {
+            continue;
+        }
+        skipping = false;
+        if !replaced && line.starts_with(&prefix) {
+            lines.push(entry.trim_end().to_string());
+            replaced = true;
+            skipping = true;
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    if !replaced {
+        lines.push(entry.trim_end().to_string());
+    }
+
+    Ok(format!("---\n{}{}", lines.join("\n"), rest))
+}
+
+/// Writes every update atomically. If any write fails, the files already written
+/// are restored to their old content and the original error is returned.
+fn write_all_or_roll_back(updates: &[FileUpdate]) -> Result<()> {
+    let mut successfully_updated: Vec<&FileUpdate> = Vec::new();
+    for update in updates {
+        if let Err(e) = atomic_write(&update.path, &update.new_content) {
+            // --- ROLLBACK ---
+            warn!(
+                "Failed to write file {:?}, rolling back changes. Error: {}",
+                &update.path, e
+            );
+
+            roll_back(&successfully_updated);
+            return Err(e); // Return the original error
+        } else {
+            // On success, add the update to our list for potential rollback.
+            successfully_updated.push(update);
+        }
+    }
+    Ok(())
+}
+
+/// Parses raw frontmatter into a YAML mapping. Empty frontmatter is an empty mapping.
+fn frontmatter_mapping(frontmatter: &str) -> Result<serde_yaml::Mapping> {
+    if frontmatter.trim().is_empty() {
+        return Ok(serde_yaml::Mapping::new());
+    }
+    match serde_yaml::from_str(frontmatter)? {
+        serde_yaml::Value::Mapping(mapping) => Ok(mapping),
+        serde_yaml::Value::Null => Ok(serde_yaml::Mapping::new()),
+        _ => Err(ChroniclerError::InvalidArgument(
+            "frontmatter is not a set of key-value pairs".to_string(),
+        )),
+    }
+}
+
+/// Returns `content` with `tags` added to its frontmatter. When `keep_existing` is
+/// false, any tags the content already has are replaced instead.
+fn add_default_tags_in(content: &str, tags: &[String], keep_existing: bool) -> Result<String> {
+    let defaults = serde_yaml::Value::Sequence(tags.iter().map(|t| t.as_str().into()).collect());
+    let tags = if keep_existing {
+        // A template's frontmatter that isn't valid YAML still gets the tags.
+        let (frontmatter, _) = parser::extract_frontmatter(content);
+        let existing = frontmatter_mapping(frontmatter)
+            .ok()
+            .and_then(|fields| fields.get("tags").cloned())
+            .unwrap_or(serde_yaml::Value::Null);
+        union_tags(&existing, &defaults)
+    } else {
+        defaults
+    };
+    set_frontmatter_entry_in(content, "tags", tags)
+}
+
+/// Returns the tags in `target` followed by those only in `source`, as a list.
+/// Each may hold a list of tags or a single tag.
+fn union_tags(target: &serde_yaml::Value, source: &serde_yaml::Value) -> serde_yaml::Value {
+    let as_list = |value: &serde_yaml::Value| match value {
+        serde_yaml::Value::Sequence(items) => items.clone(),
+        serde_yaml::Value::Null => Vec::new(),
+        other => vec![other.clone()],
+    };
+    let mut tags = as_list(target);
+    for tag in as_list(source) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    serde_yaml::Value::Sequence(tags)
+}
+
+/// Returns `target` with the content of `source` merged into it.
+///
+/// Frontmatter is merged with the target winning: fields only the source has are
+/// added, fields both have keep the target's value, and `tags` are combined. The
+/// target's frontmatter is otherwise kept as written. The source's body is
+/// appended after the target's.
+fn merge_page_content(target: &str, source: &str) -> Result<String> {
+    let (target_frontmatter, _) = parser::extract_frontmatter(target);
+    let (source_frontmatter, source_body) = parser::extract_frontmatter(source);
+    let target_fields = frontmatter_mapping(target_frontmatter)?;
+    let source_fields = frontmatter_mapping(source_frontmatter)?;
+
+    let mut merged = target.to_string();
+    for (key, value) in &source_fields {
+        let Some(key_str) = key.as_str() else {
+            continue;
+        };
+        match target_fields.get(key) {
+            None => merged = set_frontmatter_entry_in(&merged, key_str, value.clone())?,
+            Some(existing) if key_str == DEFAULT_TAG_KEY => {
+                let tags = union_tags(existing, value);
+                if tags != *existing {
+                    merged = set_frontmatter_entry_in(&merged, key_str, tags)?;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    let source_body = source_body.trim();
+    if !source_body.is_empty() {
+        merged.truncate(merged.trim_end().len());
+        merged.push_str("\n\n");
+        merged.push_str(source_body);
+        merged.push('\n');
+    }
+    Ok(merged)
+}
+
+/// Rolls back already written updates by writing their old content back, newest first.
+fn roll_back(updates: &[&FileUpdate]) {
+    for change_to_revert in updates.iter().rev() {
+        if let Err(rollback_err) =
+            atomic_write(&change_to_revert.path, &change_to_revert.old_content)
+        {
+            error!(
+                "CRITICAL: FAILED TO ROLL BACK FILE {:?}: {}. Vault may be inconsistent.",
+                &change_to_revert.path, rollback_err
+            );
+            // Continue trying to roll back the rest of the transaction.
+        }
+    }
+}
+
+/// Returns `content` with the top-level frontmatter key `old_key` renamed to
+/// `new_key`, or `None` if there is no such key or `new_key` is already present.
+///
+/// Only the key itself is replaced, so the value and any lines continuing it are
+/// kept byte for byte. Quoted keys are not recognized.
+fn rename_frontmatter_key_in(content: &str, old_key: &str, new_key: &str) -> Option<String> {
+    let (frontmatter, _) = parser::extract_frontmatter(content);
+    if frontmatter.is_empty() {
+        return None;
+    }
+    let is_key_line = |line: &str, key: &str| {
+        line.strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    };
+    if frontmatter.lines().any(|line| is_key_line(line, new_key)) {
+        return None;
+    }
+
+    // `frontmatter` starts right after the opening `---\n`.
+    let mut offset = 4;
+    for line in frontmatter.split_inclusive('\n') {
+        if is_key_line(line, old_key) {
+            let mut renamed = String::with_capacity(content.len() + new_key.len());
+            renamed.push_str(&content[..offset]);
+            renamed.push_str(new_key);
+            renamed.push_str(&content[offset + old_key.len()..]);
+            return Some(renamed);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Returns `content` with every line ending as LF (`to_lf`) or CRLF.
+fn normalize_line_endings_in(content: &str, to_lf: bool) -> String {
+    let lf = content.replace("\r\n", "\n");
+    if to_lf {
+        lf
+    } else {
+        lf.replace('\n', "\r\n")
+    }
+}
+
+/// Best-effort removal of files created by a transaction that failed part-way.
+fn remove_created(paths: &[PathBuf]) {
+    for path in paths {
+        if let Err(e) = fs::remove_file(path) {
+            error!("Failed to remove {:?} during rollback: {}", path, e);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -428,6 +1423,97 @@ mod tests {
         (dir, page1_path, backlink1_path, backlink2_path)
     }
 
+    #[test]
+    fn test_set_frontmatter_field_in() {
+        let content = "---\n# Character sheet\ntitle: Old\naliases:\n  - Oldie\nage: 30\n---\nBody";
+        assert_eq!(
+            set_frontmatter_field_in(content, "aliases", "New").unwrap(),
+            "---\n# Character sheet\ntitle: Old\naliases: New\nage: 30\n---\nBody"
+        );
+        assert_eq!(
+            set_frontmatter_field_in(content, "status", "Draft: v2").unwrap(),
+            "---\n# Character sheet\ntitle: Old\naliases:\n  - Oldie\nage: 30\nstatus: 'Draft: v2'\n---\nBody"
+        );
+        assert_eq!(
+            set_frontmatter_field_in("Just a body.", "title", "New").unwrap(),
+            "---\ntitle: New\n---\n\nJust a body."
+        );
+    }
+
+    #[test]
+    fn test_set_frontmatter_field_in_empty_and_crlf_frontmatter() {
+        assert_eq!(
+            set_frontmatter_field_in("---\n---\nBody", "title", "New").unwrap(),
+            "---\ntitle: New\n---\nBody"
+        );
+        assert_eq!(
+            set_frontmatter_field_in(
+                "---\r\ntitle: Old\r\nage: 30\r\n---\r\nBody\r\n",
+                "title",
+                "New"
+            )
+            .unwrap(),
+            "---\r\ntitle: New\r\nage: 30\r\n---\r\nBody\r\n"
+        );
+        assert_eq!(
+            set_frontmatter_field_in("---\r\n---\r\nBody", "title", "New").unwrap(),
+            "---\r\ntitle: New\r\n---\r\nBody"
+        );
+    }
+
+    #[test]
+    fn test_replace_image_refs_in() {
+        let rewrite = |src: &str| (src.ends_with("old.png")).then(|| src.replace("old", "new"));
+        let content = "---\nimage: \"maps/old.png\"\nbanner:\n  - other.png\n  - old.png\nalt: old.png\n---\n\
+                       ![[old.png|A map]] and ![Map](maps/old.png \"Map\") but not old.png.";
+        assert_eq!(
+            replace_image_refs_in(content, rewrite).unwrap(),
+            "---\nimage: \"maps/new.png\"\nbanner:\n  - other.png\n  - new.png\nalt: old.png\n---\n\
+             ![[new.png|A map]] and ![Map](maps/new.png \"Map\") but not old.png."
+        );
+        assert_eq!(replace_image_refs_in("![[other.png]]", rewrite), None);
+
+        // In a flow list, only the matching item is replaced.
+        let rewrite = |src: &str| (src == "nana.png").then(|| "fruit/nana.png".to_string());
+        assert_eq!(
+            replace_image_refs_in("---\nimage: [banana.png, 'nana.png']\n---\n", rewrite).unwrap(),
+            "---\nimage: [banana.png, 'fruit/nana.png']\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_page_content() {
+        let target = "---\ntitle: Vex\n# The rogue\ntags: [npc]\n---\nA rogue.\n";
+        let source = "---\ntitle: Vex the Bold\nstatus: alive\ntags:\n  - npc\n  - villain\n---\n\nShe fled.\n";
+        assert_eq!(
+            merge_page_content(target, source).unwrap(),
+            "---\ntitle: Vex\n# The rogue\ntags:\n- npc\n- villain\nstatus: alive\n---\nA rogue.\n\nShe fled.\n"
+        );
+
+        // A target without frontmatter gets the source's.
+        assert_eq!(
+            merge_page_content("A rogue.", "---\nstatus: alive\n---\nShe fled.").unwrap(),
+            "---\nstatus: alive\n---\n\nA rogue.\n\nShe fled.\n"
+        );
+    }
+
+    #[test]
+    fn test_rename_frontmatter_key_in() {
+        let content =
+            "---\ntypes: [a]\ntype:\n  - npc\n  - villain\nname: Vex\n---\ntype: body text";
+        assert_eq!(
+            rename_frontmatter_key_in(content, "type", "category").unwrap(),
+            "---\ntypes: [a]\ncategory:\n  - npc\n  - villain\nname: Vex\n---\ntype: body text"
+        );
+        // An existing value is never overwritten, and a missing key changes nothing.
+        assert_eq!(rename_frontmatter_key_in(content, "type", "name"), None);
+        assert_eq!(rename_frontmatter_key_in(content, "status", "state"), None);
+        assert_eq!(
+            rename_frontmatter_key_in("type: npc", "type", "category"),
+            None
+        );
+    }
+
     #[test]
     fn test_rename_path_updates_links() {
         let (_dir, page1_path, page2_path) = setup_writer_test_vault();
@@ -449,6 +1535,40 @@ mod tests {
         assert!(!page2_content.contains("[[Page One]]"));
     }
 
+    #[test]
+    fn test_rename_path_updates_self_links() {
+        let dir = tempdir().unwrap();
+        let page1_path = dir.path().join("Page One.md");
+        fs::write(
+            &page1_path,
+            "See [[Page One#History]] and [[page one|this page]].",
+        )
+        .unwrap();
+        let writer = Writer::new();
+
+        // The indexer lists a self-linking page among its own backlinks.
+        let backlinks = HashSet::from([page1_path.clone()]);
+        let new_path = writer
+            .rename_path(&page1_path, "First Chapter", &backlinks)
+            .unwrap();
+
+        let content = fs::read_to_string(&new_path).unwrap();
+        assert_eq!(
+            content,
+            "See [[First Chapter#History]] and [[First Chapter|this page]]."
+        );
+
+        // The file is updated even when it isn't in the backlinks set.
+        fs::write(&new_path, "Back to [[First Chapter]].").unwrap();
+        let newer_path = writer
+            .rename_path(&new_path, "Chapter One", &HashSet::new())
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&newer_path).unwrap(),
+            "Back to [[Chapter One]]."
+        );
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_rename_path_full_transaction_rollback() {
@@ -502,4 +1622,81 @@ mod tests {
             "The second backlink's content should be unchanged."
         );
     }
+
+    #[test]
+    fn test_split_page_by_heading() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let page_path = root.join("Atlas.md");
+        fs::write(
+            &page_path,
+            "---\ntitle: Atlas\n---\nIntro text.\n\n## The North\n\nCold lands.\n\n### Peaks\n\nTall.\n\n```\n## Not a heading\n```\n\n## The South\n\nWarm lands.\n",
+        )
+        .unwrap();
+        // An existing page forces the second section onto a suffixed name.
+        fs::write(root.join("The South.md"), "Existing page.").unwrap();
+
+        let writer = Writer::new();
+        let created = writer.split_page_by_heading(&page_path, 2).unwrap();
+
+        assert_eq!(
+            created,
+            vec![root.join("The North.md"), root.join("The South 2.md")]
+        );
+
+        let north = fs::read_to_string(root.join("The North.md")).unwrap();
+        assert!(north.starts_with("---\ntitle: The North\n---\n"));
+        assert!(north.contains("Cold lands."));
+        assert!(north.contains("### Peaks"));
+        assert!(north.contains("## Not a heading"));
+        assert!(!north.contains("Warm lands."));
+
+        let south = fs::read_to_string(root.join("The South 2.md")).unwrap();
+        assert!(south.starts_with("---\ntitle: The South\n---\n"));
+        assert!(south.contains("Warm lands."));
+
+        let index = fs::read_to_string(&page_path).unwrap();
+        assert_eq!(
+            index,
+            "---\ntitle: Atlas\n---\nIntro text.\n\n- [[The North]]\n- [[The South 2]]\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("The South.md")).unwrap(),
+            "Existing page."
+        );
+    }
+
+    #[test]
+    fn test_write_backlinks_block_is_idempotent() {
+        let dir = tempdir().unwrap();
+        let page_path = dir.path().join("Hero.md");
+        fs::write(&page_path, "---\ntitle: Hero\n---\nThe hero's story.\n").unwrap();
+
+        let writer = Writer::new();
+        let sources = vec!["[[Chapter One]]".to_string(), "[[Villain]]".to_string()];
+        writer.write_backlinks_block(&page_path, &sources).unwrap();
+
+        let expected = "---\ntitle: Hero\n---\nThe hero's story.\n\n<!-- backlinks -->\n## Backlinks\n\n- [[Chapter One]]\n- [[Villain]]\n<!-- /backlinks -->\n";
+        assert_eq!(fs::read_to_string(&page_path).unwrap(), expected);
+
+        // Re-running with the same sources leaves the file unchanged.
+        writer.write_backlinks_block(&page_path, &sources).unwrap();
+        assert_eq!(fs::read_to_string(&page_path).unwrap(), expected);
+
+        // New sources replace the block in place instead of adding a second one.
+        writer
+            .write_backlinks_block(&page_path, &["[[Sidekick]]".to_string()])
+            .unwrap();
+        let updated = fs::read_to_string(&page_path).unwrap();
+        assert_eq!(updated.matches(BACKLINKS_BLOCK_START).count(), 1);
+        assert!(updated.contains("- [[Sidekick]]"));
+        assert!(!updated.contains("[[Villain]]"));
+
+        // With no sources left, the block is removed.
+        writer.write_backlinks_block(&page_path, &[]).unwrap();
+        assert_eq!(
+            fs::read_to_string(&page_path).unwrap(),
+            "---\ntitle: Hero\n---\nThe hero's story.\n"
+        );
+    }
 }
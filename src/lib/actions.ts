@@ -62,10 +62,7 @@ export function handleContentClick(event: Event) {
                 link.hasAttribute("data-target")
             ) {
                 const targetName = link.getAttribute("data-target")!;
-                const currentVaultPath = get(world).vaultPath;
-                if (currentVaultPath) {
-                    promptAndCreateItem("file", currentVaultPath, targetName);
-                }
+                openBrokenLinkTarget(targetName);
             } else if (link.hasAttribute("data-path")) {
                 const path = link.getAttribute("data-path")!;
                 const title = getTitleFromPath(path);
@@ -74,14 +71,21 @@ export function handleContentClick(event: Event) {
             return;
         }
 
-        // B) Handle external links
+        // B) Handle inline #tags
+        if (link.classList.contains("tag") && link.hasAttribute("data-tag")) {
+            event.preventDefault();
+            navigateToTag(link.getAttribute("data-tag")!);
+            return;
+        }
+
+        // C) Handle external links
         if (href && (href.startsWith("http:") || href.startsWith("https:"))) {
             event.preventDefault(); // Prevent default for this case
             openUrl(href);
             return;
         }
 
-        // C) Handle and neutralize any other non-TOC links to prevent 404s
+        // D) Handle and neutralize any other non-TOC links to prevent 404s
         // We check if the href starts with '#' to allow TOC links to pass through.
         if (href && !href.startsWith("#")) {
             event.preventDefault(); // Prevent default for this case
@@ -95,6 +99,25 @@ export function handleContentClick(event: Event) {
     }
 }
 
+/**
+ * Handles a click on a link that was broken when the page was rendered.
+ * The target may have been created since, so it is resolved again first;
+ * if it still doesn't exist, the user is prompted to create it.
+ * @param targetName The wikilink target of the broken link.
+ */
+async function openBrokenLinkTarget(targetName: string) {
+    const path = await commands.resolveWikilink(targetName);
+    if (path) {
+        navigateToPage({ path, title: getTitleFromPath(path) });
+        return;
+    }
+
+    const currentVaultPath = get(world).vaultPath;
+    if (currentVaultPath) {
+        promptAndCreateItem("file", currentVaultPath, targetName);
+    }
+}
+
 /**
  * Navigates to the tag index view for a specific tag.
  * @param tagName The name of the tag to display.
@@ -10,7 +10,7 @@
  * A specific type for the file node category. This improves type safety
  * over using a generic string. It mirrors the `FileType` enum in Rust.
  */
-export type FileType = "Directory" | "Markdown" | "Image";
+export type FileType = "Directory" | "Markdown" | "Image" | "Other";
 
 /**
  * A lightweight representation of a page, containing only the data needed
@@ -71,6 +71,19 @@ export interface RenderedPage {
     html_after_toc: string;
     /** The generated Table of Contents for the page. */
     toc: TocEntry[];
+    /** The widest the body should be shown, in pixels, if limited. */
+    max_content_width: number | null;
+    /** How many of the page's task list items are done, if it has any. */
+    tasks: TaskProgress | null;
+}
+
+/**
+ * The number of checked task list items on a page, out of all of them.
+ * This mirrors the `TaskProgress` struct in `src-tauri/src/models.rs`.
+ */
+export interface TaskProgress {
+    completed: number;
+    total: number;
 }
 
 /**
@@ -84,6 +97,8 @@ export interface Backlink {
     path: string;
     /** The number of times the source page links to the current page. */
     count: number;
+    /** The alias the source page uses for its link, when backlink aliases are shown. */
+    alias?: string;
 }
 
 /**
@@ -97,6 +112,30 @@ export interface FullPageData {
     rendered_page: RenderedPage;
     /** A list of all pages that link to this page. */
     backlinks: Backlink[];
+    /** Pages that share tags or link targets with this page, most related first. */
+    related: PageHeader[];
+    /** The page with its direct link targets and backlinks, if it was requested. */
+    local_graph: GraphData | null;
+}
+
+/**
+ * A link between two pages in a graph, weighted by how many links there are.
+ * This mirrors the `GraphEdge` struct in `src-tauri/src/models.rs`.
+ */
+export interface GraphEdge {
+    source: string;
+    target: string;
+    /** The number of links from the source page to the target page. */
+    weight: number;
+}
+
+/**
+ * A set of pages and the links between them, ready to be drawn as a graph.
+ * This mirrors the `GraphData` struct in `src-tauri/src/models.rs`.
+ */
+export interface GraphData {
+    nodes: PageHeader[];
+    edges: GraphEdge[];
 }
 
 /**
@@ -120,6 +159,22 @@ export interface BrokenLink {
     sources: PageHeader[];
 }
 
+/**
+ * Aggregate storage statistics for a single folder, including its subfolders.
+ * This mirrors the `FolderStat` struct in `src-tauri/src/models.rs`.
+ */
+export interface FolderStat {
+    path: string;
+    /** The number of Markdown pages. */
+    pages: number;
+    /** The number of supported image files. */
+    images: number;
+    /** The number of any other files. */
+    attachments: number;
+    /** The combined size of all files, in bytes. */
+    total_bytes: number;
+}
+
 /**
  * Represents a single user-provided font, prepared for frontend consumption.
  * This mirrors the `UserFont` struct in `src-tauri/src/fonts.rs`.
@@ -130,3 +185,274 @@ export interface UserFont {
     /** The full Base64-encoded Data URI of the font file. */
     base64: string;
 }
+
+/**
+ * The payload of the `pandoc-download-progress` event.
+ * This mirrors the `DownloadProgress` struct in `src-tauri/src/importer.rs`.
+ */
+export interface DownloadProgress {
+    /** The number of bytes received so far. */
+    downloaded: number;
+    /** The total size of the download, if known. */
+    total: number | null;
+    /** The completion percentage (0-100), if the total size is known. */
+    percentage: number | null;
+}
+
+/**
+ * A file left out of a plain-text import.
+ * This mirrors the `SkippedImport` struct in `src-tauri/src/importer.rs`.
+ */
+export interface SkippedImport {
+    path: string;
+    /** Why the file was not imported. */
+    reason: string;
+}
+
+/**
+ * The outcome of importing plain-text files.
+ * This mirrors the `TextImportResult` struct in `src-tauri/src/importer.rs`.
+ */
+export interface TextImportResult {
+    /** The Markdown pages that were created. */
+    imported: string[];
+    /** The files that could not be imported. */
+    skipped: SkippedImport[];
+}
+
+/**
+ * The vault's total word count on a given day.
+ * This mirrors the `WordCountSnapshot` struct in `src-tauri/src/word_count.rs`.
+ */
+export interface WordCountSnapshot {
+    /** The day of the snapshot, formatted as `YYYY-MM-DD`. */
+    date: string;
+    /** The total number of words across all pages. */
+    words: number;
+    /** The change since the previous snapshot (zero for the first one). */
+    delta: number;
+}
+
+/**
+ * The pages that differ between two vault manifests. Paths are vault-relative.
+ * This mirrors the `ManifestDiff` struct in `src-tauri/src/models.rs`.
+ */
+export interface ManifestDiff {
+    /** Pages in the newer manifest only. */
+    added: string[];
+    /** Pages in the older manifest only. */
+    removed: string[];
+    /** Pages in both manifests whose content changed. */
+    modified: string[];
+}
+
+/**
+ * The differences between the in-memory index and a fresh scan of the vault.
+ * Paths are vault-relative.
+ * This mirrors the `IndexReport` struct in `src-tauri/src/models.rs`.
+ */
+export interface IndexReport {
+    /** Indexed pages whose files no longer exist. */
+    missing_on_disk: string[];
+    /** Pages on disk that aren't indexed. */
+    missing_from_index: string[];
+    /** Indexed pages whose files have changed since they were indexed. */
+    outdated: string[];
+    /** Pages whose indexed links or backlinks differ from a fresh scan's. */
+    link_mismatches: string[];
+    /** Whether the index was replaced by the fresh scan to repair it. */
+    repaired: boolean;
+}
+
+/**
+ * A page matching a full-text search, with where the text was found.
+ * This mirrors the `SearchResult` struct in `src-tauri/src/models.rs`.
+ */
+export interface SearchResult {
+    page: PageHeader;
+    /** Whether the text was found in the page body. */
+    in_body: boolean;
+    /** Whether the text was found in a frontmatter value. */
+    in_frontmatter: boolean;
+}
+
+/**
+ * An external link that couldn't be reached when checked.
+ * This mirrors the `DeadLink` struct in `src-tauri/src/models.rs`.
+ */
+export interface DeadLink {
+    /** The absolute path of the page containing the link. */
+    path: string;
+    /** The URL as written. */
+    url: string;
+    /** Why the link counts as dead: the HTTP status, or the request error. */
+    reason: string;
+}
+
+/**
+ * A link to a page, as written on the page containing it.
+ * This mirrors the `Reference` struct in `src-tauri/src/models.rs`.
+ */
+export interface Reference {
+    /** The page containing the link. */
+    source: PageHeader;
+    /** The link as written, e.g. `[[Vex#Past|the rogue]]`. */
+    text: string;
+}
+
+/**
+ * Every link to a page, grouped by how it refers to the page.
+ * This mirrors the `References` struct in `src-tauri/src/models.rs`.
+ */
+export interface References {
+    /** Plain links by name, e.g. `[[Vex]]`. */
+    direct: Reference[];
+    /** Links with display text, e.g. `[[Vex|the rogue]]`. */
+    aliased: Reference[];
+    /** Links to a section of the page, e.g. `[[Vex#Past]]`. */
+    sections: Reference[];
+    /** Embeds of the page, e.g. `![[Vex]]`. */
+    embeds: Reference[];
+}
+
+/**
+ * A stored copy of a file's earlier content.
+ * This mirrors the `Backup` struct in `src-tauri/src/backup.rs`.
+ */
+export interface Backup {
+    /** The absolute path of the backup file. */
+    path: string;
+    /** When the backup was taken, formatted as `YYYY-MM-DDTHH:MM:SS`. */
+    created: string;
+    /** The size of the backup in bytes. */
+    size: number;
+}
+
+/**
+ * User-configurable options that control how pages are indexed.
+ * This mirrors the `IndexSettings` struct in `src-tauri/src/config.rs`.
+ */
+export interface IndexSettings {
+    /** Frontmatter keys whose values are collected into a page's tags. */
+    tag_keys: string[];
+    /** Extensions of other files to list in the file tree, such as `canvas`. */
+    extra_file_extensions: string[];
+    /** Frontmatter keys checked for a page's title, in priority order. */
+    title_keys: string[];
+    /** Leaves the asset folders out of the file tree. */
+    hide_asset_folders: boolean;
+    /** Vault-relative folders that hold images and other assets. */
+    asset_folders: string[];
+}
+
+/**
+ * How generated wikilinks to a page are written: its file name, its file name
+ * aliased by its title, or its vault-relative path.
+ * This mirrors the `LinkStyle` enum in `src-tauri/src/config.rs`.
+ */
+export type LinkStyle = "Bare" | "Aliased" | "Path";
+
+/**
+ * User-configurable options for file operations.
+ * This mirrors the `FileSettings` struct in `src-tauri/src/config.rs`.
+ */
+export interface FileSettings {
+    /** Whether renaming a page also updates its `title` frontmatter field. */
+    sync_title_on_rename: boolean;
+    /** Whether files are backed up before they are overwritten, renamed or deleted. */
+    backup_before_changes: boolean;
+    /** How generated links to pages are written. */
+    link_style: LinkStyle;
+    /** Tags given to new pages created inside particular folders. */
+    folder_tags: FolderTags[];
+}
+
+/**
+ * Tags that new pages created in a folder start with.
+ * This mirrors the `FolderTags` struct in `src-tauri/src/config.rs`.
+ */
+export interface FolderTags {
+    /** The folder, relative to the vault root. Its subfolders are covered too. */
+    folder: string;
+    /** The tags to add to the new page's frontmatter. */
+    tags: string[];
+}
+
+/**
+ * An advisory maximum length for a frontmatter field.
+ * This mirrors the `FieldLengthLimit` struct in `src-tauri/src/config.rs`.
+ */
+export interface FieldLengthLimit {
+    /** The frontmatter key to check. */
+    field: string;
+    /** The maximum number of characters before the field is flagged. */
+    max_length: number;
+    /** Only check pages whose `type` field has this value; all pages if null. */
+    page_type: string | null;
+}
+
+/**
+ * Which way a page's `type` field and its tags are expected to agree.
+ * This mirrors the `TypeTagCheck` enum in `src-tauri/src/config.rs`.
+ */
+export type TypeTagCheck = "TypeNeedsTag" | "TagNeedsType" | "Both";
+
+/**
+ * Options for the non-blocking frontmatter checks.
+ * This mirrors the `ValidationSettings` struct in `src-tauri/src/config.rs`.
+ */
+export interface ValidationSettings {
+    field_length_limits: FieldLengthLimit[];
+    /** Flags pages whose `type` field and tags disagree; off if null. */
+    type_tag_check: TypeTagCheck | null;
+}
+
+/**
+ * A non-blocking issue found in a page's frontmatter.
+ * This mirrors the `ValidationAdvisory` struct in `src-tauri/src/models.rs`.
+ */
+export interface ValidationAdvisory {
+    page: PageHeader;
+    field: string;
+    message: string;
+}
+
+/**
+ * User-configurable options that control how pages are rendered.
+ * This mirrors the `RenderSettings` struct in `src-tauri/src/config.rs`.
+ */
+export interface RenderSettings {
+    /** Omits a leading H1 from the body when it matches the frontmatter title. */
+    suppress_duplicate_title: boolean;
+    /** Images larger than this many bytes are not embedded as Base64 Data URLs. */
+    max_embed_image_bytes: number;
+    /** Shows ISO dates in date-like frontmatter fields in a human-readable format. */
+    humanize_dates: boolean;
+    /** The strftime-style format used when `humanize_dates` is enabled. */
+    date_format: string;
+    /** Removes `:::gm` blocks from rendered pages entirely. */
+    player_mode: boolean;
+    /** Adds a `data-suggestion` attribute with the closest page name to broken links. */
+    suggest_broken_links: boolean;
+    /** Includes the alias each backlinking page uses for its link. */
+    show_backlink_aliases: boolean;
+    /** The glossary page whose headings are linked where they're used. */
+    glossary_page: string | null;
+    /** The widest the page body is shown, in pixels; wider tables and images scroll. */
+    max_content_width: number | null;
+    /** Prefixes each rendered heading with its number from the table of contents. */
+    number_headings: boolean;
+    /** Adds a `data-preview` attribute with the target page's excerpt to internal links. */
+    link_previews: boolean;
+}
+
+/**
+ * The payload of an `export-progress` event.
+ * This mirrors the `ExportProgress` struct in `src-tauri/src/exporter.rs`.
+ */
+export interface ExportProgress {
+    /** The number of pages copied so far. */
+    exported: number;
+    /** The total number of pages being exported. */
+    total: number;
+}
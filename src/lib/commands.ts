@@ -12,9 +12,23 @@ import type {
     License,
     PageHeader,
     RenderedPage,
+    RenderSettings,
+    IndexSettings,
+    FileSettings,
+    ValidationSettings,
+    ValidationAdvisory,
     TagMap,
     BrokenLink,
+    FolderStat,
+    TextImportResult,
     UserFont,
+    WordCountSnapshot,
+    Backup,
+    ManifestDiff,
+    IndexReport,
+    References,
+    DeadLink,
+    SearchResult,
 } from "./bindings";
 
 // --- Vault Commands ---
@@ -25,6 +39,13 @@ import type {
  */
 export const getVaultPath = () => invoke<string | null>("get_vault_path");
 
+/**
+ * Retrieves the root of the vault currently loaded by the backend. Unlike
+ * `getVaultPath`, this reflects the vault that is actually being served.
+ * @returns A promise that resolves to the vault path string or null if no vault is loaded.
+ */
+export const getActiveVault = () => invoke<string | null>("get_active_vault");
+
 /**
  * Sets the vault path, saves it to config, and initializes the world state.
  * @param path The absolute path to the new vault directory.
@@ -47,6 +68,221 @@ export const getFileTree = () => invoke<FileNode>("get_file_tree");
  */
 export const getAllTags = () => invoke<TagMap>("get_all_tags");
 
+/**
+ * Returns each tag with the number of pages using it. Lighter than `getAllTags`
+ * when the pages themselves aren't needed.
+ * @returns A promise that resolves to `[tag, count]` pairs, sorted by tag.
+ */
+export const getTagCounts = () =>
+    invoke<[string, number][]>("get_tag_counts");
+
+/**
+ * Returns pairs of tags that appear together on the same pages.
+ * @returns A promise that resolves to `[tagA, tagB, count]` tuples, most frequent first.
+ */
+export const getTagCooccurrence = () =>
+    invoke<[string, string, number][]>("get_tag_cooccurrence");
+
+/**
+ * Exports the tags as an OPML outline for mind-mapping tools. Tags are nested on
+ * `/`, so `location/city` sits inside `location`, with each tag's pages as leaves.
+ * @returns A promise that resolves to the OPML document.
+ */
+export const exportTagsOpml = () => invoke<string>("export_tags_opml");
+
+/**
+ * Records today's total vault word count, replacing any snapshot already
+ * taken today.
+ * @returns A promise that resolves to the daily word-count history, oldest first.
+ */
+export const recordWordCountSnapshot = () =>
+    invoke<WordCountSnapshot[]>("record_word_count_snapshot");
+
+/**
+ * Retrieves the vault's daily word-count history for tracking writing goals.
+ * @returns A promise that resolves to the daily snapshots, oldest first.
+ */
+export const getWordCountHistory = () =>
+    invoke<WordCountSnapshot[]>("get_word_count_history");
+
+/**
+ * Returns the number of distinct pages each page links to and is linked from.
+ * @returns A promise that resolves to a map of page paths to `[outbound, inbound]` counts.
+ */
+export const getLinkDegrees = () =>
+    invoke<Record<string, [number, number]>>("get_link_degrees");
+
+/**
+ * Returns the hub pages of the vault: those linked from at least a given number
+ * of other pages.
+ * @param minBacklinks The fewest backlinks a page needs to be included.
+ * @returns A promise that resolves to `[page, backlinkCount]` pairs, most linked first.
+ */
+export const getHubPages = (minBacklinks: number) =>
+    invoke<[PageHeader, number][]>("get_hub_pages", { minBacklinks });
+
+/**
+ * Returns the pages modified after a given time, to review recent changes.
+ * @param since An RFC 3339 timestamp, e.g. `2024-03-05T10:00:00Z`.
+ * @returns A promise that resolves to the pages, most recently modified first.
+ */
+export const getPagesModifiedSince = (since: string) =>
+    invoke<PageHeader[]>("get_pages_modified_since", { since });
+
+/**
+ * Returns all pages grouped by the first letter of their title, for an
+ * alphabetical index page. Titles that don't start with a letter are grouped
+ * under `#`.
+ * @returns A promise that resolves to the groups in order, each with its pages
+ * sorted by title.
+ */
+export const getAlphabeticalIndex = () =>
+    invoke<[string, PageHeader[]][]>("get_alphabetical_index");
+
+/**
+ * Returns pages paired with the word count of their body, to find stubs worth
+ * merging or long pages worth splitting.
+ * @param ascending Whether to list the shortest pages first.
+ * @param limit The maximum number of pages to return.
+ * @returns A promise that resolves to `[page, wordCount]` pairs.
+ */
+export const getPagesByLength = (ascending: boolean, limit: number) =>
+    invoke<[PageHeader, number][]>("get_pages_by_length", { ascending, limit });
+
+/**
+ * Finds the images a page uses (embeds and `image`/`banner` fields) that don't
+ * exist on disk.
+ * @param path The absolute path of the page.
+ * @returns A promise that resolves to the missing image paths, as written in the page.
+ */
+export const getPageMissingImages = (path: string) =>
+    invoke<string[]>("get_page_missing_images", { path });
+
+/**
+ * Finds every page that uses images missing from disk.
+ * @returns A promise that resolves to `[page, missingImages]` pairs, sorted by title.
+ */
+export const getAllMissingImages = () =>
+    invoke<[PageHeader, string[]][]>("get_all_missing_images");
+
+/**
+ * Finds every web URL in the vault, from Markdown links and bare URLs.
+ * @returns A promise that resolves to `[pagePath, url]` pairs, sorted by page path.
+ */
+export const getExternalLinks = () =>
+    invoke<[string, string][]>("get_external_links");
+
+/**
+ * Requests every web URL in the vault, a few at a time, to find dead links.
+ * This can take a while on vaults with many links.
+ * @returns A promise that resolves to the links that failed, timed out, or
+ * answered with an error status.
+ */
+export const checkExternalLinks = () =>
+    invoke<DeadLink[]>("check_external_links");
+
+/**
+ * Finds pages without any tags, for keeping the vault's taxonomy complete.
+ * @returns A promise that resolves to `[page, parseFailed]` pairs sorted by title.
+ * `parseFailed` is true for pages whose frontmatter couldn't be read.
+ */
+export const getUntaggedPages = () =>
+    invoke<[PageHeader, boolean][]>("get_untagged_pages");
+
+/**
+ * Finds every link to a page, grouped by how it refers to the page. Each link is
+ * in one group: embeds first, then section links, then aliased links, and the
+ * remaining plain links are direct.
+ * @param path The absolute path of the page.
+ * @returns A promise that resolves to the grouped links, each sorted by the
+ * linking page's title.
+ */
+export const getAllReferences = (path: string) =>
+    invoke<References>("get_all_references", { path });
+
+/**
+ * Finds pages that link to themselves, which is usually a mistake. Links to a
+ * section of the same page are not counted.
+ * @returns A promise that resolves to the self-linking pages, sorted by title.
+ */
+export const getSelfLinkingPages = () =>
+    invoke<PageHeader[]>("get_self_linking_pages");
+
+/**
+ * Captures a snapshot of the vault that can be saved and compared later.
+ * @returns A promise that resolves to each page's vault-relative path mapped to
+ * a hash of its content.
+ */
+export const getManifest = () =>
+    invoke<Record<string, string>>("get_manifest");
+
+/**
+ * Compares two manifests from `getManifest`.
+ * @param oldManifest The earlier manifest.
+ * @param newManifest The later manifest.
+ * @returns A promise that resolves to the pages added, removed and modified.
+ */
+export const diffManifests = (
+    oldManifest: Record<string, string>,
+    newManifest: Record<string, string>,
+) => invoke<ManifestDiff>("diff_manifests", { old: oldManifest, new: newManifest });
+
+/**
+ * Compares the index against a fresh scan of the vault, to catch changes made
+ * outside the app that were missed.
+ * @param repair Whether to replace a disagreeing index with the fresh scan.
+ * @returns A promise that resolves to the discrepancies found.
+ */
+export const verifyIndex = (repair = false) =>
+    invoke<IndexReport>("verify_index", { repair });
+
+/**
+ * Finds pages whose removal would disconnect parts of the link graph, treating
+ * links as undirected. These pages are the only route between parts of the vault.
+ * @returns A promise that resolves to the critical pages, sorted by title.
+ */
+export const getCriticalPages = () =>
+    invoke<PageHeader[]>("get_critical_pages");
+
+/**
+ * Groups pages into board columns by the value of a frontmatter field.
+ * Pages without the field are grouped under "Unset".
+ * @param field The frontmatter field to group by (e.g., "status").
+ * @returns A promise that resolves to a map of field values to page headers.
+ */
+export const getBoard = (field: string) =>
+    invoke<Record<string, PageHeader[]>>("get_board", { field });
+
+/**
+ * Finds the pages containing some text, ignoring case.
+ * @param query The text to look for.
+ * @param includeFrontmatter Whether to also search the string values in each
+ * page's frontmatter, such as infobox fields. This is slower on large vaults.
+ * @returns A promise that resolves to the matching pages, sorted by title, each
+ * saying whether it matched in the body, the frontmatter, or both.
+ */
+export const search = (query: string, includeFrontmatter = false) =>
+    invoke<SearchResult[]>("search", { query, includeFrontmatter });
+
+/**
+ * Finds pages by fuzzy matching their titles and `aliases`, for a quick
+ * switcher: the query's letters must appear in order but may be spread out, so
+ * `gnd` finds "Gandalf". Page bodies aren't searched.
+ * @param query The letters to look for.
+ * @returns A promise that resolves to the matching pages, best match first.
+ */
+export const fuzzyFindPages = (query: string) =>
+    invoke<PageHeader[]>("fuzzy_find_pages", { query });
+
+/**
+ * Finds all pages whose vault-relative path matches a glob pattern.
+ * `*` matches within a single folder, while `**` matches across folders.
+ * @param pattern The glob pattern, matched against vault-relative paths with forward slashes.
+ * @returns A promise that resolves to the matching page headers, sorted by path.
+ */
+export const findPagesByGlob = (pattern: string) =>
+    invoke<PageHeader[]>("find_pages_by_glob", { pattern });
+
 /**
  * Returns a list of all directory paths in the vault.
  * @returns A promise that resolves to an array of directory path strings.
@@ -54,6 +290,23 @@ export const getAllTags = () => invoke<TagMap>("get_all_tags");
 export const getAllDirectoryPaths = () =>
     invoke<string[]>("get_all_directory_paths");
 
+/**
+ * Returns storage statistics for every folder in the vault. Counts and sizes
+ * include the contents of subfolders.
+ * @returns A promise that resolves to an array of FolderStat objects, sorted by path.
+ */
+export const getFolderStats = () => invoke<FolderStat[]>("get_folder_stats");
+
+/**
+ * Returns the pages in a folder, sorted by title.
+ * @param path The absolute path of the folder.
+ * @param recursive Whether to include the pages in its subfolders too, rather
+ * than only its direct children.
+ * @returns A promise that resolves to the pages' headers.
+ */
+export const getFolderPages = (path: string, recursive: boolean) =>
+    invoke<PageHeader[]>("get_folder_pages", { path, recursive });
+
 /**
  * Returns a list of all broken links in the vault.
  * @returns A promise that resolves to an array of BrokenLink objects.
@@ -61,15 +314,62 @@ export const getAllDirectoryPaths = () =>
 export const getAllBrokenLinks = () =>
     invoke<BrokenLink[]>("get_all_broken_links");
 
+/**
+ * Returns the unresolved link targets of a single page. This is much cheaper
+ * than `getAllBrokenLinks` for per-page UI.
+ * @param path The absolute path of the page.
+ * @returns A promise that resolves to the broken link targets, in order of appearance.
+ */
+export const getPageBrokenLinks = (path: string) =>
+    invoke<string[]>("get_page_broken_links", { path });
+
+/**
+ * Returns a short plain-text excerpt of a page for search results and cards: its
+ * `summary` or `description` field, or else the start of its first paragraph.
+ * @param path The absolute path of the page.
+ * @param maxLength The maximum length of an excerpt taken from the body.
+ * Defaults to 200 characters.
+ * @returns A promise that resolves to the excerpt, or null if the page has no text.
+ */
+export const getPageExcerpt = (path: string, maxLength?: number) =>
+    invoke<string | null>("get_page_excerpt", { path, maxLength });
+
+/**
+ * Resolves a wikilink target (e.g., "Page#Section|Alias") to the path of an existing page.
+ * @param target The wikilink target to resolve.
+ * @returns A promise that resolves to the page path, or null if the target is unresolved.
+ */
+export const resolveWikilink = (target: string) =>
+    invoke<string | null>("resolve_wikilink", { target });
+
+/**
+ * Resolves a page's stable id (its `uid` or `id` frontmatter field) to its path.
+ * Pages can also be linked by id with `[[uid:...]]`.
+ * @param uid The id to resolve.
+ * @returns A promise that resolves to the page path, or null if no page has the id.
+ */
+export const resolveUid = (uid: string) =>
+    invoke<string | null>("resolve_uid", { uid });
+
+/**
+ * Finds ids that more than one page claims. Only the first of those pages (in
+ * path order) is reachable by the id.
+ * @returns A promise that resolves to `[id, pages]` pairs, sorted by id.
+ */
+export const getUidCollisions = () =>
+    invoke<[string, PageHeader[]][]>("get_uid_collisions");
+
 // --- Page & File Operation Commands ---
 
 /**
  * Parses a file on disk, renders it, and returns all data needed for the file view.
  * @param path The path to the file to build the view for.
+ * @param includeLocalGraph Whether to also build the graph of the page and the
+ * pages it links to or is linked from, e.g. for an inline graph widget.
  * @returns A promise that resolves to the complete data for the page view.
  */
-export const buildPageView = (path: string) =>
-    invoke<FullPageData>("build_page_view", { path });
+export const buildPageView = (path: string, includeLocalGraph = false) =>
+    invoke<FullPageData>("build_page_view", { path, includeLocalGraph });
 
 /**
  * Writes new content to a page on disk.
@@ -83,10 +383,39 @@ export const writePageContent = (path: string, content: string) =>
 /**
  * Renders a preview of markdown content without saving it to disk.
  * @param content The raw markdown content to render.
+ * @param path The path of the page the content belongs to, if any. Used to
+ * resolve links whose name is shared by several pages to the nearest one.
+ * @param focus Renders only the body for a distraction-free reading view: the
+ * frontmatter is returned as null, the TOC is empty, and the whole body is in
+ * `html_after_toc`.
+ * @param tocDepth The deepest heading level listed in the TOC, e.g. `3` for
+ * H1 to H3. Deeper headings can still be linked to. Defaults to all levels.
  * @returns A promise that resolves to the rendered page data.
  */
-export const renderPagePreview = (content: string) =>
-    invoke<RenderedPage>("render_page_preview", { content });
+export const renderPagePreview = (
+    content: string,
+    path?: string,
+    focus = false,
+    tocDepth = 6,
+) =>
+    invoke<RenderedPage>("render_page_preview", {
+        content,
+        path,
+        focus,
+        tocDepth,
+    });
+
+/**
+ * Renders one section of a page, for previewing a `[[Page#Section]]` link.
+ * The section runs from its heading to the next heading of the same or a
+ * higher level.
+ * @param path The absolute path of the page.
+ * @param section The heading's text or its anchor slug.
+ * @returns A promise that resolves to the section's HTML, or rejects if the
+ * page has no such section.
+ */
+export const renderSectionPreview = (path: string, section: string) =>
+    invoke<string>("render_section_preview", { path, section });
 
 /**
  * Renders pure markdown content (no wikilink resolution, or YAML frontmatter)
@@ -96,6 +425,96 @@ export const renderPagePreview = (content: string) =>
 export const renderMarkdown = (content: string) =>
     invoke<RenderedPage>("render_markdown", { content });
 
+/**
+ * Returns the render settings currently in effect.
+ * @returns A promise that resolves to the render settings.
+ */
+export const getRenderSettings = () =>
+    invoke<RenderSettings>("get_render_settings");
+
+/**
+ * Saves the render settings and applies them to subsequent renders.
+ * @param settings The new render settings.
+ */
+export const setRenderSettings = (settings: RenderSettings) =>
+    invoke<void>("set_render_settings", { settings });
+
+/**
+ * Retrieves the index settings currently in effect.
+ * @returns A promise that resolves to the index settings.
+ */
+export const getIndexSettings = () =>
+    invoke<IndexSettings>("get_index_settings");
+
+/**
+ * Saves the index settings and re-indexes the vault with them.
+ * @param settings The new index settings.
+ */
+export const setIndexSettings = (settings: IndexSettings) =>
+    invoke<void>("set_index_settings", { settings });
+
+/**
+ * Rewrites the line endings of every page in the vault, so that they are all
+ * the same. Pages already using the chosen style are left alone.
+ * @param toLf Whether to use LF (`\n`) line endings rather than CRLF (`\r\n`).
+ * @returns A promise that resolves to the number of pages changed.
+ */
+export const normalizeLineEndings = (toLf: boolean) =>
+    invoke<number>("normalize_line_endings", { toLf });
+
+/**
+ * Renames a frontmatter key on every page that has it (e.g. `type` to `category`),
+ * keeping each value as written. Pages that already have the new key are skipped.
+ * @param oldKey The key to rename.
+ * @param newKey The new name for the key.
+ * @returns A promise that resolves to the number of pages changed.
+ */
+export const renameFrontmatterKey = (oldKey: string, newKey: string) =>
+    invoke<number>("rename_frontmatter_key", { oldKey, newKey });
+
+/**
+ * Adds each linked page's `title` frontmatter field as an alias to the bare
+ * wikilinks across the vault, e.g. `[[char_042]]` becomes `[[char_042|Vex]]`.
+ * Links that already have an alias, and broken links, are left alone.
+ * @returns A promise that resolves to the number of pages changed.
+ */
+export const prettifyLinks = () => invoke<number>("prettify_links");
+
+/**
+ * Retrieves the saved file settings.
+ * @returns A promise that resolves to the file settings.
+ */
+export const getFileSettings = () => invoke<FileSettings>("get_file_settings");
+
+/**
+ * Saves the file settings.
+ * @param settings The new file settings.
+ */
+export const setFileSettings = (settings: FileSettings) =>
+    invoke<void>("set_file_settings", { settings });
+
+/**
+ * Retrieves the saved frontmatter validation settings.
+ * @returns A promise that resolves to the validation settings.
+ */
+export const getValidationSettings = () =>
+    invoke<ValidationSettings>("get_validation_settings");
+
+/**
+ * Saves the frontmatter validation settings.
+ * @param settings The new validation settings.
+ */
+export const setValidationSettings = (settings: ValidationSettings) =>
+    invoke<void>("set_validation_settings", { settings });
+
+/**
+ * Checks all pages' frontmatter against the saved validation settings and
+ * for keys that are set more than once. The results are advisories only and never block saving.
+ * @returns A promise that resolves to the advisories, ordered by page.
+ */
+export const validateFrontmatter = () =>
+    invoke<ValidationAdvisory[]>("validate_frontmatter");
+
 /**
  * Creates a new, empty markdown file.
  * @param parentDir The directory where the new file should be created.
@@ -122,6 +541,33 @@ export const createNewFile = (
 export const createNewFolder = (parentDir: string, folderName: string) =>
     invoke<void>("create_new_folder", { parentDir, folderName });
 
+/**
+ * Creates the vault's images folder if it doesn't exist yet.
+ * @returns A promise that resolves to the path of the images folder.
+ */
+export const ensureImagesDir = () => invoke<string>("ensure_images_dir");
+
+/**
+ * Saves pasted image data into the vault's images folder. The file extension is
+ * taken from the image's detected format, and existing images are never overwritten.
+ * @param bytes The raw image data.
+ * @param suggestedName The preferred file name for the image.
+ * @returns A promise that resolves to the image's path relative to the images
+ * folder, ready to embed as `![[path]]`.
+ */
+export const saveImage = (bytes: Uint8Array, suggestedName: string) =>
+    invoke<string>("save_image", { bytes: Array.from(bytes), suggestedName });
+
+/**
+ * Renames an image in-place and updates every page that uses it, in an embed or
+ * an `image` or `banner` frontmatter field.
+ * @param path The current path of the image.
+ * @param newName The new file name, without the extension, which is kept.
+ * @returns A promise that resolves to the new path of the image.
+ */
+export const renameImage = (path: string, newName: string) =>
+    invoke<string>("rename_image", { path, newName });
+
 /**
  * Renames a file or folder in-place and returns its new path.
  * @param path The current path of the item to rename.
@@ -138,6 +584,44 @@ export const renamePath = (path: string, newName: string) =>
 export const deletePath = (path: string) =>
     invoke<void>("delete_path", { path });
 
+/**
+ * Merges one page into another and deletes it. The source's body is appended to
+ * the target's, and every link to the source is redirected to the target. In the
+ * frontmatter, the target wins: fields only the source has are added, fields both
+ * have keep the target's value, and the two pages' tags are combined.
+ * @param source The path of the page to merge and delete.
+ * @param target The path of the page to merge into.
+ */
+export const mergePages = (source: string, target: string) =>
+    invoke<void>("merge_pages", { source, target });
+
+/**
+ * Retrieves the backups of a file, newest first.
+ * @param path The path of the file.
+ * @returns A promise that resolves to the file's backups.
+ */
+export const getBackups = (path: string) =>
+    invoke<Backup[]>("get_backups", { path });
+
+/**
+ * Restores a file to the content of one of its backups. The content being
+ * replaced is backed up first.
+ * @param backupPath The path of the backup to restore.
+ * @returns A promise that resolves to the path of the restored file.
+ */
+export const restoreBackup = (backupPath: string) =>
+    invoke<string>("restore_backup", { backupPath });
+
+/**
+ * Moves every page with a tag into a folder, updating the links to them. Pages
+ * whose name is taken in the folder are given a numbered name.
+ * @param tag The tag to move the pages of.
+ * @param destDir The full path of the target directory.
+ * @returns A promise that resolves to the new paths of the moved pages.
+ */
+export const moveTaggedToFolder = (tag: string, destDir: string) =>
+    invoke<string[]>("move_tagged_to_folder", { tag, destDir });
+
 /**
  * Moves a file or folder to a new directory and returns its new path.
  * This command delegates path construction to the backend, making it platform-safe.
@@ -148,6 +632,31 @@ export const deletePath = (path: string) =>
 export const movePath = (sourcePath: string, destDir: string) =>
     invoke<string>("move_path", { sourcePath, destDir });
 
+/**
+ * Renames and relocates a file or folder in one step and returns its new path.
+ * @param path The current path of the item.
+ * @param newPath The full destination path, including the file extension.
+ * @returns A promise that resolves to the new path of the item.
+ */
+export const renameAndMove = (path: string, newPath: string) =>
+    invoke<string>("rename_and_move", { path, newPath });
+
+/**
+ * Writes a "Backlinks" section into a page, listing every page that links to
+ * it. Running it again replaces the section instead of adding another.
+ * @param path The path of the page to update.
+ */
+export const materializeBacklinks = (path: string) =>
+    invoke("materialize_backlinks", { path });
+
+/**
+ * Formats a wikilink to a page in the configured link style.
+ * @param path The path of the page to link to.
+ * @returns A promise that resolves to the link, e.g. `[[people/Vex]]`.
+ */
+export const formatPageLink = (path: string) =>
+    invoke<string>("format_page_link", { path });
+
 /**
  * Duplicates a page, creating a new file with a numerical suffix.
  * @param path The path of the file to duplicate.
@@ -156,6 +665,37 @@ export const movePath = (sourcePath: string, destDir: string) =>
 export const duplicatePage = (path: string) =>
     invoke<PageHeader>("duplicate_page", { path });
 
+/**
+ * Splits a page into separate pages, one per heading of the given level.
+ * Each section is replaced in the original page by a link to its new page.
+ * @param path The path of the page to split.
+ * @param level The heading level to split at (1-6).
+ * @returns A promise that resolves to the paths of the newly created pages.
+ */
+export const splitPageByHeading = (path: string, level: number) =>
+    invoke<string[]>("split_page_by_heading", { path, level });
+
+/**
+ * Exports the vault's pages as a static HTML site. Pages matched by the
+ * `.exportignore` file at the vault root are left out of the export but
+ * remain in the app's index.
+ * @param outputDir The folder to write the site into. It must be outside the vault.
+ * @returns A promise that resolves to the paths of the written HTML files.
+ */
+export const exportStaticSite = (outputDir: string) =>
+    invoke<string[]>("export_static_site", { outputDir });
+
+/**
+ * Copies every page with the given tag, and the images they use, into a folder
+ * as Markdown. Links to pages that aren't part of the export become plain text.
+ * Progress is reported through `export-progress` events.
+ * @param tag The tag whose pages should be exported.
+ * @param outputDir The folder to copy the pages into. It must be outside the vault.
+ * @returns A promise that resolves to the paths of the written pages and images.
+ */
+export const exportTagged = (tag: string, outputDir: string) =>
+    invoke<string[]>("export_tagged", { tag, outputDir });
+
 /**
  * Opens a given path in the OS's default file explorer.
  * @param path The absolute path to the directory or file to open.
@@ -185,6 +725,13 @@ export const isPandocInstalled = () => invoke<boolean>("is_pandoc_installed");
  */
 export const downloadPandoc = () => invoke<void>("download_pandoc");
 
+/**
+ * Cancels an in-progress Pandoc download. The pending `downloadPandoc` call
+ * will reject once the backend notices the cancellation.
+ */
+export const cancelPandocDownload = () =>
+    invoke<void>("cancel_pandoc_download");
+
 /**
  * Imports a list of .docx files, converting them to Markdown.
  * @param docxPaths An array of paths to the .docx files to import.
@@ -201,6 +748,17 @@ export const importDocxFiles = (docxPaths: string[]) =>
 export const importDocxFromFolder = (folderPath: string) =>
     invoke<string[]>("import_docx_from_folder", { folderPath });
 
+/**
+ * Imports plain-text .txt files as Markdown pages, keeping their content
+ * unchanged and adding a title taken from the file name. Files that aren't
+ * UTF-8 text, or whose page name is taken, are skipped and reported.
+ * @param txtPaths An array of paths to the .txt files to import.
+ * @param destDir The vault folder to create the pages in.
+ * @returns A promise that resolves to the imported pages and any skipped files.
+ */
+export const importTextFiles = (txtPaths: string[], destDir: string) =>
+    invoke<TextImportResult>("import_text_files", { txtPaths, destDir });
+
 /**
  * Imports a MediaWiki XML dump file.
  * @param xmlPath The path to the MediaWiki XML dump file.
@@ -209,6 +767,16 @@ export const importDocxFromFolder = (folderPath: string) =>
 export const importMediawikiDump = (xmlPath: string) =>
     invoke<string[]>("import_mediawiki_dump", { xmlPath });
 
+/**
+ * Converts a pasted MediaWiki wikitext snippet to Markdown using the same
+ * conversion as dump imports. Requires Pandoc. Images are not downloaded.
+ * @param text The wikitext to convert.
+ * @returns A promise that resolves to the Markdown, with any categories as
+ * `tags` in a frontmatter block.
+ */
+export const convertWikitext = (text: string) =>
+    invoke<string>("convert_wikitext", { text });
+
 // --- Licensing Commands ---
 
 /**
@@ -38,6 +38,15 @@ export function isImage(node: FileNode): boolean {
     return node.file_type === "Image";
 }
 
+/**
+ * A helper function to check if a FileNode is another listed file, like a canvas.
+ * @param node The FileNode to check.
+ * @returns True if the node's file_type is 'Other'.
+ */
+export function isOther(node: FileNode): boolean {
+    return node.file_type === "Other";
+}
+
 /**
  * Checks if a given path string points to a Markdown file based on its extension.
  * This is useful for client-side logic where we only have the path string.
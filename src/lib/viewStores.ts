@@ -36,11 +36,13 @@ export const fileViewMode: Writable<"preview" | "split" | "editor"> =
 interface RightSidebarState {
     isVisible: boolean;
     backlinks: Backlink[];
+    related: PageHeader[];
 }
 
 const initialRightSidebarState: RightSidebarState = {
     isVisible: false,
     backlinks: [],
+    related: [],
 };
 
 /**
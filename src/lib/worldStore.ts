@@ -17,11 +17,12 @@ import { listen } from "@tauri-apps/api/event";
 import {
     getFileTree,
     getAllTags,
-    getVaultPath,
+    getActiveVault,
     getAllBrokenLinks,
+    recordWordCountSnapshot,
 } from "./commands";
 import { isMarkdown } from "./utils";
-import type { FileNode, TagMap, BrokenLink } from "./bindings";
+import type { FileNode, TagMap, BrokenLink, PageHeader } from "./bindings";
 
 /**
  * The shape of the core application data.
@@ -61,7 +62,7 @@ function createWorldStore() {
             const [files, tags, vaultPath, brokenLinks] = await Promise.all([
                 getFileTree(),
                 getAllTags(),
-                getVaultPath(),
+                getActiveVault(),
                 getAllBrokenLinks(),
             ]);
             update((s) => ({
@@ -98,6 +99,11 @@ function createWorldStore() {
 
             await loadData();
 
+            // Track daily writing progress; a failure here shouldn't block loading.
+            recordWordCountSnapshot().catch((e) =>
+                console.error("Failed to record word count:", e),
+            );
+
             unlisten = await listen("index-updated", () => {
                 console.log(
                     "Index update received from backend, refreshing world data...",
@@ -155,28 +161,27 @@ export const brokenLinks = derived(world, ($world) => $world.brokenLinks);
 export const isWorldLoaded = derived(world, ($world) => $world.isLoaded);
 
 /**
- * Recursively flattens the file tree into a simple array of file titles.
+ * A derived store that provides a flattened list of all pages, titled by their
+ * file names. Useful for link autocompletion.
+ */
+export const allPages = derived(files, ($files) => flattenFileTree($files));
+
+/**
+ * Recursively flattens the file tree into a simple array of pages.
  * This is used to generate link suggestions for autocompletion.
  */
-function flattenFileTree(node: FileNode | null): string[] {
+function flattenFileTree(node: FileNode | null): PageHeader[] {
     if (!node) return [];
-    const titles: string[] = [];
+    const pages: PageHeader[] = [];
     if (node.name && isMarkdown(node)) {
-        // Extract title from path, removing extension
-        titles.push(node.name);
+        // The node's name is the file name without the extension.
+        pages.push({ title: node.name, path: node.path });
     }
     if (node.children) {
         for (const child of node.children) {
-            titles.push(...flattenFileTree(child));
+            pages.push(...flattenFileTree(child));
         }
     }
-    return titles;
+    return pages;
 }
 
-/**
- * A derived store that provides a flattened list of all page titles.
- * Useful for autocompletion features.
- */
-export const allFileTitles = derived(files, ($files) =>
-    flattenFileTree($files),
-);